@@ -2,7 +2,7 @@
 use gtk4::gio;
 use gtk4::prelude::*;
 use gtk4::{Application, ApplicationWindow, PopoverMenuBar};
-use rusteditorkit::widget::EditorWidget;
+use rusteditorkit::prelude::{Command, Editor};
 
 /// Create the minimal menu structure
 fn create_menu() -> PopoverMenuBar {
@@ -55,7 +55,7 @@ fn main() {
         use std::rc::Rc;
         
         // Create editor
-        let editor = Rc::new(EditorWidget::new());
+        let editor = Rc::new(Editor::new());
         editor.connect_signals();
         editor.load_config_from_file("demo/src/config.ron");
         
@@ -63,17 +63,17 @@ fn main() {
         {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.lines = vec![
-                "// RustEditorKit Demo".to_string(),
-                "fn main() {".to_string(),
-                "    println!(\"Hello, world!\");".to_string(),
-                "}".to_string(),
-                "".to_string(),
-                "// Multi-language support:".to_string(),
-                "// Emoji: 😀 😁 😂 🤔".to_string(),
-                "// Chinese/Japanese: 漢字 かな カタカナ".to_string(),
-                "// Accents: é ü ñ å".to_string(),
-            ];
+            buf.reset(&[
+                "// RustEditorKit Demo",
+                "fn main() {",
+                "    println!(\"Hello, world!\");",
+                "}",
+                "",
+                "// Multi-language support:",
+                "// Emoji: 😀 😁 😂 🤔",
+                "// Chinese/Japanese: 漢字 かな カタカナ",
+                "// Accents: é ü ñ å",
+            ].join("\n"));
         }
         
         // Create menu
@@ -109,8 +109,7 @@ fn main() {
 }
 
 /// Register all menu actions
-fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
-    use rusteditorkit::keybinds::EditorAction;
+fn register_actions(app: &Application, editor: std::rc::Rc<Editor>) {
     
     // File actions
     let new_action = gio::SimpleAction::new("new", None);
@@ -119,7 +118,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::NewFile);
+            buf.handle_editor_action(Command::NewFile);
         }
     });
     app.add_action(&new_action);
@@ -130,7 +129,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::OpenFile);
+            buf.handle_editor_action(Command::OpenFile);
         }
     });
     app.add_action(&open_action);
@@ -141,7 +140,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::SaveFile);
+            buf.handle_editor_action(Command::SaveFile);
         }
     });
     app.add_action(&save_action);
@@ -152,7 +151,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::SaveAs);
+            buf.handle_editor_action(Command::SaveAs);
         }
     });
     app.add_action(&save_as_action);
@@ -171,7 +170,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::Undo);
+            buf.handle_editor_action(Command::Undo);
         }
     });
     app.add_action(&undo_action);
@@ -182,7 +181,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::Redo);
+            buf.handle_editor_action(Command::Redo);
         }
     });
     app.add_action(&redo_action);
@@ -193,7 +192,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::CutSelection);
+            buf.handle_editor_action(Command::CutSelection);
         }
     });
     app.add_action(&cut_action);
@@ -204,7 +203,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::CopySelection);
+            buf.handle_editor_action(Command::CopySelection);
         }
     });
     app.add_action(&copy_action);
@@ -215,7 +214,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::PasteClipboard);
+            buf.handle_editor_action(Command::PasteClipboard);
         }
     });
     app.add_action(&paste_action);
@@ -226,7 +225,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::Indent);
+            buf.handle_editor_action(Command::Indent);
         }
     });
     app.add_action(&indent_action);
@@ -237,7 +236,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::Unindent);
+            buf.handle_editor_action(Command::Unindent);
         }
     });
     app.add_action(&unindent_action);
@@ -248,7 +247,7 @@ fn register_actions(app: &Application, editor: std::rc::Rc<EditorWidget>) {
         move |_, _| {
             let buffer = editor.buffer();
             let mut buf = buffer.borrow_mut();
-            buf.handle_editor_action(EditorAction::SelectAll);
+            buf.handle_editor_action(Command::SelectAll);
         }
     });
     app.add_action(&select_all_action);
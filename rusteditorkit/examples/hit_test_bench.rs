@@ -0,0 +1,39 @@
+//! Drag-selection hit-testing over a long line
+//!
+//! cargo run --release -p rusteditorkit --example hit_test_bench
+//!
+//! Times the motion events of a drag across one long line: walking the line for every
+//! event (`column_at_x_offset`) against the cached edges `screen_to_buffer_position` uses.
+
+use std::time::Instant;
+use rusteditorkit::corelogic::layout::column_at_x_offset;
+use rusteditorkit::Buffer;
+
+const LINE_CHARS: usize = 20_000;
+const MOTION_EVENTS: usize = 5_000;
+const CHAR_WIDTH: f64 = 8.0;
+const LINE_HEIGHT: f64 = 20.0;
+
+fn main() {
+    let mut buffer = Buffer::new();
+    buffer.lines = vec!["lorem ipsum dolor sit amet ".repeat(LINE_CHARS / 27)];
+    let line = buffer.lines[0].clone();
+    let word_spacing = buffer.word_spacing_px();
+    let width = line.chars().count() as f64 * CHAR_WIDTH;
+    let xs: Vec<f64> = (0..MOTION_EVENTS).map(|i| i as f64 * width / MOTION_EVENTS as f64).collect();
+
+    let start = Instant::now();
+    let linear: usize = xs.iter().map(|&x| column_at_x_offset(&line, x, CHAR_WIDTH, word_spacing)).sum();
+    let linear_time = start.elapsed();
+
+    let start = Instant::now();
+    let cached: usize = xs.iter()
+        .map(|&x| buffer.screen_to_buffer_position(x, LINE_HEIGHT / 2.0, LINE_HEIGHT, CHAR_WIDTH, 0.0, 0.0).1)
+        .sum();
+    let cached_time = start.elapsed();
+
+    assert_eq!(linear, cached, "cached hit-testing disagrees with the linear walk");
+    println!("{} motion events over a {}-char line", MOTION_EVENTS, line.chars().count());
+    println!("  linear walk:  {:?} ({:?} per event)", linear_time, linear_time / MOTION_EVENTS as u32);
+    println!("  cached edges: {:?} ({:?} per event)", cached_time, cached_time / MOTION_EVENTS as u32);
+}
@@ -0,0 +1,65 @@
+//! Run an edit script on a file without opening a window
+//!
+//! rek-script [-e COMMAND]... [-f SCRIPT] [-o OUTPUT] FILE
+//!
+//! Commands from `-e` and from the `-f` script file run in the order given (see
+//! `corelogic::script` for the commands); the result is written to OUTPUT, or back to
+//! FILE. Build with `--features cli`; adding `--no-default-features` leaves GTK out.
+
+use std::process::ExitCode;
+use rusteditorkit::corelogic::{parse_script, EditorBuffer};
+
+const USAGE: &str = "usage: rek-script [-e COMMAND]... [-f SCRIPT] [-o OUTPUT] FILE";
+
+struct Args {
+    script: String,
+    input: String,
+    output: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut script = String::new();
+    let (mut input, mut output) = (None, None);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-e" => {
+                script.push_str(&value()?);
+                script.push('\n');
+            }
+            "-f" => {
+                let path = value()?;
+                let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+                script.push_str(&text);
+                script.push('\n');
+            }
+            "-o" => output = Some(value()?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if input.is_none() && !arg.starts_with('-') => input = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    let input = input.ok_or(USAGE)?;
+    Ok(Args { script, input, output })
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+    let commands = parse_script(&args.script).map_err(|e| e.to_string())?;
+    let mut buffer = EditorBuffer::new();
+    buffer.open_file(&args.input).map_err(|e| e.to_string())?;
+    buffer.run_script(&commands).map_err(|e| e.to_string())?;
+    let output = args.output.as_deref().unwrap_or(&args.input);
+    buffer.save_document(output).map_err(|e| e.to_string())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("rek-script: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,5 +1,5 @@
 // Bracket matching logic for EditorBuffer
-use crate::core::EditorBuffer;
+use crate::corelogic::buffer::EditorBuffer;
 
 impl EditorBuffer {
     /// Find matching bracket/parenthesis for cursor position
@@ -38,12 +38,19 @@ pub fn load_config(path: &str) -> Result<EditorConfig, String> {
         )
     })?;
     let reader = BufReader::new(file);
-    from_reader(reader).map_err(|e| {
+    let config: EditorConfig = from_reader(reader).map_err(|e| {
         format!(
             "Config error: Failed to parse RON config at '{}'.\nReason: {}\nSuggestion: Please check the config file format and documentation.",
             path, e
         )
-    })
+    })?;
+    config.validate_colors().map_err(|e| {
+        format!(
+            "Config error: Unresolved colors in RON config at '{}'.\nReason: {}\nSuggestion: Please define the referenced palette entries.",
+            path, e
+        )
+    })?;
+    Ok(config)
 }
 
 #[cfg(test)]
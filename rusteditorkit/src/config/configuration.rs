@@ -1,23 +1,32 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use crate::corelogic::gutter::GutterConfig;
 use crate::corelogic::font::FontConfig;
+use crate::corelogic::reflow::ReflowConfig;
+use crate::export::ExportConfig;
+use crate::corelogic::focus::FocusGranularity;
+use crate::corelogic::smoothscroll::ScrollEasing;
+use crate::corelogic::direction::TextDirection;
+use crate::corelogic::highlightrules::HighlightRule;
+use crate::corelogic::safesave::BackupMode;
+use crate::corelogic::color::{Color, ColorScheme, Palette, ThemeColor};
 
 /// Configuration for text selection appearance
 #[derive(Debug, Deserialize, Clone)]
 pub struct SelectionConfig {
     pub selection_toggle: bool,
-    pub selection_bg_color: String,
+    pub selection_bg_color: ThemeColor,
     pub selection_opacity: f64,
-    pub selection_text_color: String,
+    pub selection_text_color: ThemeColor,
 }
 
 impl Default for SelectionConfig {
     fn default() -> Self {
         Self {
             selection_toggle: true,
-            selection_bg_color: "#0050aa".to_string(),
+            selection_bg_color: Color::hex(0x0050aa).into(),
             selection_opacity: 0.3,
-            selection_text_color: "#ffffff".to_string(),
+            selection_text_color: Color::hex(0xffffff).into(),
         }
     }
 }
@@ -29,13 +38,13 @@ use crate::corelogic::cursor::CursorConfig;
 pub struct EditorConfig {
     pub font: FontConfig,
     pub cursor: CursorConfig,
-    pub editor_bg_color: String,
+    pub editor_bg_color: ThemeColor,
     pub gutter: GutterConfig,
     pub selection: SelectionConfig,
 
     // Search and whitespace guides
-    pub search_match_color: String,
-    pub whitespace_guide_color: String,
+    pub search_match_color: ThemeColor,
+    pub whitespace_guide_color: ThemeColor,
     pub show_whitespace_guides: bool,
 
     // Feature toggles
@@ -48,39 +57,256 @@ pub struct EditorConfig {
     pub margin_right: f64,
     pub margin_top: f64,
     pub margin_bottom: f64,
+
+    // Hard wrapping (reflow) settings
+    #[serde(default)]
+    pub reflow: ReflowConfig,
+
+    // Document (A4 page) mode
+    #[serde(default)]
+    pub a4_mode: bool,
+    #[serde(default = "default_page_margin_cm")]
+    pub top_margin_cm: f64,
+    #[serde(default = "default_page_margin_cm")]
+    pub bottom_margin_cm: f64,
+    #[serde(default = "default_page_margin_cm")]
+    pub left_margin_cm: f64,
+    #[serde(default = "default_page_margin_cm")]
+    pub right_margin_cm: f64,
+    /// Reading speed used for reading time estimates
+    #[serde(default = "default_words_per_minute")]
+    pub words_per_minute: u32,
+    /// Draw separators at computed page boundaries in A4 mode
+    #[serde(default = "default_true")]
+    pub show_page_breaks: bool,
+    #[serde(default = "default_page_break_color")]
+    pub page_break_color: ThemeColor,
+    /// Minimum paragraph lines carried to the top of a new page
+    #[serde(default = "default_widow_orphan_lines")]
+    pub widow_lines: usize,
+    /// Minimum paragraph lines left at the bottom of a page
+    #[serde(default = "default_widow_orphan_lines")]
+    pub orphan_lines: usize,
+
+    // Export and print (headers, footers)
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    /// Also place syntax-highlighted HTML on the clipboard when copying
+    #[serde(default)]
+    pub copy_with_formatting: bool,
+    /// Drop the selection after copying it
+    #[serde(default)]
+    pub copy_clears_selection: bool,
+    /// Pasting over a selection replaces it (otherwise the text is inserted at the caret)
+    #[serde(default = "default_true")]
+    pub paste_replaces_selection: bool,
+    /// Cut without a selection cuts the caret line (otherwise it does nothing)
+    #[serde(default = "default_true")]
+    pub cut_line_without_selection: bool,
+
+    /// Prose/Markdown editing helpers (list continuation); always on for markdown buffers
+    #[serde(default)]
+    pub prose_mode: bool,
+
+    /// Pin the enclosing heading/scope lines to the top of the viewport
+    #[serde(default)]
+    pub sticky_scroll: bool,
+    #[serde(default = "default_sticky_scroll_max_lines")]
+    pub sticky_scroll_max_lines: usize,
+    #[serde(default = "default_sticky_scroll_bg_color")]
+    pub sticky_scroll_bg_color: ThemeColor,
+
+    /// Keep the caret line vertically centered while typing
+    #[serde(default)]
+    pub typewriter_scrolling: bool,
+    /// Rows of context kept visible above and below the caret (vim's scrolloff)
+    #[serde(default)]
+    pub scroll_margin: usize,
+    /// Animate scroll jumps (search, goto, `smooth_scroll_to`) instead of jumping
+    #[serde(default)]
+    pub smooth_scrolling: bool,
+    #[serde(default = "default_smooth_scroll_duration_ms")]
+    pub smooth_scroll_duration_ms: u64,
+    /// Easing curve: Linear, EaseOut or EaseInOut
+    #[serde(default)]
+    pub smooth_scroll_easing: ScrollEasing,
+    /// Base text direction: Auto (per paragraph), Ltr or Rtl
+    #[serde(default)]
+    pub text_direction: TextDirection,
+    /// Streamed content keeps at most this many lines, trimming the oldest (0 = unlimited)
+    #[serde(default = "default_stream_max_lines")]
+    pub stream_max_lines: usize,
+    /// Dim every line outside the current paragraph
+    #[serde(default)]
+    pub zen_mode: bool,
+    /// Opacity of the veil drawn over dimmed lines (0.0 - 1.0)
+    #[serde(default = "default_zen_dim_opacity")]
+    pub zen_dim_opacity: f64,
+    /// Text kept at full opacity in zen mode: Line, Sentence or Paragraph
+    #[serde(default)]
+    pub focus_granularity: FocusGranularity,
+
+    /// Briefly flash the target line after search, goto and navigation jumps
+    #[serde(default = "default_true")]
+    pub line_flash: bool,
+    #[serde(default = "default_line_flash_color")]
+    pub line_flash_color: ThemeColor,
+    #[serde(default = "default_line_flash_duration_ms")]
+    pub line_flash_duration_ms: u64,
+
+    /// Annotated scroll track along the right edge (search, diagnostics, changes, bookmarks)
+    #[serde(default)]
+    pub scroll_map: bool,
+    #[serde(default = "default_scroll_map_width")]
+    pub scroll_map_width: f64,
+    #[serde(default = "default_scroll_map_track_color")]
+    pub scroll_map_track_color: ThemeColor,
+    #[serde(default = "default_scroll_map_search_color")]
+    pub scroll_map_search_color: ThemeColor,
+    #[serde(default = "default_scroll_map_error_color")]
+    pub scroll_map_error_color: ThemeColor,
+    #[serde(default = "default_scroll_map_warning_color")]
+    pub scroll_map_warning_color: ThemeColor,
+    #[serde(default = "default_scroll_map_modified_color")]
+    pub scroll_map_modified_color: ThemeColor,
+    #[serde(default = "default_scroll_map_bookmark_color")]
+    pub scroll_map_bookmark_color: ThemeColor,
+
+    /// Validate buffer invariants after every command in release builds too
+    #[serde(default)]
+    pub consistency_checks: bool,
+
+    /// Outline characters no font in the fallback chain can render ("tofu")
+    #[serde(default = "default_true")]
+    pub missing_glyph_indicator: bool,
+    #[serde(default = "default_missing_glyph_color")]
+    pub missing_glyph_color: ThemeColor,
+
+    /// Regex highlight rules (TODO markers, IP addresses, ...) drawn on visible lines
+    #[serde(default)]
+    pub highlight_rules: Vec<HighlightRule>,
+
+    /// Characters besides letters and digits that belong to words
+    #[serde(default = "default_word_chars")]
+    pub word_chars: String,
+    /// Per-language word characters keyed by language id (e.g. "css" => "_-")
+    #[serde(default = "default_language_word_chars")]
+    pub language_word_chars: HashMap<String, String>,
+    /// Mark other occurrences of the word under the caret
+    #[serde(default = "default_true")]
+    pub occurrence_highlight: bool,
+    #[serde(default = "default_occurrence_highlight_color")]
+    pub occurrence_highlight_color: ThemeColor,
+
+    /// Indent with tab characters instead of spaces
+    #[serde(default)]
+    pub indent_with_tabs: bool,
+    /// Spaces per indent level (and columns per tab)
+    #[serde(default = "default_indent_width")]
+    pub indent_width: usize,
+    /// Detect tabs/spaces and the indent width from the file content on open
+    #[serde(default = "default_true")]
+    pub detect_indentation: bool,
+    /// Apply vim/emacs modelines from opened files (off: files should not change settings)
+    #[serde(default)]
+    pub modelines: bool,
+    /// Lines searched for a modeline at the start and at the end of a file
+    #[serde(default = "default_modeline_scan_lines")]
+    pub modeline_scan_lines: usize,
+
+    /// Save through a temporary file renamed over the target (never leaves a half-written file)
+    #[serde(default = "default_true")]
+    pub atomic_save: bool,
+    /// Copy kept of the previous version on save: None, Tilde or Numbered
+    #[serde(default)]
+    pub backup_mode: BackupMode,
+    /// Insert pasted text verbatim as one undo step, bypassing list continuation and auto-surround
+    #[serde(default = "default_true")]
+    pub paste_guard: bool,
+
+    /// Gamma applied to the text color on dark backgrounds (above 1.0 brightens thin light-on-dark text)
+    #[serde(default = "default_dark_text_gamma")]
+    pub dark_text_gamma: f64,
+    /// Hinting used on dark backgrounds instead of `font_hinting` ("" keeps `font_hinting`)
+    #[serde(default)]
+    pub dark_text_hinting: String,
+    /// Weight added to the font on dark backgrounds (stem darkening, e.g. 50; variable fonts only)
+    #[serde(default)]
+    pub dark_text_weight_boost: u16,
+
+    /// Named colors that color settings can refer to as "$name"
+    #[serde(default)]
+    pub palette: Palette,
+    /// Variant drawn for `(light: .., dark: ..)` colors: Light or Dark
+    #[serde(default)]
+    pub color_scheme: ColorScheme,
+}
+
+fn default_page_margin_cm() -> f64 { 2.5 }
+fn default_words_per_minute() -> u32 { 230 }
+fn default_true() -> bool { true }
+fn default_page_break_color() -> ThemeColor { Color::hex(0x9e9e9e).into() }
+fn default_widow_orphan_lines() -> usize { 2 }
+fn default_sticky_scroll_max_lines() -> usize { 3 }
+fn default_sticky_scroll_bg_color() -> ThemeColor { Color::hex(0xececf4).into() }
+fn default_zen_dim_opacity() -> f64 { 0.6 }
+fn default_smooth_scroll_duration_ms() -> u64 { 200 }
+fn default_stream_max_lines() -> usize { 100_000 }
+fn default_line_flash_color() -> ThemeColor { Color::hex(0xffd54f).with_alpha(0.6).into() }
+fn default_line_flash_duration_ms() -> u64 { 450 }
+fn default_scroll_map_width() -> f64 { 12.0 }
+fn default_scroll_map_track_color() -> ThemeColor { Color::hex(0xd8d8d8).into() }
+fn default_scroll_map_search_color() -> ThemeColor { Color::hex(0xe0b000).into() }
+fn default_scroll_map_error_color() -> ThemeColor { Color::hex(0xe53935).into() }
+fn default_scroll_map_warning_color() -> ThemeColor { Color::hex(0xfb8c00).into() }
+fn default_scroll_map_modified_color() -> ThemeColor { Color::hex(0x43a047).into() }
+fn default_scroll_map_bookmark_color() -> ThemeColor { Color::hex(0x1e88e5).into() }
+fn default_missing_glyph_color() -> ThemeColor { Color::hex(0xe53935).into() }
+fn default_indent_width() -> usize { 4 }
+fn default_modeline_scan_lines() -> usize { 5 }
+fn default_dark_text_gamma() -> f64 { 1.0 }
+fn default_word_chars() -> String { "_".to_string() }
+fn default_language_word_chars() -> HashMap<String, String> {
+    [("css", "_-"), ("scss", "_-"), ("lisp", "_-"), ("clojure", "_-?!*"), ("shell", "_$"), ("bash", "_$"), ("javascript", "_$"), ("typescript", "_$")]
+        .iter()
+        .map(|(lang, chars)| (lang.to_string(), chars.to_string()))
+        .collect()
 }
+fn default_occurrence_highlight_color() -> ThemeColor { Color::hex(0x90caf9).with_alpha(0.4).into() }
 
 impl Default for EditorConfig {
     fn default() -> Self {
         Self {
             font: FontConfig::default(),
             cursor: CursorConfig::default(),
-            editor_bg_color: "#f8f8ff".to_string(),
+            editor_bg_color: Color::hex(0xf8f8ff).into(),
             gutter: GutterConfig {
                 toggle: true,
                 ltr_width: 50,
                 padding: 4,
-                bg_color: "#1e1e1e".to_string(),
+                bg_color: Color::hex(0x1e1e1e).into(),
                 border: crate::corelogic::gutter::GutterBorderConfig {
                     toggle: true,
-                    color: "#444".to_string(),
+                    color: Color::hex(0x444444).into(),
                     width: 1,
                     style: "solid".to_string(),
                 },
                 line_numbers: crate::corelogic::gutter::GutterLineNumbersConfig {
-                    color: "#aaa".to_string(),
+                    color: Color::hex(0xaaaaaa).into(),
                     ltr_width: 35,
                     align: "right".to_string(),
                     padding: 6,
-                    hover_color: "#fff".to_string(),
+                    hover_color: Color::hex(0xffffff).into(),
                     active_clickable: false,
+                    mode: crate::corelogic::gutter::LineNumberMode::Line,
                 },
                 font_size: 11,
                 font_weight: "normal".to_string(),
                 active_line: crate::corelogic::gutter::GutterActiveLineConfig {
-                    line_number_color: "#fff".to_string(),
+                    line_number_color: Color::hex(0xffffff).into(),
                     highlight_toggle: true,
-                    highlight_color: "#333".to_string(),
+                    highlight_color: Color::hex(0x333333).into(),
                     highlight_opacity: 0.6,
                 },
                 markers: crate::corelogic::gutter::GutterMarkersConfig {
@@ -88,17 +314,19 @@ impl Default for EditorConfig {
                     icon_char: "●".to_string(),
                     icon_path: "res/icons/breakpoint.svg".to_string(),
                     icon_size: 12,
-                    color: "#e06c75".to_string(),
-                    hover_color: "#ffb3b3".to_string(),
+                    color: Color::hex(0xe06c75).into(),
+                    hover_color: Color::hex(0xffb3b3).into(),
                     spacing: 4,
                     position: "left".to_string(),
                 },
+                annotations: crate::corelogic::gutter::GutterAnnotationsConfig::default(),
+                change_bars: crate::corelogic::gutter::GutterChangeBarsConfig::default(),
             },
             selection: SelectionConfig::default(),
 
             // Search and whitespace guides
-            search_match_color: "#ffff99".to_string(),
-            whitespace_guide_color: "#e0e0e0".to_string(),
+            search_match_color: Color::hex(0xffff99).into(),
+            whitespace_guide_color: Color::hex(0xe0e0e0).into(),
             show_whitespace_guides: false,
 
             // Feature toggles
@@ -111,6 +339,72 @@ impl Default for EditorConfig {
             margin_right: 8.0,
             margin_top: 4.0,
             margin_bottom: 4.0,
+
+            // Hard wrapping (reflow) settings
+            reflow: ReflowConfig::default(),
+
+            // Document (A4 page) mode
+            a4_mode: false,
+            top_margin_cm: default_page_margin_cm(),
+            bottom_margin_cm: default_page_margin_cm(),
+            left_margin_cm: default_page_margin_cm(),
+            right_margin_cm: default_page_margin_cm(),
+            words_per_minute: default_words_per_minute(),
+            show_page_breaks: true,
+            page_break_color: default_page_break_color(),
+            widow_lines: default_widow_orphan_lines(),
+            orphan_lines: default_widow_orphan_lines(),
+            export: ExportConfig::default(),
+            copy_with_formatting: false,
+            copy_clears_selection: false,
+            paste_replaces_selection: true,
+            cut_line_without_selection: true,
+            prose_mode: false,
+            sticky_scroll: false,
+            sticky_scroll_max_lines: default_sticky_scroll_max_lines(),
+            sticky_scroll_bg_color: default_sticky_scroll_bg_color(),
+            typewriter_scrolling: false,
+            scroll_margin: 0,
+            smooth_scrolling: false,
+            smooth_scroll_duration_ms: default_smooth_scroll_duration_ms(),
+            smooth_scroll_easing: ScrollEasing::default(),
+            text_direction: TextDirection::default(),
+            stream_max_lines: default_stream_max_lines(),
+            zen_mode: false,
+            zen_dim_opacity: default_zen_dim_opacity(),
+            focus_granularity: FocusGranularity::default(),
+            line_flash: true,
+            line_flash_color: default_line_flash_color(),
+            line_flash_duration_ms: default_line_flash_duration_ms(),
+            scroll_map: false,
+            scroll_map_width: default_scroll_map_width(),
+            scroll_map_track_color: default_scroll_map_track_color(),
+            scroll_map_search_color: default_scroll_map_search_color(),
+            scroll_map_error_color: default_scroll_map_error_color(),
+            scroll_map_warning_color: default_scroll_map_warning_color(),
+            scroll_map_modified_color: default_scroll_map_modified_color(),
+            scroll_map_bookmark_color: default_scroll_map_bookmark_color(),
+            consistency_checks: false,
+            missing_glyph_indicator: true,
+            missing_glyph_color: default_missing_glyph_color(),
+            highlight_rules: Vec::new(),
+            word_chars: default_word_chars(),
+            language_word_chars: default_language_word_chars(),
+            occurrence_highlight: true,
+            occurrence_highlight_color: default_occurrence_highlight_color(),
+            indent_with_tabs: false,
+            indent_width: default_indent_width(),
+            detect_indentation: true,
+            modelines: false,
+            modeline_scan_lines: default_modeline_scan_lines(),
+            atomic_save: true,
+            backup_mode: BackupMode::default(),
+            paste_guard: true,
+            dark_text_gamma: default_dark_text_gamma(),
+            dark_text_hinting: String::new(),
+            dark_text_weight_boost: 0,
+            palette: Palette::new(),
+            color_scheme: ColorScheme::default(),
         }
     }
 }
@@ -119,14 +413,22 @@ impl Default for EditorConfig {
 impl EditorConfig {
     pub fn set_font(&mut self, font: FontConfig) { self.font = font; }
     pub fn font(&self) -> &FontConfig { &self.font }
-    pub fn set_editor_bg_color(&mut self, color: &str) { self.editor_bg_color = color.to_string(); }
-    pub fn editor_bg_color(&self) -> &str { &self.editor_bg_color }
+    pub fn set_editor_bg_color(&mut self, color: ThemeColor) { self.editor_bg_color = color; }
+    pub fn editor_bg_color(&self) -> &ThemeColor { &self.editor_bg_color }
+    pub fn set_paste_guard(&mut self, v: bool) { self.paste_guard = v; }
+    pub fn paste_guard(&self) -> bool { self.paste_guard }
+    pub fn set_dark_text_gamma(&mut self, v: f64) { self.dark_text_gamma = v; }
+    pub fn dark_text_gamma(&self) -> f64 { self.dark_text_gamma }
+    pub fn set_dark_text_hinting(&mut self, v: &str) { self.dark_text_hinting = v.to_string(); }
+    pub fn dark_text_hinting(&self) -> &str { &self.dark_text_hinting }
+    pub fn set_dark_text_weight_boost(&mut self, v: u16) { self.dark_text_weight_boost = v; }
+    pub fn dark_text_weight_boost(&self) -> u16 { self.dark_text_weight_boost }
     pub fn set_gutter(&mut self, gutter: GutterConfig) { self.gutter = gutter; }
     pub fn gutter(&self) -> &GutterConfig { &self.gutter }
-    pub fn set_search_match_color(&mut self, c: &str) { self.search_match_color = c.to_string(); }
-    pub fn search_match_color(&self) -> &str { &self.search_match_color }
-    pub fn set_whitespace_guide_color(&mut self, c: &str) { self.whitespace_guide_color = c.to_string(); }
-    pub fn whitespace_guide_color(&self) -> &str { &self.whitespace_guide_color }
+    pub fn set_search_match_color(&mut self, c: ThemeColor) { self.search_match_color = c; }
+    pub fn search_match_color(&self) -> &ThemeColor { &self.search_match_color }
+    pub fn set_whitespace_guide_color(&mut self, c: ThemeColor) { self.whitespace_guide_color = c; }
+    pub fn whitespace_guide_color(&self) -> &ThemeColor { &self.whitespace_guide_color }
     pub fn set_show_whitespace_guides(&mut self, v: bool) { self.show_whitespace_guides = v; }
     pub fn show_whitespace_guides(&self) -> bool { self.show_whitespace_guides }
     pub fn set_syntax_highlighting(&mut self, v: bool) { self.syntax_highlighting = v; }
@@ -143,16 +445,167 @@ impl EditorConfig {
     pub fn margin_top(&self) -> f64 { self.margin_top }
     pub fn set_margin_bottom(&mut self, v: f64) { self.margin_bottom = v; }
     pub fn margin_bottom(&self) -> f64 { self.margin_bottom }
+    pub fn set_reflow(&mut self, reflow: ReflowConfig) { self.reflow = reflow; }
+    pub fn reflow(&self) -> &ReflowConfig { &self.reflow }
+    pub fn set_a4_mode(&mut self, v: bool) { self.a4_mode = v; }
+    pub fn a4_mode(&self) -> bool { self.a4_mode }
+    pub fn set_words_per_minute(&mut self, v: u32) { self.words_per_minute = v; }
+    pub fn words_per_minute(&self) -> u32 { self.words_per_minute }
+    pub fn set_show_page_breaks(&mut self, v: bool) { self.show_page_breaks = v; }
+    pub fn show_page_breaks(&self) -> bool { self.show_page_breaks }
+    pub fn set_page_break_color(&mut self, c: ThemeColor) { self.page_break_color = c; }
+    pub fn page_break_color(&self) -> &ThemeColor { &self.page_break_color }
+    pub fn set_widow_lines(&mut self, v: usize) { self.widow_lines = v; }
+    pub fn widow_lines(&self) -> usize { self.widow_lines }
+    pub fn set_orphan_lines(&mut self, v: usize) { self.orphan_lines = v; }
+    pub fn orphan_lines(&self) -> usize { self.orphan_lines }
+    pub fn set_copy_with_formatting(&mut self, v: bool) { self.copy_with_formatting = v; }
+    pub fn copy_with_formatting(&self) -> bool { self.copy_with_formatting }
+    pub fn set_copy_clears_selection(&mut self, v: bool) { self.copy_clears_selection = v; }
+    pub fn copy_clears_selection(&self) -> bool { self.copy_clears_selection }
+    pub fn set_paste_replaces_selection(&mut self, v: bool) { self.paste_replaces_selection = v; }
+    pub fn paste_replaces_selection(&self) -> bool { self.paste_replaces_selection }
+    pub fn set_cut_line_without_selection(&mut self, v: bool) { self.cut_line_without_selection = v; }
+    pub fn cut_line_without_selection(&self) -> bool { self.cut_line_without_selection }
+    pub fn set_prose_mode(&mut self, v: bool) { self.prose_mode = v; }
+    pub fn prose_mode(&self) -> bool { self.prose_mode }
+    pub fn set_sticky_scroll(&mut self, v: bool) { self.sticky_scroll = v; }
+    pub fn sticky_scroll(&self) -> bool { self.sticky_scroll }
+    pub fn set_typewriter_scrolling(&mut self, v: bool) { self.typewriter_scrolling = v; }
+    pub fn typewriter_scrolling(&self) -> bool { self.typewriter_scrolling }
+    pub fn set_scroll_margin(&mut self, v: usize) { self.scroll_margin = v; }
+    pub fn scroll_margin(&self) -> usize { self.scroll_margin }
+    pub fn set_smooth_scrolling(&mut self, v: bool) { self.smooth_scrolling = v; }
+    pub fn smooth_scrolling(&self) -> bool { self.smooth_scrolling }
+    pub fn set_smooth_scroll_duration_ms(&mut self, v: u64) { self.smooth_scroll_duration_ms = v; }
+    pub fn smooth_scroll_duration_ms(&self) -> u64 { self.smooth_scroll_duration_ms }
+    pub fn set_smooth_scroll_easing(&mut self, v: ScrollEasing) { self.smooth_scroll_easing = v; }
+    pub fn smooth_scroll_easing(&self) -> ScrollEasing { self.smooth_scroll_easing }
+    pub fn set_text_direction(&mut self, v: TextDirection) { self.text_direction = v; }
+    pub fn text_direction(&self) -> TextDirection { self.text_direction }
+    pub fn set_stream_max_lines(&mut self, v: usize) { self.stream_max_lines = v; }
+    pub fn stream_max_lines(&self) -> usize { self.stream_max_lines }
+    pub fn set_zen_mode(&mut self, v: bool) { self.zen_mode = v; }
+    pub fn zen_mode(&self) -> bool { self.zen_mode }
+    pub fn set_focus_granularity(&mut self, v: FocusGranularity) { self.focus_granularity = v; }
+    pub fn focus_granularity(&self) -> FocusGranularity { self.focus_granularity }
+    pub fn set_line_flash(&mut self, v: bool) { self.line_flash = v; }
+    pub fn line_flash(&self) -> bool { self.line_flash }
+    pub fn set_line_flash_color(&mut self, c: ThemeColor) { self.line_flash_color = c; }
+    pub fn line_flash_color(&self) -> &ThemeColor { &self.line_flash_color }
+    pub fn set_line_flash_duration_ms(&mut self, v: u64) { self.line_flash_duration_ms = v; }
+    pub fn line_flash_duration_ms(&self) -> u64 { self.line_flash_duration_ms }
+    pub fn set_scroll_map(&mut self, v: bool) { self.scroll_map = v; }
+    pub fn scroll_map(&self) -> bool { self.scroll_map }
+    pub fn set_scroll_map_width(&mut self, v: f64) { self.scroll_map_width = v; }
+    pub fn scroll_map_width(&self) -> f64 { self.scroll_map_width }
+    pub fn set_consistency_checks(&mut self, v: bool) { self.consistency_checks = v; }
+    pub fn consistency_checks(&self) -> bool { self.consistency_checks }
+    pub fn set_missing_glyph_indicator(&mut self, v: bool) { self.missing_glyph_indicator = v; }
+    pub fn missing_glyph_indicator(&self) -> bool { self.missing_glyph_indicator }
+    pub fn set_missing_glyph_color(&mut self, c: ThemeColor) { self.missing_glyph_color = c; }
+    pub fn missing_glyph_color(&self) -> &ThemeColor { &self.missing_glyph_color }
+    pub fn set_highlight_rules(&mut self, rules: Vec<HighlightRule>) { self.highlight_rules = rules; }
+    pub fn highlight_rules(&self) -> &[HighlightRule] { &self.highlight_rules }
+    pub fn set_word_chars(&mut self, chars: &str) { self.word_chars = chars.to_string(); }
+    pub fn word_chars(&self) -> &str { &self.word_chars }
+    pub fn set_language_word_chars(&mut self, language: &str, chars: &str) { self.language_word_chars.insert(language.to_string(), chars.to_string()); }
+    pub fn language_word_chars(&self) -> &HashMap<String, String> { &self.language_word_chars }
+    pub fn set_occurrence_highlight(&mut self, v: bool) { self.occurrence_highlight = v; }
+    pub fn occurrence_highlight(&self) -> bool { self.occurrence_highlight }
+    pub fn set_occurrence_highlight_color(&mut self, c: ThemeColor) { self.occurrence_highlight_color = c; }
+    pub fn occurrence_highlight_color(&self) -> &ThemeColor { &self.occurrence_highlight_color }
+    pub fn set_indent_with_tabs(&mut self, v: bool) { self.indent_with_tabs = v; }
+    pub fn indent_with_tabs(&self) -> bool { self.indent_with_tabs }
+    pub fn set_indent_width(&mut self, v: usize) { self.indent_width = v; }
+    pub fn indent_width(&self) -> usize { self.indent_width }
+    pub fn set_detect_indentation(&mut self, v: bool) { self.detect_indentation = v; }
+    pub fn detect_indentation(&self) -> bool { self.detect_indentation }
+    pub fn set_modelines(&mut self, v: bool) { self.modelines = v; }
+    pub fn modelines(&self) -> bool { self.modelines }
+    pub fn set_modeline_scan_lines(&mut self, v: usize) { self.modeline_scan_lines = v; }
+    pub fn modeline_scan_lines(&self) -> usize { self.modeline_scan_lines }
+    pub fn set_atomic_save(&mut self, v: bool) { self.atomic_save = v; }
+    pub fn atomic_save(&self) -> bool { self.atomic_save }
+    pub fn set_backup_mode(&mut self, v: BackupMode) { self.backup_mode = v; }
+    pub fn backup_mode(&self) -> BackupMode { self.backup_mode }
+    pub fn set_palette_color(&mut self, name: &str, color: ThemeColor) { self.palette.insert(name.to_string(), color); }
+    pub fn palette(&self) -> &Palette { &self.palette }
+    pub fn set_color_scheme(&mut self, v: ColorScheme) { self.color_scheme = v; }
+    pub fn color_scheme(&self) -> ColorScheme { self.color_scheme }
     
     // Selection configuration methods
     pub fn set_selection(&mut self, selection: SelectionConfig) { self.selection = selection; }
     pub fn selection(&self) -> &SelectionConfig { &self.selection }
     pub fn set_selection_toggle(&mut self, v: bool) { self.selection.selection_toggle = v; }
     pub fn selection_toggle(&self) -> bool { self.selection.selection_toggle }
-    pub fn set_selection_bg_color(&mut self, color: &str) { self.selection.selection_bg_color = color.to_string(); }
-    pub fn selection_bg_color(&self) -> &str { &self.selection.selection_bg_color }
+    pub fn set_selection_bg_color(&mut self, color: ThemeColor) { self.selection.selection_bg_color = color; }
+    pub fn selection_bg_color(&self) -> &ThemeColor { &self.selection.selection_bg_color }
     pub fn set_selection_opacity(&mut self, v: f64) { self.selection.selection_opacity = v; }
     pub fn selection_opacity(&self) -> f64 { self.selection.selection_opacity }
-    pub fn set_selection_text_color(&mut self, color: &str) { self.selection.selection_text_color = color.to_string(); }
-    pub fn selection_text_color(&self) -> &str { &self.selection.selection_text_color }
+    pub fn set_selection_text_color(&mut self, color: ThemeColor) { self.selection.selection_text_color = color; }
+    pub fn selection_text_color(&self) -> &ThemeColor { &self.selection.selection_text_color }
+}
+
+// Color resolution
+impl EditorConfig {
+    /// A color setting resolved against the palette and the color scheme
+    ///
+    /// The config loaders reject settings that do not resolve, so black is only drawn
+    /// for a reference, set in code, to a name missing from the palette.
+    pub fn color(&self, color: &ThemeColor) -> Color {
+        color.resolve(&self.palette, self.color_scheme).unwrap_or(Color::BLACK)
+    }
+
+    /// Check that every color setting resolves in both color schemes
+    pub fn validate_colors(&self) -> Result<(), String> {
+        let errors: Vec<String> = self.color_settings().into_iter()
+            .filter_map(|(name, color)| color.validate(&self.palette).err().map(|e| format!("{}: {}", name, e)))
+            .collect();
+        if errors.is_empty() { Ok(()) } else { Err(errors.join("\n")) }
+    }
+
+    /// Every color setting with its config path
+    fn color_settings(&self) -> Vec<(String, &ThemeColor)> {
+        let gutter = &self.gutter;
+        let mut settings: Vec<(String, &ThemeColor)> = [
+            ("font.font_color", &self.font.font_color),
+            ("font.font_shadow_color", &self.font.font_shadow_color),
+            ("cursor.cursor_color", &self.cursor.cursor_color),
+            ("editor_bg_color", &self.editor_bg_color),
+            ("gutter.bg_color", &gutter.bg_color),
+            ("gutter.border.color", &gutter.border.color),
+            ("gutter.line_numbers.color", &gutter.line_numbers.color),
+            ("gutter.line_numbers.hover_color", &gutter.line_numbers.hover_color),
+            ("gutter.active_line.line_number_color", &gutter.active_line.line_number_color),
+            ("gutter.active_line.highlight_color", &gutter.active_line.highlight_color),
+            ("gutter.markers.color", &gutter.markers.color),
+            ("gutter.markers.hover_color", &gutter.markers.hover_color),
+            ("gutter.annotations.color", &gutter.annotations.color),
+            ("gutter.annotations.bg_color", &gutter.annotations.bg_color),
+            ("gutter.change_bars.color", &gutter.change_bars.color),
+            ("selection.selection_bg_color", &self.selection.selection_bg_color),
+            ("selection.selection_text_color", &self.selection.selection_text_color),
+            ("search_match_color", &self.search_match_color),
+            ("whitespace_guide_color", &self.whitespace_guide_color),
+            ("page_break_color", &self.page_break_color),
+            ("export.header_footer_color", &self.export.header_footer_color),
+            ("sticky_scroll_bg_color", &self.sticky_scroll_bg_color),
+            ("line_flash_color", &self.line_flash_color),
+            ("scroll_map_track_color", &self.scroll_map_track_color),
+            ("scroll_map_search_color", &self.scroll_map_search_color),
+            ("scroll_map_error_color", &self.scroll_map_error_color),
+            ("scroll_map_warning_color", &self.scroll_map_warning_color),
+            ("scroll_map_modified_color", &self.scroll_map_modified_color),
+            ("scroll_map_bookmark_color", &self.scroll_map_bookmark_color),
+            ("missing_glyph_color", &self.missing_glyph_color),
+            ("occurrence_highlight_color", &self.occurrence_highlight_color),
+        ].into_iter().map(|(name, color)| (name.to_string(), color)).collect();
+        for (i, rule) in self.highlight_rules.iter().enumerate() {
+            settings.extend(rule.foreground.iter().map(|color| (format!("highlight_rules[{}].foreground", i), color)));
+            settings.extend(rule.background.iter().map(|color| (format!("highlight_rules[{}].background", i), color)));
+        }
+        settings.extend(self.palette.iter().map(|(name, color)| (format!("palette.{}", name), color)));
+        settings
+    }
 }
@@ -19,6 +19,10 @@ pub fn load_widget_config(path: &str) -> Result<crate::config::configuration::Ed
         Ok(cfg) => {
             println!("[CONFIG DEBUG] RON deserialization succeeded.");
             println!("[CONFIG DEBUG] Loaded CursorConfig: {:#?}", cfg.cursor);
+            cfg.validate_colors().map_err(|e| format!(
+                "Config error: Unresolved colors in RON config at '{}'.\nReason: {}\nSuggestion: Please define the referenced palette entries.",
+                path, e
+            ))?;
             Ok(cfg)
         },
         Err(e) => {
@@ -347,7 +347,7 @@ impl LegacyEditorBuffer {
     }
 
     /// Convenience: get font color
-    pub fn font_color(&self) -> &str {
+    pub fn font_color(&self) -> &crate::corelogic::color::ThemeColor {
         self.font.font_color()
     }
 
@@ -386,10 +386,10 @@ impl LegacyEditorBuffer {
         self.margin_top = config.margin_top;
         self.margin_bottom = config.margin_bottom;
 
-        self.editor_bg_color = config.editor_bg_color.clone();
+        self.editor_bg_color = config.editor_bg_color.to_string();
         self.syntax_highlighting = config.syntax_highlighting;
-        self.search_match_color = config.search_match_color.clone();
-        self.whitespace_guide_color = config.whitespace_guide_color.clone();
+        self.search_match_color = config.search_match_color.to_string();
+        self.whitespace_guide_color = config.whitespace_guide_color.to_string();
         self.show_whitespace_guides = config.show_whitespace_guides;
         // Gutter and nested config fields are now accessed via self.config.gutter
     }
@@ -0,0 +1,156 @@
+//! Aligning lines on a character or pattern
+//!
+//! `align_selection` pads the selected lines (or the paragraph under the cursor) so the
+//! first match of a character or regex lines up in one column, e.g. the `=` of a run of
+//! assignments or the `:` of a key list. The whitespace before each match is replaced,
+//! so aligning again (or after editing one line) gives the same layout. Lines without a
+//! match are left alone.
+
+use syntect::parsing::{Regex, Region};
+use super::buffer::EditorBuffer;
+
+/// Range edit aligning one line: replace chars `start..end` with `pad` spaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlignEdit {
+    pub start: usize,
+    pub end: usize,
+    pub pad: usize,
+}
+
+/// Edits lining up the match `find` returns (a byte offset) on each line
+///
+/// The match column becomes the widest text before a match, plus one space on lines
+/// that had whitespace before their match.
+pub fn align_edits(lines: &[&str], find: impl Fn(&str) -> Option<usize>) -> Vec<Option<AlignEdit>> {
+    let spans: Vec<Option<(usize, usize)>> = lines.iter()
+        .map(|line| {
+            let col = line[..find(line)?].chars().count();
+            let chars: Vec<char> = line.chars().take(col).collect();
+            let start = chars.iter().rposition(|c| !c.is_whitespace()).map_or(col, |i| i + 1);
+            Some((start, col))
+        })
+        .collect();
+    let target = spans.iter().flatten()
+        .map(|&(start, col)| start + usize::from(col > start))
+        .max();
+    spans.into_iter()
+        .map(|span| {
+            let (start, end) = span?;
+            Some(AlignEdit { start, end, pad: target? - start })
+        })
+        .collect()
+}
+
+/// Finds the byte offset of the match to align on a line
+pub type AlignMatcher = Box<dyn Fn(&str) -> Option<usize>>;
+
+/// Matcher for `pattern`: a single character is matched literally, anything longer as a
+/// regex (Oniguruma syntax)
+pub fn align_matcher(pattern: &str) -> Result<AlignMatcher, String> {
+    let mut chars = pattern.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Err("empty alignment pattern".to_string()),
+        (Some(c), None) => Ok(Box::new(move |line: &str| line.find(c))),
+        _ => {
+            if let Some(error) = Regex::try_compile(pattern) {
+                return Err(format!("invalid alignment pattern '{}': {}", pattern, error));
+            }
+            let regex = Regex::new(pattern.to_string());
+            Ok(Box::new(move |line: &str| {
+                let mut region = Region::new();
+                regex.search(line, 0, line.len(), Some(&mut region))
+                    .then(|| region.pos(0).map(|(from, _)| from))
+                    .flatten()
+            }))
+        }
+    }
+}
+
+impl EditorBuffer {
+    /// Align the selected lines, or the paragraph under the cursor, on `pattern`
+    ///
+    /// Returns an error for an invalid pattern and Ok(false) when nothing needed padding.
+    pub fn align_selection(&mut self, pattern: &str) -> Result<bool, String> {
+        let find = align_matcher(pattern)?;
+        if self.lines.is_empty() {
+            return Ok(false);
+        }
+        let (start_row, end_row) = match &self.selection {
+            Some(sel) if sel.is_active() => {
+                let ((start_row, _), (end_row, end_col)) = sel.normalized();
+                // A selection ending at column 0 does not include that line
+                let end_row = if end_col == 0 && end_row > start_row { end_row - 1 } else { end_row };
+                (start_row, end_row.min(self.lines.len() - 1))
+            }
+            _ => {
+                let row = self.cursor.row.min(self.lines.len() - 1);
+                let blank = |row: usize| self.lines[row].trim().is_empty();
+                if blank(row) {
+                    return Ok(false);
+                }
+                let mut start = row;
+                while start > 0 && !blank(start - 1) {
+                    start -= 1;
+                }
+                let mut end = row;
+                while end + 1 < self.lines.len() && !blank(end + 1) {
+                    end += 1;
+                }
+                (start, end)
+            }
+        };
+
+        let lines: Vec<&str> = self.lines[start_row..=end_row].iter().map(String::as_str).collect();
+        let edits: Vec<(usize, AlignEdit)> = align_edits(&lines, find).into_iter()
+            .enumerate()
+            .filter_map(|(i, edit)| edit.map(|edit| (start_row + i, edit)))
+            .filter(|(row, edit)| edit.end - edit.start != edit.pad || self.lines[*row].chars().skip(edit.start).take(edit.pad).any(|c| c != ' '))
+            .collect();
+        if edits.is_empty() {
+            return Ok(false);
+        }
+        self.push_undo();
+        for (row, edit) in edits {
+            self.replace_range((row, edit.start), (row, edit.end), &" ".repeat(edit.pad));
+        }
+        self.selection = None;
+        self.cursor.row = end_row;
+        self.cursor.col = self.lines[end_row].chars().count();
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn align(lines: &[&str], pattern: &str) -> Vec<String> {
+        let edits = align_edits(lines, align_matcher(pattern).unwrap());
+        lines.iter().zip(edits)
+            .map(|(line, edit)| match edit {
+                Some(edit) => {
+                    let chars: Vec<char> = line.chars().collect();
+                    let head: String = chars[..edit.start].iter().collect();
+                    let tail: String = chars[edit.end..].iter().collect();
+                    format!("{}{}{}", head, " ".repeat(edit.pad), tail)
+                }
+                None => line.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aligns_first_match_and_is_idempotent() {
+        let lines = ["let a = 1;", "let long_name     = 2;", "// none", "x=3 == y"];
+        let aligned = align(&lines, "=");
+        assert_eq!(aligned, vec!["let a         = 1;", "let long_name = 2;", "// none", "x             =3 == y"]);
+        let again: Vec<&str> = aligned.iter().map(String::as_str).collect();
+        assert_eq!(align(&again, "="), aligned);
+    }
+
+    #[test]
+    fn aligns_on_regex() {
+        let lines = ["name: x", "id:\ty", "a => b"];
+        assert_eq!(align(&lines, ":\\s|=>"), vec!["name: x", "id  :\ty", "a   => b"]);
+    }
+}
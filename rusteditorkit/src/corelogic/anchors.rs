@@ -0,0 +1,119 @@
+//! Content-anchored rows for persisted folds and bookmarks
+//!
+//! Row numbers go stale when a file is edited outside the editor, so the recent files
+//! store keeps folds and bookmarks as `LineAnchor`s: the row plus hashes of the line and
+//! of the lines around it. On restore an anchor moves to the nearest row with the same
+//! line (preferring one whose neighbours match too) within `MAX_ANCHOR_DRIFT` rows of
+//! where it was; anchors whose line is gone are dropped.
+
+use serde::{Deserialize, Serialize};
+use super::folding::{FoldKind, FoldRegion};
+
+/// Furthest an anchored row may have moved and still be found
+pub const MAX_ANCHOR_DRIFT: usize = 500;
+
+/// A row identified by its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineAnchor {
+    /// Row when the anchor was taken
+    pub row: usize,
+    /// Hash of the line
+    pub line: u64,
+    /// Hash of the lines before and after it
+    pub context: u64,
+}
+
+/// A manual fold as stored in the recent files store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedFold {
+    pub start: LineAnchor,
+    pub end: LineAnchor,
+    pub collapsed: bool,
+    pub summary: Option<String>,
+}
+
+/// FNV-1a, stable across builds and platforms (unlike `DefaultHasher`)
+fn hash_parts(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0xff)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+fn line_hash(lines: &[String], row: usize) -> u64 {
+    hash_parts(&[lines.get(row).map_or("", String::as_str)])
+}
+
+fn context_hash(lines: &[String], row: usize) -> u64 {
+    let before = row.checked_sub(1).and_then(|r| lines.get(r)).map_or("", String::as_str);
+    let after = lines.get(row + 1).map_or("", String::as_str);
+    hash_parts(&[before, after])
+}
+
+impl LineAnchor {
+    /// Anchor for `row` of `lines`
+    pub fn new(lines: &[String], row: usize) -> Self {
+        Self { row, line: line_hash(lines, row), context: context_hash(lines, row) }
+    }
+
+    /// Row of `lines` the anchor now points at, None if its line is no longer nearby
+    pub fn resolve(&self, lines: &[String]) -> Option<usize> {
+        let from = self.row.saturating_sub(MAX_ANCHOR_DRIFT);
+        let to = (self.row + MAX_ANCHOR_DRIFT).min(lines.len().saturating_sub(1));
+        (from..=to)
+            .filter(|&row| row < lines.len() && line_hash(lines, row) == self.line)
+            .min_by_key(|&row| (context_hash(lines, row) != self.context, row.abs_diff(self.row)))
+    }
+}
+
+impl SavedFold {
+    /// Saved form of the fold over `start..=fold.end`
+    pub fn new(lines: &[String], start: usize, fold: &FoldRegion) -> Self {
+        Self {
+            start: LineAnchor::new(lines, start),
+            end: LineAnchor::new(lines, fold.end),
+            collapsed: fold.collapsed,
+            summary: fold.summary.clone(),
+        }
+    }
+
+    /// The fold in `lines` as (start row, region), None if either end no longer matches
+    pub fn resolve(&self, lines: &[String]) -> Option<(usize, FoldRegion)> {
+        let start = self.start.resolve(lines)?;
+        let end = self.end.resolve(lines)?;
+        (start < end).then(|| (start, FoldRegion {
+            end,
+            collapsed: self.collapsed,
+            kind: FoldKind::Manual,
+            summary: self.summary.clone(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split('\n').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn anchors_follow_moved_lines_and_drop_missing_ones() {
+        let before = lines("fn a() {\n}\n\nfn b() {\n}");
+        let anchor = LineAnchor::new(&before, 3);
+        let closing = LineAnchor::new(&before, 4);
+
+        let after = lines("// header\n\nfn a() {\n}\n\nfn b() {\n}");
+        assert_eq!(anchor.resolve(&after), Some(5));
+        // Both "}" lines match; the one after "fn b() {" wins on context
+        assert_eq!(closing.resolve(&after), Some(6));
+
+        let edited = lines("fn a() {\n}\n\nfn c() {\n}");
+        assert_eq!(anchor.resolve(&edited), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Host-provided gutter annotations (blame author, log timestamp, custom text)
+//!
+//! The provider returns one short string per line. Results are cached against the text
+//! revision, so the provider only runs again after an edit or an explicit
+//! `invalidate_gutter_annotations` (e.g. when the host's blame data changes).
+
+use std::cell::{Ref, RefCell};
+use super::buffer::EditorBuffer;
+
+/// Host callback returning one annotation per line (missing entries render empty)
+pub type GutterProvider = Box<dyn Fn(&[String]) -> Vec<String>>;
+
+/// Provider output for one buffer revision
+#[derive(Debug, Default)]
+pub struct GutterAnnotationCache {
+    /// Text revision the texts were computed for (`None`: stale)
+    revision: Option<u64>,
+    texts: Vec<String>,
+}
+
+/// Cache stored on the buffer; interior mutability lets the draw path refresh it
+pub type GutterAnnotations = RefCell<GutterAnnotationCache>;
+
+impl EditorBuffer {
+    /// Install (or remove) the provider for the annotation gutter column
+    pub fn set_gutter_provider(&mut self, provider: Option<GutterProvider>) {
+        self.gutter_provider = provider;
+        self.invalidate_gutter_annotations();
+    }
+
+    /// Force the provider to run again on the next draw
+    pub fn invalidate_gutter_annotations(&self) {
+        self.gutter_annotations.borrow_mut().revision = None;
+    }
+
+    /// Annotations for the current contents, empty when no provider is installed
+    pub fn gutter_annotations(&self) -> Ref<'_, Vec<String>> {
+        if let Some(provider) = &self.gutter_provider {
+            let revision = self.text_revision;
+            let stale = self.gutter_annotations.borrow().revision != Some(revision);
+            if stale {
+                let mut cache = self.gutter_annotations.borrow_mut();
+                cache.texts = provider(&self.lines);
+                cache.revision = Some(revision);
+            }
+        }
+        Ref::map(self.gutter_annotations.borrow(), |cache| &cache.texts)
+    }
+
+    /// Width of the annotation column in characters: the longest annotation, capped
+    /// by `gutter.annotations.max_chars` (0 when there is nothing to show)
+    pub fn gutter_annotation_chars(&self) -> usize {
+        if self.gutter_provider.is_none() || !self.config.gutter.annotations.toggle {
+            return 0;
+        }
+        self.gutter_annotations().iter()
+            .map(|text| text.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(self.config.gutter.annotations.max_chars)
+    }
+}
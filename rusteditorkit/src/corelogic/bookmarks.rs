@@ -0,0 +1,43 @@
+//! Line bookmarks for EditorBuffer
+//!
+//! Bookmarks are plain row numbers kept sorted in a `BTreeSet`; they are shown in the
+//! scroll map and can be cycled with NextBookmark/PrevBookmark.
+
+use super::buffer::EditorBuffer;
+
+impl EditorBuffer {
+    /// Add or remove a bookmark on the cursor row
+    pub fn toggle_bookmark(&mut self) {
+        let row = self.cursor.row;
+        if !self.bookmarks.remove(&row) {
+            self.bookmarks.insert(row);
+        }
+    }
+
+    /// True if `row` carries a bookmark
+    pub fn is_bookmarked(&self, row: usize) -> bool {
+        self.bookmarks.contains(&row)
+    }
+
+    /// Move the cursor to the next bookmark below the cursor, wrapping to the first one
+    pub fn move_to_next_bookmark(&mut self) {
+        let next = self.bookmarks.range(self.cursor.row + 1..).next()
+            .or_else(|| self.bookmarks.iter().next())
+            .copied();
+        if let Some(row) = next.filter(|&row| row < self.lines.len()) {
+            self.cursor.row = row;
+            self.cursor.col = 0;
+        }
+    }
+
+    /// Move the cursor to the previous bookmark above the cursor, wrapping to the last one
+    pub fn move_to_prev_bookmark(&mut self) {
+        let prev = self.bookmarks.range(..self.cursor.row).next_back()
+            .or_else(|| self.bookmarks.iter().next_back())
+            .copied();
+        if let Some(row) = prev.filter(|&row| row < self.lines.len()) {
+            self.cursor.row = row;
+            self.cursor.col = 0;
+        }
+    }
+}
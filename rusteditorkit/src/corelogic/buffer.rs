@@ -19,22 +19,8 @@ impl EditorCursor {
     }
 }
 
-/// Mouse interaction state for selection handling
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum MouseState {
-    /// No mouse interaction
-    Idle,
-    /// Mouse is down and dragging to select
-    Selecting { start_row: usize, start_col: usize },
-    /// Mouse is down and dragging to extend selection
-    ExtendingSelection,
-}
-
-impl Default for MouseState {
-    fn default() -> Self {
-        MouseState::Idle
-    }
-}
+// Kept here as well for users of `buffer::MouseState`
+pub use super::pointer::MouseState;
 
 /// The main buffer struct for the custom code editor.
 /// Holds all text, cursor, selection, undo/redo, theme, and rendering state.
@@ -77,6 +63,162 @@ pub struct EditorBuffer {
     pub cursor_state: crate::corelogic::cursor::CursorState,
     /// Mouse interaction state for selection
     pub mouse_state: MouseState,
+    /// Language identifier used for per-language settings (e.g. "rust", "markdown")
+    pub language: Option<String>,
+    /// Buffer-local reflow column, overrides the config when set
+    pub reflow_column_override: Option<usize>,
+    /// Event listeners registered by the host
+    pub events: crate::corelogic::events::EventBus,
+    /// Last statistics snapshot published through `EditorEvent::StatisticsChanged`
+    pub last_statistics: Option<crate::corelogic::statistics::DocumentStatistics>,
+    /// Path of the file loaded into the buffer (`None` for unsaved buffers)
+    pub file_path: Option<String>,
+    /// The file at `file_path` was not writable when opened
+    pub file_read_only: bool,
+    /// Number shown as "untitled-N" while the buffer has no file
+    pub untitled_number: usize,
+    /// Root that title directories are shown relative to
+    pub workspace_root: Option<std::path::PathBuf>,
+    /// Last title published through `EditorEvent::TitleChanged`
+    pub last_title: Option<crate::corelogic::title::DisplayTitle>,
+    /// Natural sizes of images referenced by image anchors
+    pub image_sizes: crate::corelogic::images::ImageSizeCache,
+    /// Host callback providing outline entries (functions, types, ...) for code buffers
+    pub outline_provider: Option<crate::corelogic::outline::OutlineProvider>,
+    /// Last enclosing scope path published through `EditorEvent::ScopeChanged`
+    pub last_scope_path: Option<Vec<crate::corelogic::outline::OutlineEntry>>,
+    /// Number of rows that fit in the widget, updated by the draw function
+    pub viewport_rows: std::cell::Cell<usize>,
+    /// Measured line height in pixels of the last frame (0.0 until the first draw)
+    pub viewport_line_height: std::cell::Cell<f64>,
+    /// Region kept at full opacity in zen mode, refreshed after each command
+    pub focus_range: Option<crate::corelogic::focus::FocusRange>,
+    /// Fading highlight on the row the caret last jumped to
+    pub line_flash: Option<crate::corelogic::flash::LineFlash>,
+    /// Bookmarked rows
+    pub bookmarks: std::collections::BTreeSet<usize>,
+    /// Active search query, its matches are marked in the scroll map
+    pub search_query: Option<String>,
+    /// Buffer contents at the last open/save, used to mark modified lines (empty: no baseline)
+    pub saved_lines: Vec<String>,
+    /// Opt-in usage counters delivered to a host sink
+    pub telemetry: crate::corelogic::telemetry::Telemetry,
+    /// Host callback filling the annotation gutter column
+    pub gutter_provider: Option<crate::corelogic::annotations::GutterProvider>,
+    /// Provider output cached per buffer revision
+    pub gutter_annotations: crate::corelogic::annotations::GutterAnnotations,
+    /// Buffer-local line number mode (line numbers or byte offsets), overrides the config
+    pub line_number_mode_override: Option<crate::corelogic::gutter::LineNumberMode>,
+    /// Runtime zoom factor applied on top of the configured font sizes
+    pub zoom: f64,
+    /// Subpixel layout of the monitor showing this view (`None` until known)
+    pub monitor_subpixel_order: Option<crate::corelogic::fontoptions::SubpixelOrder>,
+    /// Bumped whenever glyph metrics may have changed; render caches keyed on it must be rebuilt
+    pub layout_generation: u64,
+    /// Missing glyph counts already reported per line
+    pub missing_glyphs: std::cell::RefCell<crate::corelogic::glyphs::MissingGlyphs>,
+    /// Buffer-local decimal alignment switch (`None` follows `font_decimal_alignment`)
+    pub decimal_alignment_override: Option<bool>,
+    /// Row ranges aligned on the decimal point regardless of the buffer setting
+    pub decimal_regions: Vec<std::ops::Range<usize>>,
+    /// Decimal tab stops measured by the last frame
+    pub decimal_tabs: std::cell::RefCell<Option<crate::corelogic::decimal::DecimalTabCache>>,
+    /// Fraction of a row scrolled past `scroll_offset` (0.0 except during scroll animations)
+    pub scroll_fraction: f64,
+    /// Running scroll animation, advanced by the widget's frame clock
+    pub smooth_scroll: Option<crate::corelogic::smoothscroll::SmoothScroll>,
+    /// Buffer-local base direction (`None` follows `text_direction` in the config)
+    pub text_direction_override: Option<crate::corelogic::direction::TextDirection>,
+    /// Explicit paragraph directions keyed by the paragraph's first row
+    pub paragraph_directions: std::collections::BTreeMap<usize, crate::corelogic::direction::TextDirection>,
+    /// Line directions resolved last, see `rtl_rows`
+    pub direction_cache: std::cell::RefCell<Option<crate::corelogic::direction::DirectionCache>>,
+    /// The hosting widget uses a right-to-left locale
+    pub widget_rtl: bool,
+    /// Right edge of the text area in pixels of the last frame (0.0 until the first draw)
+    pub viewport_text_right: std::cell::Cell<f64>,
+    /// Left edge of the text area and one-cell advance (letter spacing included) of the
+    /// last frame, used to hit-test pointer events (None until the first draw)
+    pub viewport_text_geometry: std::cell::Cell<Option<(f64, f64)>>,
+    /// Average advance of the gutter font in the last frame, which sizes the gutter columns
+    pub viewport_gutter_char_width: std::cell::Cell<f64>,
+    /// Experimental vertical writing mode
+    #[cfg(feature = "vertical-text")]
+    pub vertical_mode: bool,
+    /// Carets and selections of remote participants, pushed by a collaborative host
+    pub remote_cursors: Vec<crate::corelogic::remote::RemoteCursor>,
+    /// Recorded mutations for synchronization hosts (see `set_change_feed_enabled`)
+    pub changes: crate::corelogic::changes::ChangeFeed,
+    /// Named copies of the text (manual, plus one taken on every save)
+    pub snapshots: Vec<crate::corelogic::snapshots::Snapshot>,
+    /// Stashed buffer state while a snapshot is shown read-only
+    pub snapshot_view: Option<crate::corelogic::snapshots::SnapshotView>,
+    /// Open/save transforms (e.g. encryption) by path pattern
+    pub file_transforms: Vec<crate::corelogic::transforms::FileTransform>,
+    /// Host hook writing files with elevated permissions (see `save_file_elevated`)
+    pub elevated_writer: Option<crate::corelogic::elevated::ElevatedWriter>,
+    /// Keep the last streamed line in view (see `append_lines_streaming`)
+    pub follow_tail: bool,
+    /// Regular text or append-only log
+    pub kind: crate::corelogic::logview::BufferKind,
+    /// Line coloring rules used in log mode
+    pub log_rules: Vec<crate::corelogic::logview::LogRule>,
+    /// Compiled `config.highlight_rules`, ascending priority
+    pub highlight_rules: Vec<crate::corelogic::highlightrules::CompiledHighlightRule>,
+    /// Foldable row ranges by start row
+    pub folds: std::collections::BTreeMap<usize, crate::corelogic::folding::FoldRegion>,
+    /// Buffer-local indent style set by the host (see `indent_style`)
+    pub indent_override: Option<crate::corelogic::indentation::IndentStyle>,
+    /// Indent style detected from the content on open
+    pub detected_indent: Option<crate::corelogic::indentation::IndentStyle>,
+    /// Host replacement for the built-in indentation detector
+    pub indent_detector: Option<crate::corelogic::indentation::IndentDetector>,
+    /// Modeline read from the file on open (only with `config.modelines`)
+    pub modeline: Option<crate::corelogic::modeline::Modeline>,
+    /// Recently opened files and their positions (disabled unless the host sets a store)
+    pub recent_files: Option<crate::corelogic::recent::RecentFiles>,
+    /// Fields left to visit in the last inserted snippet
+    pub snippet: Option<crate::corelogic::snippet::SnippetSession>,
+    /// Templates offered for new files
+    pub templates: Vec<crate::corelogic::templates::FileTemplate>,
+    /// Host source of Unicode character names for the caret context
+    pub char_namer: Option<crate::corelogic::caretcontext::CharNamer>,
+    /// Last caret context published through `EditorEvent::CaretContextChanged`
+    pub last_caret_context: Option<crate::corelogic::caretcontext::CaretContext>,
+    /// Character edges of the line hit-tested last, reused while dragging over it
+    pub hit_test_cache: std::cell::RefCell<Option<crate::corelogic::hittest::LineEdges>>,
+    /// Pango layouts of the rows the text layer drew last, for pointer hit-testing
+    #[cfg(feature = "gtk")]
+    pub drawn_rows: std::cell::RefCell<crate::corelogic::hittest::DrawnRows>,
+    /// Host callback overriding the height of individual rows
+    pub line_height_provider: Option<crate::corelogic::lineheights::LineHeightProvider>,
+    /// Layers invalidated since the last frame
+    pub dirty_layers: std::cell::Cell<crate::corelogic::redraw::RedrawLayers>,
+    /// A frame was asked for and not drawn yet
+    pub redraw_queued: std::cell::Cell<bool>,
+    /// Depth of open redraw batches
+    pub redraw_batch: std::cell::Cell<u32>,
+    /// Column Up/Down aim for while the caret stays where they left it
+    pub preferred_col: Option<crate::corelogic::cursor::PreferredColumn>,
+    /// Bumped by every change of the text (see `edit.rs`)
+    pub text_revision: u64,
+    /// Per-line word and character counts behind `document_statistics`
+    pub statistics_cache: crate::corelogic::statistics::StatisticsCache,
+    /// Thumbnails decoded for the image layer
+    #[cfg(feature = "gtk")]
+    pub image_thumbnails: crate::corelogic::images::ImageThumbnailCache,
+    /// Outline entries last built by `outline_flat`
+    pub outline_cache: std::cell::RefCell<Option<crate::corelogic::outline::OutlineCache>>,
+    /// Rows with search matches last found for the scroll map
+    pub search_mark_cache: std::cell::RefCell<Option<crate::corelogic::scrollmap::SearchMarkCache>>,
+    /// Rows changed since the last save, last found for the gutter and the scroll map
+    pub modified_rows_cache: std::cell::RefCell<Option<crate::corelogic::scrollmap::ModifiedRowsCache>>,
+    /// A4 page breaks last found by `page_breaks`
+    pub page_break_cache: std::cell::RefCell<Option<crate::corelogic::layout::PageBreakCache>>,
+    /// Encoding, byte order mark and line endings of the file behind the buffer
+    pub text_format: crate::corelogic::textformat::TextFormat,
+    /// Line start offsets in the encoded file, see `line_start_offset`
+    pub line_offsets: std::cell::RefCell<crate::corelogic::textformat::LineOffsets>,
 }
 
 impl EditorBuffer {
@@ -140,8 +282,34 @@ impl EditorBuffer {
         }
     }
 
+    /// Toggle the task checkbox clicked at `(row, col)`, returns true if it was toggled
+    pub fn handle_checkbox_click(&mut self, row: usize, col: usize) -> bool {
+        use crate::corelogic::dispatcher::{CommandParams, CommandDispatcher};
+        
+        let mut dispatcher = CommandDispatcher::new();
+        if self.debug_mode {
+            dispatcher.set_debug_mode(true);
+        }
+        
+        match dispatcher.execute(
+            self, 
+            crate::keybinds::EditorAction::ToggleCheckbox, 
+            CommandParams::Position { row, col }
+        ) {
+            Ok(_) => true,
+            Err(e) => {
+                if self.debug_mode {
+                    println!("[ERROR] Failed to toggle checkbox: {}", e);
+                }
+                false
+            }
+        }
+    }
+
     /// Open file (for file operations)
-    pub fn handle_open_file(&mut self, file_path: &str) {
+    ///
+    /// Failures come back as `CommandError::FileError` holding the typed `FileError`.
+    pub fn handle_open_file(&mut self, file_path: &str) -> crate::corelogic::dispatcher::CommandResult {
         use crate::corelogic::dispatcher::{CommandParams, CommandDispatcher};
         
         let mut dispatcher = CommandDispatcher::new();
@@ -149,19 +317,24 @@ impl EditorBuffer {
             dispatcher.set_debug_mode(true);
         }
         
-        if let Err(e) = dispatcher.execute(
+        let result = dispatcher.execute(
             self, 
             crate::keybinds::EditorAction::OpenFile, 
             CommandParams::FilePath(file_path.to_string())
-        ) {
+        );
+        if let Err(e) = &result {
             if self.debug_mode {
                 println!("[ERROR] Failed to open file '{}': {}", file_path, e);
             }
         }
+        result
     }
 
     /// Save file (for file operations)
-    pub fn handle_save_file(&mut self, file_path: &str) {
+    ///
+    /// Failures come back as `CommandError::FileError` holding the typed `FileError`, so
+    /// a `PermissionDenied` can be retried with `save_file_elevated`.
+    pub fn handle_save_file(&mut self, file_path: &str) -> crate::corelogic::dispatcher::CommandResult {
         use crate::corelogic::dispatcher::{CommandParams, CommandDispatcher};
         
         let mut dispatcher = CommandDispatcher::new();
@@ -169,15 +342,17 @@ impl EditorBuffer {
             dispatcher.set_debug_mode(true);
         }
         
-        if let Err(e) = dispatcher.execute(
+        let result = dispatcher.execute(
             self, 
             crate::keybinds::EditorAction::SaveFile, 
             CommandParams::FilePath(file_path.to_string())
-        ) {
+        );
+        if let Err(e) = &result {
             if self.debug_mode {
                 println!("[ERROR] Failed to save file '{}': {}", file_path, e);
             }
         }
+        result
     }
     /// Returns the unified line height for rendering (max of text font size, gutter font size, font_line_height)
     pub fn unified_line_height(&self) -> f64 {
@@ -219,19 +394,91 @@ impl EditorBuffer {
             debug_mode: false,
             redraw_callback: None,
             mouse_state: MouseState::default(),
+            language: None,
+            reflow_column_override: None,
+            events: crate::corelogic::events::EventBus::new(),
+            last_statistics: None,
+            file_path: None,
+            file_read_only: false,
+            untitled_number: crate::corelogic::title::next_untitled_number(),
+            workspace_root: None,
+            last_title: None,
+            image_sizes: crate::corelogic::images::ImageSizeCache::default(),
+            outline_provider: None,
+            last_scope_path: None,
+            viewport_rows: std::cell::Cell::new(0),
+            viewport_line_height: std::cell::Cell::new(0.0),
+            focus_range: None,
+            line_flash: None,
+            bookmarks: std::collections::BTreeSet::new(),
+            search_query: None,
+            saved_lines: Vec::new(),
+            telemetry: crate::corelogic::telemetry::Telemetry::default(),
+            gutter_provider: None,
+            gutter_annotations: Default::default(),
+            line_number_mode_override: None,
+            zoom: 1.0,
+            monitor_subpixel_order: None,
+            layout_generation: 0,
+            missing_glyphs: Default::default(),
+            decimal_alignment_override: None,
+            decimal_regions: Vec::new(),
+            decimal_tabs: Default::default(),
+            scroll_fraction: 0.0,
+            smooth_scroll: None,
+            text_direction_override: None,
+            paragraph_directions: std::collections::BTreeMap::new(),
+            direction_cache: Default::default(),
+            widget_rtl: false,
+            viewport_text_right: std::cell::Cell::new(0.0),
+            viewport_text_geometry: std::cell::Cell::new(None),
+            viewport_gutter_char_width: std::cell::Cell::new(10.0),
+            #[cfg(feature = "vertical-text")]
+            vertical_mode: false,
+            remote_cursors: Vec::new(),
+            changes: crate::corelogic::changes::ChangeFeed::default(),
+            snapshots: Vec::new(),
+            snapshot_view: None,
+            file_transforms: Vec::new(),
+            elevated_writer: None,
+            follow_tail: false,
+            kind: crate::corelogic::logview::BufferKind::Text,
+            log_rules: Vec::new(),
+            highlight_rules: Vec::new(),
+            folds: std::collections::BTreeMap::new(),
+            indent_override: None,
+            detected_indent: None,
+            indent_detector: None,
+            modeline: None,
+            recent_files: None,
+            snippet: None,
+            templates: Vec::new(),
+            char_namer: None,
+            last_caret_context: None,
+            hit_test_cache: std::cell::RefCell::new(None),
+            #[cfg(feature = "gtk")]
+            drawn_rows: Default::default(),
+            line_height_provider: None,
+            dirty_layers: std::cell::Cell::new(crate::corelogic::redraw::RedrawLayers::ALL),
+            redraw_queued: std::cell::Cell::new(false),
+            redraw_batch: std::cell::Cell::new(0),
+            preferred_col: None,
+            text_revision: 0,
+            statistics_cache: Default::default(),
+            #[cfg(feature = "gtk")]
+            image_thumbnails: Default::default(),
+            outline_cache: Default::default(),
+            search_mark_cache: Default::default(),
+            modified_rows_cache: Default::default(),
+            page_break_cache: Default::default(),
+            text_format: Default::default(),
+            line_offsets: Default::default(),
         }
     }
 
-    /// Request a redraw of the editor UI (calls the redraw_callback if set)
+    /// Request a redraw of every layer of the editor UI (see `invalidate` for fewer)
     pub fn request_redraw(&self) {
-        if let Some(ref cb) = self.redraw_callback {
-            println!("[DEBUG] EditorBuffer::redraw_callback executing");
-            cb();
-        }
-        else {
-            println!("[DEBUG] EditorBuffer::redraw_callback is None");
-        }
-        println!("[DEBUG] EditorBuffer::request_redraw called");
+        self.invalidate(crate::corelogic::redraw::RedrawLayers::ALL);
     }
 
     /// Apply settings from EditorConfig to this buffer
@@ -242,6 +489,7 @@ impl EditorBuffer {
         self.gutter_width = config.gutter.ltr_width;
         // Update runtime cursor state from config
         self.update_cursor_state_from_config();
+        self.refresh_highlight_rules();
     }
 
     /// Get a reference to the font config
@@ -265,7 +513,7 @@ impl EditorBuffer {
     }
 
     /// Convenience: get font color
-    pub fn font_color(&self) -> &str {
+    pub fn font_color(&self) -> &crate::corelogic::color::ThemeColor {
         self.config.font.font_color()
     }
 
@@ -278,9 +526,4 @@ impl EditorBuffer {
     pub fn font_character_spacing(&self) -> f64 {
         self.config.font.font_character_spacing()
     }
-
-    /// Toggle A4 mode (stubbed for now)
-    pub fn toggle_a4_mode(&mut self) {
-        println!("[DEBUG] toggle_a4_mode called but not implemented yet");
-    }
 }
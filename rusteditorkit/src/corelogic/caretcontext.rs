@@ -0,0 +1,104 @@
+//! Caret context for speech and accessibility hosts
+//!
+//! `caret_context()` returns what a screen reader announces around the caret: the line
+//! text, the word under the caret and the character at the caret with its Unicode name.
+//! `refresh_caret_context` emits `EditorEvent::CaretContextChanged` when any of it
+//! changes. Names come from the Unicode name table; hosts can supply their own (e.g.
+//! localized names) with `set_char_namer`, and characters without a name are named by
+//! code point ("U+E000").
+
+use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+
+/// Host callback naming a character (None falls back to the Unicode name table)
+pub type CharNamer = Box<dyn Fn(char) -> Option<String>>;
+
+/// What surrounds the caret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaretContext {
+    pub row: usize,
+    pub col: usize,
+    /// Text of the caret line
+    pub line: String,
+    /// Word containing the caret, or ending right before it
+    pub word: Option<String>,
+    /// Character after the caret (None at the end of the line)
+    pub character: Option<char>,
+    /// Unicode name of `character`, e.g. "LATIN SMALL LETTER A"
+    pub character_name: Option<String>,
+}
+
+/// Unicode name of `c` from the Unicode name table, with the formal alias for tab
+/// (control characters have no name of their own)
+pub fn builtin_char_name(c: char) -> Option<String> {
+    match c {
+        '\t' => Some("CHARACTER TABULATION".to_string()),
+        _ => unicode_names2::name(c).map(|name| name.to_string()),
+    }
+}
+
+impl EditorBuffer {
+    /// Use `namer` (or with None, only the Unicode name table) for `CaretContext::character_name`
+    pub fn set_char_namer(&mut self, namer: Option<CharNamer>) {
+        self.char_namer = namer;
+    }
+
+    /// Unicode name of `c`: the host namer, else the Unicode name table, else "U+XXXX"
+    pub fn char_name(&self, c: char) -> String {
+        self.char_namer.as_ref()
+            .and_then(|namer| namer(c))
+            .or_else(|| builtin_char_name(c))
+            .unwrap_or_else(|| format!("U+{:04X}", c as u32))
+    }
+
+    /// Line, word and character at the caret
+    pub fn caret_context(&self) -> CaretContext {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let line = self.lines.get(row).cloned().unwrap_or_default();
+        let chars: Vec<char> = line.chars().collect();
+        // The word under the caret, or the one just typed when the caret sits after it
+        let word = self.word_range_at(row, col)
+            .or_else(|| col.checked_sub(1).and_then(|before| self.word_range_at(row, before)))
+            .map(|(start, end)| chars[start..end].iter().collect());
+        let character = chars.get(col).copied();
+        CaretContext {
+            row,
+            col,
+            line,
+            word,
+            character,
+            character_name: character.map(|c| self.char_name(c)),
+        }
+    }
+
+    /// Recompute the caret context and emit `CaretContextChanged` if it differs from the last one
+    ///
+    /// Does nothing when no event listeners are connected.
+    pub fn refresh_caret_context(&mut self) {
+        if !self.events.has_listeners() {
+            return;
+        }
+        let context = self.caret_context();
+        if self.last_caret_context.as_ref() != Some(&context) {
+            self.last_caret_context = Some(context.clone());
+            self.events.emit(&EditorEvent::CaretContextChanged(context));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_names() {
+        assert_eq!(builtin_char_name('a').as_deref(), Some("LATIN SMALL LETTER A"));
+        assert_eq!(builtin_char_name('Q').as_deref(), Some("LATIN CAPITAL LETTER Q"));
+        assert_eq!(builtin_char_name('7').as_deref(), Some("DIGIT SEVEN"));
+        assert_eq!(builtin_char_name('-').as_deref(), Some("HYPHEN-MINUS"));
+        assert_eq!(builtin_char_name('中').as_deref(), Some("CJK UNIFIED IDEOGRAPH-4E2D"));
+        assert_eq!(builtin_char_name('é').as_deref(), Some("LATIN SMALL LETTER E WITH ACUTE"));
+        assert_eq!(builtin_char_name('\u{1F600}').as_deref(), Some("GRINNING FACE"));
+        assert_eq!(builtin_char_name('\u{E000}'), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Change feed for synchronization and audit hosts
+//!
+//! When enabled, every mutation of the text is recorded as a `ChangeOp` by the shared edit
+//! path (`edit.rs`), whether it came through the dispatcher, a paste, a checkbox click or
+//! the input method: the replaced range in the text *before* the edit and the text
+//! inserted in its place. Replaying the ops in order on a copy of the document reproduces
+//! the buffer exactly, so hosts can sync peers or keep an audit log without diffing
+//! snapshots themselves. Positions are (row, char column).
+
+use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+
+/// Number of ops kept for `changes_since`; older ops must be recovered by a full resync
+pub const MAX_CHANGE_LOG: usize = 1000;
+
+/// (row, char column) in the buffer
+pub type Position = (usize, usize);
+
+/// Stable identifier of an operation: unique per site and never reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OpId {
+    /// Host-assigned id of the editor that produced the op (0 by default)
+    pub site: u64,
+    /// Sequence number within the site, starting at 1
+    pub seq: u64,
+}
+
+/// One recorded mutation: replace `start..end` with `inserted`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeOp {
+    pub id: OpId,
+    /// Document revision after applying this op
+    pub revision: u64,
+    /// Start of the replaced range in the text before the edit
+    pub start: Position,
+    /// End (exclusive) of the replaced range in the text before the edit
+    pub end: Position,
+    /// Text inserted at `start`; may contain newlines, empty for pure deletions
+    pub inserted: String,
+}
+
+/// Recorded ops and revision counter of a buffer
+#[derive(Debug, Clone, Default)]
+pub struct ChangeFeed {
+    pub enabled: bool,
+    pub site: u64,
+    pub revision: u64,
+    pub log: std::collections::VecDeque<ChangeOp>,
+}
+
+/// Smallest replacement turning `before` into `after`, as (start, end, inserted)
+///
+/// Returns `None` when the texts are equal.
+pub fn diff_lines(before: &[String], after: &[String]) -> Option<(Position, Position, String)> {
+    if before == after {
+        return None;
+    }
+    let old: Vec<char> = before.join("\n").chars().collect();
+    let new: Vec<char> = after.join("\n").chars().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old.iter().rev().zip(new.iter().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+    let inserted: String = new[prefix..new.len() - suffix].iter().collect();
+    Some((offset_to_position(&old, prefix), offset_to_position(&old, old.len() - suffix), inserted))
+}
+
+/// (row, col) of char `offset` in newline-joined text
+fn offset_to_position(text: &[char], offset: usize) -> Position {
+    let before = &text[..offset];
+    let row = before.iter().filter(|&&c| c == '\n').count();
+    let col = before.iter().rev().take_while(|&&c| c != '\n').count();
+    (row, col)
+}
+
+impl EditorBuffer {
+    /// Start or stop recording the change feed (off by default)
+    pub fn set_change_feed_enabled(&mut self, enabled: bool) {
+        self.changes.enabled = enabled;
+    }
+
+    /// True while mutations are recorded
+    pub fn change_feed_enabled(&self) -> bool {
+        self.changes.enabled
+    }
+
+    /// Set the site part of future op ids, so ops from several editors never collide
+    pub fn set_change_site(&mut self, site: u64) {
+        self.changes.site = site;
+    }
+
+    /// Current document revision (number of recorded ops)
+    pub fn revision(&self) -> u64 {
+        self.changes.revision
+    }
+
+    /// Ops applied after `revision`, oldest first
+    ///
+    /// Returns `None` when some of those ops were already dropped from the log; the host
+    /// should then resync from the full text and continue from `revision()`.
+    pub fn changes_since(&self, revision: u64) -> Option<Vec<ChangeOp>> {
+        let oldest_kept = self.changes.log.front().map_or(self.changes.revision, |op| op.revision - 1);
+        if revision < oldest_kept {
+            return None;
+        }
+        Some(self.changes.log.iter().filter(|op| op.revision > revision).cloned().collect())
+    }
+
+    /// Append an op to the feed and publish it as `EditorEvent::TextChanged`
+    pub(crate) fn record_change(&mut self, start: Position, end: Position, inserted: String) {
+        if !self.changes.enabled {
+            return;
+        }
+        self.changes.revision += 1;
+        let op = ChangeOp {
+            id: OpId { site: self.changes.site, seq: self.changes.revision },
+            revision: self.changes.revision,
+            start,
+            end,
+            inserted,
+        };
+        self.changes.log.push_back(op.clone());
+        if self.changes.log.len() > MAX_CHANGE_LOG {
+            self.changes.log.pop_front();
+        }
+        self.events.emit(&EditorEvent::TextChanged(op));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split('\n').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn diff_finds_minimal_replacement() {
+        assert_eq!(diff_lines(&lines("abc"), &lines("abc")), None);
+        assert_eq!(diff_lines(&lines("abc"), &lines("abXc")), Some(((0, 2), (0, 2), "X".to_string())));
+        assert_eq!(diff_lines(&lines("one\ntwo"), &lines("one")), Some(((0, 3), (1, 3), String::new())));
+        assert_eq!(diff_lines(&lines("héllo wörld"), &lines("héllo\nwörld")), Some(((0, 5), (0, 6), "\n".to_string())));
+        // Repeated characters: the suffix must not overlap the prefix
+        assert_eq!(diff_lines(&lines("aa"), &lines("aaa")), Some(((0, 2), (0, 2), "a".to_string())));
+    }
+}
@@ -3,8 +3,12 @@
 //! This module contains copy, cut, and paste operations with system clipboard integration.
 
 use super::buffer::EditorBuffer;
+#[cfg(feature = "gtk")]
 use gtk4::gdk;
-use gtk4::prelude::DisplayExt;
+#[cfg(feature = "gtk")]
+use gtk4::glib;
+#[cfg(feature = "gtk")]
+use gtk4::prelude::{DisplayExt, ToValue};
 
 impl EditorBuffer {
     /// Return selected text or current line if no selection
@@ -54,59 +58,99 @@ impl EditorBuffer {
         self.lines.get(self.cursor.row).cloned().unwrap_or_default()
     }
 
-    /// Copy selected text to system clipboard (GTK4 GDK API)
-    pub fn copy_to_clipboard(&self) {
+    /// Place `text` on the clipboard, adding a text/html flavor when `copy_with_formatting` is on
+    #[cfg(feature = "gtk")]
+    fn set_clipboard_text(&self, clipboard: &gdk::Clipboard, text: &str) {
+        if !self.config.copy_with_formatting {
+            clipboard.set_text(text);
+            return;
+        }
+        let lines: Vec<String> = text.split('\n').map(|line| line.to_string()).collect();
+        let html = crate::export::html_fragment(&self.styled_lines(&lines));
+        let provider = gdk::ContentProvider::new_union(&[
+            gdk::ContentProvider::for_bytes("text/html", &glib::Bytes::from_owned(html.into_bytes())),
+            gdk::ContentProvider::for_value(&text.to_value()),
+        ]);
+        if let Err(e) = clipboard.set_content(Some(&provider)) {
+            eprintln!("[ERROR] Failed to set formatted clipboard content: {}", e);
+            clipboard.set_text(text);
+        }
+    }
+
+    /// Text to copy (see `copy`); with `copy_clears_selection` the selection is dropped
+    pub fn copy_text(&mut self) -> String {
         let text = self.copy();
-        if let Some(display) = gdk::Display::default() {
-            let clipboard = display.clipboard();
-            clipboard.set_text(&text);
-            println!("[DEBUG] Copied to clipboard: {:?}", text);
-        } else {
-            eprintln!("[ERROR] No display found for clipboard access");
+        if self.config.copy_clears_selection && self.selection.take().is_some() {
+            self.request_redraw();
         }
+        text
     }
 
-    /// Cut selected text to clipboard and delete it from buffer
-    pub fn cut_to_clipboard(&mut self) {
+    /// Remove and return the selected text, or the caret line without a selection
+    ///
+    /// Returns None (and changes nothing) without a selection when
+    /// `cut_line_without_selection` is off.
+    pub fn cut_text(&mut self) -> Option<String> {
+        let has_selection = self.selection.as_ref().is_some_and(|sel| sel.is_active());
+        if !has_selection && !self.config.cut_line_without_selection {
+            return None;
+        }
         let text = self.copy();
+        if has_selection {
+            self.delete_selection();
+        } else {
+            self.selection = None;
+            self.delete_line();
+        }
+        self.request_redraw();
+        Some(text)
+    }
+
+    /// Copy selected text to system clipboard (GTK4 GDK API)
+    #[cfg(feature = "gtk")]
+    pub fn copy_to_clipboard(&mut self) {
+        let text = self.copy_text();
         if let Some(display) = gdk::Display::default() {
             let clipboard = display.clipboard();
-            clipboard.set_text(&text);
-            
-            // Delete the selected text or current line
-            if self.selection.is_some() {
-                self.delete_selection();
-            } else {
-                self.delete_line();
-            }
-            
-            println!("[DEBUG] Cut to clipboard: {:?}", text);
+            self.set_clipboard_text(&clipboard, &text);
+            println!("[DEBUG] Copied to clipboard: {:?}", text);
         } else {
             eprintln!("[ERROR] No display found for clipboard access");
         }
     }
 
-    /// Request paste from system clipboard
-    /// Note: Due to async nature of GTK4 clipboard, actual implementation
-    /// should be handled at the widget level with proper async handling
-    pub fn paste_from_clipboard(&mut self) {
-        println!("[DEBUG] Paste from clipboard requested");
-        
-        // The async clipboard access should be implemented at the widget level
-        // For now, indicate that this needs to be handled elsewhere
-        println!("[DEBUG] Clipboard paste requires widget-level async handling");
+    /// Cut selected text (or the caret line, see `cut_text`) to the clipboard
+    #[cfg(feature = "gtk")]
+    pub fn cut_to_clipboard(&mut self) {
+        let Some(display) = gdk::Display::default() else {
+            eprintln!("[ERROR] No display found for clipboard access");
+            return;
+        };
+        if let Some(text) = self.cut_text() {
+            self.set_clipboard_text(&display.clipboard(), &text);
+            println!("[DEBUG] Cut to clipboard: {:?}", text);
+        }
     }
 
     /// Synchronous paste operation (requires clipboard text to be provided)
+    ///
+    /// Replaces the selection, or with `paste_replaces_selection` off inserts at the caret
+    /// and keeps the selected text.
     pub fn paste_text(&mut self, text: &str) {
-        if !text.is_empty() {
-            // Delete selection if any
-            if self.selection.is_some() {
-                self.delete_selection();
+        if !text.is_empty() && self.allows_edit() {
+            if !self.config.paste_replaces_selection {
+                self.selection = None;
+            }
+            // Carets on several rows take one clipboard line each
+            if self.paste_column(text) {
+                return;
+            }
+            // Verbatim as one undo step, or through the typing features without the paste guard
+            if self.config.paste_guard {
+                self.insert_pasted(text);
+            } else {
+                self.type_text(text);
             }
-            
-            // Insert the text at cursor
-            self.insert_text(text);
             println!("[DEBUG] Pasted text: {:?}", text);
         }
     }
@@ -126,3 +170,33 @@ impl EditorBuffer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use crate::corelogic::dispatcher::{CommandDispatcher, CommandParams};
+    use crate::corelogic::events::EditorEvent;
+    use crate::keybinds::EditorAction;
+
+    #[test]
+    fn paste_command_pastes_its_text_or_asks_for_the_clipboard() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec![String::new()];
+        let requested = Rc::new(Cell::new(0));
+        let sink = requested.clone();
+        buffer.events.connect(move |event| {
+            if let EditorEvent::PasteRequested = event {
+                sink.set(sink.get() + 1);
+            }
+        });
+        let mut dispatcher = CommandDispatcher::new();
+        dispatcher.execute(&mut buffer, EditorAction::PasteClipboard, CommandParams::Text("a\nb".to_string())).unwrap();
+        assert_eq!(buffer.lines, vec!["a", "b"]);
+        assert_eq!(requested.get(), 0);
+        dispatcher.execute(&mut buffer, EditorAction::PasteClipboard, CommandParams::None).unwrap();
+        assert_eq!(requested.get(), 1);
+        assert_eq!(buffer.lines, vec!["a", "b"]);
+    }
+}
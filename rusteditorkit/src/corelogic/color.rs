@@ -0,0 +1,307 @@
+//! Colors and the config palette
+//!
+//! Color settings are parsed once, when the config is deserialized, into a `ThemeColor`:
+//!
+//! - a literal: `"#rgb"`, `"#rgba"`, `"#rrggbb"`, `"#rrggbbaa"`, `"rgb(r, g, b)"`,
+//!   `"rgba(r, g, b, a)"` (channels 0-255, alpha 0.0-1.0) or a basic CSS name (`"white"`,
+//!   `"transparent"`, ...)
+//! - a palette reference: `"$accent"` is the `accent` entry of the config `palette`
+//! - light and dark variants: `(light: "#f8f8ff", dark: "#1e1e1e")`, picked by the
+//!   config `color_scheme`
+//!
+//! A malformed literal fails the config load; references to missing palette entries
+//! and reference cycles are reported by `EditorConfig::validate_colors`, which the
+//! config loaders run. Drawing code resolves settings to a plain RGBA `Color` with
+//! `EditorConfig::color`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use super::buffer::EditorBuffer;
+
+/// Red, green, blue and alpha, each 0.0-1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+/// Basic CSS color names
+const NAMED_COLORS: [(&str, (u8, u8, u8)); 17] = [
+    ("black", (0, 0, 0)), ("silver", (192, 192, 192)), ("gray", (128, 128, 128)),
+    ("grey", (128, 128, 128)), ("white", (255, 255, 255)), ("maroon", (128, 0, 0)),
+    ("red", (255, 0, 0)), ("purple", (128, 0, 128)), ("fuchsia", (255, 0, 255)),
+    ("green", (0, 128, 0)), ("lime", (0, 255, 0)), ("olive", (128, 128, 0)),
+    ("yellow", (255, 255, 0)), ("navy", (0, 0, 128)), ("blue", (0, 0, 255)),
+    ("teal", (0, 128, 128)), ("aqua", (0, 255, 255)),
+];
+
+/// Palette references deeper than this are treated as a cycle
+const MAX_PALETTE_DEPTH: usize = 16;
+
+impl Color {
+    pub const BLACK: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(r: f64, g: f64, b: f64, a: f64) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Opaque color from `0xrrggbb`
+    pub fn hex(rgb: u32) -> Self {
+        Self::from_rgb8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Opaque color from 8-bit channels
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0, 1.0)
+    }
+
+    /// The channels as a tuple, as drawing backends take them
+    pub fn rgba(self) -> (f64, f64, f64, f64) {
+        (self.r, self.g, self.b, self.a)
+    }
+
+    /// Same color with alpha `a`
+    pub fn with_alpha(self, a: f64) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Relative luminance (Rec. 709 weights), ignoring alpha
+    pub fn luminance(self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let color = text.trim();
+        if let Some(hex) = color.strip_prefix('#') {
+            return parse_hex(hex)
+                .ok_or_else(|| format!("invalid color '{}': expected #rgb, #rgba, #rrggbb or #rrggbbaa", text));
+        }
+        if let Some(args) = function_args(color, "rgba") {
+            return parse_channels(args, true).map_err(|e| format!("invalid color '{}': {}", text, e));
+        }
+        if let Some(args) = function_args(color, "rgb") {
+            return parse_channels(args, false).map_err(|e| format!("invalid color '{}': {}", text, e));
+        }
+        if color.eq_ignore_ascii_case("transparent") {
+            return Ok(Color::TRANSPARENT);
+        }
+        NAMED_COLORS.iter()
+            .find(|(name, _)| color.eq_ignore_ascii_case(name))
+            .map(|&(_, (r, g, b))| Color::from_rgb8(r, g, b))
+            .ok_or_else(|| format!("unknown color '{}'", text))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let byte = |channel: f64| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        write!(f, "#{:02x}{:02x}{:02x}", byte(self.r), byte(self.g), byte(self.b))?;
+        if self.a < 1.0 {
+            write!(f, "{:02x}", byte(self.a))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channels: Vec<u8> = match hex.len() {
+        3 | 4 => hex.chars().map(|c| c.to_digit(16).unwrap() as u8 * 17).collect(),
+        6 | 8 => (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect(),
+        _ => return None,
+    };
+    let alpha = channels.get(3).map_or(1.0, |&a| a as f64 / 255.0);
+    Some(Color::from_rgb8(channels[0], channels[1], channels[2]).with_alpha(alpha))
+}
+
+/// The text between the parentheses of `name(...)`
+fn function_args<'a>(color: &'a str, name: &str) -> Option<&'a str> {
+    let rest = color.get(..name.len()).filter(|prefix| prefix.eq_ignore_ascii_case(name)).map(|_| &color[name.len()..])?;
+    rest.trim_start().strip_prefix('(')?.strip_suffix(')')
+}
+
+fn parse_channels(args: &str, with_alpha: bool) -> Result<Color, String> {
+    let values = args.split(',')
+        .map(|arg| arg.trim().parse::<f64>().map_err(|_| format!("'{}' is not a number", arg.trim())))
+        .collect::<Result<Vec<f64>, String>>()?;
+    let expected = if with_alpha { 4 } else { 3 };
+    if values.len() != expected {
+        return Err(format!("expected {} values, found {}", expected, values.len()));
+    }
+    if let Some(channel) = values[..3].iter().find(|v| !(0.0..=255.0).contains(*v)) {
+        return Err(format!("channel {} is outside 0-255", channel));
+    }
+    let alpha = values.get(3).copied().unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(format!("alpha {} is outside 0.0-1.0", alpha));
+    }
+    Ok(Color::new(values[0] / 255.0, values[1] / 255.0, values[2] / 255.0, alpha))
+}
+
+/// Light or dark variant of the `(light: .., dark: ..)` color settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// Named colors that color settings refer to as `$name`
+pub type Palette = HashMap<String, ThemeColor>;
+
+/// A color setting as written in the config
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThemeColor {
+    /// A literal color
+    Fixed(Color),
+    /// An entry of the palette
+    Palette(String),
+    /// One color per color scheme
+    Scheme { light: Box<ThemeColor>, dark: Box<ThemeColor> },
+}
+
+impl ThemeColor {
+    /// Variant for `scheme` with palette references followed
+    pub fn resolve(&self, palette: &Palette, scheme: ColorScheme) -> Result<Color, String> {
+        self.resolve_at(palette, scheme, 0)
+    }
+
+    fn resolve_at(&self, palette: &Palette, scheme: ColorScheme, depth: usize) -> Result<Color, String> {
+        match self {
+            ThemeColor::Fixed(color) => Ok(*color),
+            ThemeColor::Palette(name) if depth >= MAX_PALETTE_DEPTH => Err(format!("palette color '${}' refers back to itself", name)),
+            ThemeColor::Palette(name) => palette.get(name)
+                .ok_or_else(|| format!("no palette color '${}'", name))?
+                .resolve_at(palette, scheme, depth + 1),
+            ThemeColor::Scheme { light, dark } => match scheme {
+                ColorScheme::Light => light.resolve_at(palette, scheme, depth),
+                ColorScheme::Dark => dark.resolve_at(palette, scheme, depth),
+            },
+        }
+    }
+
+    /// Check that both variants resolve
+    pub fn validate(&self, palette: &Palette) -> Result<(), String> {
+        self.resolve(palette, ColorScheme::Light)?;
+        self.resolve(palette, ColorScheme::Dark).map(|_| ())
+    }
+}
+
+impl From<Color> for ThemeColor {
+    fn from(color: Color) -> Self {
+        ThemeColor::Fixed(color)
+    }
+}
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        match text.trim().strip_prefix('$') {
+            Some(name) if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') => {
+                Ok(ThemeColor::Palette(name.to_string()))
+            }
+            Some(_) => Err(format!("invalid palette reference '{}'", text)),
+            None => text.parse().map(ThemeColor::Fixed),
+        }
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeColor::Fixed(color) => write!(f, "{}", color),
+            ThemeColor::Palette(name) => write!(f, "${}", name),
+            ThemeColor::Scheme { light, dark } => write!(f, "(light: \"{}\", dark: \"{}\")", light, dark),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Variants {
+            light: ThemeColor,
+            dark: ThemeColor,
+        }
+
+        struct ThemeColorVisitor;
+
+        impl<'de> Visitor<'de> for ThemeColorVisitor {
+            type Value = ThemeColor;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a color string or (light: .., dark: ..)")
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<ThemeColor, E> {
+                text.parse().map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<ThemeColor, A::Error> {
+                let variants = Variants::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(ThemeColor::Scheme { light: Box::new(variants.light), dark: Box::new(variants.dark) })
+            }
+        }
+
+        deserializer.deserialize_any(ThemeColorVisitor)
+    }
+}
+
+impl EditorBuffer {
+    /// Switch the light/dark variant of the color settings and redraw
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
+        self.config.color_scheme = scheme;
+        self.request_redraw();
+    }
+
+    /// Variant of the color settings in use
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.config.color_scheme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_literals_palette_references_and_variants() {
+        assert_eq!("#ff000080".parse::<Color>().unwrap(), Color::from_rgb8(255, 0, 0).with_alpha(128.0 / 255.0));
+        assert_eq!("#0f0".parse::<Color>().unwrap(), Color::from_rgb8(0, 255, 0));
+        assert_eq!("rgba(0, 0, 255, 0.5)".parse::<Color>().unwrap(), Color::from_rgb8(0, 0, 255).with_alpha(0.5));
+        assert_eq!("White".parse::<Color>().unwrap(), Color::WHITE);
+        assert_eq!(Color::from_rgb8(0x90, 0xca, 0xf9).with_alpha(0.4).to_string(), "#90caf966");
+        for bad in ["#12345", "#ggg", "rgb(1, 2)", "rgba(0, 0, 300, 1)", "blurple", "$"] {
+            assert!(bad.parse::<ThemeColor>().is_err(), "{} parsed", bad);
+        }
+
+        let palette: Palette = ron::from_str(r##"{
+            "accent": "#1e88e5",
+            "surface": (light: "#f8f8ff", dark: "#1e1e1e"),
+            "loop": "$loop",
+        }"##).unwrap();
+        let surface: ThemeColor = "$surface".parse().unwrap();
+        assert_eq!(surface.resolve(&palette, ColorScheme::Light).unwrap(), Color::from_rgb8(0xf8, 0xf8, 0xff));
+        assert_eq!(surface.resolve(&palette, ColorScheme::Dark).unwrap(), Color::from_rgb8(0x1e, 0x1e, 0x1e));
+        let tint: ThemeColor = ron::from_str(r#"(light: "$accent", dark: "rgb(255, 255, 255)")"#).unwrap();
+        assert_eq!(tint.resolve(&palette, ColorScheme::Light).unwrap(), Color::from_rgb8(0x1e, 0x88, 0xe5));
+        assert!("$missing".parse::<ThemeColor>().unwrap().validate(&palette).is_err());
+        assert!("$loop".parse::<ThemeColor>().unwrap().validate(&palette).is_err());
+        assert!(ron::from_str::<ThemeColor>(r##""#12""##).is_err());
+    }
+}
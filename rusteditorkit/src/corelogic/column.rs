@@ -0,0 +1,181 @@
+//! Column editing for EditorBuffer
+//!
+//! A column block is one caret or single-row selection per row: the main cursor or
+//! selection together with `multi_cursors` and `multi_selections`. With carets on two or
+//! more rows, paste puts the clipboard lines down the rows, and the fill commands
+//! write the same text (or a running number) into every row at the block's left edge,
+//! replacing what each row has selected. Each command is a single undo step.
+
+use super::buffer::EditorBuffer;
+
+/// Per-row (row, start col, end col) ranges, sorted by row with one range per row
+///
+/// Multi-row selections are not part of a column block and are left out.
+pub fn column_ranges(ranges: impl IntoIterator<Item = ((usize, usize), (usize, usize))>) -> Vec<(usize, usize, usize)> {
+    let mut rows: Vec<(usize, usize, usize)> = ranges.into_iter()
+        .filter(|(start, end)| start.0 == end.0)
+        .map(|(start, end)| (start.0, start.1.min(end.1), start.1.max(end.1)))
+        .collect();
+    rows.sort();
+    rows.dedup_by_key(|&mut (row, _, _)| row);
+    rows
+}
+
+/// Numbers `start`, `start + 1`, ... for `count` rows, right-aligned to the widest one
+pub fn sequence_texts(start: i64, count: usize) -> Vec<String> {
+    let numbers: Vec<String> = (0..count as i64).map(|i| (start + i).to_string()).collect();
+    let width = numbers.iter().map(String::len).max().unwrap_or(0);
+    numbers.into_iter().map(|n| format!("{:>width$}", n)).collect()
+}
+
+impl EditorBuffer {
+    /// The column block: one (row, start col, end col) per row holding a caret or selection
+    pub fn column_block(&self) -> Vec<(usize, usize, usize)> {
+        let main = match self.selection.as_ref().filter(|sel| sel.is_active()) {
+            Some(sel) => sel.normalized(),
+            None => ((self.cursor.row, self.cursor.col), (self.cursor.row, self.cursor.col)),
+        };
+        let selections = self.multi_selections.iter()
+            .filter_map(|&(start, end)| start.zip(end));
+        let cursors = self.multi_cursors.iter().map(|&pos| (pos, pos));
+        column_ranges(std::iter::once(main).chain(selections).chain(cursors))
+    }
+
+    /// True if carets or selections on two or more rows form a column block
+    pub fn in_column_mode(&self) -> bool {
+        self.column_block().len() > 1
+    }
+
+    /// Paste `text` down the column block, one line per row
+    ///
+    /// A single line goes on every row, as with [`fill_column`](Self::fill_column).
+    /// Otherwise the lines go in as given: rows left over when the clipboard is shorter
+    /// keep their text, and lines left over when it is longer extend the block down the
+    /// following rows at the column of its last row (padded with spaces, adding rows at
+    /// the end of the text as needed). Returns false when there is no column block.
+    pub fn paste_column(&mut self, text: &str) -> bool {
+        let lines: Vec<&str> = text.lines().collect();
+        if let [line] = lines[..] {
+            return self.fill_column(line);
+        }
+        let mut block = self.column_block();
+        if lines.is_empty() || !self.is_column_block(&block) {
+            return false;
+        }
+        self.push_undo();
+        let (last_row, col, _) = block[block.len() - 1];
+        let extra = lines.len().saturating_sub(block.len());
+        let missing = (last_row + extra + 1).saturating_sub(self.lines.len());
+        if missing > 0 {
+            let len = self.lines.len();
+            self.replace_rows(len..len, vec![String::new(); missing]);
+        }
+        let mut texts: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        for row in last_row + 1..=last_row + extra {
+            let at = col.min(self.lines[row].chars().count());
+            texts[block.len()].insert_str(0, &" ".repeat(col - at));
+            block.push((row, at, at));
+        }
+        self.replace_column_ranges(&block, |i| texts.get(i).cloned());
+        true
+    }
+
+    /// Insert `text` on every row of the column block at its left edge
+    pub fn fill_column(&mut self, text: &str) -> bool {
+        self.replace_column_block(|_| Some(text.to_string()))
+    }
+
+    /// Number the rows of the column block from `start` down (right-aligned)
+    pub fn fill_column_sequence(&mut self, start: i64) -> bool {
+        let count = self.column_block().len();
+        let texts = sequence_texts(start, count);
+        self.replace_column_block(|i| Some(texts[i].clone()))
+    }
+
+    /// True if `block` spans two or more rows of the text
+    fn is_column_block(&self, block: &[(usize, usize, usize)]) -> bool {
+        block.len() >= 2 && block.iter().all(|&(row, _, _)| row < self.lines.len())
+    }
+
+    /// Replace each row's range in the column block with `text_for(index)`
+    fn replace_column_block(&mut self, text_for: impl Fn(usize) -> Option<String>) -> bool {
+        let block = self.column_block();
+        if !self.is_column_block(&block) {
+            return false;
+        }
+        self.push_undo();
+        self.replace_column_ranges(&block, text_for);
+        true
+    }
+
+    /// Replace each of `block`'s ranges with `text_for(index)`, leaving the ranges it
+    /// gives no text for as they are
+    ///
+    /// Carets end up after the inserted text (at the end of an untouched range): the main
+    /// cursor on its own row, the other rows as `multi_cursors`.
+    fn replace_column_ranges(&mut self, block: &[(usize, usize, usize)], text_for: impl Fn(usize) -> Option<String>) {
+        let main_row = self.selection.as_ref().filter(|sel| sel.is_active())
+            .map_or(self.cursor.row, |sel| sel.normalized().0.0);
+        let mut carets = Vec::with_capacity(block.len());
+        for (i, &(row, start, end)) in block.iter().enumerate() {
+            match text_for(i) {
+                Some(text) => {
+                    self.replace_range((row, start), (row, end), &text);
+                    carets.push((row, start + text.chars().count()));
+                }
+                None => carets.push((row, end)),
+            }
+        }
+        self.selection = None;
+        self.multi_selections.clear();
+        let main = carets.iter().position(|&(row, _)| row == main_row).unwrap_or(0);
+        (self.cursor.row, self.cursor.col) = carets.remove(main);
+        self.multi_cursors = carets;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_ranges_keep_one_single_row_range_per_row() {
+        let ranges = vec![((3, 4), (3, 2)), ((1, 5), (1, 5)), ((2, 0), (4, 1)), ((1, 7), (1, 7))];
+        assert_eq!(column_ranges(ranges), vec![(1, 5, 5), (3, 2, 4)]);
+    }
+
+    #[test]
+    fn sequence_is_right_aligned() {
+        assert_eq!(sequence_texts(8, 3), vec![" 8", " 9", "10"]);
+        assert_eq!(sequence_texts(-1, 2), vec!["-1", " 0"]);
+    }
+
+    #[test]
+    fn paste_puts_each_clipboard_line_on_one_row() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["a".into(), "b".into(), "c".into()];
+        buffer.cursor = crate::corelogic::EditorCursor::new(0, 1);
+        buffer.multi_cursors = vec![(1, 1), (2, 1)];
+        // Shorter than the block: the last row keeps its text
+        assert!(buffer.paste_column("x\ny"));
+        assert_eq!(buffer.lines, ["ax", "by", "c"]);
+        assert_eq!(buffer.multi_cursors, vec![(1, 2), (2, 1)]);
+        // Longer than the block: the block grows down and the text gets a new row
+        buffer.cursor = crate::corelogic::EditorCursor::new(0, 1);
+        buffer.multi_cursors = vec![(1, 1)];
+        assert!(buffer.paste_column("1\n2\n3\n4"));
+        assert_eq!(buffer.lines, ["a1x", "b2y", "c3", " 4"]);
+        assert_eq!(buffer.multi_cursors, vec![(1, 2), (2, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn paste_repeats_a_single_line_on_every_row() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["a".into(), "b".into(), "c".into()];
+        buffer.cursor = crate::corelogic::EditorCursor::new(0, 1);
+        buffer.multi_cursors = vec![(1, 1), (2, 1)];
+        assert!(buffer.paste_column("x\n"));
+        assert_eq!(buffer.lines, ["ax", "bx", "cx"]);
+        assert_eq!(buffer.multi_cursors, vec![(1, 2), (2, 2)]);
+    }
+}
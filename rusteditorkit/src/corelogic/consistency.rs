@@ -0,0 +1,111 @@
+//! State consistency checks for EditorBuffer
+//!
+//! `validate` lists every broken invariant instead of stopping at the first one. The
+//! dispatcher runs it after each command in debug builds, or in release builds when
+//! `consistency_checks` is enabled in the config, and logs violations together with the
+//! command that caused them.
+
+use super::buffer::EditorBuffer;
+use super::undo::{BufferState, MAX_UNDO_STACK_SIZE};
+
+/// Number of chars on `row`, or `None` if the row does not exist
+fn row_len(lines: &[String], row: usize) -> Option<usize> {
+    lines.get(row).map(|line| line.chars().count())
+}
+
+/// Check that `(row, col)` lies inside `lines`; `what` names the position in the message
+fn check_position(lines: &[String], (row, col): (usize, usize), what: &str, out: &mut Vec<String>) {
+    match row_len(lines, row) {
+        None => out.push(format!("{} row {} out of bounds ({} lines)", what, row, lines.len())),
+        Some(len) if col > len => out.push(format!("{} col {} past end of row {} ({} chars)", what, col, row, len)),
+        _ => {}
+    }
+}
+
+fn check_undo_state(state: &BufferState, what: &str, out: &mut Vec<String>) {
+    if state.lines.is_empty() {
+        out.push(format!("{} has no lines", what));
+        return;
+    }
+    check_position(&state.lines, (state.cursor.row, state.cursor.col), &format!("{} cursor", what), out);
+    if let Some(sel) = &state.selection {
+        let (start, end) = sel.normalized();
+        check_position(&state.lines, start, &format!("{} selection start", what), out);
+        check_position(&state.lines, end, &format!("{} selection end", what), out);
+    }
+}
+
+impl EditorBuffer {
+    /// Check the buffer invariants, returning one message per violation (empty when consistent)
+    pub fn validate(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        let lines = &self.lines;
+        if lines.is_empty() {
+            out.push("buffer has no lines".to_string());
+            return out;
+        }
+
+        check_position(lines, (self.cursor.row, self.cursor.col), "cursor", &mut out);
+        if self.scroll_offset >= lines.len() {
+            out.push(format!("scroll offset {} past last row {}", self.scroll_offset, lines.len() - 1));
+        }
+        if !(0.0..1.0).contains(&self.scroll_fraction) {
+            out.push(format!("scroll fraction {} outside 0..1", self.scroll_fraction));
+        }
+
+        // Selection endpoints, in document order
+        if let Some(sel) = &self.selection {
+            let (start, end) = sel.normalized();
+            check_position(lines, start, "selection start", &mut out);
+            check_position(lines, end, "selection end", &mut out);
+        }
+        if let Some((start, end)) = self.focus_range {
+            if start > end {
+                out.push(format!("focus range {:?}..{:?} is reversed", start, end));
+            }
+            check_position(lines, start, "focus range start", &mut out);
+        }
+
+        // Anchors: extra cursors and bookmarks
+        for (i, &pos) in self.multi_cursors.iter().enumerate() {
+            check_position(lines, pos, &format!("multi-cursor {}", i), &mut out);
+            if self.multi_cursors[..i].contains(&pos) {
+                out.push(format!("multi-cursor {} duplicates {:?}", i, pos));
+            }
+        }
+        if let Some(&row) = self.bookmarks.iter().next_back().filter(|&&row| row >= lines.len()) {
+            out.push(format!("bookmark row {} out of bounds ({} lines)", row, lines.len()));
+        }
+
+        // Undo/redo history
+        if self.undo_stack.len() > MAX_UNDO_STACK_SIZE {
+            out.push(format!("undo stack holds {} states (max {})", self.undo_stack.len(), MAX_UNDO_STACK_SIZE));
+        }
+        for (i, state) in self.undo_stack.iter().enumerate() {
+            check_undo_state(state, &format!("undo state {}", i), &mut out);
+        }
+        for (i, state) in self.redo_stack.iter().enumerate() {
+            check_undo_state(state, &format!("redo state {}", i), &mut out);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_position_reports_rows_and_char_columns() {
+        let lines = vec!["héllo".to_string()];
+        let mut out = Vec::new();
+        check_position(&lines, (0, 5), "cursor", &mut out);
+        assert!(out.is_empty());
+        check_position(&lines, (0, 6), "cursor", &mut out);
+        check_position(&lines, (1, 0), "cursor", &mut out);
+        assert_eq!(out, vec![
+            "cursor col 6 past end of row 0 (5 chars)".to_string(),
+            "cursor row 1 out of bounds (1 lines)".to_string(),
+        ]);
+    }
+}
@@ -95,11 +95,12 @@ impl CursorState {
 // This module contains all cursor movement, selection, and multi-cursor functionality.
 /// Modular cursor configuration for the editor
 use serde::Deserialize;
+use super::color::{Color, ThemeColor};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct CursorConfig {
     pub cursor_type: String,            // "bar", "block", "underline", or custom shape
-    pub cursor_color: String,           // Hex or RGBA color
+    pub cursor_color: ThemeColor,       // Color, palette reference or light/dark pair
     pub cursor_blink: bool,             // Enables blinking behavior
     pub cursor_blink_rate: u64,         // Milliseconds for blink interval
     pub cursor_thickness: f64,          // Line width for "bar" or underline type
@@ -115,7 +116,7 @@ impl Default for CursorConfig {
     fn default() -> Self {
         Self {
             cursor_type: "bar".to_string(),
-            cursor_color: "#000000".to_string(),
+            cursor_color: Color::hex(0x000000).into(),
             cursor_blink: true,
             cursor_blink_rate: 500,
             cursor_thickness: 2.0,
@@ -129,9 +130,21 @@ impl Default for CursorConfig {
     }
 }
 
-use super::buffer::EditorBuffer;
+use super::buffer::{EditorBuffer, EditorCursor};
 use crate::corelogic::Selection;
 
+/// Column vertical movement aims for, so a caret passing through short lines returns to
+/// its column on longer ones
+///
+/// It only holds while the caret is still at `at`, where the last vertical move left it;
+/// any other movement makes it stale without having to reset it. Edits clear it in
+/// `push_undo`, and undo states keep it, so undoing an edit made after Up/Down restores it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreferredColumn {
+    pub at: EditorCursor,
+    pub col: usize,
+}
+
 impl EditorBuffer {
     /// Update cursor state from the latest config (call after config changes)
     pub fn update_cursor_state_from_config(&mut self) {
@@ -211,8 +224,7 @@ impl EditorBuffer {
     /// Internal move up without clearing selection
     fn move_up_internal(&mut self) {
         if self.cursor.row > 0 {
-            self.cursor.row -= 1;
-            self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].chars().count());
+            self.move_to_row(self.cursor.row - 1);
         }
     }
 
@@ -226,11 +238,26 @@ impl EditorBuffer {
     /// Internal move down without clearing selection
     fn move_down_internal(&mut self) {
         if self.cursor.row + 1 < self.lines.len() {
-            self.cursor.row += 1;
-            self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].chars().count());
+            self.move_to_row(self.cursor.row + 1);
+        }
+    }
+
+    /// Column the next vertical move aims for
+    pub fn preferred_column(&self) -> usize {
+        match self.preferred_col {
+            Some(preferred) if preferred.at == self.cursor => preferred.col,
+            _ => self.cursor.col,
         }
     }
 
+    /// Move the caret to `row`, at the preferred column or the end of a shorter line
+    pub fn move_to_row(&mut self, row: usize) {
+        let col = self.preferred_column();
+        self.cursor.row = row.min(self.lines.len().saturating_sub(1));
+        self.cursor.col = col.min(self.lines.get(self.cursor.row).map_or(0, |line| line.chars().count()));
+        self.preferred_col = Some(PreferredColumn { at: self.cursor, col });
+    }
+
     /// Move cursor to start of line
     pub fn move_home(&mut self) {
         // Clear selection on movement (non-Shift movement)
@@ -250,12 +277,7 @@ impl EditorBuffer {
         // Clear selection on movement (non-Shift movement)
         self.clear_selection();
         
-        if self.cursor.row > lines_per_page {
-            self.cursor.row -= lines_per_page;
-        } else {
-            self.cursor.row = 0;
-        }
-        self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].chars().count());
+        self.move_to_row(self.cursor.row.saturating_sub(lines_per_page));
     }
 
     /// Move cursor down by one visible page (PgDn)
@@ -263,13 +285,7 @@ impl EditorBuffer {
         // Clear selection on movement (non-Shift movement)
         self.clear_selection();
         
-        let max_row = self.lines.len().saturating_sub(1);
-        if self.cursor.row + lines_per_page < max_row {
-            self.cursor.row += lines_per_page;
-        } else {
-            self.cursor.row = max_row;
-        }
-        self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].chars().count());
+        self.move_to_row(self.cursor.row + lines_per_page);
     }
 
     /// Start or extend selection to the left
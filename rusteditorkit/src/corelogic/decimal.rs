@@ -0,0 +1,170 @@
+//! Decimal tab alignment for EditorBuffer
+//!
+//! In decimal-aligned rows, tab-separated cells are positioned so that the decimal points
+//! of each column line up (cells without a point align on their end). Alignment applies to
+//! the whole buffer when `font_decimal_alignment` (or the buffer override) is set, and to
+//! explicitly registered row regions otherwise. Regions are plain row ranges and are not
+//! adjusted by edits.
+//!
+//! The renderer measures the stops once per text revision and font (`refresh_decimal_tabs`)
+//! and every user of a row's geometry reads them back through `tab_stops`: the text layer,
+//! the caret and selection layouts and pointer hit-testing.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use super::buffer::EditorBuffer;
+
+/// Decimal separator cells are aligned on
+pub const DECIMAL_POINT: char = '.';
+
+/// Part of `cell` left of the decimal point (the whole cell if it has none)
+fn integer_part(cell: &str) -> &str {
+    cell.split(DECIMAL_POINT).next().unwrap_or(cell)
+}
+
+/// Left-aligned tab stop positions for a block of consecutive aligned rows
+///
+/// Returns one stop per tab in each row. `measure` gives the advance width of a string and
+/// `gap` is the minimum space between the end of a cell and the start of the next one.
+pub fn decimal_tab_stops(rows: &[&str], measure: impl Fn(&str) -> f64, gap: f64) -> Vec<Vec<f64>> {
+    let cells: Vec<Vec<&str>> = rows.iter().map(|row| row.split('\t').collect()).collect();
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    // Right edge of the previous cell in every row
+    let mut right: Vec<f64> = cells.iter().map(|row| measure(row[0])).collect();
+    let mut stops = vec![Vec::new(); rows.len()];
+    for column in 1..columns {
+        let decimal_x = cells.iter().enumerate()
+            .filter_map(|(i, row)| row.get(column).map(|cell| right[i] + gap + measure(integer_part(cell))))
+            .fold(0.0, f64::max);
+        for (i, row) in cells.iter().enumerate() {
+            if let Some(cell) = row.get(column) {
+                let start = decimal_x - measure(integer_part(cell));
+                stops[i].push(start);
+                right[i] = start + measure(cell);
+            }
+        }
+    }
+    stops
+}
+
+/// Decimal tab stops last measured for the buffer
+#[derive(Debug, Clone, Default)]
+pub struct DecimalTabCache {
+    revision: u64,
+    aligned: bool,
+    /// Font and spacing the stops were measured with
+    measured_with: String,
+    stops: BTreeMap<usize, Vec<f64>>,
+}
+
+impl EditorBuffer {
+    /// True if decimal alignment applies to `row`
+    pub fn is_decimal_aligned(&self, row: usize) -> bool {
+        self.decimal_alignment_override.unwrap_or(self.config.font.font_decimal_alignment())
+            || self.decimal_regions.iter().any(|region| region.contains(&row))
+    }
+
+    /// Enable or disable decimal alignment for the whole buffer (`None` follows the font config)
+    pub fn set_decimal_alignment(&mut self, enabled: Option<bool>) {
+        self.decimal_alignment_override = enabled;
+        self.decimal_tabs.get_mut().take();
+        self.request_redraw();
+    }
+
+    /// Align the rows in `rows` on the decimal point regardless of the buffer setting
+    pub fn add_decimal_region(&mut self, rows: Range<usize>) {
+        self.decimal_regions.push(rows);
+        self.decimal_tabs.get_mut().take();
+        self.request_redraw();
+    }
+
+    /// Remove all decimal-aligned regions
+    pub fn clear_decimal_regions(&mut self) {
+        self.decimal_regions.clear();
+        self.decimal_tabs.get_mut().take();
+        self.request_redraw();
+    }
+
+    /// Tab stops for every decimal-aligned row containing tabs, keyed by row
+    ///
+    /// Consecutive such rows form one block whose columns align together.
+    pub fn decimal_tab_layout(&self, measure: impl Fn(&str) -> f64, gap: f64) -> BTreeMap<usize, Vec<f64>> {
+        let mut layout = BTreeMap::new();
+        let mut row = 0;
+        while row < self.lines.len() {
+            let start = row;
+            while row < self.lines.len() && self.is_decimal_aligned(row) && self.lines[row].contains('\t') {
+                row += 1;
+            }
+            if row == start {
+                row += 1;
+                continue;
+            }
+            let block: Vec<&str> = self.lines[start..row].iter().map(String::as_str).collect();
+            for (offset, stops) in decimal_tab_stops(&block, &measure, gap).into_iter().enumerate() {
+                layout.insert(start + offset, stops);
+            }
+        }
+        layout
+    }
+
+    /// True unless the stops were measured for the current text and alignment setting
+    /// with `measured_with` (a description of the font and spacing measured with)
+    pub fn decimal_tabs_stale(&self, measured_with: &str) -> bool {
+        let aligned = self.decimal_alignment_override.unwrap_or(self.config.font.font_decimal_alignment());
+        !self.decimal_tabs.borrow().as_ref().is_some_and(|cache| {
+            cache.revision == self.text_revision && cache.aligned == aligned && cache.measured_with == measured_with
+        })
+    }
+
+    /// Measure the decimal tab stops with `measure` and keep them for `tab_stops`
+    pub fn refresh_decimal_tabs(&self, measured_with: &str, measure: impl Fn(&str) -> f64, gap: f64) {
+        let stops = self.decimal_tab_layout(measure, gap);
+        self.decimal_tabs.replace(Some(DecimalTabCache {
+            revision: self.text_revision,
+            aligned: self.decimal_alignment_override.unwrap_or(self.config.font.font_decimal_alignment()),
+            measured_with: measured_with.to_string(),
+            stops,
+        }));
+    }
+
+    /// Tab stops of `row` in pixels from the start of its text, as last measured by
+    /// `refresh_decimal_tabs` (empty: Pango's default stops)
+    pub fn tab_stops(&self, row: usize) -> Vec<f64> {
+        self.decimal_tabs.borrow().as_ref()
+            .and_then(|cache| cache.stops.get(&row).cloned())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_line_up_across_rows() {
+        let rows = ["Tea\t3.5\tx", "Coffee\t12.25\ty", "Cake\t100\tz"];
+        let stops = decimal_tab_stops(&rows, |s| s.chars().count() as f64, 1.0);
+        // Integer parts "3", "12", "100" end at the same x
+        let decimal_x: Vec<f64> = stops.iter().zip(["3", "12", "100"]).map(|(s, int)| s[0] + int.len() as f64).collect();
+        assert_eq!(decimal_x, vec![9.0, 9.0, 9.0]);
+        // The next column starts after the widest cell of the previous one
+        assert_eq!(stops[1][1], 7.0 + 5.0 + 1.0);
+    }
+
+    #[test]
+    fn stops_are_measured_again_after_edits() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["a\t1.5".into(), "bb\t10.25".into()];
+        buffer.set_decimal_alignment(Some(true));
+        let measure = |s: &str| s.chars().count() as f64;
+        assert!(buffer.decimal_tabs_stale("mono"));
+        buffer.refresh_decimal_tabs("mono", measure, 1.0);
+        assert!(!buffer.decimal_tabs_stale("mono"));
+        assert!(buffer.decimal_tabs_stale("serif"));
+        assert_eq!(buffer.tab_stops(0), [4.0]);
+        assert_eq!(buffer.tab_stops(1), [3.0]);
+        buffer.replace_rows(1..2, vec!["b".into()]);
+        assert!(buffer.decimal_tabs_stale("mono"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Bidirectional text base direction for EditorBuffer
+//!
+//! Every paragraph (block of non-blank rows) is laid out left-to-right or right-to-left.
+//! A paragraph override wins, then an explicit buffer direction; with Auto the first
+//! strong character of the paragraph decides. Paragraph overrides are keyed by the
+//! paragraph's first row and are not adjusted by edits.
+//!
+//! The resolved direction of every line is kept until the text, the buffer direction or
+//! a paragraph override changes, so per-row lookups while drawing or moving the caret
+//! cost nothing.
+//!
+//! The view itself is mirrored (gutter on the right, margins swapped) when the widget's
+//! text direction or the buffer direction is RTL. Arrow keys move visually in RTL rows,
+//! and Home/End go to the line edge next to the gutter first.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use serde::Deserialize;
+use super::buffer::EditorBuffer;
+use crate::keybinds::EditorAction;
+
+/// Base direction setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum TextDirection {
+    /// Follow the first strong character of each paragraph
+    #[default]
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    /// Next setting in the SetTextDirection cycle: Auto, Ltr, Rtl
+    pub fn next(self) -> Self {
+        match self {
+            TextDirection::Auto => TextDirection::Ltr,
+            TextDirection::Ltr => TextDirection::Rtl,
+            TextDirection::Rtl => TextDirection::Auto,
+        }
+    }
+}
+
+/// True for characters of right-to-left scripts (Hebrew, Arabic, Syriac, Thaana, N'Ko, ...)
+fn is_strong_rtl(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF)
+}
+
+/// Direction of the first strong character in `lines` (`None` if there is none)
+pub fn detect_direction<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<TextDirection> {
+    lines.into_iter()
+        .flat_map(str::chars)
+        .find_map(|c| {
+            if is_strong_rtl(c) {
+                Some(TextDirection::Rtl)
+            } else if c.is_alphabetic() {
+                Some(TextDirection::Ltr)
+            } else {
+                None
+            }
+        })
+}
+
+/// Right-to-left flags of every line, with what they were resolved from
+#[derive(Debug, Clone)]
+pub struct DirectionCache {
+    revision: u64,
+    direction: TextDirection,
+    log: bool,
+    overrides: BTreeMap<usize, TextDirection>,
+    rows: Rc<[bool]>,
+}
+
+impl EditorBuffer {
+    /// Buffer-wide base direction: the buffer override, else the config
+    pub fn text_direction(&self) -> TextDirection {
+        self.text_direction_override.unwrap_or(self.config.text_direction)
+    }
+
+    /// Set (or clear) the buffer-wide base direction
+    pub fn set_text_direction(&mut self, direction: Option<TextDirection>) {
+        self.text_direction_override = direction;
+        self.request_redraw();
+    }
+
+    /// Direction set explicitly on the paragraph containing `row` (Auto if none)
+    pub fn paragraph_text_direction(&self, row: usize) -> TextDirection {
+        self.paragraph_range(row)
+            .and_then(|(start, _)| self.paragraph_directions.get(&start).copied())
+            .unwrap_or(TextDirection::Auto)
+    }
+
+    /// Set the direction of the paragraph containing `row` (Auto removes the override)
+    pub fn set_paragraph_text_direction(&mut self, row: usize, direction: TextDirection) {
+        let Some((start, _)) = self.paragraph_range(row) else { return };
+        if direction == TextDirection::Auto {
+            self.paragraph_directions.remove(&start);
+        } else {
+            self.paragraph_directions.insert(start, direction);
+        }
+        self.request_redraw();
+    }
+
+    /// Cycle the direction of the cursor paragraph (SetTextDirection action)
+    pub fn cycle_paragraph_text_direction(&mut self) {
+        let row = self.cursor.row;
+        let next = self.paragraph_text_direction(row).next();
+        self.set_paragraph_text_direction(row, next);
+    }
+
+    /// Resolved right-to-left flag for every row
+    ///
+    /// The flags are shared with the cache, so the layers of a frame, hit-testing and
+    /// the visual caret movement all read the same vector.
+    pub fn rtl_rows(&self) -> Rc<[bool]> {
+        self.refresh_directions();
+        self.direction_cache.borrow().as_ref().map_or_else(|| Rc::from([]), |cache| cache.rows.clone())
+    }
+
+    /// Resolve the line directions again if anything they depend on changed
+    fn refresh_directions(&self) {
+        let (direction, log) = (self.text_direction(), self.is_log_buffer());
+        let fresh = |cache: &DirectionCache| {
+            cache.revision == self.text_revision && cache.direction == direction && cache.log == log
+                && cache.rows.len() == self.lines.len() && cache.overrides == self.paragraph_directions
+        };
+        if self.direction_cache.borrow().as_ref().is_some_and(fresh) {
+            return;
+        }
+        self.direction_cache.replace(Some(DirectionCache {
+            revision: self.text_revision,
+            direction,
+            log,
+            overrides: self.paragraph_directions.clone(),
+            rows: self.resolve_rtl_rows().into(),
+        }));
+    }
+
+    /// Right-to-left flag for every row, in one pass over the paragraphs
+    fn resolve_rtl_rows(&self) -> Vec<bool> {
+        // Log lines are always laid out left to right
+        if self.is_log_buffer() {
+            return vec![false; self.lines.len()];
+        }
+        let buffer_direction = self.text_direction();
+        let mut rows = vec![buffer_direction == TextDirection::Rtl; self.lines.len()];
+        let mut row = 0;
+        while row < self.lines.len() {
+            let Some((start, end)) = self.paragraph_range(row) else {
+                row += 1;
+                continue;
+            };
+            let direction = match self.paragraph_directions.get(&start) {
+                Some(&direction) => direction,
+                None if buffer_direction != TextDirection::Auto => buffer_direction,
+                None => detect_direction(self.lines[start..=end].iter().map(String::as_str))
+                    .unwrap_or(TextDirection::Ltr),
+            };
+            rows[start..=end].fill(direction == TextDirection::Rtl);
+            row = end + 1;
+        }
+        rows
+    }
+
+    /// True if `row` is laid out right-to-left
+    pub fn is_rtl_row(&self, row: usize) -> bool {
+        self.refresh_directions();
+        self.direction_cache.borrow().as_ref().and_then(|cache| cache.rows.get(row).copied()).unwrap_or(false)
+    }
+
+    /// Record the text direction of the hosting widget (GTK locale direction)
+    pub fn set_widget_rtl(&mut self, rtl: bool) {
+        if self.widget_rtl != rtl {
+            self.widget_rtl = rtl;
+            self.request_redraw();
+        }
+    }
+
+    /// True if the whole view is mirrored: RTL widget or RTL buffer direction
+    pub fn view_is_rtl(&self) -> bool {
+        self.widget_rtl || self.text_direction() == TextDirection::Rtl
+    }
+
+    /// Map a key action to its visual meaning on the cursor row
+    ///
+    /// Left/Right are swapped in RTL rows so the caret moves the way the arrow points;
+    /// Home/End are swapped when the row runs against the view, so Home always goes to
+    /// the edge next to the gutter.
+    pub fn visual_action(&self, action: EditorAction) -> EditorAction {
+        let row_rtl = self.is_rtl_row(self.cursor.row);
+        let flip_home_end = row_rtl != self.view_is_rtl();
+        match action {
+            EditorAction::MoveCursorLeft if row_rtl => EditorAction::MoveCursorRight,
+            EditorAction::MoveCursorRight if row_rtl => EditorAction::MoveCursorLeft,
+            EditorAction::SelectLeft if row_rtl => EditorAction::SelectRight,
+            EditorAction::SelectRight if row_rtl => EditorAction::SelectLeft,
+            EditorAction::MoveCursorHome if flip_home_end => EditorAction::MoveCursorEnd,
+            EditorAction::MoveCursorEnd if flip_home_end => EditorAction::MoveCursorHome,
+            EditorAction::MoveCursorStartOfLine if flip_home_end => EditorAction::MoveCursorEndOfLine,
+            EditorAction::MoveCursorEndOfLine if flip_home_end => EditorAction::MoveCursorStartOfLine,
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_strong_character_decides() {
+        assert_eq!(detect_direction(["123 שלום world"]), Some(TextDirection::Rtl));
+        assert_eq!(detect_direction(["- hello مرحبا"]), Some(TextDirection::Ltr));
+        assert_eq!(detect_direction(["", "42."]), None);
+    }
+
+    #[test]
+    fn paragraph_override_beats_detection() {
+        let mut buf = EditorBuffer::new();
+        buf.lines = vec!["שלום".into(), "".into(), "hello".into(), "world".into()];
+        assert_eq!(*buf.rtl_rows(), [true, false, false, false]);
+        buf.set_paragraph_text_direction(3, TextDirection::Rtl);
+        assert_eq!(*buf.rtl_rows(), [true, false, true, true]);
+        buf.set_text_direction(Some(TextDirection::Ltr));
+        assert_eq!(*buf.rtl_rows(), [false, false, true, true]);
+    }
+
+    #[test]
+    fn directions_follow_edits() {
+        let mut buf = EditorBuffer::new();
+        buf.lines = vec!["hello".into(), "".into(), "world".into()];
+        assert!(!buf.is_rtl_row(2));
+        buf.replace_rows(2..3, vec!["שלום".into()]);
+        assert!(buf.is_rtl_row(2));
+        assert!(!buf.is_rtl_row(0));
+    }
+
+    #[test]
+    fn arrows_and_home_follow_the_view() {
+        let mut buf = EditorBuffer::new();
+        buf.lines = vec!["שלום".into(), "".into(), "hello".into()];
+        // RTL row in an LTR view: arrows and Home/End flip
+        assert_eq!(buf.visual_action(EditorAction::MoveCursorLeft), EditorAction::MoveCursorRight);
+        assert_eq!(buf.visual_action(EditorAction::MoveCursorHome), EditorAction::MoveCursorEnd);
+        // RTL row in an RTL view: Home is already next to the gutter
+        buf.set_widget_rtl(true);
+        assert_eq!(buf.visual_action(EditorAction::MoveCursorHome), EditorAction::MoveCursorHome);
+        // LTR row in an RTL view
+        buf.cursor.row = 2;
+        assert_eq!(buf.visual_action(EditorAction::MoveCursorLeft), EditorAction::MoveCursorLeft);
+        assert_eq!(buf.visual_action(EditorAction::MoveCursorEnd), EditorAction::MoveCursorHome);
+    }
+}
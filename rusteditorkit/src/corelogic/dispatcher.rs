@@ -8,8 +8,14 @@
 //! - Makes the system extensible for plugins
 
 use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+use super::recovery::panic_message;
+use super::surround;
+use super::transforms::FileError;
+use super::redraw::RedrawLayers;
 use crate::keybinds::editor_action::EditorAction;
 use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
 
 /// Result type for command execution
 pub type CommandResult<T = ()> = Result<T, CommandError>;
@@ -25,8 +31,10 @@ pub enum CommandError {
     BufferError(String),
     /// Clipboard operation failed
     ClipboardError(String),
-    /// File operation failed
-    FileError(String),
+    /// File operation failed; hosts can match on the cause (e.g. `PermissionDenied`)
+    FileError(FileError),
+    /// A command panicked; the buffer state was repaired afterwards
+    Internal(String),
 }
 
 impl fmt::Display for CommandError {
@@ -36,12 +44,26 @@ impl fmt::Display for CommandError {
             CommandError::InvalidParameters(msg) => write!(f, "Invalid parameters: {}", msg),
             CommandError::BufferError(msg) => write!(f, "Buffer error: {}", msg),
             CommandError::ClipboardError(msg) => write!(f, "Clipboard error: {}", msg),
-            CommandError::FileError(msg) => write!(f, "File error: {}", msg),
+            CommandError::FileError(error) => write!(f, "File error: {}", error),
+            CommandError::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
 }
 
-impl std::error::Error for CommandError {}
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandError::FileError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<FileError> for CommandError {
+    fn from(error: FileError) -> Self {
+        CommandError::FileError(error)
+    }
+}
 
 /// Parameters for commands that need additional data
 #[derive(Debug, Clone)]
@@ -115,12 +137,20 @@ impl CommandDispatcher {
             EditorAction::MoveCursorUp | EditorAction::MoveCursorDown |
             EditorAction::MoveCursorHome | EditorAction::MoveCursorStartOfLine |
             EditorAction::MoveCursorEnd | EditorAction::MoveCursorEndOfLine |
-            EditorAction::MoveCursorPageUp | EditorAction::MoveCursorPageDown => true,
+            EditorAction::MoveCursorPageUp | EditorAction::MoveCursorPageDown |
+            EditorAction::MoveWordLeft | EditorAction::MoveWordRight |
+            EditorAction::NextPage | EditorAction::PrevPage |
+            EditorAction::NextHeading | EditorAction::PrevHeading |
+            EditorAction::NextBookmark | EditorAction::PrevBookmark => true,
             
             // Input and editing keys - clear selection (handled in their methods)
             EditorAction::InsertText | EditorAction::InsertNewline |
             EditorAction::Backspace | EditorAction::Delete |
-            EditorAction::DeleteLeft | EditorAction::DeleteRight => false, // These handle selection themselves
+            EditorAction::DeleteLeft | EditorAction::DeleteRight |
+            EditorAction::DeleteWordLeft | EditorAction::DeleteWordRight |
+            EditorAction::SurroundSelection | EditorAction::UnSurround |
+            EditorAction::FillColumn | EditorAction::FillColumnSequence |
+            EditorAction::AlignSelection => false, // These handle selection themselves
             
             // Escape key - clear selection
             EditorAction::Escape | EditorAction::ClearSelection => true,
@@ -128,6 +158,7 @@ impl CommandDispatcher {
             // Selection keys - preserve selection (extend it)
             EditorAction::SelectLeft | EditorAction::SelectRight |
             EditorAction::SelectUp | EditorAction::SelectDown |
+            EditorAction::SelectWordLeft | EditorAction::SelectWordRight |
             EditorAction::SelectAll => false,
             
             // Copy/paste operations - preserve selection
@@ -136,10 +167,20 @@ impl CommandDispatcher {
             
             // Indent/unindent operations - preserve selection (they work on selected lines)
             EditorAction::Indent | EditorAction::Unindent => false,
+
+            // Reflow works on the selected lines and clears the selection itself
+            EditorAction::ReflowParagraph => false,
+
+            // Table commands rewrite the table and clear the selection themselves
+            EditorAction::FormatTable | EditorAction::InsertTableRow |
+            EditorAction::InsertTableColumn => false,
+
+            // Checkbox toggling works on the selected lines
+            EditorAction::ToggleCheckbox => false,
             
             // File operations - preserve selection
             EditorAction::OpenFile | EditorAction::SaveFile | EditorAction::SaveAs |
-            EditorAction::NewFile => false,
+            EditorAction::NewFile | EditorAction::NewFileFromTemplate => false,
             
             // Search operations - clear selection (will create new selection if found)
             EditorAction::Find | EditorAction::FindNext | EditorAction::Replace => true,
@@ -162,13 +203,146 @@ impl CommandDispatcher {
         // Validate buffer state
         self.validate_buffer_state(buffer)?;
 
+        // A snapshot view is read-only
+        if let Some(name) = buffer.viewed_snapshot() {
+            if Self::edits_text(&action) || Self::is_file_action(&action) {
+                return Err(CommandError::InvalidState(format!("Snapshot '{}' is read-only", name)));
+            }
+        }
+
+        // Log buffers only grow through `append_lines_streaming`
+        if buffer.is_log_buffer() && Self::edits_text(&action) {
+            return Err(CommandError::InvalidState("Log buffers are append-only".to_string()));
+        }
+
+        // Count the action for opt-in telemetry (names and lengths only, never content)
+        if buffer.telemetry.is_enabled() {
+            let typed_chars = match &params {
+                CommandParams::Text(text) if action == EditorAction::InsertText => Some(text.chars().count()),
+                _ => None,
+            };
+            buffer.telemetry.record(action, typed_chars);
+        }
+
+        // Every redraw requested while the command runs becomes one frame
+        buffer.begin_redraw_batch();
+
+        // Any command counts as user input and stops a running scroll animation
+        buffer.cancel_smooth_scroll();
+
         // Auto-clear selection for appropriate actions
         if Self::should_clear_selection_for_action(&action) {
             buffer.clear_selection_if_exists();
         }
 
-        // Execute the command
-        let result = match action {
+        let row_before = buffer.cursor.row;
+
+        // Execute the command; a panic inside a command is contained here so a bug in one
+        // command cannot take down the host application
+        let result = match panic::catch_unwind(AssertUnwindSafe(|| Self::run_action(buffer, action, params))) {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = panic_message(payload.as_ref());
+                eprintln!("[ERROR] Command {:?} panicked: {}", action, message);
+                buffer.repair_state();
+                buffer.events.emit(&EditorEvent::CommandPanicked {
+                    action: format!("{:?}", action),
+                    message: message.clone(),
+                });
+                Err(CommandError::Internal(format!("{:?} panicked: {}", action, message)))
+            }
+        };
+
+        // Step over collapsed folds, or open the one a jump landed in
+        buffer.settle_cursor_after_fold(action);
+
+        // A caret moved off the snippet field being filled in ends the snippet session
+        buffer.end_snippet_if_caret_left();
+
+        // Log result if debug mode is enabled
+        if self.debug_mode {
+            match &result {
+                Ok(_) => println!("[COMMAND] Successfully executed {:?}", action),
+                Err(e) => println!("[COMMAND] Failed to execute {:?}: {}", action, e),
+            }
+        }
+
+        // Check the buffer invariants in debug builds (or when enabled in the config)
+        if cfg!(debug_assertions) || buffer.config.consistency_checks {
+            self.report_violations(buffer);
+        }
+
+        // Keep the caret in view (centered in typewriter mode)
+        buffer.ensure_cursor_visible();
+
+        // Flash the landing line after a jump so it is easy to spot
+        if Self::is_jump_action(&action) && buffer.cursor.row != row_before {
+            buffer.flash_line(buffer.cursor.row);
+        }
+
+        // Invalidate the layers the command changed (the overlay when the zen focus region moved)
+        let mut layers = self.redraw_layers_after_command(&action);
+        if buffer.refresh_focus() {
+            layers |= RedrawLayers::OVERLAY;
+        }
+        buffer.invalidate(layers);
+
+        // Publish updated word/page statistics, the enclosing scope, title and caret context to listeners
+        buffer.refresh_statistics();
+        buffer.refresh_scope();
+        buffer.refresh_title();
+        buffer.refresh_caret_context();
+
+        buffer.end_redraw_batch();
+        result
+    }
+
+    /// Log every invariant the last command broke, together with that command
+    fn report_violations(&self, buffer: &EditorBuffer) {
+        let violations = buffer.validate();
+        if violations.is_empty() {
+            return;
+        }
+        let command = self.command_history.last()
+            .map(|(action, params)| format!("{:?} {:?}", action, params))
+            .unwrap_or_else(|| "<none>".to_string());
+        for violation in &violations {
+            eprintln!("[CONSISTENCY] After {}: {}", command, violation);
+        }
+    }
+
+    /// True for commands that change the text
+    fn edits_text(action: &EditorAction) -> bool {
+        matches!(action,
+            EditorAction::CutSelection | EditorAction::PasteClipboard |
+            EditorAction::DeleteLeft | EditorAction::DeleteRight |
+            EditorAction::DeleteWordLeft | EditorAction::DeleteWordRight |
+            EditorAction::Backspace | EditorAction::Delete |
+            EditorAction::InsertText | EditorAction::InsertNewline |
+            EditorAction::SurroundSelection | EditorAction::UnSurround |
+            EditorAction::FillColumn | EditorAction::FillColumnSequence |
+            EditorAction::AlignSelection |
+            EditorAction::ReflowParagraph | EditorAction::FormatTable |
+            EditorAction::InsertTableRow | EditorAction::InsertTableColumn |
+            EditorAction::ToggleCheckbox | EditorAction::Undo | EditorAction::Redo |
+            EditorAction::Indent | EditorAction::Unindent |
+            EditorAction::ConvertTabsToSpaces | EditorAction::Replace)
+    }
+
+    /// True for commands that replace or write the document
+    fn is_file_action(action: &EditorAction) -> bool {
+        matches!(action,
+            EditorAction::NewFile | EditorAction::NewFileFromTemplate | EditorAction::OpenFile |
+            EditorAction::SaveFile | EditorAction::SaveAs)
+    }
+
+    /// Run the command for `action` against the buffer
+    fn run_action(buffer: &mut EditorBuffer, action: EditorAction, params: CommandParams) -> CommandResult {
+        // Arrow and Home/End keys act visually in right-to-left rows
+        let action = buffer.visual_action(action);
+        #[cfg(feature = "vertical-text")]
+        let action = buffer.vertical_action(action);
+        match action {
             // === Navigation Commands ===
             EditorAction::MoveCursorLeft => {
                 buffer.move_left();
@@ -182,6 +356,14 @@ impl CommandDispatcher {
                 buffer.move_up();
                 Ok(())
             },
+            EditorAction::MoveWordLeft => {
+                buffer.move_word_left();
+                Ok(())
+            },
+            EditorAction::MoveWordRight => {
+                buffer.move_word_right();
+                Ok(())
+            },
             EditorAction::MoveCursorDown => {
                 buffer.move_down();
                 Ok(())
@@ -201,7 +383,7 @@ impl CommandDispatcher {
                         Ok(())
                     },
                     CommandParams::None => {
-                        buffer.move_page_up(25); // Default page size
+                        buffer.move_page_up(buffer.page_rows());
                         Ok(())
                     },
                     _ => Err(CommandError::InvalidParameters("PageUp requires PageLines or None".to_string()))
@@ -214,7 +396,7 @@ impl CommandDispatcher {
                         Ok(())
                     },
                     CommandParams::None => {
-                        buffer.move_page_down(25); // Default page size
+                        buffer.move_page_down(buffer.page_rows());
                         Ok(())
                     },
                     _ => Err(CommandError::InvalidParameters("PageDown requires PageLines or None".to_string()))
@@ -238,18 +420,32 @@ impl CommandDispatcher {
                 buffer.select_down();
                 Ok(())
             },
+            EditorAction::SelectWordLeft => {
+                buffer.select_word_left();
+                Ok(())
+            },
+            EditorAction::SelectWordRight => {
+                buffer.select_word_right();
+                Ok(())
+            },
             EditorAction::SelectAll => {
                 buffer.select_all();
                 Ok(())
             },
             EditorAction::ClearSelection => {
+                // Escape also leaves a read-only snapshot view and ends snippet field navigation
+                buffer.close_snapshot_view();
+                buffer.end_snippet();
                 buffer.clear_selection();
                 Ok(())
             },
 
             // === Editing Commands ===
             EditorAction::Backspace => {
-                buffer.backspace();
+                // In prose mode Backspace on an empty list item removes the marker
+                if !buffer.remove_empty_list_marker() {
+                    buffer.backspace();
+                }
                 Ok(())
             },
             EditorAction::Delete => {
@@ -257,46 +453,156 @@ impl CommandDispatcher {
                 Ok(())
             },
             EditorAction::DeleteLeft => {
-                buffer.backspace();
+                if !buffer.remove_empty_list_marker() {
+                    buffer.backspace();
+                }
                 Ok(())
             },
             EditorAction::DeleteRight => {
                 buffer.delete();
                 Ok(())
             },
+            EditorAction::DeleteWordLeft => {
+                buffer.delete_word_left();
+                Ok(())
+            },
+            EditorAction::DeleteWordRight => {
+                buffer.delete_word_right();
+                Ok(())
+            },
             EditorAction::InsertNewline => {
-                buffer.insert_newline();
+                // In prose mode Enter continues (or ends) the list item under the cursor
+                if !buffer.continue_list() {
+                    buffer.insert_newline();
+                }
                 Ok(())
             },
             EditorAction::InsertText => {
                 match params {
                     CommandParams::Text(text) => {
-                        buffer.insert_text(&text);
+                        // A bracket or quote typed over a selection wraps it; pasted-looking input is inserted verbatim
+                        buffer.insert_typed(&text);
                         Ok(())
                     },
                     _ => Err(CommandError::InvalidParameters("InsertText requires Text parameter".to_string()))
                 }
             },
+            EditorAction::SurroundSelection => {
+                match params {
+                    CommandParams::Text(text) => {
+                        let mut chars = text.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) if surround::surround_pair(c).is_some() => {
+                                buffer.surround_selection(c);
+                                Ok(())
+                            },
+                            _ => Err(CommandError::InvalidParameters("SurroundSelection requires a bracket or quote character".to_string()))
+                        }
+                    },
+                    _ => Err(CommandError::InvalidParameters("SurroundSelection requires Text parameter".to_string()))
+                }
+            },
+            EditorAction::UnSurround => {
+                buffer.unsurround();
+                Ok(())
+            },
+            EditorAction::FillColumn => {
+                match params {
+                    CommandParams::Text(text) => {
+                        buffer.fill_column(&text);
+                        Ok(())
+                    },
+                    _ => Err(CommandError::InvalidParameters("FillColumn requires Text parameter".to_string()))
+                }
+            },
+            EditorAction::FillColumnSequence => {
+                let start = match params {
+                    CommandParams::Text(text) => text.trim().parse::<i64>()
+                        .map_err(|_| CommandError::InvalidParameters(format!("FillColumnSequence start is not a number: {:?}", text)))?,
+                    _ => 1,
+                };
+                buffer.fill_column_sequence(start);
+                Ok(())
+            },
+            EditorAction::AlignSelection => {
+                match params {
+                    CommandParams::Text(pattern) => buffer.align_selection(&pattern)
+                        .map(|_| ())
+                        .map_err(CommandError::InvalidParameters),
+                    _ => Err(CommandError::InvalidParameters("AlignSelection requires Text parameter".to_string()))
+                }
+            },
             EditorAction::Indent => {
-                buffer.indent();
+                // Tab moves to the next snippet field, or between cells inside a Markdown table
+                if buffer.next_snippet_field() {
+                    // The field is selected, ready to be typed over
+                } else if buffer.selection.is_none() && buffer.in_table() {
+                    buffer.table_next_cell();
+                } else {
+                    buffer.indent();
+                }
                 Ok(())
             },
             EditorAction::Unindent => {
-                buffer.unindent();
+                if buffer.selection.is_none() && buffer.in_table() {
+                    buffer.table_prev_cell();
+                } else {
+                    buffer.unindent();
+                }
+                Ok(())
+            },
+            EditorAction::ReflowParagraph => {
+                buffer.reflow_paragraph();
+                Ok(())
+            },
+            EditorAction::FormatTable => {
+                buffer.format_table_at_cursor();
                 Ok(())
             },
+            EditorAction::InsertTableRow => {
+                buffer.insert_table_row();
+                Ok(())
+            },
+            EditorAction::InsertTableColumn => {
+                buffer.insert_table_column();
+                Ok(())
+            },
+            EditorAction::ToggleCheckbox => {
+                // With a position (a click) only the checkbox there, else the caret's lines
+                match params {
+                    CommandParams::Position { row, col } => if buffer.toggle_checkbox_at(row, col) {
+                        Ok(())
+                    } else {
+                        Err(CommandError::InvalidParameters(format!("No checkbox at {}:{}", row + 1, col + 1)))
+                    },
+                    _ => {
+                        buffer.toggle_checkbox();
+                        Ok(())
+                    }
+                }
+            },
 
             // === Clipboard Commands ===
+            #[cfg(feature = "gtk")]
             EditorAction::CopySelection => {
                 buffer.copy_to_clipboard();
                 Ok(())
             },
+            #[cfg(feature = "gtk")]
             EditorAction::CutSelection => {
                 buffer.cut_to_clipboard();
                 Ok(())
             },
+            #[cfg(not(feature = "gtk"))]
+            EditorAction::CopySelection | EditorAction::CutSelection => {
+                Err(CommandError::ClipboardError("built without the gtk feature".to_string()))
+            },
             EditorAction::PasteClipboard => {
-                buffer.paste_from_clipboard();
+                match params {
+                    CommandParams::Text(text) => buffer.paste_text(&text),
+                    // Ask whoever owns the clipboard to read it and paste
+                    _ => buffer.events.emit(&crate::corelogic::events::EditorEvent::PasteRequested),
+                }
                 Ok(())
             },
 
@@ -311,11 +617,27 @@ impl CommandDispatcher {
             },
 
             // === File Operations ===
+            EditorAction::NewFile => {
+                buffer.new_file();
+                Ok(())
+            },
+            EditorAction::NewFileFromTemplate => {
+                match params {
+                    CommandParams::Text(name) => {
+                        if buffer.new_file_from_template(&name) {
+                            Ok(())
+                        } else {
+                            Err(CommandError::InvalidParameters(format!("No file template named {:?}", name)))
+                        }
+                    },
+                    _ => Err(CommandError::InvalidParameters("NewFileFromTemplate requires Text parameter".to_string()))
+                }
+            },
             EditorAction::OpenFile => {
                 match params {
                     CommandParams::FilePath(path) => {
                         buffer.open_file(&path)
-                            .map_err(|e| CommandError::FileError(e))
+                            .map_err(CommandError::from)
                     },
                     _ => Err(CommandError::InvalidParameters("OpenFile requires FilePath parameter".to_string()))
                 }
@@ -323,23 +645,81 @@ impl CommandDispatcher {
             EditorAction::SaveFile => {
                 match params {
                     CommandParams::FilePath(path) => {
-                        buffer.save_file(&path)
-                            .map_err(|e| CommandError::FileError(e))
+                        buffer.save_document(&path)
+                            .map_err(CommandError::from)
                     },
                     _ => Err(CommandError::InvalidParameters("SaveFile requires FilePath parameter".to_string()))
                 }
             },
+            EditorAction::SaveAs => {
+                match params {
+                    CommandParams::FilePath(path) => {
+                        buffer.save_file_as(&path)
+                            .map_err(CommandError::from)
+                    },
+                    _ => Err(CommandError::InvalidParameters("SaveAs requires FilePath parameter".to_string()))
+                }
+            },
 
             // === Layout Commands ===
             EditorAction::ToggleA4Mode => {
                 buffer.toggle_a4_mode();
                 Ok(())
             },
+            EditorAction::NextPage => {
+                buffer.move_to_next_page();
+                Ok(())
+            },
+            EditorAction::PrevPage => {
+                buffer.move_to_prev_page();
+                Ok(())
+            },
+            EditorAction::NextHeading => {
+                buffer.move_to_next_heading();
+                Ok(())
+            },
+            EditorAction::PrevHeading => {
+                buffer.move_to_prev_heading();
+                Ok(())
+            },
+            EditorAction::ToggleBookmark => {
+                buffer.toggle_bookmark();
+                Ok(())
+            },
+            EditorAction::NextBookmark => {
+                buffer.move_to_next_bookmark();
+                Ok(())
+            },
+            EditorAction::PrevBookmark => {
+                buffer.move_to_prev_bookmark();
+                Ok(())
+            },
+            EditorAction::ToggleFold => {
+                buffer.toggle_fold(buffer.cursor.row);
+                Ok(())
+            },
+            EditorAction::ZoomIn => {
+                buffer.zoom_in();
+                Ok(())
+            },
+            EditorAction::ZoomOut => {
+                buffer.zoom_out();
+                Ok(())
+            },
+            EditorAction::ZoomReset => {
+                buffer.reset_zoom();
+                Ok(())
+            },
+            EditorAction::SetTextDirection => {
+                buffer.cycle_paragraph_text_direction();
+                Ok(())
+            },
 
             // === Search Commands ===
             EditorAction::FindNext => {
                 match params {
                     CommandParams::Text(query) => {
+                        buffer.set_search_query(Some(&query));
                         if let Some((row, col)) = buffer.find_next(&query, None) {
                             buffer.cursor.row = row;
                             buffer.cursor.col = col;
@@ -369,67 +749,84 @@ impl CommandDispatcher {
             _ => {
                 Err(CommandError::InvalidState(format!("Command {:?} not yet implemented", action)))
             }
-        };
-
-        // Log result if debug mode is enabled
-        if self.debug_mode {
-            match &result {
-                Ok(_) => println!("[COMMAND] Successfully executed {:?}", action),
-                Err(e) => println!("[COMMAND] Failed to execute {:?}: {}", action, e),
-            }
-        }
-
-        // Request redraw for commands that modify the buffer
-        if self.should_redraw_after_command(&action) {
-            buffer.request_redraw();
         }
+    }
 
-        result
+    /// Actions that can move the caret far enough to warrant a line flash
+    fn is_jump_action(action: &EditorAction) -> bool {
+        matches!(action,
+            EditorAction::FindNext |
+            EditorAction::NextPage | EditorAction::PrevPage |
+            EditorAction::NextHeading | EditorAction::PrevHeading |
+            EditorAction::NextBookmark | EditorAction::PrevBookmark)
     }
 
-    /// Determine if a redraw is needed after executing a command
-    fn should_redraw_after_command(&self, action: &EditorAction) -> bool {
+    /// Layers to redraw after executing a command
+    fn redraw_layers_after_command(&self, action: &EditorAction) -> RedrawLayers {
         match action {
-            // Navigation and selection always need redraw
+            // Navigation and selection move the caret and the active line, not the text
             EditorAction::MoveCursorLeft | EditorAction::MoveCursorRight |
             EditorAction::MoveCursorUp | EditorAction::MoveCursorDown |
             EditorAction::MoveCursorHome | EditorAction::MoveCursorStartOfLine |
             EditorAction::MoveCursorEnd | EditorAction::MoveCursorEndOfLine |
             EditorAction::MoveCursorPageUp | EditorAction::MoveCursorPageDown |
+            EditorAction::MoveWordLeft | EditorAction::MoveWordRight |
+            EditorAction::NextPage | EditorAction::PrevPage |
+            EditorAction::NextHeading | EditorAction::PrevHeading |
+            EditorAction::NextBookmark | EditorAction::PrevBookmark |
             EditorAction::SelectLeft | EditorAction::SelectRight |
             EditorAction::SelectUp | EditorAction::SelectDown |
-            EditorAction::SelectAll | EditorAction::ClearSelection => true,
+            EditorAction::SelectWordLeft | EditorAction::SelectWordRight |
+            EditorAction::SelectAll | EditorAction::ClearSelection => RedrawLayers::CARET,
+
+            // Bookmarks show in the gutter and the scroll map
+            EditorAction::ToggleBookmark => RedrawLayers::GUTTER | RedrawLayers::OVERLAY,
+
+            // Folding hides rows
+            EditorAction::ToggleFold => RedrawLayers::ALL,
 
-            // Editing operations need redraw
+            // Editing operations change the text
             EditorAction::Backspace | EditorAction::Delete |
             EditorAction::DeleteLeft | EditorAction::DeleteRight |
+            EditorAction::DeleteWordLeft | EditorAction::DeleteWordRight |
             EditorAction::InsertNewline | EditorAction::InsertText |
+            EditorAction::SurroundSelection | EditorAction::UnSurround |
+            EditorAction::FillColumn | EditorAction::FillColumnSequence |
+            EditorAction::AlignSelection |
             EditorAction::Indent | EditorAction::Unindent |
-            EditorAction::PasteClipboard => true,
+            EditorAction::ReflowParagraph |
+            EditorAction::FormatTable | EditorAction::InsertTableRow |
+            EditorAction::InsertTableColumn | EditorAction::ToggleCheckbox |
+            EditorAction::PasteClipboard => RedrawLayers::ALL,
 
-            // Undo/Redo need redraw
-            EditorAction::Undo | EditorAction::Redo => true,
+            // Undo/Redo change the text
+            EditorAction::Undo | EditorAction::Redo => RedrawLayers::ALL,
 
-            // File operations that change content need redraw
-            EditorAction::OpenFile => true,
+            // File operations that change content
+            EditorAction::OpenFile | EditorAction::NewFile | EditorAction::NewFileFromTemplate => RedrawLayers::ALL,
 
-            // Layout changes need redraw
-            EditorAction::ToggleA4Mode => true,
+            // Save As can switch the language and its highlighting
+            EditorAction::SaveAs => RedrawLayers::ALL,
 
-            // Search operations need redraw
-            EditorAction::FindNext => true,
+            // Layout changes
+            EditorAction::ToggleA4Mode => RedrawLayers::ALL,
+            EditorAction::ZoomIn | EditorAction::ZoomOut | EditorAction::ZoomReset => RedrawLayers::ALL,
+            EditorAction::SetTextDirection => RedrawLayers::ALL,
 
-            // Copy operations don't need redraw
-            EditorAction::CopySelection | EditorAction::CutSelection => false,
+            // Search highlights the match
+            EditorAction::FindNext => RedrawLayers::ALL,
+
+            // Copy operations don't need redraw (cut invalidates when it removes text)
+            EditorAction::CopySelection | EditorAction::CutSelection => RedrawLayers::NONE,
 
             // Save operations don't need redraw
-            EditorAction::SaveFile => false,
+            EditorAction::SaveFile => RedrawLayers::NONE,
 
-            // Multi-cursor operations need redraw
-            EditorAction::AddCursor => true,
+            // Multi-cursor operations
+            EditorAction::AddCursor => RedrawLayers::ALL,
 
             // Default to no redraw for unknown actions
-            _ => false,
+            _ => RedrawLayers::NONE,
         }
     }
 
@@ -464,15 +861,18 @@ impl CommandDispatcher {
             EditorAction::MoveCursorDown => buffer.cursor.row + 1 < buffer.lines.len(),
             EditorAction::Undo => buffer.can_undo(),
             EditorAction::Redo => buffer.can_redo(),
-            EditorAction::CopySelection | EditorAction::CutSelection => buffer.has_selection(),
+            // Without a selection copy takes the caret line; cut does when configured to
+            EditorAction::CutSelection => buffer.has_selection() || buffer.config.cut_line_without_selection,
             
             // File operations need valid paths
-            EditorAction::OpenFile | EditorAction::SaveFile => {
+            EditorAction::OpenFile | EditorAction::SaveFile | EditorAction::SaveAs => {
                 matches!(params, CommandParams::FilePath(_))
             },
             
             // Text operations need text parameter
-            EditorAction::InsertText | EditorAction::FindNext => {
+            EditorAction::InsertText | EditorAction::SurroundSelection |
+            EditorAction::FillColumn | EditorAction::AlignSelection | EditorAction::FindNext |
+            EditorAction::NewFileFromTemplate => {
                 matches!(params, CommandParams::Text(_))
             },
             
@@ -0,0 +1,301 @@
+//! The shared mutation path for the buffer text
+//!
+//! Every edit of `lines` goes through `apply_rows`, which replaces a run of rows and then
+//! brings everything keyed by rows up to date: the text revision, the change feed and the
+//! anchors and caches that follow the text. The public helpers (`replace_range`,
+//! `replace_rows`, `set_lines`) are what the editing commands, paste, undo and the list
+//! and table helpers call, so no command has to snapshot and diff the buffer afterwards.
+//! They change nothing while `allows_edit` is false, whoever calls them; log buffers
+//! only take the appends and top trims of streaming (`append_rows`, `trim_top_rows`).
+//!
+//! Hosts that assign `lines` directly call `text_replaced` afterwards.
+
+use std::ops::Range;
+use super::buffer::EditorBuffer;
+use super::changes::{diff_lines, Position};
+
+/// Rows replaced by one edit: `old_rows` rows at `start` became `new_rows` rows
+///
+/// Rows equal on both sides at either end are not counted, so typing on a line is
+/// `{ row, 1, 1 }` and Enter at the end of it `{ row + 1, 0, 1 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowEdit {
+    pub start: usize,
+    pub old_rows: usize,
+    pub new_rows: usize,
+}
+
+impl RowEdit {
+    /// Change in the number of lines
+    pub fn delta(&self) -> isize {
+        self.new_rows as isize - self.old_rows as isize
+    }
+
+    /// Where row `row` of the text before the edit is after it
+    ///
+    /// Rows inside the replaced run stay in the rows that replaced them; `None` when the
+    /// run was removed without replacement.
+    pub fn map_row(&self, row: usize) -> Option<usize> {
+        if row < self.start {
+            Some(row)
+        } else if row >= self.start + self.old_rows {
+            Some(row + self.new_rows - self.old_rows)
+        } else if self.new_rows == 0 {
+            None
+        } else {
+            Some(row.min(self.start + self.new_rows - 1))
+        }
+    }
+
+    /// Splice a per-line vector the way the lines were spliced, filling new rows with
+    /// `fill`
+    ///
+    /// A vector that was not the length of the text before the edit is cleared, so it
+    /// gets rebuilt instead of staying misaligned.
+    pub fn splice_rows<T: Clone>(&self, rows: &mut Vec<T>, old_len: usize, fill: T) {
+        if rows.len() != old_len {
+            rows.clear();
+            return;
+        }
+        rows.splice(self.start..self.start + self.old_rows, std::iter::repeat_n(fill, self.new_rows));
+    }
+}
+
+/// Split `text` at newlines into the rows it occupies
+fn split_rows(text: &str) -> Vec<String> {
+    text.split('\n').map(str::to_string).collect()
+}
+
+/// Byte index of char column `col` in `line`, or its length past the end
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(idx, _)| idx)
+}
+
+impl EditorBuffer {
+    /// Counter bumped by every change of the text, including undo, loads and snapshot
+    /// views
+    ///
+    /// Unlike `revision()` it counts whether or not the change feed is enabled, so
+    /// per-line caches can be keyed on it.
+    pub fn text_revision(&self) -> u64 {
+        self.text_revision
+    }
+
+    /// False while a snapshot is viewed and for log buffers, which only grow by streaming
+    pub fn allows_edit(&self) -> bool {
+        self.snapshot_view.is_none() && !self.is_log_buffer()
+    }
+
+    /// Replace `start..end` (char positions, end exclusive) with `text`
+    ///
+    /// Positions past the end of a line are clamped to it. Returns the rows that changed,
+    /// or None when the text stayed the same or may not be edited.
+    pub fn replace_range(&mut self, start: Position, end: Position, text: &str) -> Option<RowEdit> {
+        if !self.allows_edit() {
+            return None;
+        }
+        let last = self.lines.len() - 1;
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        let (start_row, end_row) = (start.0.min(last), end.0.min(last));
+        let head = &self.lines[start_row];
+        let tail = &self.lines[end_row];
+        let mut joined = head[..byte_index(head, start.1)].to_string();
+        joined.push_str(text);
+        let tail_start = if end.0 > last { tail.len() } else { byte_index(tail, end.1) };
+        joined.push_str(&tail[tail_start..]);
+        self.apply_rows(start_row..end_row + 1, split_rows(&joined))
+    }
+
+    /// Replace whole rows `range` with `lines` (which may be empty to delete them)
+    pub fn replace_rows(&mut self, range: Range<usize>, lines: Vec<String>) -> Option<RowEdit> {
+        if !self.allows_edit() {
+            return None;
+        }
+        let range = range.start.min(self.lines.len())..range.end.min(self.lines.len());
+        self.apply_rows(range, lines)
+    }
+
+    /// Replace the whole text with `lines`, as an edit of the rows that differ
+    ///
+    /// Used by undo/redo: anchors outside the changed rows stay put and the change feed
+    /// records only the difference.
+    pub fn set_lines(&mut self, lines: Vec<String>) -> Option<RowEdit> {
+        if !self.allows_edit() {
+            return None;
+        }
+        self.load_lines_unchecked(lines)
+    }
+
+    /// `set_lines` for loads, which replace the document in any buffer
+    pub(crate) fn load_lines_unchecked(&mut self, lines: Vec<String>) -> Option<RowEdit> {
+        let len = self.lines.len();
+        self.apply_rows(0..len, lines)
+    }
+
+    /// Replace the rows from `from` to the end with `lines`: the append of streaming,
+    /// allowed in log buffers
+    pub(crate) fn append_rows(&mut self, from: usize, lines: Vec<String>) -> Option<RowEdit> {
+        if self.snapshot_view.is_some() {
+            return None;
+        }
+        let len = self.lines.len();
+        self.apply_rows(from.min(len)..len, lines)
+    }
+
+    /// Remove the first `count` rows: the trim of streaming, allowed in log buffers
+    pub(crate) fn trim_top_rows(&mut self, count: usize) -> Option<RowEdit> {
+        if self.snapshot_view.is_some() {
+            return None;
+        }
+        let count = count.min(self.lines.len());
+        self.apply_rows(0..count, Vec::new())
+    }
+
+    /// Tell the buffer `lines` was replaced without going through the edit path
+    ///
+    /// Per-line caches are dropped and the text revision moves on, but no change op is
+    /// recorded and no anchors move; snapshot views use this, as should hosts writing
+    /// `lines` themselves.
+    pub fn text_replaced(&mut self) {
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.text_revision += 1;
+        self.statistics_cache.clear();
+        self.missing_glyphs.get_mut().clear();
+        self.line_offsets.get_mut().clear();
+        self.request_redraw();
+    }
+
+    /// Splice `lines` into `range` and update whatever follows the text
+    ///
+    /// This is the single place the text changes: no permission checks, those are done
+    /// by the helpers above, which are the only callers.
+    fn apply_rows(&mut self, range: Range<usize>, mut lines: Vec<String>) -> Option<RowEdit> {
+        let old_len = self.lines.len();
+        // Never leave the buffer without a line
+        if lines.is_empty() && range.len() == old_len {
+            lines.push(String::new());
+        }
+        // Drop rows equal on both sides, so the edit covers only what changed
+        let old = &self.lines[range.clone()];
+        let prefix = old.iter().zip(&lines).take_while(|(a, b)| a == b).count();
+        let max_suffix = old.len().min(lines.len()) - prefix;
+        let suffix = old.iter().rev().zip(lines.iter().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+        if prefix == old.len() && prefix == lines.len() {
+            return None;
+        }
+        let edit = RowEdit {
+            start: range.start + prefix,
+            old_rows: range.len() - prefix - suffix,
+            new_rows: lines.len() - prefix - suffix,
+        };
+        lines.truncate(lines.len() - suffix);
+        let inserted = lines.split_off(prefix);
+        let removed: Vec<String> = self.lines.splice(edit.start..edit.start + edit.old_rows, inserted).collect();
+        self.text_revision += 1;
+        self.statistics_cache.rows_replaced(&edit, old_len, &self.lines);
+        self.missing_glyphs.get_mut().rows_replaced(&edit, old_len);
+        self.line_offsets.get_mut().rows_replaced(&edit);
+        self.bookmarks = self.bookmarks.iter().filter_map(|&row| edit.map_row(row)).collect();
+        self.map_folds(&edit);
+        self.snippet_rows_replaced(&edit);
+        if self.changes.enabled {
+            self.record_row_edit(&edit, &removed);
+        }
+        Some(edit)
+    }
+
+    /// Record `edit` in the change feed; `removed` holds the rows it replaced
+    fn record_row_edit(&mut self, edit: &RowEdit, removed: &[String]) {
+        let inserted = &self.lines[edit.start..edit.start + edit.new_rows];
+        // Rows only inserted or only removed: widen by the unchanged row before them
+        // (after them at the top) so both sides are text and the joined newline is part of the op
+        let (base, before, after) = if edit.old_rows == 0 || edit.new_rows == 0 {
+            let base = edit.start.saturating_sub(1);
+            let lead = &self.lines[base..edit.start];
+            let trail = self.lines.get(edit.start + edit.new_rows).filter(|_| edit.start == 0);
+            let mut before: Vec<String> = lead.to_vec();
+            before.extend_from_slice(removed);
+            before.extend(trail.cloned());
+            let mut after: Vec<String> = lead.to_vec();
+            after.extend_from_slice(inserted);
+            after.extend(trail.cloned());
+            (base, before, after)
+        } else {
+            (edit.start, removed.to_vec(), inserted.to_vec())
+        };
+        if let Some(((start_row, start_col), (end_row, end_col), text)) = diff_lines(&before, &after) {
+            self.record_change((base + start_row, start_col), (base + end_row, end_col), text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(text: &str) -> EditorBuffer {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = split_rows(text);
+        buffer.set_change_feed_enabled(true);
+        buffer
+    }
+
+    /// Apply the recorded ops to `text` and return the result
+    fn replay(text: &str, buffer: &EditorBuffer) -> String {
+        let mut copy = EditorBuffer::new();
+        copy.lines = split_rows(text);
+        for op in buffer.changes_since(0).unwrap() {
+            copy.replace_range(op.start, op.end, &op.inserted);
+        }
+        copy.lines.join("\n")
+    }
+
+    #[test]
+    fn edits_cover_only_the_changed_rows() {
+        let mut b = buffer("one\ntwo\nthree");
+        assert_eq!(b.replace_range((1, 3), (1, 3), "\n"), Some(RowEdit { start: 2, old_rows: 0, new_rows: 1 }));
+        assert_eq!(b.replace_range((3, 0), (3, 0), "\n"), Some(RowEdit { start: 3, old_rows: 0, new_rows: 1 }));
+        assert_eq!(b.replace_range((3, 0), (4, 0), ""), Some(RowEdit { start: 3, old_rows: 1, new_rows: 0 }));
+        assert_eq!(b.replace_range((0, 1), (0, 3), "NE"), Some(RowEdit { start: 0, old_rows: 1, new_rows: 1 }));
+        assert_eq!(b.replace_range((0, 0), (0, 3), "oNE"), None);
+        assert_eq!(b.lines, ["oNE", "two", "", "three"]);
+    }
+
+    #[test]
+    fn recorded_ops_replay_to_the_same_text() {
+        let text = "alpha\nbeta\ngamma";
+        let mut b = buffer(text);
+        b.replace_range((0, 5), (0, 5), "\n");
+        b.replace_range((0, 0), (0, 0), "\n");
+        b.replace_rows(0..1, Vec::new());
+        b.replace_rows(3..4, vec!["gamma".into(), "delta".into()]);
+        b.replace_range((1, 0), (2, 0), "");
+        b.set_lines(split_rows("x\nalpha\nbeta"));
+        assert_eq!(replay(text, &b), b.lines.join("\n"));
+        assert_eq!(b.revision(), b.changes_since(0).unwrap().len() as u64);
+    }
+
+    #[test]
+    fn edits_outside_the_dispatcher_are_recorded() {
+        let mut b = buffer("- [ ] task");
+        b.language = Some("markdown".to_string());
+        assert!(b.toggle_checkbox_at(0, 3));
+        b.cursor = crate::corelogic::EditorCursor::new(0, 10);
+        b.insert_pasted("\nnext");
+        let ops = b.changes_since(0).unwrap();
+        assert_eq!(ops.len(), 2);
+        assert_eq!((ops[0].start, ops[0].end, ops[0].inserted.as_str()), ((0, 3), (0, 4), "x"));
+        assert_eq!((ops[1].start, ops[1].inserted.as_str()), ((0, 10), "\nnext"));
+    }
+
+    #[test]
+    fn rows_map_through_an_edit() {
+        let edit = RowEdit { start: 5, old_rows: 2, new_rows: 1 };
+        assert_eq!(edit.map_row(4), Some(4));
+        assert_eq!(edit.map_row(6), Some(5));
+        assert_eq!(edit.map_row(9), Some(8));
+        assert_eq!(RowEdit { start: 5, old_rows: 1, new_rows: 0 }.map_row(5), None);
+    }
+}
@@ -7,129 +7,88 @@ use super::buffer::EditorBuffer;
 impl EditorBuffer {
     /// Delete character before cursor (backspace)
     pub fn backspace(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
         // If there's a selection, delete it instead of just the character
         if self.delete_selection() {
             return;
         }
         
-        if self.cursor.col > 0 {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        if col > 0 {
             self.push_undo();
-            let line = &mut self.lines[self.cursor.row];
-            
-            // Convert cursor.col to byte index for the character before cursor
-            let chars: Vec<(usize, char)> = line.char_indices().collect();
-            if let Some((byte_idx, _)) = chars.get(self.cursor.col - 1) {
-                line.remove(*byte_idx);
-            }
+            self.replace_range((row, col - 1), (row, col), "");
             self.cursor.col -= 1;
-        } else if self.cursor.row > 0 {
+        } else if row > 0 {
             self.push_undo();
-            let prev_len = self.lines[self.cursor.row - 1].chars().count();
-            let current = self.lines.remove(self.cursor.row);
+            let prev_len = self.lines[row - 1].chars().count();
+            self.replace_range((row - 1, prev_len), (row, 0), "");
             self.cursor.row -= 1;
             self.cursor.col = prev_len;
-            self.lines[self.cursor.row].push_str(&current);
         }
     }
 
     /// Delete character at cursor (delete)
     pub fn delete(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
         // If there's a selection, delete it instead of just the character
         if self.delete_selection() {
             return;
         }
         
-        if self.cursor.row < self.lines.len() {
-            if self.cursor.col < self.lines[self.cursor.row].chars().count() {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        if row < self.lines.len() {
+            if col < self.lines[row].chars().count() {
                 self.push_undo();
-                let line = &mut self.lines[self.cursor.row];
-                
-                // Convert cursor.col to byte index for the character at cursor
-                let chars: Vec<(usize, char)> = line.char_indices().collect();
-                if let Some((byte_idx, _)) = chars.get(self.cursor.col) {
-                    line.remove(*byte_idx);
-                }
-            } else if self.cursor.row + 1 < self.lines.len() {
+                self.replace_range((row, col), (row, col + 1), "");
+            } else if row + 1 < self.lines.len() {
                 self.push_undo();
-                let next_line = self.lines.remove(self.cursor.row + 1);
-                self.lines[self.cursor.row].push_str(&next_line);
+                self.replace_range((row, col), (row + 1, 0), "");
             }
         }
     }
 
     /// Insert text at current cursor position
+    ///
+    /// Snapshot views and log buffers reject the edit and keep the caret where it is.
     pub fn insert_text(&mut self, text: &str) {
+        if !self.allows_edit() {
+            return;
+        }
         // If there's a selection, delete it first (typing replaces selection)
         self.delete_selection();
         
         self.push_undo();
+        let at = (self.cursor.row, self.cursor.col);
+        self.replace_range(at, at, text);
         
-        // Handle newline insertions
-        if text.contains('\n') {
-            let lines: Vec<&str> = text.split('\n').collect();
-            let current_line = &mut self.lines[self.cursor.row];
-            
-            // Convert cursor.col (char index) to byte index safely
-            let cursor_byte_idx = current_line.char_indices()
-                .nth(self.cursor.col)
-                .map(|(idx, _)| idx)
-                .unwrap_or(current_line.len());
-            
-            // Split current line at cursor using byte index
-            let after_cursor = current_line.split_off(cursor_byte_idx);
-            current_line.push_str(lines[0]);
-            
-            // Insert intermediate lines
-            for (i, line) in lines.iter().enumerate().skip(1) {
-                let new_line = if i == lines.len() - 1 {
-                    format!("{}{}", line, after_cursor)
-                } else {
-                    line.to_string()
-                };
-                self.lines.insert(self.cursor.row + i, new_line);
-            }
-            
-            // Update cursor position
-            self.cursor.row += lines.len() - 1;
-            self.cursor.col = if lines.len() > 1 {
-                lines.last().unwrap().chars().count()
-            } else {
-                self.cursor.col + text.chars().count()
-            };
+        // Update cursor position
+        let lines: Vec<&str> = text.split('\n').collect();
+        self.cursor.row += lines.len() - 1;
+        self.cursor.col = if lines.len() > 1 {
+            lines.last().unwrap().chars().count()
         } else {
-            // Simple text insertion
-            let line = &mut self.lines[self.cursor.row];
-            
-            // Convert cursor.col (char index) to byte index safely
-            let cursor_byte_idx = line.char_indices()
-                .nth(self.cursor.col)
-                .map(|(idx, _)| idx)
-                .unwrap_or(line.len());
-            
-            line.insert_str(cursor_byte_idx, text);
-            self.cursor.col += text.chars().count();
-        }
+            self.cursor.col + text.chars().count()
+        };
     }
 
     /// Insert a newline at current cursor position
     pub fn insert_newline(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
         // If there's a selection, delete it first
         self.delete_selection();
         
         self.push_undo();
-        let current_line = &mut self.lines[self.cursor.row];
-        
-        // Convert cursor.col (char index) to byte index safely
-        let cursor_byte_idx = current_line.char_indices()
-            .nth(self.cursor.col)
-            .map(|(idx, _)| idx)
-            .unwrap_or(current_line.len());
-        
-        let after_cursor = current_line.split_off(cursor_byte_idx);
+        let at = (self.cursor.row, self.cursor.col);
+        self.replace_range(at, at, "\n");
         
         self.cursor.row += 1;
         self.cursor.col = 0;
-        self.lines.insert(self.cursor.row, after_cursor);
     }
 
     /// Paste text at cursor
@@ -139,9 +98,13 @@ impl EditorBuffer {
 
     /// Delete the current line
     pub fn delete_line(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
+        let row = self.cursor.row;
         if self.lines.len() > 1 {
             self.push_undo();
-            self.lines.remove(self.cursor.row);
+            self.replace_rows(row..row + 1, Vec::new());
             
             // Adjust cursor if we deleted the last line
             if self.cursor.row >= self.lines.len() {
@@ -153,58 +116,37 @@ impl EditorBuffer {
         } else {
             // If only one line, just clear it
             self.push_undo();
-            self.lines[0].clear();
+            self.replace_rows(0..1, vec![String::new()]);
             self.cursor.col = 0;
         }
     }
 
     /// Duplicate the current line
     pub fn duplicate_line(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
         self.push_undo();
-        let line_content = self.lines[self.cursor.row].clone();
-        self.lines.insert(self.cursor.row + 1, line_content);
+        let row = self.cursor.row;
+        let line_content = self.lines[row].clone();
+        self.replace_rows(row + 1..row + 1, vec![line_content]);
         self.cursor.row += 1;
     }
 
     /// Delete selected text if any selection exists
+    ///
+    /// Returns false, keeping the selection, when the buffer takes no edits.
     pub fn delete_selection(&mut self) -> bool {
+        if !self.allows_edit() {
+            return false;
+        }
         if let Some(sel) = self.selection.clone() {
             self.push_undo();
             
-            let ((start_row, start_col), (end_row, end_col)) = sel.normalized();
-            
-            if start_row == end_row {
-                // Single line deletion - use character-based operations
-                let line = &mut self.lines[start_row];
-                let chars: Vec<char> = line.chars().collect();
-                
-                // Rebuild the line without the selected characters
-                let before: String = chars.get(..start_col).unwrap_or(&[]).iter().collect();
-                let after: String = chars.get(end_col..).unwrap_or(&[]).iter().collect();
-                *line = format!("{}{}", before, after);
-                
-                self.cursor.row = start_row;
-                self.cursor.col = start_col;
-            } else {
-                // Multi-line deletion - use character-based operations
-                let end_line = &self.lines[end_row];
-                let end_chars: Vec<char> = end_line.chars().collect();
-                let end_part: String = end_chars.get(end_col..).unwrap_or(&[]).iter().collect();
-                
-                let start_line = &mut self.lines[start_row];
-                let start_chars: Vec<char> = start_line.chars().collect();
-                let before_part: String = start_chars.get(..start_col).unwrap_or(&[]).iter().collect();
-                
-                *start_line = format!("{}{}", before_part, end_part);
-                
-                // Remove intermediate lines
-                for _ in start_row + 1..=end_row {
-                    self.lines.remove(start_row + 1);
-                }
-                
-                self.cursor.row = start_row;
-                self.cursor.col = start_col;
-            }
+            let (start, end) = sel.normalized();
+            self.replace_range(start, end, "");
+            self.cursor.row = start.0;
+            self.cursor.col = start.1;
             
             self.selection = None;
             true
@@ -220,161 +162,6 @@ impl EditorBuffer {
         }
     }
 
-    /// Convert screen coordinates to buffer position (row, col)
-    /// This is a simplified version - in a real implementation you'd need layout metrics
-    pub fn screen_to_buffer_position(&self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) -> (usize, usize) {
-        // Calculate row from y coordinate
-        let row = ((y - top_margin) / line_height).max(0.0) as usize;
-        let row = row.min(self.lines.len().saturating_sub(1));
-        
-        // Calculate column from x coordinate
-        let col = ((x - left_margin) / char_width).max(0.0) as usize;
-        let col = if row < self.lines.len() {
-            col.min(self.lines[row].chars().count())
-        } else {
-            0
-        };
-        
-        (row, col)
-    }
-
-    /// Handle mouse click - sets cursor position and clears selection (unless Shift is held)
-    pub fn handle_mouse_click(&mut self, x: f64, y: f64, shift_held: bool, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
-        let (row, col) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
-        
-        if shift_held && self.selection.is_some() {
-            // Extend existing selection
-            if let Some(sel) = &mut self.selection {
-                sel.end_row = row;
-                sel.end_col = col;
-                sel.clamp_to_buffer(&self.lines);
-            }
-        } else {
-            // Clear selection and set cursor position
-            self.selection = None;
-            self.cursor.row = row;
-            self.cursor.col = col;
-        }
-        
-        // Update mouse state
-        use crate::corelogic::buffer::MouseState;
-        self.mouse_state = if shift_held {
-            MouseState::ExtendingSelection
-        } else {
-            MouseState::Selecting { start_row: row, start_col: col }
-        };
-    }
-
-    /// Handle mouse drag - creates or extends selection
-    pub fn handle_mouse_drag(&mut self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
-        let (row, col) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
-        
-        use crate::corelogic::buffer::MouseState;
-        match self.mouse_state {
-            MouseState::Selecting { start_row, start_col } => {
-                // Create new selection from start to current position
-                let mut sel = crate::corelogic::selection::Selection::new(start_row, start_col);
-                sel.end_row = row;
-                sel.end_col = col;
-                sel.clamp_to_buffer(&self.lines);
-                
-                // Only set selection if there's an actual area selected
-                if sel.is_active() {
-                    self.selection = Some(sel);
-                } else {
-                    self.selection = None;
-                }
-                
-                // Update cursor to current position
-                self.cursor.row = row;
-                self.cursor.col = col;
-            },
-            MouseState::ExtendingSelection => {
-                // Extend existing selection
-                if let Some(sel) = &mut self.selection {
-                    sel.end_row = row;
-                    sel.end_col = col;
-                    sel.clamp_to_buffer(&self.lines);
-                }
-                
-                // Update cursor to current position
-                self.cursor.row = row;
-                self.cursor.col = col;
-            },
-            MouseState::Idle => {
-                // Start new selection
-                self.mouse_state = MouseState::Selecting { start_row: row, start_col: col };
-            }
-        }
-    }
-
-    /// Handle mouse release - finalize selection
-    pub fn handle_mouse_release(&mut self) {
-        use crate::corelogic::buffer::MouseState;
-        self.mouse_state = MouseState::Idle;
-    }
-
-    /// Handle double-click - select word at position
-    pub fn handle_double_click(&mut self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
-        let (row, col) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
-        
-        if row < self.lines.len() {
-            let line = &self.lines[row];
-            let chars: Vec<char> = line.chars().collect();
-            
-            if col < chars.len() {
-                // Find word boundaries
-                let mut start_col = col;
-                let mut end_col = col;
-                
-                // Move start back to beginning of word
-                while start_col > 0 && (chars[start_col - 1].is_alphanumeric() || chars[start_col - 1] == '_') {
-                    start_col -= 1;
-                }
-                
-                // Move end forward to end of word
-                while end_col < chars.len() && (chars[end_col].is_alphanumeric() || chars[end_col] == '_') {
-                    end_col += 1;
-                }
-                
-                // Create selection for the word
-                if start_col < end_col {
-                    let mut sel = crate::corelogic::selection::Selection::new(row, start_col);
-                    sel.end_row = row;
-                    sel.end_col = end_col;
-                    self.selection = Some(sel);
-                    
-                    // Position cursor at end of selection
-                    self.cursor.row = row;
-                    self.cursor.col = end_col;
-                }
-            }
-        }
-        
-        use crate::corelogic::buffer::MouseState;
-        self.mouse_state = MouseState::Idle;
-    }
-
-    /// Handle triple-click - select entire line
-    pub fn handle_triple_click(&mut self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
-        let (row, _) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
-        
-        if row < self.lines.len() {
-            // Select entire line
-            let mut sel = crate::corelogic::selection::Selection::new(row, 0);
-            sel.end_row = row;
-            sel.end_col = self.lines[row].chars().count();
-            self.selection = Some(sel);
-            
-            // Position cursor at end of line
-            self.cursor.row = row;
-            self.cursor.col = self.lines[row].chars().count();
-        }
-        
-        use crate::corelogic::buffer::MouseState;
-        self.mouse_state = MouseState::Idle;
-    }
-
     /// Get the currently selected text
     pub fn get_selected_text(&self) -> Option<String> {
         if let Some(sel) = &self.selection {
@@ -416,121 +203,152 @@ impl EditorBuffer {
         }
     }
 
-    /// Insert indentation (tab or spaces) at current cursor position or all selected lines
+    /// Insert indentation at the cursor, or one indent level at the start of every selected line
+    ///
+    /// Each selection end (and the cursor) moves with the text of its own row; an end at
+    /// column 0 stays there, so a selection of whole lines keeps covering them. A last row
+    /// the selection only reaches at column 0 is left alone.
     pub fn indent(&mut self) {
+        let unit = self.indent_style().unit();
+        let Some(rows) = self.indent_rows() else {
+            // No selection: Tab types the indent string
+            self.insert_text(&unit);
+            return;
+        };
         self.push_undo();
-        
-        // For now, use hardcoded defaults - can be made configurable later
-        // Use 4 spaces as default indent
-        let indent_str = "    ";
-        
-        if let Some(sel) = &self.selection {
-            // Indent all selected lines
-            let ((start_row, _), (end_row, _)) = sel.normalized();
-            
-            for row in start_row..=end_row {
-                if row < self.lines.len() {
-                    self.lines[row].insert_str(0, indent_str);
-                }
-            }
-            
-            // Adjust cursor and selection to account for added indentation
-            self.cursor.col += indent_str.len();
-            
-            // Update selection to reflect the new positions
-            if let Some(sel) = &mut self.selection {
-                sel.start_col += indent_str.len();
-                sel.end_col += indent_str.len();
+        let inserted = unit.chars().count();
+        for row in rows {
+            if self.replace_range((row, 0), (row, 0), &unit).is_some() {
+                self.shift_row_positions(row, inserted, 0);
             }
-        } else {
-            // Single line indent - use existing insert_text logic
-            self.insert_text(indent_str);
         }
     }
 
-    /// Remove one level of indentation from current line or all selected lines
+    /// Remove one level of indentation from the current line or every selected line
+    ///
+    /// Positions move by what was removed from their own row.
     pub fn unindent(&mut self) {
+        let width = self.indent_style().width.max(1);
+        let rows = self.indent_rows().unwrap_or(self.cursor.row..=self.cursor.row);
+        let removals: Vec<(usize, usize)> = rows
+            .filter_map(|row| Some((row, unindent_width(self.lines.get(row)?, width))))
+            .filter(|&(_, removed)| removed > 0)
+            .collect();
+        // Lines without indentation leave nothing to undo
+        if removals.is_empty() {
+            return;
+        }
         self.push_undo();
-        
-        if let Some(sel) = &self.selection {
-            // Unindent all selected lines
-            let ((start_row, _), (end_row, _)) = sel.normalized();
-            let mut total_removed_from_cursor_line = 0;
-            let mut total_removed_from_start = 0;
-            let mut total_removed_from_end = 0;
-            
-            for row in start_row..=end_row {
-                if row < self.lines.len() {
-                    let line = &mut self.lines[row];
-                    let removed = unindent_single_line(line);
-                    
-                    // Track removals for cursor and selection adjustment
-                    if row == self.cursor.row {
-                        total_removed_from_cursor_line = removed;
-                    }
-                    if row == start_row {
-                        total_removed_from_start = removed;
-                    }
-                    if row == end_row {
-                        total_removed_from_end = removed;
-                    }
-                }
+        for (row, removed) in removals {
+            if self.replace_range((row, 0), (row, removed), "").is_some() {
+                self.shift_row_positions(row, 0, removed);
             }
-            
-            // Adjust cursor position
-            if self.cursor.col >= total_removed_from_cursor_line {
-                self.cursor.col -= total_removed_from_cursor_line;
-            } else {
-                self.cursor.col = 0;
+        }
+    }
+
+    /// Rows a selection indent applies to, or None without a (non-empty) selection
+    fn indent_rows(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        let sel = self.selection.as_ref().filter(|sel| sel.is_active())?;
+        let ((start_row, _), (end_row, end_col)) = sel.normalized();
+        let end_row = if end_col == 0 && end_row > start_row { end_row - 1 } else { end_row };
+        Some(start_row..=end_row)
+    }
+
+    /// Move the cursor, selection ends and extra cursors on `row` after `removed`
+    /// characters at its start were replaced by `inserted` ones
+    fn shift_row_positions(&mut self, row: usize, inserted: usize, removed: usize) {
+        let shift = |col: usize| if col == 0 { 0 } else { col.saturating_sub(removed) + inserted };
+        if self.cursor.row == row {
+            self.cursor.col = shift(self.cursor.col);
+        }
+        if let Some(sel) = &mut self.selection {
+            if sel.start_row == row {
+                sel.start_col = shift(sel.start_col);
             }
-            
-            // Adjust selection positions
-            if let Some(sel) = &mut self.selection {
-                if sel.start_col >= total_removed_from_start {
-                    sel.start_col -= total_removed_from_start;
-                } else {
-                    sel.start_col = 0;
-                }
-                
-                if sel.end_col >= total_removed_from_end {
-                    sel.end_col -= total_removed_from_end;
-                } else {
-                    sel.end_col = 0;
-                }
+            if sel.end_row == row {
+                sel.end_col = shift(sel.end_col);
             }
-        } else {
-            // Single line unindent
-            let line = &mut self.lines[self.cursor.row];
-            let removed = unindent_single_line(line);
-            
-            // Adjust cursor position
-            if self.cursor.col >= removed {
-                self.cursor.col -= removed;
-            } else {
-                self.cursor.col = 0;
+        }
+        for (cursor_row, col) in &mut self.multi_cursors {
+            if *cursor_row == row {
+                *col = shift(*col);
             }
         }
     }
 }
 
-/// Helper function to unindent a single line and return the number of characters removed
-fn unindent_single_line(line: &mut String) -> usize {
-    // Try to remove 4 spaces first
-    if line.starts_with("    ") {
-        line.drain(..4);
-        4
+/// Number of leading whitespace characters making up one indent level of `line`
+///
+/// Spaces count one column and a tab reaches the next multiple of `width`, so mixed
+/// indentation such as "  \t" loses exactly one level.
+fn unindent_width(line: &str, width: usize) -> usize {
+    let mut column = 0;
+    let mut count = 0;
+    for c in line.chars() {
+        if column >= width {
+            break;
+        }
+        match c {
+            ' ' => column += 1,
+            '\t' => column = (column / width + 1) * width,
+            _ => break,
+        }
+        count += 1;
     }
-    // If not 4 spaces, try to remove a tab
-    else if line.starts_with('\t') {
-        line.remove(0);
-        1
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corelogic::selection::Selection;
+
+    fn buffer(text: &[&str], (start_row, start_col): (usize, usize), (end_row, end_col): (usize, usize)) -> EditorBuffer {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = text.iter().map(|s| s.to_string()).collect();
+        buffer.selection = Some(Selection { start_row, start_col, end_row, end_col });
+        buffer.cursor = crate::corelogic::EditorCursor::new(end_row, end_col);
+        buffer
     }
-    // Otherwise, try to remove individual spaces at the beginning
-    else if line.starts_with(' ') {
-        let spaces_to_remove = line.chars().take_while(|&c| c == ' ').count().min(4);
-        line.drain(..spaces_to_remove);
-        spaces_to_remove
-    } else {
-        0
+
+    fn ends(buffer: &EditorBuffer) -> ((usize, usize), (usize, usize), (usize, usize)) {
+        let sel = buffer.selection.as_ref().unwrap();
+        ((sel.start_row, sel.start_col), (sel.end_row, sel.end_col), (buffer.cursor.row, buffer.cursor.col))
+    }
+
+    #[test]
+    fn indent_moves_each_selection_end_with_its_row() {
+        // Mid-line ends on rows with different indentation
+        let mut spaces = buffer(&["fn a() {", "  let x = 1;", "}"], (0, 3), (1, 5));
+        spaces.indent();
+        assert_eq!(spaces.lines, ["    fn a() {", "      let x = 1;", "}"]);
+        assert_eq!(ends(&spaces), ((0, 7), (1, 9), (1, 9)));
+
+        // Whole lines with tabs: ends at column 0 stay put, the row reached at column 0 is skipped
+        let mut tabs = buffer(&["a", "b", "c"], (2, 0), (0, 0));
+        tabs.config.indent_with_tabs = true;
+        tabs.indent();
+        assert_eq!(tabs.lines, ["\ta", "\tb", "c"]);
+        assert_eq!(ends(&tabs), ((2, 0), (0, 0), (0, 0)));
+    }
+
+    #[test]
+    fn unindent_moves_each_selection_end_by_what_its_row_lost() {
+        // Backwards selection; the rows lose 4, 2 and 0 characters
+        let mut mixed = buffer(&["    alpha", "  beta", "gamma"], (2, 3), (0, 6));
+        mixed.unindent();
+        assert_eq!(mixed.lines, ["alpha", "beta", "gamma"]);
+        assert_eq!(ends(&mixed), ((2, 3), (0, 2), (0, 2)));
+
+        // An end inside the removed indentation lands at column 0; "  \t" is one level
+        let mut inside = buffer(&["    a", "  \t\tb"], (0, 2), (1, 5));
+        inside.unindent();
+        assert_eq!(inside.lines, ["a", "\tb"]);
+        assert_eq!(ends(&inside), ((0, 0), (1, 2), (1, 2)));
+
+        // Nothing to remove: no undo step
+        let mut flat = buffer(&["a", "b"], (0, 0), (1, 1));
+        flat.unindent();
+        assert!(!flat.can_undo());
     }
 }
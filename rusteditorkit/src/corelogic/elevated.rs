@@ -0,0 +1,90 @@
+//! Saving with elevated permissions
+//!
+//! A save the user may not perform fails with `FileError::PermissionDenied`. The host can
+//! then ask for confirmation and retry with `save_file_elevated`, which hands the encoded
+//! bytes to the registered `ElevatedWriter`: `pkexec_writer` (polkit via `pkexec tee`) or
+//! any host callback. GIO hosts can use `EditorWidget::save_location_as_admin` (the
+//! `admin://` backend) instead.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use super::buffer::EditorBuffer;
+use super::transforms::FileError;
+
+/// Host callback writing `bytes` to a path with elevated permissions; returns an error message on failure
+pub type ElevatedWriter = Box<dyn Fn(&str, &[u8]) -> Result<(), String>>;
+
+/// Writer running `pkexec tee <path>`, so polkit asks for the administrator password
+pub fn pkexec_writer() -> ElevatedWriter {
+    Box::new(|path, bytes| {
+        let mut child = Command::new("pkexec")
+            .args(["tee", "--", path])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("could not run pkexec: {}", e))?;
+        child.stdin.take()
+            .ok_or_else(|| "pkexec stdin unavailable".to_string())?
+            .write_all(bytes)
+            .map_err(|e| e.to_string())?;
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            // 126: the user dismissed the authentication dialog, 127: not authorized
+            Err(format!("pkexec failed ({}): {}", output.status, String::from_utf8_lossy(&output.stderr).trim()))
+        }
+    })
+}
+
+impl EditorBuffer {
+    /// Set (or remove) the hook used by `save_file_elevated`
+    pub fn set_elevated_writer(&mut self, writer: Option<ElevatedWriter>) {
+        self.elevated_writer = writer;
+    }
+
+    /// Retry a save through the elevated writer after `FileError::PermissionDenied`
+    ///
+    /// Fails with `PermissionDenied` again when no writer is registered.
+    pub fn save_file_elevated(&mut self, path: &str) -> Result<(), FileError> {
+        if let Some(name) = self.viewed_snapshot() {
+            return Err(FileError::SnapshotView { name: name.to_string() });
+        }
+        let Some(writer) = &self.elevated_writer else {
+            return Err(FileError::PermissionDenied { path: path.to_string() });
+        };
+        let bytes = self.encode_file_bytes(path)?;
+        writer(path, &bytes).map_err(|message| FileError::Io { path: path.to_string(), message })?;
+        self.mark_saved(path, self.lines.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::corelogic::dispatcher::CommandError;
+
+    #[test]
+    fn permission_denied_reaches_the_host_and_can_be_retried() {
+        let denied = FileError::from_io("/etc/motd", &std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        let CommandError::FileError(FileError::PermissionDenied { path }) = CommandError::from(denied) else {
+            panic!("permission error lost its type");
+        };
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["motd".into()];
+        assert_eq!(buffer.save_file_elevated(&path), Err(FileError::PermissionDenied { path: path.clone() }));
+        let written = Rc::new(RefCell::new(Vec::new()));
+        let sink = written.clone();
+        buffer.set_elevated_writer(Some(Box::new(move |_, bytes| {
+            sink.borrow_mut().extend_from_slice(bytes);
+            Ok(())
+        })));
+        assert_eq!(buffer.save_file_elevated(&path), Ok(()));
+        assert_eq!(*written.borrow(), b"motd");
+        assert!(!buffer.is_modified());
+    }
+}
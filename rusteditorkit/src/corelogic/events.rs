@@ -0,0 +1,89 @@
+//! Editor event system for RustEditorKit
+//!
+//! Hosts register listeners on the buffer's `EventBus` to be notified about
+//! state changes (statistics, cursor context, file paths, ...) without polling.
+
+use super::caretcontext::CaretContext;
+use super::changes::ChangeOp;
+use super::outline::OutlineEntry;
+use super::statistics::DocumentStatistics;
+use super::title::DisplayTitle;
+
+/// Events emitted by the editor core
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditorEvent {
+    /// Word/page statistics changed after an edit or cursor move
+    StatisticsChanged(DocumentStatistics),
+    /// The outline entries enclosing the cursor changed (outermost first, for breadcrumbs)
+    ScopeChanged(Vec<OutlineEntry>),
+    /// A command panicked; the error was contained and the buffer state repaired
+    CommandPanicked { action: String, message: String },
+    /// The view zoom factor changed (1.0 = configured font size)
+    ZoomChanged(f64),
+    /// Characters on `row` could not be rendered by any font in the fallback chain
+    MissingGlyphs { row: usize, count: usize },
+    /// The text was mutated (only while the change feed is enabled)
+    TextChanged(ChangeOp),
+    /// The buffer's file changed (open, save-as or new file; None for an untitled buffer)
+    PathChanged { old: Option<String>, new: Option<String> },
+    /// The tab/window title changed (name, directory, modified or read-only state)
+    TitleChanged(DisplayTitle),
+    /// The line, word or character at the caret changed (for speech feedback)
+    CaretContextChanged(CaretContext),
+    /// All text was replaced by `EditorBuffer::reset`
+    DocumentReset { lines: usize },
+    /// `PasteClipboard` ran without text: clipboard reads are async, so the owner of the
+    /// clipboard (the widget, or the host) reads it and passes the text to `paste_text`
+    PasteRequested,
+}
+
+/// Identifier returned by `EventBus::connect`, used to disconnect a listener
+pub type ListenerId = usize;
+
+/// Registry of event listeners owned by an `EditorBuffer`
+#[derive(Default)]
+pub struct EventBus {
+    #[allow(clippy::type_complexity)]
+    listeners: Vec<(ListenerId, Box<dyn Fn(&EditorEvent)>)>,
+    next_id: ListenerId,
+}
+
+impl EventBus {
+    /// Create an empty event bus
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a listener, returns an id for `disconnect`
+    pub fn connect<F: Fn(&EditorEvent) + 'static>(&mut self, listener: F) -> ListenerId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.listeners.push((id, Box::new(listener)));
+        id
+    }
+
+    /// Remove a previously registered listener
+    pub fn disconnect(&mut self, id: ListenerId) {
+        self.listeners.retain(|(listener_id, _)| *listener_id != id);
+    }
+
+    /// True if at least one listener is registered
+    pub fn has_listeners(&self) -> bool {
+        !self.listeners.is_empty()
+    }
+
+    /// Deliver an event to all listeners in registration order
+    pub fn emit(&self, event: &EditorEvent) {
+        for (_, listener) in &self.listeners {
+            listener(event);
+        }
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
@@ -1,8 +1,20 @@
 //! File I/O operations for EditorBuffer
 //!
 //! This module contains file loading, saving, and cross-platform file operations.
+//!
+//! Every open and save resolves its path or GVFS URI to a `gio::File`: `read_location`
+//! and `EditorBuffer::write_location` are the only places bytes are read and written.
+//! Local files are written with the crash-safe strategy of `safesave`, other locations
+//! through GIO. The asynchronous `EditorWidget::open_location`/`save_location` use the
+//! same resolution, decoding and error mapping. Builds without the `gtk` feature have no
+//! GIO and read and write local paths only.
 
+#[cfg(feature = "gtk")]
+use gio::prelude::*;
 use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+use super::textformat::TextFormat;
+use super::transforms::FileError;
 
 // Re-export cross-platform file operations
 pub use crate::crossplatform::{
@@ -10,30 +22,69 @@ pub use crate::crossplatform::{
     save_file as x_save_file,
     list_fonts, 
     find_font, 
-    open_font, 
     close_font, 
     FontHandle
 };
+#[cfg(feature = "gtk")]
+pub use crate::crossplatform::open_font;
+
+/// The file at a path or GVFS URI
+#[cfg(feature = "gtk")]
+pub(crate) fn location_file(location: &str) -> gio::File {
+    gio::File::for_commandline_arg(location)
+}
+
+/// Local path of `location`, or None for a URI only GIO can reach
+#[cfg(feature = "gtk")]
+fn local_path(location: &str) -> Option<std::path::PathBuf> {
+    location_file(location).path()
+}
+
+/// Local path of `location`, or None for a URI (without GIO no URI is local)
+#[cfg(not(feature = "gtk"))]
+fn local_path(location: &str) -> Option<std::path::PathBuf> {
+    (!location.contains("://")).then(|| location.into())
+}
+
+/// Error for a URI that cannot be reached without GIO
+#[cfg(not(feature = "gtk"))]
+fn unsupported_location(location: &str) -> FileError {
+    FileError::Io { path: location.to_string(), message: "only local files can be used without the gtk feature".to_string() }
+}
+
+/// Read the whole file at `location`
+#[cfg(feature = "gtk")]
+pub(crate) fn read_location(location: &str) -> Result<Vec<u8>, FileError> {
+    location_file(location).load_contents(gio::Cancellable::NONE)
+        .map(|(bytes, _etag)| bytes.to_vec())
+        .map_err(|e| FileError::from_gio(location, &e))
+}
+
+/// Read the whole file at `location`
+#[cfg(not(feature = "gtk"))]
+pub(crate) fn read_location(location: &str) -> Result<Vec<u8>, FileError> {
+    let path = local_path(location).ok_or_else(|| unsupported_location(location))?;
+    std::fs::read(path).map_err(|e| FileError::from_io(location, &e))
+}
+
+/// True when `location` is a local file whose permissions forbid writing (URIs are
+/// never read-only here)
+fn is_read_only(location: &str) -> bool {
+    local_path(location)
+        .and_then(|path| std::fs::metadata(path).ok())
+        .is_some_and(|meta| meta.permissions().readonly())
+}
 
 impl EditorBuffer {
-    /// Open a file and load its contents into the buffer (cross-platform)
-    pub fn open_file(&mut self, path: &str) -> Result<(), String> {
-        match x_open_file(path) {
-            Ok(lines) => {
-                self.lines = lines;
-                self.cursor.row = 0;
-                self.cursor.col = 0;
-                self.scroll_offset = 0;
-                self.selection = None;
-                self.undo_stack.clear();
-                self.redo_stack.clear();
-                
-                // Ensure we have at least one line
-                if self.lines.is_empty() {
-                    self.lines.push(String::new());
-                }
-                
-                println!("[DEBUG] Opened file: {} ({} lines)", path, self.lines.len());
+    /// Open a file (path or GVFS URI) and load its contents into the buffer
+    ///
+    /// Files matching a registered `FileTransform` are decoded in memory. The encoding,
+    /// BOM and line endings found are kept in `text_format`.
+    pub fn open_file(&mut self, path: &str) -> Result<(), FileError> {
+        let read = read_location(path).and_then(|bytes| self.decode_file_bytes(path, bytes));
+        match read {
+            Ok((lines, format)) => {
+                self.load_lines(path, lines, format);
                 Ok(())
             }
             Err(e) => {
@@ -43,36 +94,148 @@ impl EditorBuffer {
         }
     }
 
+    /// Replace the buffer with freshly read `lines` of the file at `path`, stored in `format`
+    pub(crate) fn load_lines(&mut self, path: &str, lines: Vec<String>, format: TextFormat) {
+        self.remember_position();
+        self.load_lines_unchecked(lines);
+        self.text_format = format;
+        self.clear_document_state();
+        self.set_file_path(Some(path.to_string()));
+        self.file_read_only = is_read_only(path);
+        self.set_saved_lines(self.lines.clone());
+        self.detect_indentation();
+        self.apply_modeline();
+        self.detect_language();
+        self.restore_recent_position(path);
+
+        println!("[DEBUG] Opened file: {} ({} lines)", path, self.lines.len());
+    }
+
     /// Save buffer contents to a file (cross-platform)
-    pub fn save_file(&self, path: &str) -> Result<(), String> {
-        match x_save_file(path, &self.lines) {
-            Ok(()) => {
-                println!("[DEBUG] Saved file: {} ({} lines)", path, self.lines.len());
-                Ok(())
-            }
-            Err(e) => {
-                eprintln!("[ERROR] Failed to save file '{}': {}", path, e);
-                Err(e)
+    ///
+    /// The lines are written back in the buffer's `text_format` (encoding, BOM and line
+    /// endings). Files matching a registered `FileTransform` are encoded before anything
+    /// is written. The write goes through `write_location` (atomic replace and backups per
+    /// config for local files). Fails with `FileError::PermissionDenied` when the user may
+    /// not write the file.
+    ///
+    /// Only writes the file; `save_document` also marks the buffer unmodified.
+    pub fn save_file(&self, path: &str) -> Result<(), FileError> {
+        if let Some(name) = self.viewed_snapshot() {
+            return Err(FileError::SnapshotView { name: name.to_string() });
+        }
+        let written = self.encode_file_bytes(path).and_then(|bytes| {
+            self.write_location(path, &bytes)
+        });
+        if let Err(e) = &written {
+            eprintln!("[ERROR] Failed to save file '{}': {}", path, e);
+        }
+        written
+    }
+
+    /// Save the buffer to `path` and record the written text as the saved state
+    ///
+    /// Modified-line marks and `is_modified` are measured from this state afterwards.
+    pub fn save_document(&mut self, path: &str) -> Result<(), FileError> {
+        self.save_file(path)?;
+        self.mark_saved(path, self.lines.clone());
+        Ok(())
+    }
+
+    /// Save the buffer to `path` and make it the buffer's file
+    ///
+    /// On success the buffer is marked unmodified, the language is re-detected from the
+    /// new name and `EditorEvent::PathChanged` is emitted if the path changed.
+    pub fn save_file_as(&mut self, path: &str) -> Result<(), FileError> {
+        self.save_document(path)?;
+        self.set_file_path(Some(path.to_string()));
+        self.file_read_only = false;
+        self.detect_language();
+        if let Some(store) = self.recent_files.as_mut() {
+            store.record_open(path);
+        }
+        self.remember_position();
+        self.request_redraw();
+        Ok(())
+    }
+
+    /// Change the recorded file path, emitting `EditorEvent::PathChanged` when it differs
+    fn set_file_path(&mut self, path: Option<String>) {
+        if self.file_path == path {
+            return;
+        }
+        let old = std::mem::replace(&mut self.file_path, path.clone());
+        self.events.emit(&EditorEvent::PathChanged { old, new: path });
+    }
+
+    /// True when the file backing the buffer cannot be written by the user
+    pub fn is_file_read_only(&self) -> bool {
+        self.file_read_only
+    }
+
+    /// Write `bytes` to the file at `location`
+    ///
+    /// Local files go through `write_file_bytes`; other locations are replaced through
+    /// GIO, keeping a `~` backup when `config.backup_mode` asks for one.
+    pub(crate) fn write_location(&self, location: &str, bytes: &[u8]) -> Result<(), FileError> {
+        match local_path(location) {
+            Some(path) => self.write_file_bytes(&path, bytes).map_err(|e| FileError::from_io(location, &e)),
+            #[cfg(feature = "gtk")]
+            None => {
+                let backup = self.config.backup_mode != super::safesave::BackupMode::None;
+                location_file(location).replace_contents(bytes, None, backup, gio::FileCreateFlags::NONE, gio::Cancellable::NONE)
+                    .map(|_etag| ())
+                    .map_err(|e| FileError::from_gio(location, &e))
             }
+            #[cfg(not(feature = "gtk"))]
+            None => Err(unsupported_location(location)),
         }
     }
 
+    /// Record that `lines` were written to `path`
+    pub(crate) fn mark_saved(&mut self, path: &str, lines: Vec<String>) {
+        println!("[DEBUG] Saved file: {} ({} lines)", path, lines.len());
+        self.store_snapshot(crate::corelogic::snapshots::SAVE_SNAPSHOT, lines.clone());
+        self.set_saved_lines(lines);
+    }
+
     /// Create a new empty buffer
     pub fn new_file(&mut self) {
-        self.lines = vec![String::new()];
-        self.cursor.row = 0;
-        self.cursor.col = 0;
-        self.scroll_offset = 0;
-        self.selection = None;
-        self.undo_stack.clear();
-        self.redo_stack.clear();
+        self.remember_position();
+        self.load_lines_unchecked(vec![String::new()]);
+        self.clear_document_state();
+        if self.file_path.is_some() {
+            self.untitled_number = super::title::next_untitled_number();
+        }
+        self.set_file_path(None);
+        self.file_read_only = false;
+        self.detected_indent = None;
+        self.modeline = None;
+        self.language = None;
+        self.text_format = TextFormat::default();
+        self.set_saved_lines(self.lines.clone());
         println!("[DEBUG] Created new empty file");
     }
 
+    /// Line number mode for this buffer: the buffer override, else the gutter config
+    pub fn line_number_mode(&self) -> crate::corelogic::gutter::LineNumberMode {
+        self.line_number_mode_override.unwrap_or(self.config.gutter.line_numbers.mode)
+    }
+
+    /// Set (or clear) the buffer-local line number mode
+    pub fn set_line_number_mode(&mut self, mode: Option<crate::corelogic::gutter::LineNumberMode>) {
+        self.line_number_mode_override = mode;
+        self.request_redraw();
+    }
+
     /// Check if the buffer has been modified since last save
     pub fn is_modified(&self) -> bool {
-        // A simple heuristic: if there's anything in the undo stack, consider it modified
-        !self.undo_stack.is_empty()
+        if self.saved_lines.is_empty() {
+            // Never opened or saved: any edit counts
+            !self.undo_stack.is_empty()
+        } else {
+            self.lines != self.saved_lines
+        }
     }
 
     /// Get buffer statistics
@@ -97,12 +260,8 @@ impl EditorBuffer {
     /// Import text into buffer (replacing current content)
     pub fn import_from_text(&mut self, text: &str) {
         self.push_undo();
-        self.lines = text.lines().map(|line| line.to_string()).collect();
-        
-        // Ensure we have at least one line
-        if self.lines.is_empty() {
-            self.lines.push(String::new());
-        }
+        // An empty text still leaves one line
+        self.set_lines(text.lines().map(|line| line.to_string()).collect());
         
         self.cursor.row = 0;
         self.cursor.col = 0;
@@ -128,6 +287,11 @@ impl EditorBuffer {
     }
 }
 
+/// Decode file bytes as text: UTF-8 (an optional BOM is dropped) or BOM-marked UTF-16
+pub fn decode_text(bytes: &[u8]) -> Option<String> {
+    TextFormat::decode(bytes).map(|(text, _)| text)
+}
+
 /// File statistics for display and analysis
 #[derive(Debug, Clone)]
 pub struct FileStats {
@@ -144,3 +308,18 @@ impl std::fmt::Display for FileStats {
                self.lines, self.characters, self.cursor_line, self.cursor_column)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_text_handles_byte_order_marks() {
+        assert_eq!(decode_text(b"plain").as_deref(), Some("plain"));
+        assert_eq!(decode_text(b"\xEF\xBB\xBFbom").as_deref(), Some("bom"));
+        assert_eq!(decode_text(b"\xFF\xFEh\x00\xE9\x00").as_deref(), Some("hé"));
+        assert_eq!(decode_text(b"\xFE\xFF\x00h").as_deref(), Some("h"));
+        assert_eq!(decode_text(b"\xFF\xFEh"), None);
+        assert_eq!(decode_text(b"\xC3"), None);
+    }
+}
@@ -0,0 +1,50 @@
+//! File type detection
+//!
+//! The buffer language (used for per-language settings, highlight rules and export) comes
+//! from the file's modeline when there is one, otherwise from the syntect syntax matching
+//! the file name. Detection runs on open and again when `save_file_as` renames the buffer.
+
+use syntect::parsing::SyntaxSet;
+use super::buffer::EditorBuffer;
+use super::modeline::parse_language;
+
+/// Language id for `path` from the syntax definitions (None for plain text or unknown files)
+pub fn language_for_path(syntax_set: &SyntaxSet, path: &str) -> Option<String> {
+    let path = std::path::Path::new(path);
+    // Extensionless names like "Makefile" are listed as extensions too
+    let (token, syntax) = [path.extension(), path.file_name()].into_iter()
+        .flatten()
+        .map(|token| token.to_string_lossy())
+        .find_map(|token| syntax_set.find_syntax_by_extension(&token).map(|syntax| (token.to_string(), syntax)))?;
+    if syntax.name == syntax_set.find_syntax_plain_text().name {
+        return None;
+    }
+    // Names like "Bourne Again Shell (bash)" are not ids; use the extension for those
+    parse_language(&syntax.name).or_else(|| parse_language(&token))
+}
+
+impl EditorBuffer {
+    /// Set the language from the modeline or the file name (done on open and save-as)
+    pub fn detect_language(&mut self) -> Option<&str> {
+        self.language = self.modeline.as_ref()
+            .and_then(|modeline| modeline.language.clone())
+            .or_else(|| self.file_path.as_deref().and_then(|path| language_for_path(&self.syntax_set, path)));
+        self.language.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_follows_extension() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        assert_eq!(language_for_path(&syntax_set, "/src/main.rs").as_deref(), Some("rust"));
+        assert_eq!(language_for_path(&syntax_set, "notes.md").as_deref(), Some("markdown"));
+        assert_eq!(language_for_path(&syntax_set, "a.cpp").as_deref(), Some("cpp"));
+        assert_eq!(language_for_path(&syntax_set, "Makefile").as_deref(), Some("makefile"));
+        assert_eq!(language_for_path(&syntax_set, "run.sh").as_deref(), Some("shell"));
+        assert_eq!(language_for_path(&syntax_set, "notes.txt"), None);
+    }
+}
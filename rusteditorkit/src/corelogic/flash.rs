@@ -0,0 +1,58 @@
+//! Line flash shown after the caret jumps (search, goto, page/heading navigation)
+//!
+//! The buffer only records which row is flashing and how far the fade has progressed.
+//! The widget drives `advance_line_flash` from the GTK frame clock while a flash is
+//! active, so nothing ticks when the editor is idle.
+
+use super::buffer::EditorBuffer;
+use super::redraw::RedrawLayers;
+
+/// A fading highlight on a single row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineFlash {
+    pub row: usize,
+    /// Frame time (microseconds) of the first frame, set on the first tick
+    pub start_time: Option<i64>,
+    /// Fade progress from 0.0 (full color) to 1.0 (gone)
+    pub progress: f64,
+}
+
+impl EditorBuffer {
+    /// Start flashing `row` if line flash is enabled
+    pub fn flash_line(&mut self, row: usize) {
+        if !self.config.line_flash || row >= self.lines.len() {
+            return;
+        }
+        self.line_flash = Some(LineFlash { row, start_time: None, progress: 0.0 });
+        self.invalidate(RedrawLayers::OVERLAY);
+    }
+
+    /// Move the cursor to `(row, col)` (clamped) and flash the target line
+    pub fn goto_position(&mut self, row: usize, col: usize) {
+        let row = row.min(self.lines.len().saturating_sub(1));
+        let col = col.min(self.lines.get(row).map_or(0, |l| l.chars().count()));
+        self.cursor.row = row;
+        self.cursor.col = col;
+        self.selection = None;
+        let scroll_before = self.scroll_position();
+        self.cancel_smooth_scroll();
+        self.ensure_cursor_visible();
+        self.animate_scroll_from(scroll_before);
+        self.flash_line(row);
+    }
+
+    /// Advance the fade to `frame_time` (microseconds); returns false once the flash is over
+    pub fn advance_line_flash(&mut self, frame_time: i64) -> bool {
+        let duration = self.config.line_flash_duration_ms.max(1) as f64 * 1000.0;
+        let Some(flash) = self.line_flash.as_mut() else {
+            return false;
+        };
+        let start = *flash.start_time.get_or_insert(frame_time);
+        flash.progress = ((frame_time - start) as f64 / duration).clamp(0.0, 1.0);
+        if flash.progress >= 1.0 {
+            self.line_flash = None;
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,126 @@
+//! Focus (zen) mode helpers for EditorBuffer
+//!
+//! In zen mode text outside the focused region (current line, sentence or paragraph) is
+//! dimmed by the focus render layer. The region is recomputed after each command and a
+//! redraw is only requested when it actually changes.
+
+use serde::Deserialize;
+use super::buffer::EditorBuffer;
+
+/// Unit of text kept at full opacity in zen mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum FocusGranularity {
+    Line,
+    Sentence,
+    #[default]
+    Paragraph,
+}
+
+/// Focused region as `((start_row, start_col), (end_row, end_col))`, end exclusive
+pub type FocusRange = ((usize, usize), (usize, usize));
+
+/// Bounds of the sentence containing `cursor` within the paragraph rows `start..=end`
+///
+/// A sentence ends after `.`, `!` or `?` followed by whitespace or the end of a line.
+pub fn sentence_range(lines: &[String], (start, end): (usize, usize), cursor: (usize, usize)) -> FocusRange {
+    // Flatten the paragraph; line breaks count as a space at (row, line_len)
+    let mut chars: Vec<(usize, usize, char)> = Vec::new();
+    for (row, line) in lines.iter().enumerate().take(end + 1).skip(start) {
+        chars.extend(line.chars().enumerate().map(|(col, c)| (row, col, c)));
+        if row < end {
+            chars.push((row, line.chars().count(), ' '));
+        }
+    }
+    let cursor_index = chars.iter()
+        .position(|&(row, col, _)| (row, col) >= cursor)
+        .unwrap_or(chars.len());
+    let position = |index: usize| -> (usize, usize) {
+        match chars.get(index) {
+            Some(&(row, col, _)) => (row, col),
+            None => (end, lines[end].chars().count()),
+        }
+    };
+
+    let mut sentence_start = chars.iter().position(|&(_, _, c)| !c.is_whitespace()).unwrap_or(0);
+    let mut i = sentence_start;
+    while i < chars.len() {
+        let is_end = matches!(chars[i].2, '.' | '!' | '?')
+            && chars.get(i + 1).is_none_or(|&(_, _, c)| c.is_whitespace());
+        if is_end {
+            if cursor_index <= i + 1 {
+                let (end_row, end_col) = position(i);
+                return (position(sentence_start), (end_row, end_col + 1));
+            }
+            sentence_start = (i + 1..chars.len())
+                .find(|&j| !chars[j].2.is_whitespace())
+                .unwrap_or(chars.len());
+            i = sentence_start;
+            continue;
+        }
+        i += 1;
+    }
+    (position(sentence_start), position(chars.len()))
+}
+
+impl EditorBuffer {
+    /// Row range `(start, end)` of the paragraph containing `row` (`None` on a blank line)
+    pub fn paragraph_range(&self, row: usize) -> Option<(usize, usize)> {
+        let is_blank = |r: usize| self.lines[r].trim().is_empty();
+        if row >= self.lines.len() || is_blank(row) {
+            return None;
+        }
+        let mut start = row;
+        while start > 0 && !is_blank(start - 1) {
+            start -= 1;
+        }
+        let mut end = row;
+        while end + 1 < self.lines.len() && !is_blank(end + 1) {
+            end += 1;
+        }
+        Some((start, end))
+    }
+
+    /// Region kept at full opacity in zen mode, based on `focus_granularity`
+    pub fn compute_focus_range(&self) -> FocusRange {
+        let row = self.cursor.row.min(self.lines.len().saturating_sub(1));
+        let line_end = |r: usize| self.lines.get(r).map(|l| l.chars().count()).unwrap_or(0);
+        let whole_line = ((row, 0), (row, line_end(row)));
+        match self.config.focus_granularity {
+            FocusGranularity::Line => whole_line,
+            FocusGranularity::Paragraph => match self.paragraph_range(row) {
+                Some((start, end)) => ((start, 0), (end, line_end(end))),
+                None => whole_line,
+            },
+            FocusGranularity::Sentence => match self.paragraph_range(row) {
+                Some(paragraph) => sentence_range(&self.lines, paragraph, (row, self.cursor.col)),
+                None => whole_line,
+            },
+        }
+    }
+
+    /// Recompute the focus region; returns true if it changed and a redraw is needed
+    pub fn refresh_focus(&mut self) -> bool {
+        if !self.config.zen_mode {
+            return self.focus_range.take().is_some();
+        }
+        let range = self.compute_focus_range();
+        if self.focus_range == Some(range) {
+            return false;
+        }
+        self.focus_range = Some(range);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sentence_range_spans_the_sentences_the_selection_touches() {
+        let lines: Vec<String> = ["One two. Three", "four! Five?"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(sentence_range(&lines, (0, 1), (0, 3)), ((0, 0), (0, 8)));
+        assert_eq!(sentence_range(&lines, (0, 1), (1, 2)), ((0, 9), (1, 5)));
+        assert_eq!(sentence_range(&lines, (0, 1), (1, 11)), ((1, 6), (1, 11)));
+    }
+}
@@ -0,0 +1,347 @@
+//! Folding of row ranges
+//!
+//! A fold keeps its first row (the header) visible and hides the rows after it up to
+//! `end` while collapsed; the header can carry a summary drawn after its text. With folds
+//! collapsed, `scroll_offset` counts display rows (hidden rows take no space) while
+//! cursor positions stay in buffer rows. Log buffers fold runs of repeated lines
+//! automatically ("last line repeated N times"), see `fold_repeated_lines`.
+
+use std::collections::BTreeMap;
+use super::buffer::EditorBuffer;
+use super::edit::RowEdit;
+use crate::keybinds::EditorAction;
+
+/// Shortest run of repeated log lines that is folded (the header plus two repeats)
+pub const MIN_REPEAT_RUN: usize = 3;
+
+/// Why a fold exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldKind {
+    /// Added by the host (`add_fold`)
+    Manual,
+    /// A run of repeated lines in a log buffer
+    Repeat,
+}
+
+/// A foldable range starting at its key row in `EditorBuffer::folds`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldRegion {
+    /// Last row of the range (inclusive)
+    pub end: usize,
+    pub collapsed: bool,
+    pub kind: FoldKind,
+    /// Text drawn after the header row while collapsed
+    pub summary: Option<String>,
+}
+
+/// Rows hidden by collapsed folds, for mapping between buffer rows and display rows
+#[derive(Debug, Clone, Default)]
+pub struct HiddenRows {
+    /// Sorted, non-overlapping inclusive ranges
+    ranges: Vec<(usize, usize)>,
+    /// Hidden rows in `ranges[..i]`
+    before: Vec<usize>,
+}
+
+impl HiddenRows {
+    fn new(ranges: Vec<(usize, usize)>) -> Self {
+        let before = ranges.iter()
+            .scan(0, |hidden, &(from, to)| {
+                let count = *hidden;
+                *hidden += to - from + 1;
+                Some(count)
+            })
+            .collect();
+        Self { ranges, before }
+    }
+
+    /// True if no row is hidden
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Index of the last range starting at or before `row`
+    fn range_before(&self, row: usize) -> Option<usize> {
+        self.ranges.partition_point(|&(from, _)| from <= row).checked_sub(1)
+    }
+
+    /// The hidden range containing `row`
+    pub fn range_containing(&self, row: usize) -> Option<(usize, usize)> {
+        self.range_before(row).map(|i| self.ranges[i]).filter(|&(_, to)| row <= to)
+    }
+
+    pub fn is_hidden(&self, row: usize) -> bool {
+        self.range_containing(row).is_some()
+    }
+
+    /// Position of `row` among the displayed rows (a hidden row maps to its fold header)
+    pub fn display_row(&self, row: usize) -> usize {
+        match self.range_before(row) {
+            Some(i) => {
+                let (from, to) = self.ranges[i];
+                row - self.before[i] - (to.min(row) - from + 1)
+            }
+            None => row,
+        }
+    }
+
+    /// Buffer row displayed at position `display`
+    pub fn row_at_display(&self, display: usize) -> usize {
+        let mut row = display;
+        for &(from, to) in &self.ranges {
+            if from > row {
+                break;
+            }
+            row += to - from + 1;
+        }
+        row
+    }
+
+    /// Total number of hidden rows
+    pub fn count(&self) -> usize {
+        self.ranges.last().map_or(0, |&(from, to)| self.before[self.ranges.len() - 1] + to - from + 1)
+    }
+}
+
+/// Line with digit runs masked, so lines differing only in timestamps or counters compare equal
+pub fn repeat_key(line: &str) -> String {
+    let mut key = String::with_capacity(line.len());
+    let mut in_digits = false;
+    for c in line.chars() {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                key.push('#');
+            }
+            in_digits = true;
+        } else {
+            key.push(c);
+            in_digits = false;
+        }
+    }
+    key
+}
+
+/// Runs of at least `MIN_REPEAT_RUN` near-identical, non-blank lines from `from` on, as (start, end)
+pub fn repeated_runs(lines: &[String], from: usize) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = from;
+    while start < lines.len() {
+        let key = repeat_key(&lines[start]);
+        let mut end = start;
+        while end + 1 < lines.len() && repeat_key(&lines[end + 1]) == key {
+            end += 1;
+        }
+        if end + 1 - start >= MIN_REPEAT_RUN && !key.trim().is_empty() {
+            runs.push((start, end));
+        }
+        start = end + 1;
+    }
+    runs
+}
+
+impl EditorBuffer {
+    /// Add a collapsed fold over `start..=end` (rows after `start` are hidden)
+    ///
+    /// Replaces a fold starting at the same row; ranges outside the buffer are ignored.
+    pub fn add_fold(&mut self, start: usize, end: usize, summary: Option<String>) {
+        if start >= end || end >= self.lines.len() {
+            return;
+        }
+        self.folds.insert(start, FoldRegion { end, collapsed: true, kind: FoldKind::Manual, summary });
+        self.reveal_cursor_row();
+        self.request_redraw();
+    }
+
+    /// Remove the fold starting at `start`, returns false if there was none
+    pub fn remove_fold(&mut self, start: usize) -> bool {
+        let removed = self.folds.remove(&start).is_some();
+        if removed {
+            self.request_redraw();
+        }
+        removed
+    }
+
+    /// All folds as (start row, region)
+    pub fn folds(&self) -> impl Iterator<Item = (usize, &FoldRegion)> {
+        self.folds.iter().map(|(&start, fold)| (start, fold))
+    }
+
+    /// Start row of the innermost fold containing `row`
+    pub fn fold_at(&self, row: usize) -> Option<usize> {
+        self.folds.range(..=row).rev()
+            .find(|(_, fold)| fold.end >= row)
+            .map(|(&start, _)| start)
+    }
+
+    /// Collapse or expand the fold containing `row`, returns false if there is none
+    ///
+    /// A cursor that would end up hidden moves to the fold header.
+    pub fn toggle_fold(&mut self, row: usize) -> bool {
+        let Some(fold) = self.fold_at(row).and_then(|start| self.folds.get_mut(&start)) else {
+            return false;
+        };
+        fold.collapsed = !fold.collapsed;
+        if let Some((from, _)) = self.hidden_rows().range_containing(self.cursor.row) {
+            self.cursor.row = from - 1;
+            self.cursor.col = 0;
+        }
+        self.request_redraw();
+        true
+    }
+
+    /// Rows currently hidden by collapsed folds
+    pub fn hidden_rows(&self) -> HiddenRows {
+        let last = self.lines.len().saturating_sub(1);
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (&start, fold) in self.folds.iter().filter(|(_, fold)| fold.collapsed) {
+            let (from, to) = (start + 1, fold.end.min(last));
+            match ranges.last_mut() {
+                // A fold whose header is already hidden extends the range
+                Some(range) if from <= range.1 + 1 => range.1 = range.1.max(to),
+                _ if from <= to => ranges.push((from, to)),
+                _ => {}
+            }
+        }
+        HiddenRows::new(ranges)
+    }
+
+    /// True if `row` is inside a collapsed fold (headers are never hidden)
+    pub fn is_row_hidden(&self, row: usize) -> bool {
+        !self.folds.is_empty() && self.hidden_rows().is_hidden(row)
+    }
+
+    /// Position of `row` among the displayed rows
+    pub fn display_row(&self, row: usize) -> usize {
+        if self.folds.is_empty() { row } else { self.hidden_rows().display_row(row) }
+    }
+
+    /// Buffer row displayed at position `display`
+    pub fn row_at_display(&self, display: usize) -> usize {
+        let row = if self.folds.is_empty() { display } else { self.hidden_rows().row_at_display(display) };
+        row.min(self.lines.len().saturating_sub(1))
+    }
+
+    /// Number of displayed rows
+    pub fn display_row_count(&self) -> usize {
+        if self.folds.is_empty() {
+            return self.lines.len();
+        }
+        self.lines.len() - self.hidden_rows().count()
+    }
+
+    /// Keep the cursor off hidden rows after `action`
+    ///
+    /// Up/Down movement steps over a collapsed fold (landing on its header going up);
+    /// any other command that puts the cursor inside one expands it.
+    pub(crate) fn settle_cursor_after_fold(&mut self, action: EditorAction) {
+        if self.folds.is_empty() {
+            return;
+        }
+        let Some((from, to)) = self.hidden_rows().range_containing(self.cursor.row) else {
+            return;
+        };
+        match action {
+            EditorAction::MoveCursorDown | EditorAction::SelectDown | EditorAction::MoveCursorPageDown
+                if to + 1 < self.lines.len() => self.cursor.row = to + 1,
+            EditorAction::MoveCursorUp | EditorAction::SelectUp | EditorAction::MoveCursorPageUp |
+            EditorAction::MoveCursorDown | EditorAction::SelectDown | EditorAction::MoveCursorPageDown => self.cursor.row = from - 1,
+            _ => {
+                self.reveal_cursor_row();
+                return;
+            }
+        }
+        self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].chars().count());
+    }
+
+    /// Expand every fold hiding the cursor row
+    pub(crate) fn reveal_cursor_row(&mut self) {
+        let row = self.cursor.row;
+        for (_, fold) in self.folds.range_mut(..row).filter(|(_, fold)| fold.end >= row) {
+            fold.collapsed = false;
+        }
+    }
+
+    /// Move the folds with an edit of the text, dropping folds whose header was removed
+    /// or that no longer fit the text
+    pub(crate) fn map_folds(&mut self, edit: &RowEdit) {
+        let len = self.lines.len();
+        self.folds = std::mem::take(&mut self.folds).into_iter()
+            .filter_map(|(start, mut fold)| {
+                fold.end = edit.map_row(fold.end)?;
+                Some((edit.map_row(start)?, fold))
+            })
+            .filter(|(start, fold)| *start < fold.end && fold.end < len)
+            .collect();
+    }
+
+    /// Fold runs of repeated lines at or after `from` (log buffers)
+    ///
+    /// A repeat fold the new lines extend is rebuilt and keeps its expanded state.
+    pub(crate) fn fold_repeated_lines(&mut self, from: usize) {
+        // Start over at the run the rows before `from` belong to
+        let mut from = self.folds.range(..from).next_back()
+            .filter(|(_, fold)| fold.kind == FoldKind::Repeat && fold.end + 1 >= from)
+            .map_or(from, |(&start, _)| start);
+        while from > 0 && from < self.lines.len() && repeat_key(&self.lines[from - 1]) == repeat_key(&self.lines[from]) {
+            from -= 1;
+        }
+        let mut previous: BTreeMap<usize, FoldRegion> = self.folds.split_off(&from);
+        previous.retain(|&start, fold| {
+            let manual = fold.kind == FoldKind::Manual;
+            if manual {
+                self.folds.insert(start, fold.clone());
+            }
+            !manual
+        });
+        for (start, end) in repeated_runs(&self.lines, from) {
+            let collapsed = previous.get(&start).is_none_or(|fold| fold.collapsed);
+            let summary = format!("last line repeated {} times", end - start);
+            self.folds.entry(start).or_insert(FoldRegion { end, collapsed, kind: FoldKind::Repeat, summary: Some(summary) });
+        }
+        self.reveal_cursor_row();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn repeated_runs_ignore_changing_numbers() {
+        let log = lines("start\n10:01 retry 1\n10:02 retry 2\n10:03 retry 3\ndone\nx\nx\n\n\n\n");
+        assert_eq!(repeated_runs(&log, 0), vec![(1, 3)]);
+        assert!(repeated_runs(&log, 2).is_empty());
+    }
+
+    #[test]
+    fn hidden_rows_map_display_positions() {
+        let hidden = HiddenRows::new(vec![(2, 4), (7, 7)]);
+        assert_eq!(hidden.count(), 4);
+        assert_eq!((hidden.display_row(1), hidden.display_row(3), hidden.display_row(5)), (1, 1, 2));
+        assert_eq!((hidden.display_row(6), hidden.display_row(8)), (3, 4));
+        assert_eq!((0..6).map(|d| hidden.row_at_display(d)).collect::<Vec<_>>(), vec![0, 1, 5, 6, 8, 9]);
+        assert!(hidden.is_hidden(7) && !hidden.is_hidden(6) && !hidden.is_hidden(1));
+    }
+
+    #[test]
+    fn folds_follow_edits_above_them() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = lines("a\nb\nc\nd\ne\nf\ng");
+        buffer.add_fold(3, 5, None);
+        // Backspace at the start of a line joins it to the one above
+        buffer.cursor = crate::corelogic::EditorCursor::new(1, 0);
+        buffer.backspace();
+        assert_eq!(buffer.folds.keys().copied().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(buffer.folds[&2].end, 4);
+        buffer.undo();
+        assert_eq!(buffer.folds.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(buffer.folds[&3].end, 5);
+        // Removing the header drops the fold
+        buffer.replace_rows(2..4, Vec::new());
+        assert!(buffer.folds.is_empty());
+    }
+}
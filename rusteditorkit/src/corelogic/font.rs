@@ -1,5 +1,6 @@
 // Modular FontConfig and font API for RustEditorKit (cross-platform)
 use serde::Deserialize;
+use super::color::{Color, ThemeColor};
 
 /// Modular font configuration for the editor core and widgets
 #[derive(Debug, Clone, Deserialize)]
@@ -7,7 +8,7 @@ pub struct FontConfig {
     // === Basic Font Settings ===
     pub font_name: String,
     pub font_size: f64,
-    pub font_color: String,
+    pub font_color: ThemeColor,
 
     // === Layout & Spacing ===
     pub font_line_height: f64,
@@ -24,7 +25,7 @@ pub struct FontConfig {
     pub font_antialias: bool,
     pub font_hinting: String,     // "None", "Slight", "Medium", "Full"
     pub font_ligatures: bool,
-    pub font_subpixel_order: String,
+    pub font_subpixel_order: String, // "RGB", "BGR", "VRGB", "VBGR", "None", or "Auto" to follow the monitor
 
     // === Advanced Typography ===
     pub font_features: Vec<String>,
@@ -36,7 +37,7 @@ pub struct FontConfig {
 
     // === Shadow / Glow ===
     pub font_shadow_toggle: bool,
-    pub font_shadow_color: String,
+    pub font_shadow_color: ThemeColor,
     pub font_shadow_offset_x: f64,
     pub font_shadow_offset_y: f64,
     pub font_shadow_blur_radius: f64,
@@ -52,7 +53,7 @@ impl Default for FontConfig {
         Self {
             font_name: "Fira Code".to_string(),
             font_size: 14.0,
-            font_color: "#222222".to_string(),
+            font_color: Color::hex(0x222222).into(),
             font_line_height: 22.0,
             font_character_spacing: 0.0,
             font_word_spacing: 0.0,
@@ -63,7 +64,7 @@ impl Default for FontConfig {
             font_antialias: true,
             font_hinting: "Medium".to_string(),
             font_ligatures: true,
-            font_subpixel_order: "RGB".to_string(),
+            font_subpixel_order: "Auto".to_string(),
             font_features: vec!["kern".to_string(), "liga".to_string(), "clig".to_string()],
             font_variants: vec!["normal".to_string()],
             font_language: "en".to_string(),
@@ -74,7 +75,7 @@ impl Default for FontConfig {
                 "Monospace".to_string(),
             ],
             font_shadow_toggle: false,
-            font_shadow_color: "#888888".to_string(),
+            font_shadow_color: Color::hex(0x888888).into(),
             font_shadow_offset_x: 1.0,
             font_shadow_offset_y: 1.0,
             font_shadow_blur_radius: 2.0,
@@ -90,8 +91,8 @@ impl FontConfig {
     pub fn font_name(&self) -> &str { &self.font_name }
     pub fn set_font_size(&mut self, size: f64) { self.font_size = size; }
     pub fn font_size(&self) -> f64 { self.font_size }
-    pub fn set_font_color(&mut self, color: &str) { self.font_color = color.to_string(); }
-    pub fn font_color(&self) -> &str { &self.font_color }
+    pub fn set_font_color(&mut self, color: ThemeColor) { self.font_color = color; }
+    pub fn font_color(&self) -> &ThemeColor { &self.font_color }
     pub fn set_font_line_height(&mut self, h: f64) { self.font_line_height = h; }
     pub fn font_line_height(&self) -> f64 { self.font_line_height }
     pub fn set_font_character_spacing(&mut self, s: f64) { self.font_character_spacing = s; }
@@ -122,10 +123,21 @@ impl FontConfig {
     pub fn font_language(&self) -> &str { &self.font_language }
     pub fn set_font_fallbacks(&mut self, f: Vec<String>) { self.font_fallbacks = f; }
     pub fn font_fallbacks(&self) -> &Vec<String> { &self.font_fallbacks }
+    /// Primary font followed by the fallbacks, as a comma separated Pango family list
+    pub fn font_family_chain(&self) -> String {
+        let mut families: Vec<&str> = Vec::new();
+        for family in std::iter::once(&self.font_name).chain(&self.font_fallbacks) {
+            let family = family.trim();
+            if !family.is_empty() && !families.iter().any(|f| f.eq_ignore_ascii_case(family)) {
+                families.push(family);
+            }
+        }
+        families.join(",")
+    }
     pub fn set_font_shadow_toggle(&mut self, v: bool) { self.font_shadow_toggle = v; }
     pub fn font_shadow_toggle(&self) -> bool { self.font_shadow_toggle }
-    pub fn set_font_shadow_color(&mut self, c: &str) { self.font_shadow_color = c.to_string(); }
-    pub fn font_shadow_color(&self) -> &str { &self.font_shadow_color }
+    pub fn set_font_shadow_color(&mut self, c: ThemeColor) { self.font_shadow_color = c; }
+    pub fn font_shadow_color(&self) -> &ThemeColor { &self.font_shadow_color }
     pub fn set_font_shadow_offset_x(&mut self, x: f64) { self.font_shadow_offset_x = x; }
     pub fn font_shadow_offset_x(&self) -> f64 { self.font_shadow_offset_x }
     pub fn set_font_shadow_offset_y(&mut self, y: f64) { self.font_shadow_offset_y = y; }
@@ -137,3 +149,16 @@ impl FontConfig {
     pub fn set_font_use_core_text(&mut self, v: bool) { self.font_use_core_text = v; }
     pub fn font_use_core_text(&self) -> bool { self.font_use_core_text }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_chain_skips_duplicates() {
+        let mut font = FontConfig::default();
+        font.set_font_name("DejaVu Sans Mono");
+        font.set_font_fallbacks(vec!["Noto Color Emoji".into(), " dejavu sans mono ".into(), "".into()]);
+        assert_eq!(font.font_family_chain(), "DejaVu Sans Mono,Noto Color Emoji");
+    }
+}
@@ -0,0 +1,180 @@
+//! Font rendering options for EditorBuffer
+//!
+//! Resolves the `font_antialias`, `font_hinting` and `font_subpixel_order` config fields,
+//! together with the subpixel layout of the monitor the view is shown on, into the
+//! options the render pipeline applies to every Pango layout.
+//!
+//! Light text on a dark `editor_bg_color` tends to look thinner than dark-on-light text.
+//! The `dark_text_*` fields compensate on dark backgrounds only: a gamma lift of the text
+//! color, a separate hinting level and a heavier weight (stem darkening).
+
+use super::buffer::EditorBuffer;
+use super::font::FontConfig;
+use super::color::Color;
+
+/// Order of the colour subpixels on the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubpixelOrder {
+    /// No subpixel rendering (grayscale antialiasing)
+    None,
+    Rgb,
+    Bgr,
+    Vrgb,
+    Vbgr,
+}
+
+impl SubpixelOrder {
+    /// Parse a `font_subpixel_order` value; `None` for "Auto" (follow the monitor) or unknown values
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "rgb" => Some(Self::Rgb),
+            "bgr" => Some(Self::Bgr),
+            "vrgb" => Some(Self::Vrgb),
+            "vbgr" => Some(Self::Vbgr),
+            _ => None,
+        }
+    }
+}
+
+/// Glyph outline hinting strength
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintLevel {
+    None,
+    Slight,
+    Medium,
+    Full,
+}
+
+impl HintLevel {
+    /// Parse a `font_hinting` value; unknown values fall back to Medium
+    pub fn from_config(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Self::None,
+            "slight" => Self::Slight,
+            "full" => Self::Full,
+            _ => Self::Medium,
+        }
+    }
+}
+
+/// Effective font rendering options for the current view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontRenderOptions {
+    pub antialias: bool,
+    pub hinting: HintLevel,
+    pub subpixel: SubpixelOrder,
+}
+
+impl FontRenderOptions {
+    /// An explicit subpixel order in the config wins; "Auto" uses the monitor's (grayscale if unknown)
+    pub fn resolve(font: &FontConfig, monitor: Option<SubpixelOrder>) -> Self {
+        let subpixel = SubpixelOrder::from_config(font.font_subpixel_order())
+            .or(monitor)
+            .unwrap_or(SubpixelOrder::None);
+        Self {
+            antialias: font.font_antialias(),
+            hinting: HintLevel::from_config(font.font_hinting()),
+            subpixel,
+        }
+    }
+}
+
+/// True for colors with a relative luminance below one half
+pub fn is_dark_color(color: Color) -> bool {
+    color.luminance() < 0.5
+}
+
+/// Raise the color channels to `1 / gamma` (gamma above 1.0 brightens, 1.0 keeps the color)
+pub fn apply_gamma((r, g, b, a): (f64, f64, f64, f64), gamma: f64) -> (f64, f64, f64, f64) {
+    if gamma <= 0.0 {
+        return (r, g, b, a);
+    }
+    let lift = |c: f64| c.clamp(0.0, 1.0).powf(1.0 / gamma);
+    (lift(r), lift(g), lift(b), a)
+}
+
+impl EditorBuffer {
+    /// Font rendering options resolved against the current monitor and background
+    pub fn font_render_options(&self) -> FontRenderOptions {
+        let mut options = FontRenderOptions::resolve(&self.config.font, self.monitor_subpixel_order);
+        if self.dark_background() && !self.config.dark_text_hinting.is_empty() {
+            options.hinting = HintLevel::from_config(&self.config.dark_text_hinting);
+        }
+        options
+    }
+
+    /// True when the editor background is dark, so the `dark_text_*` tuning applies
+    pub fn dark_background(&self) -> bool {
+        is_dark_color(self.config.color(&self.config.editor_bg_color))
+    }
+
+    /// Text color as RGBA, gamma-adjusted on dark backgrounds
+    pub fn text_color(&self) -> (f64, f64, f64, f64) {
+        let color = self.config.color(self.config.font.font_color()).rgba();
+        if self.dark_background() { apply_gamma(color, self.config.dark_text_gamma) } else { color }
+    }
+
+    /// Weight added to the text font (non-zero only on dark backgrounds)
+    pub fn text_weight_boost(&self) -> u16 {
+        if self.dark_background() { self.config.dark_text_weight_boost } else { 0 }
+    }
+
+    /// Change the dark background text tuning at runtime
+    pub fn set_dark_text_tuning(&mut self, gamma: f64, hinting: &str, weight_boost: u16) {
+        self.config.set_dark_text_gamma(gamma);
+        self.config.set_dark_text_hinting(hinting);
+        self.config.set_dark_text_weight_boost(weight_boost);
+        self.invalidate_layout_caches();
+    }
+
+    /// Record the subpixel layout of the monitor the view moved to (`None` if unknown)
+    pub fn set_monitor_subpixel_order(&mut self, order: Option<SubpixelOrder>) {
+        if self.monitor_subpixel_order != order {
+            self.monitor_subpixel_order = order;
+            self.invalidate_layout_caches();
+        }
+    }
+
+    /// Change antialiasing, hinting and subpixel order at runtime
+    pub fn set_font_rendering(&mut self, antialias: bool, hinting: &str, subpixel_order: &str) {
+        let font = &mut self.config.font;
+        font.set_font_antialias(antialias);
+        font.set_font_hinting(hinting);
+        font.set_font_subpixel_order(subpixel_order);
+        self.invalidate_layout_caches();
+    }
+
+    /// Drop anything derived from glyph metrics and redraw
+    pub fn invalidate_layout_caches(&mut self) {
+        self.layout_generation = self.layout_generation.wrapping_add(1);
+        self.request_redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_order_overrides_monitor() {
+        let mut font = FontConfig::default();
+        font.set_font_subpixel_order("BGR");
+        let opts = FontRenderOptions::resolve(&font, Some(SubpixelOrder::Rgb));
+        assert_eq!(opts.subpixel, SubpixelOrder::Bgr);
+
+        font.set_font_subpixel_order("Auto");
+        assert_eq!(FontRenderOptions::resolve(&font, Some(SubpixelOrder::Vrgb)).subpixel, SubpixelOrder::Vrgb);
+        assert_eq!(FontRenderOptions::resolve(&font, None).subpixel, SubpixelOrder::None);
+    }
+
+    #[test]
+    fn gamma_brightens_text_on_dark_backgrounds() {
+        assert!(is_dark_color(Color::hex(0x1e1e1e)));
+        assert!(!is_dark_color(Color::hex(0xf8f8ff)));
+        let (r, _, _, a) = apply_gamma((0.5, 0.5, 0.5, 0.8), 2.0);
+        assert!((r - 0.5f64.sqrt()).abs() < 1e-9);
+        assert_eq!(a, 0.8);
+        assert_eq!(apply_gamma((0.25, 0.5, 1.0, 1.0), 1.0), (0.25, 0.5, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,97 @@
+//! Missing glyph diagnostics for EditorBuffer
+//!
+//! The text layer reports lines containing characters that no font of the fallback chain
+//! can render. Each line is reported once per content through `EditorEvent::MissingGlyphs`
+//! (nothing is printed outside debug mode), so hosts can point users at a missing
+//! emoji/CJK font without being flooded on every frame.
+//!
+//! The reported counts are kept per line and spliced along with the text by the edit
+//! path, so an edited line is checked again while the others cost a lookup per frame.
+
+use super::buffer::EditorBuffer;
+use super::edit::RowEdit;
+use super::events::EditorEvent;
+
+/// Missing glyph counts reported per line
+#[derive(Debug, Default)]
+pub struct MissingGlyphs {
+    /// Count last reported for each line; None until the line is drawn after an edit
+    rows: Vec<Option<usize>>,
+    /// `layout_generation` the counts were measured with
+    generation: u64,
+}
+
+impl MissingGlyphs {
+    /// Forget every count, so each line is reported again when drawn
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Follow an edit of the text: the replaced lines are checked again
+    pub fn rows_replaced(&mut self, edit: &RowEdit, old_len: usize) {
+        edit.splice_rows(&mut self.rows, old_len, None);
+    }
+}
+
+impl EditorBuffer {
+    /// Record the number of unrenderable glyphs on `row`, emitting `EditorEvent::MissingGlyphs` for new findings
+    pub fn report_missing_glyphs(&self, row: usize, count: usize) {
+        let mut seen = self.missing_glyphs.borrow_mut();
+        // Other fonts may cover other characters
+        if seen.generation != self.layout_generation {
+            seen.rows.clear();
+            seen.generation = self.layout_generation;
+        }
+        if seen.rows.len() != self.lines.len() {
+            seen.rows.resize(self.lines.len(), None);
+        }
+        let Some(reported) = seen.rows.get_mut(row) else {
+            return;
+        };
+        if reported.replace(count) == Some(count) || count == 0 {
+            return;
+        }
+        drop(seen);
+        if self.debug_mode {
+            println!("[DEBUG] Line {} has {} glyph(s) missing from fonts {:?}", row + 1, count, self.config.font.font_family_chain());
+        }
+        self.events.emit(&EditorEvent::MissingGlyphs { row, count });
+    }
+
+    /// Rows (as of the last frame) showing at least one missing glyph
+    pub fn missing_glyph_rows(&self) -> Vec<usize> {
+        let seen = self.missing_glyphs.borrow();
+        seen.rows.iter().enumerate().filter(|(_, count)| count.is_some_and(|n| n > 0)).map(|(row, _)| row).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn lines_are_reported_again_only_after_an_edit() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["a\u{e000}".into(), "b\u{e000}".into()];
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let sink = reported.clone();
+        buffer.events.connect(move |event| {
+            if let EditorEvent::MissingGlyphs { row, .. } = event {
+                sink.borrow_mut().push(*row);
+            }
+        });
+        buffer.report_missing_glyphs(0, 1);
+        buffer.report_missing_glyphs(1, 1);
+        buffer.report_missing_glyphs(0, 1);
+        // Row 0 only moves down, the old row 1 changes
+        buffer.replace_range((0, 0), (0, 0), "x\n");
+        buffer.replace_range((2, 0), (2, 0), "y");
+        buffer.report_missing_glyphs(0, 0);
+        buffer.report_missing_glyphs(1, 1);
+        buffer.report_missing_glyphs(2, 1);
+        assert_eq!(*reported.borrow(), [0, 1, 2]);
+        assert_eq!(buffer.missing_glyph_rows(), [1, 2]);
+    }
+}
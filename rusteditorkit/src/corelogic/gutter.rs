@@ -1,60 +1,114 @@
-//! Modular gutter rendering logic for EditorBuffer
+//! Gutter configuration and column layout
+//!
+//! The gutter is a row of independent columns, from the outer edge towards the text:
+//! markers | line numbers | folding | change bars | host annotations. Each column
+//! declares its width here (zero hides it) and is drawn on its own by `render::gutter`,
+//! so a new gutter feature adds a column instead of growing one render function.
 
 
 use serde::Deserialize;
-use gtk4::cairo::Context;
-use gtk4::pango;
-use crate::core::EditorBuffer;
+use crate::corelogic::buffer::EditorBuffer;
+use crate::corelogic::color::{Color, ThemeColor};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct GutterConfig {
     pub toggle: bool,
     pub ltr_width: i32,
     pub padding: i32,
-    pub bg_color: String,
+    pub bg_color: ThemeColor,
     pub border: GutterBorderConfig,
     pub line_numbers: GutterLineNumbersConfig,
     pub font_size: i32,
     pub font_weight: String,
     pub active_line: GutterActiveLineConfig,
     pub markers: GutterMarkersConfig,
+    #[serde(default)]
+    pub annotations: GutterAnnotationsConfig,
+    #[serde(default)]
+    pub change_bars: GutterChangeBarsConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct GutterBorderConfig {
     pub toggle: bool,
-    pub color: String,
+    pub color: ThemeColor,
     pub width: i32,
     pub style: String,
 }
 
+/// What the line number column shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum LineNumberMode {
+    /// 1-based line numbers
+    #[default]
+    Line,
+    /// Byte offset of the line start, in decimal
+    OffsetDecimal,
+    /// Byte offset of the line start, in hexadecimal (e.g. for logs and dumps)
+    OffsetHex,
+}
+
+impl LineNumberMode {
+    /// Gutter label for the line at `row` starting at byte `offset`
+    pub fn label(self, row: usize, offset: usize) -> String {
+        match self {
+            LineNumberMode::Line => format!("{}", row + 1),
+            LineNumberMode::OffsetDecimal => format!("{}", offset),
+            LineNumberMode::OffsetHex => format!("{:08x}", offset),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct GutterLineNumbersConfig {
-    pub color: String,
+    pub color: ThemeColor,
     pub ltr_width: i32,
     pub align: String,
     pub padding: i32,
-    pub hover_color: String,
+    pub hover_color: ThemeColor,
     pub active_clickable: bool,
+    #[serde(default)]
+    pub mode: LineNumberMode,
 }
 
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct GutterActiveLineConfig {
-    pub line_number_color: String,
+    pub line_number_color: ThemeColor,
     pub highlight_toggle: bool,
-    pub highlight_color: String,
+    pub highlight_color: ThemeColor,
     pub highlight_opacity: f64,
 }
 
+/// Extra gutter column filled by a host `GutterProvider` (blame, timestamps, ...)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GutterAnnotationsConfig {
+    pub toggle: bool,
+    pub color: ThemeColor,
+    pub bg_color: ThemeColor,
+    /// Longer annotations are truncated with an ellipsis
+    pub max_chars: usize,
+    pub padding: i32,
+}
+
+/// Bar next to the text marking lines changed since the last save
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GutterChangeBarsConfig {
+    pub toggle: bool,
+    pub width: i32,
+    pub color: ThemeColor,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct GutterMarkersConfig {
     pub enabled: bool,
     pub icon_char: String,
     pub icon_path: String,
     pub icon_size: i32,
-    pub color: String,
-    pub hover_color: String,
+    pub color: ThemeColor,
+    pub hover_color: ThemeColor,
     pub spacing: i32,
     pub position: String,
 }
@@ -65,13 +119,15 @@ impl Default for GutterConfig {
             toggle: true,
             ltr_width: 50,
             padding: 4,
-            bg_color: "#1e1e1e".to_string(),
+            bg_color: Color::hex(0x1e1e1e).into(),
             border: GutterBorderConfig::default(),
             line_numbers: GutterLineNumbersConfig::default(),
             font_size: 11,
             font_weight: "normal".to_string(),
             active_line: GutterActiveLineConfig::default(),
             markers: GutterMarkersConfig::default(),
+            annotations: GutterAnnotationsConfig::default(),
+            change_bars: GutterChangeBarsConfig::default(),
         }
     }
 }
@@ -80,7 +136,7 @@ impl Default for GutterBorderConfig {
     fn default() -> Self {
         Self {
             toggle: true,
-            color: "#444".to_string(),
+            color: Color::hex(0x444444).into(),
             width: 1,
             style: "solid".to_string(),
         }
@@ -90,12 +146,13 @@ impl Default for GutterBorderConfig {
 impl Default for GutterLineNumbersConfig {
     fn default() -> Self {
         Self {
-            color: "#aaa".to_string(),
+            color: Color::hex(0xaaaaaa).into(),
             ltr_width: 35,
             align: "right".to_string(),
             padding: 6,
-            hover_color: "#fff".to_string(),
+            hover_color: Color::hex(0xffffff).into(),
             active_clickable: false,
+            mode: LineNumberMode::Line,
         }
     }
 }
@@ -104,14 +161,36 @@ impl Default for GutterLineNumbersConfig {
 impl Default for GutterActiveLineConfig {
     fn default() -> Self {
         Self {
-            line_number_color: "#fff".to_string(),
+            line_number_color: Color::hex(0xffffff).into(),
             highlight_toggle: true,
-            highlight_color: "#333".to_string(),
+            highlight_color: Color::hex(0x333333).into(),
             highlight_opacity: 0.6,
         }
     }
 }
 
+impl Default for GutterAnnotationsConfig {
+    fn default() -> Self {
+        Self {
+            toggle: true,
+            color: Color::hex(0x888888).into(),
+            bg_color: Color::TRANSPARENT.into(),
+            max_chars: 16,
+            padding: 6,
+        }
+    }
+}
+
+impl Default for GutterChangeBarsConfig {
+    fn default() -> Self {
+        Self {
+            toggle: true,
+            width: 3,
+            color: Color::hex(0x43a047).into(),
+        }
+    }
+}
+
 impl Default for GutterMarkersConfig {
     fn default() -> Self {
         Self {
@@ -119,117 +198,125 @@ impl Default for GutterMarkersConfig {
             icon_char: "●".to_string(),
             icon_path: "res/icons/breakpoint.svg".to_string(),
             icon_size: 12,
-            color: "#e06c75".to_string(),
-            hover_color: "#ffb3b3".to_string(),
+            color: Color::hex(0xe06c75).into(),
+            hover_color: Color::hex(0xffb3b3).into(),
             spacing: 4,
             position: "left".to_string(),
         }
     }
 }
 
-/// Helper: parse color string to RGBA (reuse from render.rs or move to a utils module)
-pub fn parse_color(color: &str) -> (f64, f64, f64, f64) {
-    if let Some(stripped) = color.strip_prefix('#') {
-        match stripped.len() {
-            6 => {
-                let r = u8::from_str_radix(&stripped[0..2], 16).unwrap_or(0) as f64 / 255.0;
-                let g = u8::from_str_radix(&stripped[2..4], 16).unwrap_or(0) as f64 / 255.0;
-                let b = u8::from_str_radix(&stripped[4..6], 16).unwrap_or(0) as f64 / 255.0;
-                (r, g, b, 1.0)
-            }
-            8 => {
-                let r = u8::from_str_radix(&stripped[0..2], 16).unwrap_or(0) as f64 / 255.0;
-                let g = u8::from_str_radix(&stripped[2..4], 16).unwrap_or(0) as f64 / 255.0;
-                let b = u8::from_str_radix(&stripped[4..6], 16).unwrap_or(0) as f64 / 255.0;
-                let a = u8::from_str_radix(&stripped[6..8], 16).unwrap_or(255) as f64 / 255.0;
-                (r, g, b, a)
-            }
-            _ => (0.0, 0.0, 0.0, 1.0)
+/// A column of the gutter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GutterColumn {
+    /// Bookmark and diagnostic markers
+    Markers,
+    LineNumbers,
+    /// Expand/collapse markers of fold headers
+    Folding,
+    /// Lines changed since the last save
+    ChangeBars,
+    /// Text from the host `GutterProvider`
+    Annotations,
+}
+
+/// Where a gutter column is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GutterColumnSpan {
+    pub column: GutterColumn,
+    /// X position of the column's left edge
+    pub x: f64,
+    pub width: f64,
+}
+
+/// Place columns of the given widths side by side, skipping zero-width ones
+///
+/// Columns run rightwards from `edge`, or leftwards from it when `mirrored` (the first
+/// column then sits at the right edge of the view).
+pub fn layout_gutter_columns(widths: &[(GutterColumn, f64)], edge: f64, mirrored: bool) -> Vec<GutterColumnSpan> {
+    let mut x = edge;
+    widths.iter()
+        .filter(|(_, width)| *width > 0.0)
+        .map(|&(column, width)| {
+            let left = if mirrored { x - width } else { x };
+            x = if mirrored { left } else { x + width };
+            GutterColumnSpan { column, x: left, width }
+        })
+        .collect()
+}
+
+/// Column under the horizontal position `x`
+pub fn gutter_column_at(spans: &[GutterColumnSpan], x: f64) -> Option<GutterColumn> {
+    spans.iter().find(|span| x >= span.x && x < span.x + span.width).map(|span| span.column)
+}
+
+impl EditorBuffer {
+    /// Width of each gutter column, in drawing order; `char_width` is the gutter font's
+    pub fn gutter_column_widths(&self, char_width: f64) -> Vec<(GutterColumn, f64)> {
+        let cfg = &self.config.gutter;
+        if !cfg.toggle {
+            return Vec::new();
         }
-    } else if color.starts_with("rgba") {
-        let nums: Vec<f64> = color[5..color.len()-1].split(',').filter_map(|s| s.trim().parse().ok()).collect();
-        if nums.len() == 4 {
-            (nums[0]/255.0, nums[1]/255.0, nums[2]/255.0, nums[3])
+        let markers = if cfg.markers.enabled { (cfg.markers.icon_size + cfg.markers.spacing) as f64 } else { 0.0 };
+        // Reserved only while the buffer has folds to mark
+        let folding = if self.folds.is_empty() { 0.0 } else { char_width + cfg.padding as f64 };
+        let change_bars = if cfg.change_bars.toggle { cfg.change_bars.width as f64 } else { 0.0 };
+        let annotation_chars = self.gutter_annotation_chars();
+        let annotations = if annotation_chars > 0 {
+            annotation_chars as f64 * char_width + 2.0 * cfg.annotations.padding as f64
+        } else {
+            0.0
+        };
+        vec![
+            (GutterColumn::Markers, markers),
+            (GutterColumn::LineNumbers, cfg.ltr_width as f64),
+            (GutterColumn::Folding, folding),
+            (GutterColumn::ChangeBars, change_bars),
+            (GutterColumn::Annotations, annotations),
+        ]
+    }
+
+    /// Gutter columns of a view whose text area (scroll map excluded) ends at `view_right`
+    pub fn gutter_columns(&self, char_width: f64, view_right: f64) -> Vec<GutterColumnSpan> {
+        let widths = self.gutter_column_widths(char_width);
+        if self.view_is_rtl() {
+            layout_gutter_columns(&widths, view_right, true)
         } else {
-            (0.0, 0.0, 0.0, 1.0)
+            layout_gutter_columns(&widths, 0.0, false)
         }
-    } else {
-        (0.0, 0.0, 0.0, 1.0)
     }
 }
 
-/// Render the gutter (background, border, line numbers, markers, etc.)
-pub fn render_gutter(
-    rkit: &EditorBuffer,
-    ctx: &Context,
-    height: i32,
-    gutter_cfg: &GutterConfig,
-    line_count: usize,
-    active_row: usize,
-    global_line_height: f64,
-    top_offset: f64,
-    layout: &crate::render::layout::LayoutMetrics,
-) {
-    if !gutter_cfg.toggle { return; }
-    // Draw gutter background
-    let (r, g, b, a) = parse_color(&gutter_cfg.bg_color);
-    ctx.set_source_rgba(r, g, b, a);
-    ctx.rectangle(0.0, 0.0, gutter_cfg.ltr_width as f64, height as f64);
-    ctx.fill().unwrap_or(());
-
-    // Draw gutter border if enabled
-    if gutter_cfg.border.toggle {
-        let (r, g, b, a) = parse_color(&gutter_cfg.border.color);
-        ctx.set_source_rgba(r, g, b, a);
-        ctx.set_line_width(gutter_cfg.border.width as f64);
-        // Right border
-        ctx.move_to(gutter_cfg.ltr_width as f64, 0.0);
-        ctx.line_to(gutter_cfg.ltr_width as f64, height as f64);
-        ctx.stroke().unwrap_or(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_number_mode_labels() {
+        assert_eq!(LineNumberMode::Line.label(41, 1234), "42");
+        assert_eq!(LineNumberMode::OffsetDecimal.label(41, 1234), "1234");
+        assert_eq!(LineNumberMode::OffsetHex.label(41, 1234), "000004d2");
     }
 
-    // Prepare font for line numbers
-    // Always use the same font as in font config.ron for gutter font
-    let font_name = rkit.config.font.font_name();
-    let gutter_font_size = gutter_cfg.font_size.max(8);
-    let font_desc = pango::FontDescription::from_string(&format!(
-        "{} {}",
-        font_name,
-        gutter_font_size
-    ));
-    let char_spacing = 0;
-    // Calculate gutter line height from font metrics using the same Pango context as rendering
-    // (gutter_line_height is now measured in render_editor and maxed with editor font height)
-
-    for i in 0..line_count {
-        let y = top_offset + i as f64 * global_line_height;
-        // ...highlight is now drawn in render/highlight.rs...
-        // Line number color
-        let color = if i == active_row {
-            &gutter_cfg.active_line.line_number_color
-        } else {
-            &gutter_cfg.line_numbers.color
-        };
-        let (r, g, b, a) = parse_color(color);
-        ctx.set_source_rgba(r, g, b, a);
-        let pango_layout = pangocairo::functions::create_layout(ctx);
-        pango_layout.set_text(&format!("{}", i + 1));
-        pango_layout.set_font_description(Some(&font_desc));
-        pango_layout.set_spacing(char_spacing);
-        let context = pango_layout.context();
-        context.set_round_glyph_positions(true);
-        // Alignment
-        let text_width = pango_layout.pixel_size().0 as f64;
-        let align = gutter_cfg.line_numbers.align.as_str();
-        let x = match align {
-            "left" => gutter_cfg.line_numbers.padding as f64,
-            "center" => (gutter_cfg.ltr_width as f64 - text_width) / 2.0,
-            _ => gutter_cfg.ltr_width as f64 - text_width - gutter_cfg.line_numbers.padding as f64,
-        };
-        // Align gutter number to text baseline using gutter_metrics.baseline_offset from LayoutMetrics
-        let y_baseline = y + layout.gutter_metrics.baseline_offset;
-        ctx.move_to(x, y_baseline);
-        pangocairo::functions::show_layout(ctx, &pango_layout);
-        // TODO: Markers, hover, clickable, etc.
+    #[test]
+    fn columns_are_laid_out_side_by_side() {
+        let widths = [
+            (GutterColumn::Markers, 0.0),
+            (GutterColumn::LineNumbers, 50.0),
+            (GutterColumn::Folding, 12.0),
+            (GutterColumn::ChangeBars, 3.0),
+        ];
+        let spans = layout_gutter_columns(&widths, 0.0, false);
+        assert_eq!(spans.iter().map(|s| (s.column, s.x)).collect::<Vec<_>>(), vec![
+            (GutterColumn::LineNumbers, 0.0),
+            (GutterColumn::Folding, 50.0),
+            (GutterColumn::ChangeBars, 62.0),
+        ]);
+        assert_eq!(gutter_column_at(&spans, 55.0), Some(GutterColumn::Folding));
+        assert_eq!(gutter_column_at(&spans, 65.0), None);
+
+        let mirrored = layout_gutter_columns(&widths, 400.0, true);
+        assert_eq!(mirrored.iter().map(|s| s.x).collect::<Vec<_>>(), vec![350.0, 338.0, 335.0]);
+        assert_eq!(gutter_column_at(&mirrored, 399.0), Some(GutterColumn::LineNumbers));
     }
 }
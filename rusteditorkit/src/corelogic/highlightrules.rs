@@ -0,0 +1,136 @@
+//! User-defined regex highlight rules
+//!
+//! Rules come from the `highlight_rules` config list (e.g. TODO/FIXME markers, IP
+//! addresses, timestamps). Each rule styles its matches on visible lines; where rules
+//! overlap the higher `priority` wins, and `file_types` limits a rule to buffers whose
+//! language id or file extension is listed. Patterns are compiled once per config load,
+//! so a reload (see `ConfigManager::watch_config_file`) takes effect on the next frame.
+
+use serde::Deserialize;
+use syntect::parsing::{Regex, Region};
+use super::buffer::EditorBuffer;
+use super::color::ThemeColor;
+
+/// One highlight rule as written in the config
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HighlightRule {
+    /// Regular expression (Oniguruma syntax) matched against each line
+    pub pattern: String,
+    /// Text color, e.g. "#d32f2f"
+    pub foreground: Option<ThemeColor>,
+    /// Background color, e.g. "#fff59d"
+    pub background: Option<ThemeColor>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Higher priorities are drawn over lower ones where matches overlap
+    pub priority: i32,
+    /// Language ids or file extensions this rule applies to (empty = all files)
+    pub file_types: Vec<String>,
+}
+
+/// A rule with its pattern compiled, pointing back to the config entry
+#[derive(Debug, Clone)]
+pub struct CompiledHighlightRule {
+    pub regex: Regex,
+    /// Index into `config.highlight_rules`
+    pub rule: usize,
+}
+
+/// Compile `rules` in ascending priority order, skipping (and logging) invalid patterns
+pub fn compile_highlight_rules(rules: &[HighlightRule]) -> Vec<CompiledHighlightRule> {
+    let mut compiled: Vec<CompiledHighlightRule> = rules.iter().enumerate()
+        .filter(|(_, rule)| !rule.pattern.is_empty())
+        .filter_map(|(index, rule)| match Regex::try_compile(&rule.pattern) {
+            None => Some(CompiledHighlightRule { regex: Regex::new(rule.pattern.clone()), rule: index }),
+            Some(error) => {
+                eprintln!("[ERROR] Invalid highlight rule pattern '{}': {}", rule.pattern, error);
+                None
+            }
+        })
+        .collect();
+    compiled.sort_by_key(|compiled| rules[compiled.rule].priority);
+    compiled
+}
+
+/// Styled byte ranges of `line` as (start, end, rule index), non-overlapping and in order
+///
+/// `rules` must be sorted by ascending priority; later rules overwrite earlier ones.
+pub fn highlight_spans(line: &str, rules: &[&CompiledHighlightRule]) -> Vec<(usize, usize, usize)> {
+    if rules.is_empty() || line.is_empty() {
+        return Vec::new();
+    }
+    let mut owner: Vec<Option<usize>> = vec![None; line.len()];
+    let mut region = Region::new();
+    for compiled in rules {
+        let mut start = 0;
+        while start <= line.len() && compiled.regex.search(line, start, line.len(), Some(&mut region)) {
+            let Some((from, to)) = region.pos(0) else { break };
+            owner[from..to].fill(Some(compiled.rule));
+            // Step past empty matches to the next character
+            start = if to > from { to } else {
+                match line[to..].chars().next() {
+                    Some(c) => to + c.len_utf8(),
+                    None => break,
+                }
+            };
+        }
+    }
+    let mut spans = Vec::new();
+    let mut index = 0;
+    while index < owner.len() {
+        let Some(rule) = owner[index] else {
+            index += 1;
+            continue;
+        };
+        let start = index;
+        while index < owner.len() && owner[index] == Some(rule) {
+            index += 1;
+        }
+        spans.push((start, index, rule));
+    }
+    spans
+}
+
+impl EditorBuffer {
+    /// Recompile the configured highlight rules (called when the config is applied)
+    pub fn refresh_highlight_rules(&mut self) {
+        self.highlight_rules = compile_highlight_rules(&self.config.highlight_rules);
+        self.request_redraw();
+    }
+
+    /// Compiled rules that apply to this buffer's language or file extension
+    pub fn active_highlight_rules(&self) -> Vec<&CompiledHighlightRule> {
+        let extension = self.file_path.as_deref()
+            .and_then(|path| std::path::Path::new(path).extension())
+            .and_then(|ext| ext.to_str());
+        self.highlight_rules.iter()
+            .filter(|compiled| {
+                let types = &self.config.highlight_rules[compiled.rule].file_types;
+                types.is_empty() || types.iter().any(|t| {
+                    Some(t.as_str()) == self.language.as_deref() || Some(t.as_str()) == extension
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, priority: i32) -> HighlightRule {
+        HighlightRule { pattern: pattern.to_string(), priority, ..Default::default() }
+    }
+
+    #[test]
+    fn higher_priority_wins_on_overlap() {
+        let rules = vec![rule(r"\d+\.\d+\.\d+\.\d+", 1), rule(r"\bTODO\b", 5), rule(r"\d+", 0), rule("(", 9)];
+        let compiled = compile_highlight_rules(&rules);
+        assert_eq!(compiled.len(), 3);
+        let active: Vec<&CompiledHighlightRule> = compiled.iter().collect();
+        assert_eq!(highlight_spans("TODO ping 10.0.0.1 x2", &active), vec![(0, 4, 1), (10, 18, 0), (20, 21, 2)]);
+        assert!(highlight_spans("nothing here", &active).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! Pointer hit-testing within a line
+//!
+//! Rows drawn by the text layer are hit-tested with Pango on the layouts they were drawn
+//! with (`DrawnRows`), so shaped clusters, wide characters and emoji hit where they are
+//! on screen. Rows not drawn at the current text revision, and builds without GTK, fall
+//! back to uniform character cells.
+//!
+//! Mapping an x offset to a cell column walks the line once to add up advances, which gets
+//! slow on long lines when it happens for every motion event of a drag. `LineEdges`
+//! keeps the right edge of every character of the line hit-tested last, so later hits
+//! on the same line are a binary search until the text revision changes. Results are the same as
+//! `layout::column_at_x_offset`.
+
+#[cfg(feature = "gtk")]
+use std::collections::HashMap;
+#[cfg(feature = "gtk")]
+use gtk4::pango;
+use super::buffer::EditorBuffer;
+use super::layout::char_edges;
+
+/// Pango layout of a row as the text layer drew it
+#[cfg(feature = "gtk")]
+#[derive(Debug, Clone)]
+struct DrawnRow {
+    layout: pango::Layout,
+    /// Width the layout right-aligns into for right-to-left rows, whose offsets run from its right edge
+    rtl_width: Option<f64>,
+}
+
+/// Layouts of the rows drawn by the last text layer, at the text revision they show
+#[cfg(feature = "gtk")]
+#[derive(Debug, Default)]
+pub struct DrawnRows {
+    revision: u64,
+    rows: HashMap<usize, DrawnRow>,
+}
+
+#[cfg(feature = "gtk")]
+impl DrawnRows {
+    /// Start a new frame of rows showing text `revision`
+    pub fn begin(&mut self, revision: u64) {
+        self.revision = revision;
+        self.rows.clear();
+    }
+
+    /// Keep `layout` as the one `row` was drawn with
+    ///
+    /// `rtl_width` is the width a right-to-left row is aligned into, `None` for left-to-right rows.
+    pub fn record(&mut self, row: usize, layout: &pango::Layout, rtl_width: Option<f64>) {
+        self.rows.insert(row, DrawnRow { layout: layout.clone(), rtl_width });
+    }
+
+    fn row(&self, row: usize, revision: u64) -> Option<&DrawnRow> {
+        self.rows.get(&row).filter(|_| self.revision == revision)
+    }
+
+    /// Char column of the last character boundary at or before offset `x` of `row`
+    /// (from the right edge of right-to-left rows), `None` if the row was not drawn at `revision`
+    ///
+    /// Offsets past the end of the line give its length.
+    pub fn column_at(&self, row: usize, revision: u64, x: f64) -> Option<usize> {
+        let drawn = self.row(row, revision)?;
+        let x = drawn.rtl_width.map_or(x, |width| width - x);
+        let (inside, index, trailing) = drawn.layout.xy_to_index((x * pango::SCALE as f64) as i32, 0);
+        let text = drawn.layout.text();
+        let col = text.char_indices().take_while(|&(start, _)| start < index as usize).count();
+        Some(if inside { col } else { col + trailing.max(0) as usize })
+    }
+
+    /// Start and width of the cluster at char column `col` of `row` in layout coordinates
+    /// (the text area's left edge is 0), `None` if the row was not drawn at `revision`
+    ///
+    /// The width is negative in right-to-left runs and 0 past the end of the line.
+    pub fn cell(&self, row: usize, revision: u64, col: usize) -> Option<(f64, f64)> {
+        let drawn = self.row(row, revision)?;
+        let text = drawn.layout.text();
+        let index = text.char_indices().nth(col).map_or(text.len(), |(index, _)| index);
+        let pos = drawn.layout.index_to_pos(index as i32);
+        let scale = pango::SCALE as f64;
+        Some((pos.x() as f64 / scale, pos.width() as f64 / scale))
+    }
+}
+
+/// Right edges of the characters of one line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineEdges {
+    pub row: usize,
+    /// Text revision (`EditorBuffer::text_revision`) the edges were measured at
+    pub revision: u64,
+    pub char_width: f64,
+    pub word_spacing: f64,
+    /// Tab stops of the row the edges were measured with
+    pub tab_stops: Vec<f64>,
+    /// X offset of the right edge of each character
+    pub edges: Vec<f64>,
+}
+
+impl LineEdges {
+    /// Measure `line` (the text of `row` at `revision`) with the given advances and tab stops
+    pub fn measure(row: usize, revision: u64, line: &str, char_width: f64, word_spacing: f64, tab_stops: Vec<f64>) -> Self {
+        let edges = char_edges(line, char_width, word_spacing, &tab_stops).collect();
+        Self { row, revision, char_width, word_spacing, tab_stops, edges }
+    }
+
+    /// True if the edges still describe `row` at `revision` with these advances
+    pub fn matches(&self, row: usize, revision: u64, char_width: f64, word_spacing: f64, tab_stops: &[f64]) -> bool {
+        self.row == row && self.revision == revision && self.char_width == char_width
+            && self.word_spacing == word_spacing && self.tab_stops == tab_stops
+    }
+
+    /// Char column of the last character boundary at or left of offset `x`
+    ///
+    /// Past the end of the line the column keeps counting in `char_width` steps, so
+    /// callers can clamp.
+    pub fn column_at(&self, x: f64) -> usize {
+        let col = self.edges.partition_point(|&edge| edge <= x);
+        if col < self.edges.len() {
+            return col;
+        }
+        let end = self.edges.last().copied().unwrap_or(0.0);
+        col + ((x - end) / self.char_width).max(0.0) as usize
+    }
+}
+
+impl EditorBuffer {
+    /// Char column at offset `x` from the start of `row`'s text, at most the line length
+    ///
+    /// Rows the text layer drew at the current revision are hit-tested on their Pango
+    /// layout. Other rows use `char_width` cells with the buffer's word spacing
+    /// (`word_spacing_px`), tabs advancing to the stops the row was last drawn with (`tab_stops`).
+    pub fn column_at_x(&self, row: usize, x: f64, char_width: f64) -> usize {
+        let Some(line) = self.lines.get(row) else { return 0 };
+        #[cfg(feature = "gtk")]
+        if let Some(col) = self.drawn_rows.borrow().column_at(row, self.text_revision, x) {
+            return col.min(line.chars().count());
+        }
+        let word_spacing = self.word_spacing_px();
+        let tab_stops = self.tab_stops(row);
+        let mut cache = self.hit_test_cache.borrow_mut();
+        if !cache.as_ref().is_some_and(|edges| edges.matches(row, self.text_revision, char_width, word_spacing, &tab_stops)) {
+            *cache = Some(LineEdges::measure(row, self.text_revision, line, char_width, word_spacing, tab_stops));
+        }
+        cache.as_ref().map_or(0, |edges| edges.column_at(x).min(edges.edges.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corelogic::layout::column_at_x_offset;
+
+    #[test]
+    fn binary_search_matches_linear_walk() {
+        let line = "ab cd  é\tf";
+        let edges = LineEdges::measure(0, 0, line, 12.0, 4.0, Vec::new());
+        let mut x = -5.0;
+        while x < 200.0 {
+            assert_eq!(edges.column_at(x), column_at_x_offset(line, x, 12.0, 4.0), "x = {}", x);
+            x += 0.5;
+        }
+        assert_eq!(LineEdges::measure(0, 0, "", 10.0, 0.0, Vec::new()).column_at(25.0), 2);
+    }
+
+    #[test]
+    fn edits_measure_the_line_again() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["abc".into()];
+        assert_eq!(buffer.column_at_x(0, 25.0, 10.0), 2);
+        buffer.replace_range((0, 0), (0, 3), "a");
+        assert_eq!(buffer.column_at_x(0, 25.0, 10.0), 1);
+    }
+}
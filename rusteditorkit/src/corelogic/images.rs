@@ -0,0 +1,179 @@
+//! Inline image placeholders for document (A4) mode
+//!
+//! An image is anchored by a line containing only Markdown image markup:
+//! `![caption](path/to/image.png)`. In A4 mode the layout reserves vertical space for the
+//! image scaled to the page text width, the image layer draws a placeholder in the editor,
+//! and export/print draws the image itself.
+
+use std::collections::HashMap;
+use super::buffer::EditorBuffer;
+use super::layout::PageMetrics;
+
+/// An image anchored to a buffer row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageAnchor {
+    pub row: usize,
+    pub caption: String,
+    pub path: String,
+}
+
+/// Parse `![caption](path)` markup that fills the whole line
+pub fn parse_image_markup(line: &str) -> Option<(String, String)> {
+    let rest = line.trim().strip_prefix("![")?;
+    let (caption, rest) = rest.split_once("](")?;
+    let path = rest.strip_suffix(')')?;
+    if path.is_empty() || path.contains(')') {
+        return None;
+    }
+    Some((caption.to_string(), path.trim().to_string()))
+}
+
+/// Scale `(width, height)` down to fit within `max_width` x `max_height`, keeping the aspect ratio
+pub fn fit_image(width: f64, height: f64, max_width: f64, max_height: f64) -> (f64, f64) {
+    if width <= 0.0 || height <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let scale = (max_width / width).min(max_height / height).min(1.0);
+    (width * scale, height * scale)
+}
+
+/// Number of visual lines needed for an image of the given (scaled) height
+pub fn image_line_count(height: f64, metrics: &PageMetrics) -> usize {
+    if metrics.line_height <= 0.0 {
+        return 1;
+    }
+    ((height / metrics.line_height).ceil() as usize).clamp(1, metrics.lines_per_page)
+}
+
+/// Cache of natural image sizes keyed by path (`None` if the file could not be read)
+#[derive(Debug, Default)]
+pub struct ImageSizeCache {
+    sizes: std::cell::RefCell<HashMap<String, Option<(i32, i32)>>>,
+}
+
+impl ImageSizeCache {
+    /// Natural pixel size of the image at `path`, read from the file header once
+    pub fn size(&self, path: &str) -> Option<(i32, i32)> {
+        if let Some(size) = self.sizes.borrow().get(path) {
+            return *size;
+        }
+        // Without GTK there is no image decoder: images take no space
+        #[cfg(feature = "gtk")]
+        let size = gtk4::gdk_pixbuf::Pixbuf::file_info(path).map(|(_, w, h)| (w, h));
+        #[cfg(not(feature = "gtk"))]
+        let size = None;
+        self.sizes.borrow_mut().insert(path.to_string(), size);
+        size
+    }
+
+    /// Forget cached sizes (e.g. after images changed on disk)
+    pub fn clear(&self) {
+        self.sizes.borrow_mut().clear();
+    }
+}
+
+/// A decoded thumbnail and what it was decoded from
+#[cfg(feature = "gtk")]
+#[derive(Debug)]
+struct Thumbnail {
+    modified: Option<std::time::SystemTime>,
+    height: i32,
+    pixbuf: Option<gtk4::gdk_pixbuf::Pixbuf>,
+}
+
+/// Thumbnails drawn by the image layer, keyed by path (`None` if the file could not be read)
+///
+/// A thumbnail is decoded again only when the row height or the file's modification time
+/// changes, not on every frame.
+#[cfg(feature = "gtk")]
+#[derive(Debug, Default)]
+pub struct ImageThumbnailCache {
+    thumbnails: std::cell::RefCell<HashMap<String, Thumbnail>>,
+}
+
+#[cfg(feature = "gtk")]
+impl ImageThumbnailCache {
+    /// The image at `path` scaled to `height` pixels
+    pub fn thumbnail(&self, path: &str, height: i32) -> Option<gtk4::gdk_pixbuf::Pixbuf> {
+        let modified = std::fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        if let Some(thumbnail) = self.thumbnails.borrow().get(path) {
+            if thumbnail.modified == modified && thumbnail.height == height {
+                return thumbnail.pixbuf.clone();
+            }
+        }
+        let pixbuf = gtk4::gdk_pixbuf::Pixbuf::from_file_at_scale(path, -1, height, true).ok();
+        self.thumbnails.borrow_mut().insert(path.to_string(), Thumbnail { modified, height, pixbuf: pixbuf.clone() });
+        pixbuf
+    }
+
+    /// Forget all thumbnails
+    pub fn clear(&self) {
+        self.thumbnails.borrow_mut().clear();
+    }
+}
+
+impl EditorBuffer {
+    /// All image anchors in the buffer, in row order
+    pub fn image_anchors(&self) -> Vec<ImageAnchor> {
+        self.lines.iter().enumerate()
+            .filter_map(|(row, line)| {
+                parse_image_markup(line).map(|(caption, path)| ImageAnchor {
+                    row,
+                    caption,
+                    path: self.resolve_image_path(&path),
+                })
+            })
+            .collect()
+    }
+
+    /// Resolve an image path relative to the directory of the open file
+    pub fn resolve_image_path(&self, path: &str) -> String {
+        let candidate = std::path::Path::new(path);
+        if candidate.is_absolute() {
+            return path.to_string();
+        }
+        self.file_path.as_deref()
+            .and_then(|file| std::path::Path::new(file).parent())
+            .map(|dir| dir.join(candidate).to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    /// Scaled size of the image anchored at `row`, fitted to the page text area
+    pub fn image_size_for_row(&self, row: usize, metrics: &PageMetrics) -> Option<(f64, f64)> {
+        let (_, path) = parse_image_markup(self.lines.get(row)?)?;
+        let (width, height) = self.image_sizes.size(&self.resolve_image_path(&path))?;
+        Some(fit_image(width as f64, height as f64, metrics.text_width, metrics.text_height))
+    }
+
+    /// Insert an image anchor line for `path` below the cursor line
+    pub fn insert_image(&mut self, path: &str, caption: &str) {
+        self.push_undo();
+        let row = (self.cursor.row + 1).min(self.lines.len());
+        self.replace_rows(row..row, vec![format!("![{}]({})", caption, path)]);
+        self.selection = None;
+        self.cursor.row = row;
+        self.cursor.col = self.lines[row].chars().count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_markup_must_fill_the_line_and_name_a_file() {
+        assert_eq!(
+            parse_image_markup("  ![Figure 1](img/fig1.png) "),
+            Some(("Figure 1".to_string(), "img/fig1.png".to_string()))
+        );
+        assert_eq!(parse_image_markup("text ![a](b.png)"), None);
+        assert_eq!(parse_image_markup("![a]()"), None);
+    }
+
+    #[test]
+    fn images_shrink_to_fit_keeping_their_aspect() {
+        assert_eq!(fit_image(200.0, 100.0, 100.0, 1000.0), (100.0, 50.0));
+        assert_eq!(fit_image(50.0, 40.0, 100.0, 100.0), (50.0, 40.0));
+        assert_eq!(fit_image(100.0, 400.0, 100.0, 200.0), (50.0, 200.0));
+    }
+}
@@ -0,0 +1,144 @@
+//! Indentation settings for EditorBuffer
+//!
+//! The effective indent (tabs or spaces, and the width) is, in order: a buffer-local
+//! override set by the host, the file's modeline, the style detected from the file
+//! content on open, or the `indent_with_tabs`/`indent_width` config defaults. Detection
+//! looks at the leading whitespace of every line; hosts can plug in their own detector
+//! (e.g. one reading `.editorconfig`) with `set_indent_detector`.
+
+use super::buffer::EditorBuffer;
+
+/// Fewest indented lines needed before the content is trusted over the config
+pub const MIN_INDENTED_LINES: usize = 2;
+
+/// How one indent level is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentStyle {
+    pub use_tabs: bool,
+    /// Spaces per level, or columns per tab
+    pub width: usize,
+}
+
+impl IndentStyle {
+    /// Text inserted for one indent level
+    pub fn unit(&self) -> String {
+        if self.use_tabs { "\t".to_string() } else { " ".repeat(self.width.max(1)) }
+    }
+
+    /// Short description for status bars, e.g. "Spaces: 2" or "Tab Size: 4"
+    pub fn label(&self) -> String {
+        if self.use_tabs { format!("Tab Size: {}", self.width) } else { format!("Spaces: {}", self.width) }
+    }
+}
+
+/// Host callback detecting the indent style of the buffer lines (None = undecided)
+pub type IndentDetector = Box<dyn Fn(&[String]) -> Option<IndentStyle>>;
+
+/// Detect the indent style from leading whitespace
+///
+/// Tabs win if more lines are indented with tabs than with spaces; the width is the most
+/// common increase in space indentation between consecutive lines. `tab_width` is used
+/// for tab-indented files. Returns None for files with too little indentation to tell.
+pub fn detect_indent_style(lines: &[String], tab_width: usize) -> Option<IndentStyle> {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut steps = [0usize; 9];
+    let mut previous = 0;
+    for line in lines {
+        let trimmed = line.trim_start();
+        // Blank lines and block comment continuations (" * text") say nothing about indentation
+        if trimmed.is_empty() || trimmed.starts_with('*') {
+            continue;
+        }
+        let lead = &line[..line.len() - trimmed.len()];
+        if lead.starts_with('\t') {
+            tab_lines += 1;
+            continue;
+        }
+        let spaces = lead.chars().take_while(|&c| c == ' ').count();
+        if spaces > 0 {
+            space_lines += 1;
+        }
+        if spaces > previous && spaces - previous < steps.len() && spaces - previous > 1 {
+            steps[spaces - previous] += 1;
+        }
+        previous = spaces;
+    }
+    if tab_lines + space_lines < MIN_INDENTED_LINES {
+        return None;
+    }
+    if tab_lines > space_lines {
+        return Some(IndentStyle { use_tabs: true, width: tab_width });
+    }
+    // Most common step; ties go to the smaller width
+    let (width, count) = steps.iter().enumerate().rev().max_by_key(|&(_, count)| *count)?;
+    (*count > 0).then_some(IndentStyle { use_tabs: false, width })
+}
+
+impl EditorBuffer {
+    /// Indent style in effect: override, else modeline, else detected, else config
+    pub fn indent_style(&self) -> IndentStyle {
+        let base = self.detected_indent.unwrap_or(IndentStyle {
+            use_tabs: self.config.indent_with_tabs,
+            width: self.config.indent_width.max(1),
+        });
+        self.indent_override
+            .or_else(|| self.modeline.as_ref().and_then(|modeline| modeline.indent_style(base)))
+            .unwrap_or(base)
+    }
+
+    /// Style found in the file content on open, if detection was conclusive
+    pub fn detected_indent(&self) -> Option<IndentStyle> {
+        self.detected_indent
+    }
+
+    /// Indent style label for status bars, marked "(detected)" when it came from the file
+    pub fn indent_label(&self) -> String {
+        let style = self.indent_style();
+        if self.detected_indent == Some(style) {
+            format!("{} (detected)", style.label())
+        } else {
+            style.label()
+        }
+    }
+
+    /// Set (or clear) the buffer-local indent style, taking precedence over detection
+    pub fn set_indent_style(&mut self, style: Option<IndentStyle>) {
+        self.indent_override = style;
+    }
+
+    /// Replace (or with None, restore) the built-in indentation detector
+    pub fn set_indent_detector(&mut self, detector: Option<IndentDetector>) {
+        self.indent_detector = detector;
+    }
+
+    /// Detect the indent style of the current content (done automatically on open)
+    pub fn detect_indentation(&mut self) -> Option<IndentStyle> {
+        self.detected_indent = if !self.config.detect_indentation {
+            None
+        } else if let Some(detector) = &self.indent_detector {
+            detector(&self.lines)
+        } else {
+            detect_indent_style(&self.lines, self.config.indent_width.max(1))
+        };
+        self.detected_indent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn detects_space_width_and_tabs() {
+        let two = lines("fn a() {\n  if x {\n    y();\n  }\n  /**\n   * doc\n   */\n}\n");
+        assert_eq!(detect_indent_style(&two, 4), Some(IndentStyle { use_tabs: false, width: 2 }));
+        let tabs = lines("a:\n\tb\n\t\tc\n    d\n");
+        assert_eq!(detect_indent_style(&tabs, 8), Some(IndentStyle { use_tabs: true, width: 8 }));
+        assert_eq!(detect_indent_style(&lines("flat\ntext\n"), 4), None);
+    }
+}
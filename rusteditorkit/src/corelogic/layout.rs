@@ -3,7 +3,7 @@
 //! This module contains A4 page layout, margins, tab width, line wrapping, and spacing calculations.
 
 use super::buffer::EditorBuffer;
-use gtk4::cairo::Context;
+use super::images::image_line_count;
 
 #[derive(Debug, Clone, Copy)]
 pub struct EditorMarginUpdate {
@@ -13,6 +13,78 @@ pub struct EditorMarginUpdate {
     pub right: f64,
 }
 
+/// Page geometry derived from the A4 config, used for pagination and statistics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageMetrics {
+    /// Number of visual lines that fit on one page
+    pub lines_per_page: usize,
+    /// Number of characters that fit on one visual line
+    pub chars_per_line: usize,
+    /// Height of one visual line in pixels
+    pub line_height: f64,
+    /// Usable text area (inside the margins) in pixels
+    pub text_width: f64,
+    pub text_height: f64,
+}
+
+/// Page breaks found for text revision `revision` with the page settings beside it
+#[derive(Debug, Clone)]
+pub struct PageBreakCache {
+    revision: u64,
+    metrics: PageMetrics,
+    widow_lines: usize,
+    orphan_lines: usize,
+    breaks: Vec<usize>,
+}
+
+/// Split rows into pages, returning the rows that start each new page
+///
+/// `rows` holds `(visual_lines, is_blank)` per buffer row. A paragraph split across
+/// a page boundary keeps at least `orphan_lines` rows at the bottom of the first page
+/// and `widow_lines` rows at the top of the next one; when that is impossible the whole
+/// paragraph moves to the next page (unless it would leave the page empty).
+pub fn paginate(rows: &[(usize, bool)], lines_per_page: usize, widow_lines: usize, orphan_lines: usize) -> Vec<usize> {
+    let lines_per_page = lines_per_page.max(1);
+    let mut breaks = Vec::new();
+    let mut page_start = 0;
+    let mut used = 0;
+    let mut row = 0;
+    while row < rows.len() {
+        let (height, _) = rows[row];
+        if used + height > lines_per_page && row > page_start {
+            let mut brk = row;
+            if !rows[row].1 {
+                let para_start = (page_start..row).rev().take_while(|&r| !rows[r].1).last().unwrap_or(row);
+                let mut para_end = row;
+                while para_end + 1 < rows.len() && !rows[para_end + 1].1 {
+                    para_end += 1;
+                }
+                let before = row - para_start;
+                let after = para_end + 1 - row;
+                if before > 0 {
+                    if after < widow_lines {
+                        brk = row - (widow_lines - after).min(before);
+                    }
+                    if brk - para_start < orphan_lines {
+                        brk = para_start;
+                    }
+                    if brk <= page_start {
+                        brk = row;
+                    }
+                }
+            }
+            breaks.push(brk);
+            page_start = brk;
+            used = 0;
+            row = brk;
+            continue;
+        }
+        used += height;
+        row += 1;
+    }
+    breaks
+}
+
 /// Convert centimeters to pixels (assuming 96 DPI)
 pub fn cm_to_px(cm: f64) -> f64 {
     cm * 96.0 / 2.54
@@ -23,7 +95,84 @@ pub fn px_to_cm(px: f64) -> f64 {
     px * 2.54 / 96.0
 }
 
+/// Advance of `c` in the uniform-cell model: `char_width`, plus `word_spacing` for a space
+///
+/// The renderer and pointer hit-testing measure shaped clusters with Pango; this is the
+/// approximation used for rows not drawn yet and for headless geometry without a font.
+pub fn char_advance(c: char, char_width: f64, word_spacing: f64) -> f64 {
+    if c == ' ' { char_width + word_spacing } else { char_width }
+}
+
+/// Tab width in cells where no tab stop of the row applies (Pango's default of 8 spaces)
+pub const DEFAULT_TAB_CELLS: f64 = 8.0;
+
+/// Offset a tab starting at `x` advances to
+///
+/// `stops` are the row's tab stops as set on its Pango layout (see `EditorBuffer::tab_stops`):
+/// the first stop right of `x` is taken. Past the last one tabs repeat at the distance
+/// between the last two stops, or every `DEFAULT_TAB_CELLS` cells with fewer stops.
+pub fn next_tab_stop(x: f64, stops: &[f64], char_width: f64) -> f64 {
+    if let Some(&stop) = stops.iter().find(|&&stop| stop > x) {
+        return stop;
+    }
+    let default = DEFAULT_TAB_CELLS * char_width;
+    let (last, interval) = match stops {
+        [] => (0.0, default),
+        [only] => (*only, default),
+        [.., prev, last] if last > prev => (*last, last - prev),
+        [.., last] => (*last, default),
+    };
+    if interval <= 0.0 {
+        return x + char_width;
+    }
+    last + (((x - last) / interval).floor() + 1.0) * interval
+}
+
+/// Right edge of each character of `line`, with tabs advancing to the next of `tab_stops`
+pub fn char_edges<'a>(line: &'a str, char_width: f64, word_spacing: f64, tab_stops: &'a [f64]) -> impl Iterator<Item = f64> + 'a {
+    line.chars().scan(0.0, move |edge, c| {
+        *edge = match c {
+            '\t' => next_tab_stop(*edge, tab_stops, char_width),
+            _ => *edge + char_advance(c, char_width, word_spacing),
+        };
+        Some(*edge)
+    })
+}
+
+/// Horizontal offset of char column `col` in `line` (tabs at the default stops)
+///
+/// `char_width` already includes letter spacing; every space additionally advances by `word_spacing`.
+pub fn column_x_offset(line: &str, col: usize, char_width: f64, word_spacing: f64) -> f64 {
+    let end = char_edges(line, char_width, word_spacing, &[]).take(col).last().unwrap_or(0.0);
+    end + col.saturating_sub(line.chars().count()) as f64 * char_width
+}
+
+/// Char column of the last character boundary at or left of offset `x` (inverse of `column_x_offset`)
+pub fn column_at_x_offset(line: &str, x: f64, char_width: f64, word_spacing: f64) -> usize {
+    let mut edge = 0.0;
+    let mut col = 0;
+    for right in char_edges(line, char_width, word_spacing, &[]) {
+        if right > x {
+            return col;
+        }
+        edge = right;
+        col += 1;
+    }
+    // Past the end of the line: keep the grid so callers can clamp
+    col + ((x - edge) / char_width).max(0.0) as usize
+}
+
 impl EditorBuffer {
+    /// Extra advance between characters in pixels at the current zoom
+    pub fn letter_spacing_px(&self) -> f64 {
+        self.config.font.font_character_spacing() * self.zoom
+    }
+
+    /// Extra advance of each space in pixels at the current zoom
+    pub fn word_spacing_px(&self) -> f64 {
+        self.config.font.font_word_spacing() * self.zoom
+    }
+
     /// Update page margins for A4 mode (stubbed for now)
     pub fn update_margins(&mut self, _top: f64, _bottom: f64, _left: f64, _right: f64) {
         // TODO: Implement with new config structure
@@ -71,12 +220,14 @@ impl EditorBuffer {
 
     /// Insert page break for A4 mode
     pub fn insert_page_break(&mut self) {
-        if self.config.a4_mode {
+        if self.config.a4_mode && self.allows_edit() {
+            self.push_undo();
             let line = self.cursor.row;
-            self.lines.insert(line, "
+            if self.replace_rows(line..line, vec!["
 [PAGE BREAK]
-".to_string());
-            self.cursor.row += 1;
+".to_string()]).is_some() {
+                self.cursor.row += 1;
+            }
         }
     }
 
@@ -139,28 +290,12 @@ impl EditorBuffer {
 
     /// Move cursor down by one A4 page
     pub fn move_a4_page_down(&mut self) {
-        if self.config.a4_mode {
-            let lines = self.max_lines_on_page();
-            self.cursor.row = (self.cursor.row + lines).min(self.lines.len().saturating_sub(1));
-            self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].len());
-            println!("[DEBUG] A4 page down: moved to line {}", self.cursor.row);
-        } else {
-            // Fallback to regular page down
-            self.move_page_down(25); // Standard page size
-        }
+        self.move_to_next_page();
     }
 
     /// Move cursor up by one A4 page
     pub fn move_a4_page_up(&mut self) {
-        if self.config.a4_mode {
-            let lines = self.max_lines_on_page();
-            self.cursor.row = self.cursor.row.saturating_sub(lines);
-            self.cursor.col = self.cursor.col.min(self.lines[self.cursor.row].len());
-            println!("[DEBUG] A4 page up: moved to line {}", self.cursor.row);
-        } else {
-            // Fallback to regular page up
-            self.move_page_up(25); // Standard page size
-        }
+        self.move_to_prev_page();
     }
 
     /// Get the usable text area dimensions in A4 mode (in pixels)
@@ -183,11 +318,129 @@ impl EditorBuffer {
         if !self.config.a4_mode {
             return 80; // Default fallback
         }
-        
-        let (text_width, _) = self.get_a4_text_area();
-        let char_width = self.font_character_spacing();
-        
-        (text_width / char_width).floor() as usize
+        self.page_metrics().chars_per_line
+    }
+
+    /// Approximate advance width of one character in pixels
+    ///
+    /// Used when no measured layout metrics are available (monospace fonts are ~0.6em wide).
+    pub fn estimated_char_width(&self) -> f64 {
+        let font_px = self.font_size() * 96.0 / 72.0;
+        font_px * 0.6 + self.font_character_spacing()
+    }
+
+    /// Page metrics using the configured line height and an estimated character width
+    pub fn page_metrics(&self) -> PageMetrics {
+        self.page_metrics_with(self.unified_line_height(), self.estimated_char_width())
+    }
+
+    /// Page metrics from measured layout values (e.g. `LayoutMetrics::line_height`
+    /// and `FontMetrics::average_char_width`)
+    pub fn page_metrics_with(&self, line_height_px: f64, char_width_px: f64) -> PageMetrics {
+        let a4_width_cm = 21.0;
+        let a4_height_cm = 29.7;
+        let usable_width = cm_to_px(a4_width_cm - self.config.left_margin_cm - self.config.right_margin_cm);
+        let usable_height = cm_to_px(a4_height_cm - self.config.top_margin_cm - self.config.bottom_margin_cm);
+        let lines_per_page = if line_height_px > 0.0 { (usable_height / line_height_px) as usize } else { 1 };
+        let chars_per_line = if char_width_px > 0.0 { (usable_width / char_width_px) as usize } else { 80 };
+        PageMetrics {
+            lines_per_page: lines_per_page.max(1),
+            chars_per_line: chars_per_line.max(1),
+            line_height: line_height_px,
+            text_width: usable_width,
+            text_height: usable_height,
+        }
+    }
+
+    /// Number of visual lines a buffer line occupies on the page when wrapped
+    ///
+    /// Image anchor rows reserve enough lines for the scaled image.
+    pub fn visual_lines_for_row(&self, row: usize, metrics: &PageMetrics) -> usize {
+        if let Some((_, height)) = self.image_size_for_row(row, metrics) {
+            return image_line_count(height, metrics);
+        }
+        let len = self.lines.get(row).map(|l| l.chars().count()).unwrap_or(0);
+        len.div_ceil(metrics.chars_per_line).max(1)
+    }
+
+    /// Total page count in A4 mode, `None` when A4 mode is off
+    pub fn page_count(&self) -> Option<usize> {
+        if !self.config.a4_mode {
+            return None;
+        }
+        Some(self.page_breaks().len() + 1)
+    }
+
+    /// 1-based page number containing the cursor in A4 mode, `None` when A4 mode is off
+    pub fn current_page(&self) -> Option<usize> {
+        if !self.config.a4_mode {
+            return None;
+        }
+        Some(self.page_index_for_row(self.cursor.row) + 1)
+    }
+
+    /// Rows that start a new page (page 1 always starts at row 0 and is not included)
+    ///
+    /// Honors `widow_lines`/`orphan_lines` so short paragraph fragments are not left
+    /// alone at the top or bottom of a page. Returns an empty list outside A4 mode.
+    /// The breaks are kept until the text or the page settings change.
+    pub fn page_breaks(&self) -> Vec<usize> {
+        if !self.config.a4_mode {
+            return Vec::new();
+        }
+        let metrics = self.page_metrics();
+        let (widow_lines, orphan_lines) = (self.config.widow_lines, self.config.orphan_lines);
+        let fresh = |cache: &PageBreakCache| cache.revision == self.text_revision
+            && cache.metrics == metrics
+            && cache.widow_lines == widow_lines
+            && cache.orphan_lines == orphan_lines;
+        if let Some(cache) = self.page_break_cache.borrow().as_ref().filter(|cache| fresh(cache)) {
+            return cache.breaks.clone();
+        }
+        let rows: Vec<(usize, bool)> = (0..self.lines.len())
+            .map(|row| (self.visual_lines_for_row(row, &metrics), self.lines[row].trim().is_empty()))
+            .collect();
+        let breaks = paginate(&rows, metrics.lines_per_page, widow_lines, orphan_lines);
+        self.page_break_cache.replace(Some(PageBreakCache { revision: self.text_revision, metrics, widow_lines, orphan_lines, breaks: breaks.clone() }));
+        breaks
+    }
+
+    /// 0-based page index containing the given row
+    pub fn page_index_for_row(&self, row: usize) -> usize {
+        self.page_breaks().partition_point(|&start| start <= row)
+    }
+
+    /// Move cursor to the start of the next page and scroll it to the top of the view
+    pub fn move_to_next_page(&mut self) {
+        if !self.config.a4_mode {
+            self.move_page_down(25);
+            return;
+        }
+        self.clear_selection();
+        let target = self
+            .page_breaks()
+            .into_iter()
+            .find(|&start| start > self.cursor.row)
+            .unwrap_or(self.lines.len().saturating_sub(1));
+        self.cursor.row = target;
+        self.cursor.col = 0;
+        self.scroll_offset = self.display_row(target);
+    }
+
+    /// Move cursor to the start of the previous page and scroll it to the top of the view
+    pub fn move_to_prev_page(&mut self) {
+        if !self.config.a4_mode {
+            self.move_page_up(25);
+            return;
+        }
+        self.clear_selection();
+        let mut starts = vec![0];
+        starts.extend(self.page_breaks());
+        let current = starts.iter().rposition(|&start| start <= self.cursor.row).unwrap_or(0);
+        let target = starts[current.saturating_sub(1)];
+        self.cursor.row = target;
+        self.cursor.col = 0;
+        self.scroll_offset = self.display_row(target);
     }
 
     /// Check if word wrapping should occur at current cursor position
@@ -216,7 +469,7 @@ impl EditorBuffer {
         let mut wrap_pos = self.cursor.col;
         
         // Look backward for a good break point
-        while wrap_pos > 0 && !line.chars().nth(wrap_pos).map_or(false, |c| c.is_whitespace()) {
+        while wrap_pos > 0 && !line.chars().nth(wrap_pos).is_some_and(|c| c.is_whitespace()) {
             wrap_pos -= 1;
         }
         
@@ -226,11 +479,12 @@ impl EditorBuffer {
         }
         
         // Split the line
-        let current_line = &mut self.lines[self.cursor.row];
+        let mut current_line = self.lines[self.cursor.row].clone();
         let new_line = current_line.split_off(wrap_pos);
         
         // Insert the new line
-        self.lines.insert(self.cursor.row + 1, new_line.trim_start().to_string());
+        let row = self.cursor.row;
+        self.replace_rows(row..row + 1, vec![current_line, new_line.trim_start().to_string()]);
         
         // Update cursor
         self.cursor.row += 1;
@@ -240,67 +494,74 @@ impl EditorBuffer {
     }
 }
 
-/// Render A4 page boundary and margin guides
-pub fn render_a4_boundary(buffer: &EditorBuffer, ctx: &Context, width: i32, height: i32) {
-    if !buffer.config.a4_mode { 
-        return; 
-    }
-    
-    // A4 size in cm
-    let a4_width_cm = 21.0;
-    let a4_height_cm = 29.7;
-    
-    // Convert to px
-    let a4_width_px = cm_to_px(a4_width_cm);
-    let a4_height_px = cm_to_px(a4_height_cm);
-    
-    // Margins in px
-    let left_px = cm_to_px(buffer.config.left_margin_cm);
-    let right_px = cm_to_px(buffer.config.right_margin_cm);
-    let top_px = cm_to_px(buffer.config.top_margin_cm);
-    let bottom_px = cm_to_px(buffer.config.bottom_margin_cm);
-    
-    // Center A4 page in viewport
-    let x = ((width as f64) - a4_width_px) / 2.0;
-    let y = ((height as f64) - a4_height_px) / 2.0;
-    
-    // Draw A4 page boundary
-    ctx.set_source_rgba(0.8, 0.8, 0.8, 1.0); // light gray
-    ctx.rectangle(x, y, a4_width_px, a4_height_px);
-    ctx.set_line_width(2.0);
-    ctx.stroke().unwrap_or(());
-    
-    // Draw margin guides
-    ctx.set_source_rgba(0.2, 0.6, 0.9, 0.5); // blue, semi-transparent
-    ctx.set_line_width(1.0);
-    
-    // Top margin
-    ctx.move_to(x, y + top_px);
-    ctx.line_to(x + a4_width_px, y + top_px);
-    ctx.stroke().unwrap_or(());
-    
-    // Bottom margin
-    ctx.move_to(x, y + a4_height_px - bottom_px);
-    ctx.line_to(x + a4_width_px, y + a4_height_px - bottom_px);
-    ctx.stroke().unwrap_or(());
-    
-    // Left margin
-    ctx.move_to(x + left_px, y);
-    ctx.line_to(x + left_px, y + a4_height_px);
-    ctx.stroke().unwrap_or(());
-    
-    // Right margin
-    ctx.move_to(x + a4_width_px - right_px, y);
-    ctx.line_to(x + a4_width_px - right_px, y + a4_height_px);
-    ctx.stroke().unwrap_or(());
-    
-    // Draw text area outline
-    ctx.set_source_rgba(0.9, 0.9, 0.9, 0.3);
-    ctx.rectangle(
-        x + left_px, 
-        y + top_px, 
-        a4_width_px - left_px - right_px, 
-        a4_height_px - top_px - bottom_px
-    );
-    ctx.fill().unwrap_or(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_break_every_page_of_rows() {
+        let rows = vec![(1, false); 10];
+        assert_eq!(paginate(&rows, 4, 0, 0), vec![4, 8]);
+    }
+
+    #[test]
+    fn breaks_move_up_to_avoid_widows_and_orphans() {
+        // Paragraph of 5 rows starting at row 2; page holds 6 rows.
+        let mut rows = vec![(1, false), (1, true)];
+        rows.extend(vec![(1, false); 5]);
+        // Without control the last paragraph row would be a widow on page 2
+        assert_eq!(paginate(&rows, 6, 0, 0), vec![6]);
+        // Widow control pulls one more row to the next page
+        assert_eq!(paginate(&rows, 6, 2, 0), vec![5]);
+        // Orphan control of 4 moves the whole paragraph
+        assert_eq!(paginate(&rows, 6, 2, 4), vec![2]);
+    }
+
+    #[test]
+    fn page_break_insert_is_undoable_and_repaginates() {
+        let mut buffer = EditorBuffer::new();
+        buffer.config.a4_mode = true;
+        buffer.set_lines(vec!["text".to_string(); 200]);
+        let lines_per_page = buffer.page_metrics().lines_per_page;
+        assert_eq!(buffer.page_breaks().first(), Some(&lines_per_page));
+
+        buffer.cursor.row = 3;
+        buffer.insert_page_break();
+        assert_eq!(buffer.lines.len(), 201);
+        assert_eq!(buffer.cursor.row, 4);
+        assert_eq!(buffer.page_index_for_row(200), buffer.page_breaks().len());
+        buffer.undo();
+        assert_eq!(buffer.lines.len(), 200);
+        assert_eq!(buffer.cursor.row, 3);
+
+        // A changed page setting is not served from the cache
+        buffer.config.top_margin_cm += 10.0;
+        assert!(buffer.page_breaks()[0] < lines_per_page);
+    }
+
+    #[test]
+    fn word_spacing_offsets_round_trip() {
+        let line = "ab cd";
+        assert_eq!(column_x_offset(line, 2, 10.0, 4.0), 20.0);
+        assert_eq!(column_x_offset(line, 4, 10.0, 4.0), 44.0);
+        assert_eq!(column_at_x_offset(line, 33.9, 10.0, 4.0), 2);
+        assert_eq!(column_at_x_offset(line, 34.0, 10.0, 4.0), 3);
+        assert_eq!(column_at_x_offset(line, 80.0, 10.0, 4.0), 7);
+        for col in 0..=5 {
+            let x = column_x_offset(line, col, 10.0, 4.0);
+            assert_eq!(column_at_x_offset(line, x, 10.0, 4.0), col);
+        }
+    }
+
+    #[test]
+    fn tabs_advance_to_the_row_stops() {
+        assert_eq!(next_tab_stop(0.0, &[], 10.0), 80.0);
+        assert_eq!(next_tab_stop(85.0, &[], 10.0), 160.0);
+        assert_eq!(next_tab_stop(12.0, &[30.0, 70.0], 10.0), 30.0);
+        assert_eq!(next_tab_stop(70.0, &[30.0, 70.0], 10.0), 110.0);
+        let edges: Vec<f64> = char_edges("a\tb\tc", 10.0, 0.0, &[30.0, 70.0]).collect();
+        assert_eq!(edges, [10.0, 30.0, 40.0, 70.0, 80.0]);
+        assert_eq!(column_at_x_offset("a\tb", 79.0, 10.0, 0.0), 1);
+        assert_eq!(column_x_offset("a\tb", 3, 10.0, 0.0), 90.0);
+    }
 }
@@ -0,0 +1,160 @@
+//! Host-overridden line heights
+//!
+//! Every row is one line height tall unless the host installs a `LineHeightProvider`,
+//! e.g. to reserve room under a line for an inline widget or a collapsed preview. The
+//! provider is asked per buffer row with the computed line height and returns the
+//! height to use instead (`None` keeps it). Text stays at the top of a taller row.
+//!
+//! Only the rows on screen are measured: `RowOffsets` holds their tops, and rows above
+//! or below it count as one line height. Layout, hit-testing, the gutter and the number
+//! of rows the viewport scrolls by all go through it.
+
+use super::buffer::EditorBuffer;
+
+/// Host callback: (buffer row, computed line height) to an overriding height
+pub type LineHeightProvider = Box<dyn Fn(usize, f64) -> Option<f64>>;
+
+/// Tops of consecutive display rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowOffsets {
+    /// First measured display row
+    pub first: usize,
+    /// Top of each measured row, then the bottom of the last one
+    pub tops: Vec<f64>,
+    /// Height assumed for rows outside the measured ones
+    pub line_height: f64,
+}
+
+impl RowOffsets {
+    /// Measure display rows from `first`, whose top is at `top`, until one reaches
+    /// `bottom` or the last of `count` rows is done
+    pub fn measure(first: usize, top: f64, bottom: f64, count: usize, line_height: f64, height_of: impl Fn(usize) -> f64) -> Self {
+        let mut tops = vec![top];
+        let (mut display, mut y) = (first, top);
+        while display < count && y < bottom {
+            y += height_of(display);
+            tops.push(y);
+            display += 1;
+        }
+        Self { first, tops, line_height }
+    }
+
+    fn last(&self) -> (usize, f64) {
+        (self.first + self.tops.len() - 1, self.tops[self.tops.len() - 1])
+    }
+
+    /// Y position of the top of display row `display`
+    pub fn top(&self, display: usize) -> f64 {
+        let (last, bottom) = self.last();
+        if display < self.first {
+            self.tops[0] - (self.first - display) as f64 * self.line_height
+        } else if display <= last {
+            self.tops[display - self.first]
+        } else {
+            bottom + (display - last) as f64 * self.line_height
+        }
+    }
+
+    /// Height of display row `display`
+    pub fn height(&self, display: usize) -> f64 {
+        self.top(display + 1) - self.top(display)
+    }
+
+    /// Display row at y position `y`
+    pub fn display_row_at(&self, y: f64) -> usize {
+        let (last, bottom) = self.last();
+        if y < self.tops[0] {
+            return self.first.saturating_sub(((self.tops[0] - y) / self.line_height).ceil() as usize);
+        }
+        let index = self.tops.partition_point(|&top| top <= y);
+        if index < self.tops.len() {
+            self.first + index - 1
+        } else {
+            last + ((y - bottom) / self.line_height) as usize
+        }
+    }
+
+    /// Number of whole rows from `first` that end at or above `bottom` (at least one)
+    pub fn rows_fitting(&self, bottom: f64) -> usize {
+        let (_, last_bottom) = self.last();
+        let measured = self.tops[1..].iter().take_while(|&&y| y <= bottom).count();
+        let beyond = if measured == self.tops.len() - 1 {
+            ((bottom - last_bottom) / self.line_height).max(0.0) as usize
+        } else {
+            0
+        };
+        (measured + beyond).max(1)
+    }
+}
+
+impl EditorBuffer {
+    /// Install (or remove) the host callback overriding line heights
+    pub fn set_line_height_provider(&mut self, provider: Option<LineHeightProvider>) {
+        self.line_height_provider = provider;
+        self.request_redraw();
+    }
+
+    /// Height of buffer `row` when rows are `line_height` tall by default
+    pub fn row_height(&self, row: usize, line_height: f64) -> f64 {
+        match &self.line_height_provider {
+            Some(provider) => provider(row, line_height).map_or(line_height, |height| height.max(1.0)),
+            None => line_height,
+        }
+    }
+
+    /// Tops of the display rows from `scroll_offset` (at `top`) down to `bottom`, None
+    /// without a provider, when every row is `line_height` tall
+    pub fn row_offsets(&self, top: f64, bottom: f64, line_height: f64) -> Option<RowOffsets> {
+        self.measure_rows(top, bottom, self.display_row_count(), line_height)
+    }
+
+    fn measure_rows(&self, top: f64, bottom: f64, count: usize, line_height: f64) -> Option<RowOffsets> {
+        self.line_height_provider.as_ref()?;
+        let hidden = self.hidden_rows();
+        Some(RowOffsets::measure(self.scroll_offset, top, bottom, count, line_height, |display| {
+            self.row_height(hidden.row_at_display(display), line_height)
+        }))
+    }
+
+    /// Display row at y position `y` of a view whose text area starts at `top`
+    ///
+    /// Positions above the text area map to the first visible row.
+    pub fn display_row_at_y(&self, y: f64, top: f64, line_height: f64) -> usize {
+        let y = y.max(top);
+        match self.row_offsets(top, y + line_height, line_height) {
+            Some(offsets) => offsets.display_row_at(y),
+            None => ((y - top) / line_height) as usize + self.scroll_offset,
+        }
+    }
+
+    /// Y position of the top of display row `display` of a view whose text area starts at `top`
+    pub fn display_row_y(&self, display: usize, top: f64, line_height: f64) -> f64 {
+        match self.measure_rows(top, f64::INFINITY, display.min(self.display_row_count()), line_height) {
+            Some(offsets) => offsets.top(display),
+            None => top + (display as f64 - self.scroll_offset as f64) * line_height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overridden_rows_shift_the_rows_below() {
+        // Display row 12 reserves room for an inline widget
+        let offsets = RowOffsets::measure(10, 5.0, 100.0, 20, 20.0, |d| if d == 12 { 50.0 } else { 20.0 });
+        assert_eq!(offsets.tops, vec![5.0, 25.0, 45.0, 95.0, 115.0]);
+        assert_eq!(offsets.top(9), -15.0);
+        assert_eq!(offsets.top(16), 155.0);
+        assert_eq!(offsets.height(12), 50.0);
+        assert_eq!(offsets.display_row_at(-15.0), 9);
+        assert_eq!(offsets.display_row_at(94.9), 12);
+        assert_eq!(offsets.display_row_at(95.0), 13);
+        assert_eq!(offsets.display_row_at(140.0), 15);
+        assert_eq!(offsets.rows_fitting(100.0), 3);
+        // Uniform heights fit as many rows as the plain division
+        let uniform = RowOffsets::measure(0, 0.0, 100.0, 3, 20.0, |_| 20.0);
+        assert_eq!(uniform.rows_fitting(100.0), 5);
+    }
+}
@@ -0,0 +1,319 @@
+//! Markdown/plain text list continuation for EditorBuffer
+//!
+//! In prose mode, Enter on a list item continues the marker on the new line (bullets,
+//! numbered items and `- [ ]` task items), Enter or Backspace on an empty item removes the
+//! marker, and ordered lists are renumbered after each change. Task items (`- [ ]`)
+//! can be checked and unchecked with `ToggleCheckbox` or by clicking the checkbox.
+
+use super::buffer::EditorBuffer;
+
+/// A parsed list item prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    /// Leading whitespace
+    pub indent: String,
+    /// Bullet character for unordered items (`-`, `*`, `+`)
+    pub bullet: Option<char>,
+    /// Number and delimiter (`.` or `)`) for ordered items
+    pub number: Option<(u64, char)>,
+    /// Digits of the number as written, leading zeros included (0 for bullets)
+    pub number_width: usize,
+    /// Task checkbox state, `Some(checked)` for `[ ]` / `[x]` items
+    pub checkbox: Option<bool>,
+    /// Char column where the item text starts
+    pub content_start: usize,
+}
+
+impl ListItem {
+    /// Marker text for the next item in the same list (numbers incremented, checkbox unchecked)
+    pub fn next_marker(&self) -> String {
+        let mut marker = self.indent.clone();
+        match (self.bullet, self.number) {
+            (Some(bullet), _) => marker.push(bullet),
+            (None, Some((n, delim))) => marker.push_str(&format!("{}{}", self.number_text(n + 1), delim)),
+            _ => {}
+        }
+        marker.push(' ');
+        if self.checkbox.is_some() {
+            marker.push_str("[ ] ");
+        }
+        marker
+    }
+
+    /// `n` written like this item's number: zero-padded to the same width if it was
+    pub fn number_text(&self, n: u64) -> String {
+        match self.number {
+            Some((current, _)) if self.number_width > current.to_string().len() => format!("{:0width$}", n, width = self.number_width),
+            _ => n.to_string(),
+        }
+    }
+}
+
+/// Parse a list item marker at the start of `line`
+pub fn parse_list_item(line: &str) -> Option<ListItem> {
+    let indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+    let rest = &line[indent.len()..];
+    let first = rest.chars().next()?;
+    let (bullet, number, marker_len) = if matches!(first, '-' | '*' | '+') {
+        (Some(first), None, 1)
+    } else {
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let delim = rest[digits.len()..].chars().next()?;
+        if digits.is_empty() || digits.len() > 9 || !matches!(delim, '.' | ')') {
+            return None;
+        }
+        (None, Some((digits.parse().ok()?, delim)), digits.len() + 1)
+    };
+    let after_marker = &rest[marker_len..];
+    if !(after_marker.is_empty() || after_marker.starts_with(' ')) {
+        return None;
+    }
+    let mut content_start = indent.chars().count() + marker_len + after_marker.len().min(1);
+    let after_space = after_marker.strip_prefix(' ').unwrap_or(after_marker);
+    let checkbox = if after_space.starts_with("[ ]") {
+        Some(false)
+    } else if after_space.starts_with("[x]") || after_space.starts_with("[X]") {
+        Some(true)
+    } else {
+        None
+    };
+    if checkbox.is_some() {
+        content_start += 3 + after_space[3..].starts_with(' ') as usize;
+    }
+    let number_width = if number.is_some() { marker_len - 1 } else { 0 };
+    Some(ListItem { indent, bullet, number, number_width, checkbox, content_start })
+}
+
+/// Renumber ordered items in the list starting at `row`, returns true if any line changed
+///
+/// Items at the same indentation are numbered sequentially from the first item's number;
+/// nested (deeper) lines are skipped and the list ends at a blank or shallower line.
+pub fn renumber_list(lines: &mut [String], row: usize) -> bool {
+    let renumbered = renumbered_lines(lines, row);
+    for (row, line) in &renumbered {
+        lines[*row] = line.clone();
+    }
+    !renumbered.is_empty()
+}
+
+/// Lines `renumber_list` would change, as (row, new line)
+fn renumbered_lines(lines: &[String], row: usize) -> Vec<(usize, String)> {
+    let mut renumbered = Vec::new();
+    let Some(first) = lines.get(row).and_then(|line| parse_list_item(line)) else { return renumbered };
+    let Some((start_number, _)) = first.number else { return renumbered };
+    // Walk back to the first item of this list
+    let mut top = row;
+    let mut number = start_number;
+    while top > 0 {
+        match parse_list_item(&lines[top - 1]) {
+            Some(item) if item.indent == first.indent => match item.number {
+                Some((n, _)) => {
+                    top -= 1;
+                    number = n;
+                }
+                None => break,
+            },
+            Some(item) if item.indent.len() > first.indent.len() => top -= 1,
+            _ => break,
+        }
+    }
+    for (row, line) in lines.iter().enumerate().skip(top) {
+        if line.trim().is_empty() {
+            break;
+        }
+        match parse_list_item(line) {
+            Some(item) if item.indent == first.indent => {
+                let Some((current, _)) = item.number else { break };
+                if current != number {
+                    let marker_end = item.indent.len() + item.number_width;
+                    renumbered.push((row, format!("{}{}{}", item.indent, item.number_text(number), &line[marker_end..])));
+                }
+                number += 1;
+            }
+            Some(item) if item.indent.len() > first.indent.len() => {}
+            Some(_) => break,
+            None => {
+                let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+                if indent <= first.indent.chars().count() {
+                    break;
+                }
+            }
+        }
+    }
+    renumbered
+}
+
+/// Char range `(start, end)` of the `[ ]` checkbox in a task item line
+pub fn checkbox_range(line: &str) -> Option<(usize, usize)> {
+    let item = parse_list_item(line)?;
+    item.checkbox?;
+    let start = line.chars().position(|c| c == '[')?;
+    Some((start, start + 3))
+}
+
+impl EditorBuffer {
+    /// Flip the checkbox on `row` without recording undo, returns true if the row has one
+    fn flip_checkbox(&mut self, row: usize) -> bool {
+        let Some(line) = self.lines.get(row) else { return false };
+        let Some((start, _)) = checkbox_range(line) else { return false };
+        let checked = line.chars().nth(start + 1) == Some(' ');
+        self.replace_range((row, start + 1), (row, start + 2), if checked { "x" } else { " " });
+        true
+    }
+
+    /// Toggle `[ ]`/`[x]` on the current line, or on every selected task line (one undo step)
+    pub fn toggle_checkbox(&mut self) {
+        let (start, end) = match &self.selection {
+            Some(sel) if sel.is_active() => {
+                let ((start, _), (end, _)) = sel.normalized();
+                (start, end)
+            }
+            _ => (self.cursor.row, self.cursor.row),
+        };
+        let end = end.min(self.lines.len().saturating_sub(1));
+        if !(start..=end).any(|row| checkbox_range(&self.lines[row]).is_some()) {
+            return;
+        }
+        self.push_undo();
+        for row in start..=end {
+            self.flip_checkbox(row);
+        }
+    }
+
+    /// True if the character at `(row, col)` is part of a task checkbox in a Markdown or
+    /// prose buffer, where clicking it toggles the box
+    pub fn checkbox_at(&self, row: usize, col: usize) -> bool {
+        self.prose_mode_active()
+            && self.lines.get(row).and_then(|line| checkbox_range(line)).is_some_and(|(start, end)| (start..end).contains(&col))
+    }
+
+    /// Toggle the checkbox if `(row, col)` is on one of its `[ ]` characters (see
+    /// `checkbox_at`), returns true if toggled
+    pub fn toggle_checkbox_at(&mut self, row: usize, col: usize) -> bool {
+        if !self.checkbox_at(row, col) {
+            return false;
+        }
+        self.push_undo();
+        self.flip_checkbox(row)
+    }
+
+    /// True if prose helpers (list continuation, ...) are active for this buffer
+    pub fn prose_mode_active(&self) -> bool {
+        self.config.prose_mode || self.is_markdown()
+    }
+
+    /// True if the buffer's language is Markdown
+    pub fn is_markdown(&self) -> bool {
+        matches!(self.language.as_deref(), Some("markdown") | Some("md"))
+    }
+
+    /// Handle Enter on a list item; returns false if the regular newline should run instead
+    pub fn continue_list(&mut self) -> bool {
+        if !self.prose_mode_active() || self.selection.as_ref().is_some_and(|sel| sel.is_active()) {
+            return false;
+        }
+        let row = self.cursor.row;
+        let Some(item) = self.lines.get(row).and_then(|line| parse_list_item(line)) else { return false };
+        if self.cursor.col < item.content_start {
+            return false;
+        }
+        let line = &self.lines[row];
+        if line.chars().skip(item.content_start).all(char::is_whitespace) {
+            // Enter on an empty item ends the list
+            self.push_undo();
+            self.replace_rows(row..row + 1, vec![String::new()]);
+            self.cursor.col = 0;
+            return true;
+        }
+        let split = line.char_indices().nth(self.cursor.col).map(|(i, _)| i).unwrap_or(line.len());
+        let (head, tail) = line.split_at(split);
+        let marker = item.next_marker();
+        let rows = vec![head.to_string(), format!("{}{}", marker, tail.trim_start())];
+        self.push_undo();
+        self.replace_rows(row..row + 1, rows);
+        self.cursor.row = row + 1;
+        self.cursor.col = marker.chars().count();
+        if item.number.is_some() {
+            self.renumber_list_at(row);
+        }
+        true
+    }
+
+    /// Handle Backspace at the end of an empty list item by removing its marker
+    ///
+    /// Returns false if the regular backspace should run instead.
+    pub fn remove_empty_list_marker(&mut self) -> bool {
+        if !self.prose_mode_active() || self.selection.as_ref().is_some_and(|sel| sel.is_active()) {
+            return false;
+        }
+        let row = self.cursor.row;
+        let Some(item) = self.lines.get(row).and_then(|line| parse_list_item(line)) else { return false };
+        let line_len = self.lines[row].chars().count();
+        if self.cursor.col != line_len || self.lines[row].chars().skip(item.content_start).any(|c| !c.is_whitespace()) {
+            return false;
+        }
+        self.push_undo();
+        self.replace_rows(row..row + 1, vec![item.indent.clone()]);
+        self.cursor.col = item.indent.chars().count();
+        if item.number.is_some() && row + 1 < self.lines.len() {
+            self.renumber_list_at(row + 1);
+        }
+        true
+    }
+
+    /// `renumber_list` on the buffer, editing only the lines whose number changes
+    fn renumber_list_at(&mut self, row: usize) {
+        for (row, line) in renumbered_lines(&self.lines, row) {
+            self.replace_rows(row..row + 1, vec![line]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_item_markers_parse_and_continue() {
+        let item = parse_list_item("  - [x] done").unwrap();
+        assert_eq!(item.bullet, Some('-'));
+        assert_eq!(item.checkbox, Some(true));
+        assert_eq!(item.content_start, 8);
+        assert_eq!(item.next_marker(), "  - [ ] ");
+        assert_eq!(parse_list_item("3) three").unwrap().next_marker(), "4) ");
+        assert_eq!(parse_list_item("-not a list"), None);
+        assert_eq!(parse_list_item("2024 was"), None);
+        assert_eq!(checkbox_range("  * [ ] task"), Some((4, 7)));
+        assert_eq!(checkbox_range("- plain"), None);
+    }
+
+    #[test]
+    fn checkbox_click_hits_only_the_box() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["- [ ] task".to_string()];
+        assert!(!buffer.checkbox_at(0, 2));
+        buffer.language = Some("markdown".to_string());
+        assert_eq!((0..7).map(|col| buffer.checkbox_at(0, col)).collect::<Vec<_>>(), [false, false, true, true, true, false, false]);
+        assert!(!buffer.toggle_checkbox_at(0, 5));
+        assert!(buffer.toggle_checkbox_at(0, 4));
+        assert_eq!(buffer.lines[0], "- [x] task");
+    }
+
+    #[test]
+    fn renumbering_keeps_the_first_number_and_its_padding() {
+        let mut lines: Vec<String> = ["1. a", "2. b", "   nested", "2. c", "7. d", "", "1. other"]
+            .iter().map(|s| s.to_string()).collect();
+        assert!(renumber_list(&mut lines, 1));
+        assert_eq!(lines[3], "3. c");
+        assert_eq!(lines[4], "4. d");
+        assert_eq!(lines[6], "1. other");
+
+        // Zero-padded numbers keep their width, unpadded ones do not gain one
+        let mut padded: Vec<String> = ["01. a", "01. b", "", "1. x", "10. y"].iter().map(|s| s.to_string()).collect();
+        assert!(renumber_list(&mut padded, 1));
+        assert!(renumber_list(&mut padded, 4));
+        assert_eq!(padded, ["01. a", "02. b", "", "1. x", "2. y"]);
+        assert_eq!(parse_list_item("09) z").unwrap().next_marker(), "10) ");
+        assert_eq!(parse_list_item("007. z").unwrap().next_marker(), "008. ");
+    }
+}
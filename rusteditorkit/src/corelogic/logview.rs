@@ -0,0 +1,116 @@
+//! Log viewer buffers (`BufferKind::Log`)
+//!
+//! A log buffer is append-only: editing commands are rejected, typed text is ignored and
+//! no undo history is kept, so streaming millions of lines stays cheap. Lines are colored
+//! by a short list of regex rules (first match wins) instead of syntax highlighting, and
+//! only the visible rows are laid out each frame. Direction detection and decimal
+//! alignment are skipped; rows are never wrapped. Runs of repeated lines are folded
+//! into one expandable row (see `folding`).
+
+use super::buffer::EditorBuffer;
+use super::color::Color;
+use syntect::parsing::Regex;
+
+/// What a buffer is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BufferKind {
+    /// Regular editable text
+    #[default]
+    Text,
+    /// Append-only log output
+    Log,
+}
+
+/// Colors every log line matching `pattern`
+#[derive(Debug, Clone)]
+pub struct LogRule {
+    pub pattern: Regex,
+    pub color: Color,
+}
+
+impl LogRule {
+    /// Rule for `pattern`, or the error if the pattern or the color is invalid
+    pub fn new(pattern: &str, color: &str) -> Result<Self, String> {
+        if let Some(error) = Regex::try_compile(pattern) {
+            return Err(error.to_string());
+        }
+        Ok(Self { pattern: Regex::new(pattern.to_string()), color: color.parse()? })
+    }
+
+    /// True if the pattern occurs anywhere in `line` (`Regex::is_match` only tries the start)
+    pub fn matches(&self, line: &str) -> bool {
+        self.pattern.search(line, 0, line.len(), None)
+    }
+}
+
+/// Level-based rules: errors red, warnings orange, debug/trace grey
+pub fn default_log_rules() -> Vec<LogRule> {
+    [
+        (r"\b(ERROR|FATAL|CRITICAL|PANIC)\b", "#e53935"),
+        (r"\b(WARN|WARNING)\b", "#fb8c00"),
+        (r"\b(DEBUG|TRACE)\b", "#9e9e9e"),
+    ]
+    .iter()
+    .filter_map(|(pattern, color)| LogRule::new(pattern, color).ok())
+    .collect()
+}
+
+impl EditorBuffer {
+    /// Switch between a regular text buffer and an append-only log buffer
+    ///
+    /// Entering log mode drops the undo history and folds runs of repeated lines;
+    /// leaving it removes those folds.
+    pub fn set_buffer_kind(&mut self, kind: BufferKind) {
+        self.kind = kind;
+        if kind == BufferKind::Log {
+            self.undo_stack.clear();
+            self.redo_stack.clear();
+            if self.log_rules.is_empty() {
+                self.log_rules = default_log_rules();
+            }
+            self.fold_repeated_lines(0);
+        } else {
+            self.folds.retain(|_, fold| fold.kind != super::folding::FoldKind::Repeat);
+        }
+        self.request_redraw();
+    }
+
+    pub fn buffer_kind(&self) -> BufferKind {
+        self.kind
+    }
+
+    /// True for `BufferKind::Log`
+    pub fn is_log_buffer(&self) -> bool {
+        self.kind == BufferKind::Log
+    }
+
+    /// Replace the line coloring rules of log mode
+    pub fn set_log_rules(&mut self, rules: Vec<LogRule>) {
+        self.log_rules = rules;
+        self.request_redraw();
+    }
+
+    /// Color of the first rule matching `line`, if any
+    pub fn log_line_color(&self, line: &str) -> Option<Color> {
+        self.log_rules.iter()
+            .find(|rule| rule.matches(line))
+            .map(|rule| rule.color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_color_by_level() {
+        let rules = default_log_rules();
+        let color = |line: &str| rules.iter().find(|rule| rule.matches(line)).map(|rule| rule.color);
+        assert_eq!(color("12:00:01 ERROR disk full"), Some(Color::hex(0xe53935)));
+        assert_eq!(color("[WARN] retrying"), Some(Color::hex(0xfb8c00)));
+        assert_eq!(color("INFO started"), None);
+        assert_eq!(color("WARNINGS are not a level"), None);
+        assert!(LogRule::new("(unclosed", "#000000").is_err());
+        assert!(LogRule::new("ERROR", "#e5393").is_err());
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod buffer;
 pub mod editing;
+pub mod edit;
 pub mod font;
 pub mod cursor;
 pub mod gutter;
@@ -12,8 +13,64 @@ pub mod undo;
 pub mod clipboard;
 pub mod search;
 pub mod fileio;
+pub mod textformat;
 pub mod selection;
-// pub mod layout;  // Temporarily disabled - needs config updates
+pub mod reflow;
+pub mod events;
+pub mod statistics;
+pub mod images;
+pub mod table;
+pub mod lists;
+pub mod outline;
+pub mod viewport;
+pub mod focus;
+pub mod flash;
+pub mod bookmarks;
+pub mod scrollmap;
+pub mod telemetry;
+pub mod recovery;
+pub mod consistency;
+pub mod annotations;
+pub mod zoom;
+pub mod fontoptions;
+pub mod glyphs;
+pub mod decimal;
+pub mod smoothscroll;
+pub mod direction;
+pub mod remote;
+pub mod changes;
+pub mod snapshots;
+pub mod transforms;
+pub mod streaming;
+pub mod logview;
+pub mod highlightrules;
+pub mod folding;
+pub mod words;
+pub mod surround;
+pub mod column;
+pub mod align;
+pub mod indentation;
+pub mod modeline;
+pub mod safesave;
+pub mod elevated;
+pub mod recent;
+pub mod anchors;
+pub mod filetype;
+pub mod title;
+pub mod caretcontext;
+pub mod paste;
+pub mod reset;
+pub mod snippet;
+pub mod templates;
+pub mod script;
+pub mod hittest;
+pub mod pointer;
+pub mod lineheights;
+pub mod color;
+pub mod redraw;
+#[cfg(feature = "vertical-text")]
+pub mod vertical;
+pub mod layout;
 pub mod dispatcher;
 
 // Re-export the main types for convenience
@@ -23,5 +80,30 @@ pub use selection::Selection;
 pub use undo::*;
 pub use search::*;
 pub use fileio::*;
-// pub use layout::*;  // Temporarily disabled
+pub use layout::*;
 pub use dispatcher::*;
+pub use events::{EditorEvent, EventBus, ListenerId};
+pub use statistics::DocumentStatistics;
+pub use images::ImageAnchor;
+pub use outline::{OutlineEntry, OutlineKind, OutlineProvider};
+pub use remote::RemoteCursor;
+pub use changes::{ChangeOp, OpId};
+pub use edit::RowEdit;
+pub use snapshots::{DiffHunk, Snapshot};
+pub use transforms::{FileError, FileTransform};
+pub use textformat::{LineEnding, TextEncoding, TextFormat};
+pub use logview::{BufferKind, LogRule};
+pub use folding::{FoldKind, FoldRegion};
+pub use indentation::{IndentDetector, IndentStyle};
+pub use modeline::Modeline;
+pub use safesave::BackupMode;
+pub use elevated::ElevatedWriter;
+pub use recent::{RecentFile, RecentFiles};
+pub use snippet::SnippetSession;
+pub use templates::FileTemplate;
+pub use title::DisplayTitle;
+pub use caretcontext::{CaretContext, CharNamer};
+pub use script::{parse_script, ScriptCommand, ScriptError};
+pub use lineheights::{LineHeightProvider, RowOffsets};
+pub use color::{Color, ColorScheme, Palette, ThemeColor};
+pub use redraw::RedrawLayers;
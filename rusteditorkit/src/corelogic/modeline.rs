@@ -0,0 +1,181 @@
+//! Vim and Emacs modelines
+//!
+//! With `config.modelines` enabled, the first and last `modeline_scan_lines` lines of an
+//! opened file are searched for a modeline such as `vim: ts=4 sw=4 et` or
+//! `-*- mode: python; indent-tabs-mode: nil -*-`. Only a fixed set of harmless options is
+//! understood (language, tabs/spaces, indent width, text width) and values are range
+//! checked; everything else is ignored. Modelines are off by default because the file
+//! author, not the user, controls them.
+
+use super::buffer::EditorBuffer;
+use super::indentation::IndentStyle;
+
+/// Largest indent or tab width a modeline may set
+pub const MAX_MODELINE_INDENT: usize = 16;
+/// Largest text width a modeline may set
+pub const MAX_MODELINE_TEXT_WIDTH: usize = 1000;
+
+/// Settings read from a modeline; None where the modeline says nothing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Modeline {
+    pub language: Option<String>,
+    pub use_tabs: Option<bool>,
+    pub tab_width: Option<usize>,
+    pub indent_width: Option<usize>,
+    /// Wrap column for reflow (`textwidth`, `fill-column`)
+    pub text_width: Option<usize>,
+}
+
+impl Modeline {
+    /// Indent style this modeline describes, filling gaps from `base`
+    pub fn indent_style(&self, base: IndentStyle) -> Option<IndentStyle> {
+        if self.use_tabs.is_none() && self.tab_width.is_none() && self.indent_width.is_none() {
+            return None;
+        }
+        let use_tabs = self.use_tabs.unwrap_or(base.use_tabs);
+        let width = if use_tabs {
+            self.tab_width.or(self.indent_width)
+        } else {
+            self.indent_width.or(self.tab_width)
+        };
+        Some(IndentStyle { use_tabs, width: width.unwrap_or(base.width) })
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Modeline::default()
+    }
+}
+
+fn parse_width(value: &str, max: usize) -> Option<usize> {
+    value.trim().parse().ok().filter(|&n| (1..=max).contains(&n))
+}
+
+/// Language id for a vim filetype or emacs mode name, rejecting anything unusual
+pub(crate) fn parse_language(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_suffix("-mode").unwrap_or(&name);
+    let name = match name {
+        "c++" => "cpp",
+        "sh" | "shell-script" => "shell",
+        "js" => "javascript",
+        "py" => "python",
+        other => other,
+    };
+    let valid = !name.is_empty() && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '#'));
+    valid.then(|| name.to_string())
+}
+
+/// Parse a vim modeline (`vim: ts=4 sw=4 et` or `vim: set ts=4 sw=4 et:`)
+pub fn parse_vim_modeline(line: &str) -> Option<Modeline> {
+    let (at, marker) = ["vim:", "Vim:", "vi:", "ex:"].iter()
+        .filter_map(|marker| line.find(marker).map(|at| (at, *marker)))
+        .filter(|&(at, marker)| {
+            // The marker starts the line or follows whitespace ("ex:" always needs whitespace)
+            let spaced = line[..at].ends_with(char::is_whitespace);
+            spaced || (at == 0 && marker != "ex:")
+        })
+        .min()?;
+    let rest = line[at + marker.len()..].trim_start();
+    let options: Vec<&str> = match rest.strip_prefix("set ").or_else(|| rest.strip_prefix("se ")) {
+        // The `set` form ends at the next colon and separates options with spaces
+        Some(set) => set.split(':').next()?.split_whitespace().collect(),
+        None => rest.split(|c: char| c == ':' || c.is_whitespace()).filter(|o| !o.is_empty()).collect(),
+    };
+    let mut modeline = Modeline::default();
+    for option in options {
+        let (name, value) = option.split_once('=').unwrap_or((option, ""));
+        match name {
+            "ts" | "tabstop" => modeline.tab_width = parse_width(value, MAX_MODELINE_INDENT),
+            // shiftwidth=0 means "use tabstop"
+            "sw" | "shiftwidth" if value != "0" => modeline.indent_width = parse_width(value, MAX_MODELINE_INDENT),
+            "et" | "expandtab" => modeline.use_tabs = Some(false),
+            "noet" | "noexpandtab" => modeline.use_tabs = Some(true),
+            "tw" | "textwidth" => modeline.text_width = parse_width(value, MAX_MODELINE_TEXT_WIDTH),
+            "ft" | "filetype" | "syn" | "syntax" => modeline.language = parse_language(value),
+            _ => {}
+        }
+    }
+    (!modeline.is_empty()).then_some(modeline)
+}
+
+/// Parse an emacs modeline (`-*- mode: python; indent-tabs-mode: nil -*-` or `-*- python -*-`)
+pub fn parse_emacs_modeline(line: &str) -> Option<Modeline> {
+    let start = line.find("-*-")? + 3;
+    let end = start + line[start..].find("-*-")?;
+    let body = line[start..end].trim();
+    let mut modeline = Modeline::default();
+    if !body.contains(':') {
+        modeline.language = parse_language(body);
+    }
+    for pair in body.split(';').filter(|_| body.contains(':')) {
+        let Some((name, value)) = pair.split_once(':') else { continue };
+        let (name, value) = (name.trim().to_lowercase(), value.trim());
+        match name.as_str() {
+            "mode" => modeline.language = parse_language(value),
+            "indent-tabs-mode" => modeline.use_tabs = Some(value != "nil"),
+            "tab-width" => modeline.tab_width = parse_width(value, MAX_MODELINE_INDENT),
+            "fill-column" => modeline.text_width = parse_width(value, MAX_MODELINE_TEXT_WIDTH),
+            // c-basic-offset, python-indent-offset, js-indent-level, ...
+            name if name == "c-basic-offset" || name.ends_with("-indent-offset") || name.ends_with("-indent-level") => {
+                modeline.indent_width = parse_width(value, MAX_MODELINE_INDENT);
+            }
+            _ => {}
+        }
+    }
+    (!modeline.is_empty()).then_some(modeline)
+}
+
+/// First modeline in the first or last `scan` lines
+pub fn find_modeline(lines: &[String], scan: usize) -> Option<Modeline> {
+    let head = lines.iter().take(scan);
+    let tail = lines.iter().skip(lines.len().saturating_sub(scan).max(scan.min(lines.len())));
+    head.chain(tail).find_map(|line| parse_emacs_modeline(line).or_else(|| parse_vim_modeline(line)))
+}
+
+impl EditorBuffer {
+    /// Modeline applied to this buffer when the file was opened
+    pub fn modeline(&self) -> Option<&Modeline> {
+        self.modeline.as_ref()
+    }
+
+    /// Read the modeline of the current content and apply it (done automatically on open)
+    ///
+    /// Does nothing unless `config.modelines` is enabled.
+    pub fn apply_modeline(&mut self) -> Option<&Modeline> {
+        self.modeline = if self.config.modelines {
+            find_modeline(&self.lines, self.config.modeline_scan_lines)
+        } else {
+            None
+        };
+        if let Some(language) = self.modeline.as_ref().and_then(|modeline| modeline.language.clone()) {
+            self.language = Some(language);
+        }
+        self.modeline.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vim_modelines() {
+        let plain = parse_vim_modeline("// vim: ts=8 sw=2 et ft=rust").unwrap();
+        assert_eq!(plain, Modeline { language: Some("rust".into()), use_tabs: Some(false), tab_width: Some(8), indent_width: Some(2), text_width: None });
+        let set = parse_vim_modeline("/* vim: set noet ts=4 tw=72: */").unwrap();
+        assert_eq!((set.use_tabs, set.tab_width, set.text_width), (Some(true), Some(4), Some(72)));
+        assert_eq!(parse_vim_modeline("# ex: sw=0 ts=99"), None);
+        assert_eq!(parse_vim_modeline("the novim: ts=4"), None);
+    }
+
+    #[test]
+    fn parses_emacs_modelines() {
+        let line = "# -*- mode: Python; indent-tabs-mode: nil; python-indent-offset: 4 -*-";
+        let modeline = parse_emacs_modeline(line).unwrap();
+        assert_eq!((modeline.language.as_deref(), modeline.use_tabs, modeline.indent_width), (Some("python"), Some(false), Some(4)));
+        assert_eq!(parse_emacs_modeline("-*- c++ -*-").unwrap().language.as_deref(), Some("cpp"));
+        let base = IndentStyle { use_tabs: true, width: 8 };
+        assert_eq!(modeline.indent_style(base), Some(IndentStyle { use_tabs: false, width: 4 }));
+    }
+}
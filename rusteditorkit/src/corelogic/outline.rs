@@ -0,0 +1,240 @@
+//! Document outline for EditorBuffer
+//!
+//! Extracts a hierarchical symbol list: Markdown headings by default, or entries supplied by
+//! a host callback (e.g. functions from a language server or tree-sitter). The outline powers
+//! `NextHeading`/`PrevHeading` navigation and the enclosing-scope APIs used for breadcrumbs.
+//!
+//! The flat outline is built when first asked for and kept until the text, the language or
+//! the provider changes, so commands and frames that do not edit reuse it.
+
+use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+
+/// Kind of an outline entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutlineKind {
+    /// Markdown heading
+    Heading,
+    /// Host provided symbol, e.g. "function", "struct", "impl"
+    Symbol(String),
+}
+
+/// An entry of the document outline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    pub title: String,
+    /// Nesting level, 1 = top level (heading level for Markdown)
+    pub level: usize,
+    pub row: usize,
+    pub col: usize,
+    /// Last row of the scope when known (host symbols); headings extend to the next heading
+    pub end_row: Option<usize>,
+    pub kind: OutlineKind,
+    pub children: Vec<OutlineEntry>,
+}
+
+/// Host callback returning flat outline entries (with levels) for the buffer lines
+pub type OutlineProvider = Box<dyn Fn(&[String]) -> Vec<OutlineEntry>>;
+
+/// Flat outline kept with the text revision and language it was built for
+#[derive(Debug, Clone)]
+pub struct OutlineCache {
+    revision: u64,
+    language: Option<String>,
+    entries: Vec<OutlineEntry>,
+}
+
+/// Extract ATX Markdown headings (`# Title`), skipping fenced code blocks
+pub fn markdown_headings(lines: &[String]) -> Vec<OutlineEntry> {
+    let mut entries = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (row, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = match fence {
+                Some(open) if open == marker => None,
+                Some(open) => Some(open),
+                None => Some(marker),
+            };
+            continue;
+        }
+        if fence.is_some() || line.len() - trimmed.len() > 3 {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if level == 0 || level > 6 {
+            continue;
+        }
+        let rest = &trimmed[level..];
+        if !(rest.is_empty() || rest.starts_with(' ') || rest.starts_with('\t')) {
+            continue;
+        }
+        let title = rest.trim().trim_end_matches('#').trim_end().to_string();
+        entries.push(OutlineEntry {
+            title,
+            level,
+            row,
+            col: line.len() - trimmed.len(),
+            end_row: None,
+            kind: OutlineKind::Heading,
+            children: Vec::new(),
+        });
+    }
+    entries
+}
+
+/// Nest flat entries (ordered by row) into a tree using their levels
+pub fn build_outline_tree(flat: Vec<OutlineEntry>) -> Vec<OutlineEntry> {
+    fn insert(siblings: &mut Vec<OutlineEntry>, entry: OutlineEntry) {
+        match siblings.last_mut() {
+            Some(parent) if parent.level < entry.level => insert(&mut parent.children, entry),
+            _ => siblings.push(entry),
+        }
+    }
+    let mut roots = Vec::new();
+    for entry in flat {
+        insert(&mut roots, entry);
+    }
+    roots
+}
+
+/// Path from the top level down to the innermost entry enclosing `row`
+pub fn enclosing_path(tree: &[OutlineEntry], row: usize) -> Vec<OutlineEntry> {
+    let mut path = Vec::new();
+    let mut level = tree;
+    while let Some(entry) = level.iter().rev().find(|entry| entry.row <= row) {
+        if entry.end_row.is_some_and(|end| row > end) {
+            break;
+        }
+        path.push(OutlineEntry { children: Vec::new(), ..entry.clone() });
+        level = &entry.children;
+    }
+    path
+}
+
+impl EditorBuffer {
+    /// Install (or remove) a host callback providing outline entries for code buffers
+    pub fn set_outline_provider(&mut self, provider: Option<OutlineProvider>) {
+        self.outline_provider = provider;
+        self.outline_cache.replace(None);
+    }
+
+    /// Flat outline entries ordered by row (children are empty)
+    ///
+    /// Uses the host provider when installed, otherwise the headings of a Markdown buffer
+    /// (a `#` line means something else in other languages, or in plain text). Log
+    /// buffers have no outline, so streaming into them never rescans the text.
+    pub fn outline_flat(&self) -> Vec<OutlineEntry> {
+        let fresh = |cache: &OutlineCache| cache.revision == self.text_revision && cache.language == self.language;
+        if let Some(cache) = self.outline_cache.borrow().as_ref().filter(|cache| fresh(cache)) {
+            return cache.entries.clone();
+        }
+        let entries = self.build_outline_flat();
+        self.outline_cache.replace(Some(OutlineCache {
+            revision: self.text_revision,
+            language: self.language.clone(),
+            entries: entries.clone(),
+        }));
+        entries
+    }
+
+    /// `outline_flat` computed from the lines
+    fn build_outline_flat(&self) -> Vec<OutlineEntry> {
+        let mut entries = if self.is_log_buffer() {
+            Vec::new()
+        } else if let Some(provider) = &self.outline_provider {
+            provider(&self.lines)
+        } else if self.is_markdown() {
+            markdown_headings(&self.lines)
+        } else {
+            Vec::new()
+        };
+        entries.sort_by_key(|entry| (entry.row, entry.col));
+        entries
+    }
+
+    /// Hierarchical outline of the buffer
+    pub fn outline(&self) -> Vec<OutlineEntry> {
+        build_outline_tree(self.outline_flat())
+    }
+
+    /// Breadcrumb path of outline entries enclosing the cursor (outermost first)
+    pub fn enclosing_scope_path(&self) -> Vec<OutlineEntry> {
+        enclosing_path(&self.outline(), self.cursor.row)
+    }
+
+    /// Innermost outline entry (heading or host scope) enclosing the cursor
+    pub fn enclosing_scope(&self) -> Option<OutlineEntry> {
+        self.enclosing_scope_path().pop()
+    }
+
+    /// Recompute the enclosing scope and emit `ScopeChanged` if it differs from the last one
+    ///
+    /// Does nothing when no event listeners are connected.
+    pub fn refresh_scope(&mut self) {
+        if !self.events.has_listeners() {
+            return;
+        }
+        let path = self.enclosing_scope_path();
+        if self.last_scope_path.as_ref() != Some(&path) {
+            self.last_scope_path = Some(path.clone());
+            self.events.emit(&EditorEvent::ScopeChanged(path));
+        }
+    }
+
+    /// Move the cursor to the next outline entry below the cursor row
+    pub fn move_to_next_heading(&mut self) {
+        let row = self.cursor.row;
+        if let Some(entry) = self.outline_flat().into_iter().find(|entry| entry.row > row) {
+            self.cursor.row = entry.row;
+            self.cursor.col = entry.col;
+        }
+    }
+
+    /// Move the cursor to the previous outline entry above the cursor row
+    pub fn move_to_prev_heading(&mut self) {
+        let row = self.cursor.row;
+        if let Some(entry) = self.outline_flat().into_iter().rev().find(|entry| entry.row < row) {
+            self.cursor.row = entry.row;
+            self.cursor.col = entry.col;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_headings_nest_into_a_tree() {
+        let lines: Vec<String> = ["# Title", "text", "## A", "```", "# not a heading", "```", "### A.1", "## B", "#hashtag"]
+            .iter().map(|s| s.to_string()).collect();
+        let flat = markdown_headings(&lines);
+        assert_eq!(flat.iter().map(|e| e.row).collect::<Vec<_>>(), vec![0, 2, 6, 7]);
+        let tree = build_outline_tree(flat);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[0].children[0].title, "A.1");
+        let path: Vec<String> = enclosing_path(&tree, 6).into_iter().map(|e| e.title).collect();
+        assert_eq!(path, vec!["Title", "A", "A.1"]);
+        assert_eq!(enclosing_path(&tree, 7).last().map(|e| e.title.as_str()), Some("B"));
+    }
+
+    #[test]
+    fn headings_only_count_in_markdown_buffers() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = ["# comment", "x = 1"].iter().map(|s| s.to_string()).collect();
+        assert!(buffer.outline_flat().is_empty());
+        buffer.language = Some("python".to_string());
+        assert!(buffer.outline_flat().is_empty());
+        buffer.language = Some("markdown".to_string());
+        assert_eq!(buffer.outline_flat().len(), 1);
+        // Built again after an edit, reused until then
+        buffer.replace_range((1, 0), (1, 0), "## ");
+        assert_eq!(buffer.outline_flat().len(), 2);
+        buffer.lines[1].clear();
+        assert_eq!(buffer.outline_flat().len(), 2);
+        buffer.text_replaced();
+        assert_eq!(buffer.outline_flat().len(), 1);
+    }
+}
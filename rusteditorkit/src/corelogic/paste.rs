@@ -0,0 +1,103 @@
+//! Paste guard
+//!
+//! Pasted text should land exactly as copied. With `config.paste_guard` (the default)
+//! clipboard pastes, and typed input that looks like a paste (several lines or a long
+//! run committed at once, as clipboard managers and some input methods deliver it), are
+//! inserted verbatim as one undo step: Enter-style list continuation and bracket/quote
+//! surrounding do not run on them. Without the guard a paste is fed through the same
+//! handling as typing.
+
+use super::buffer::EditorBuffer;
+
+/// Text committed at once with more characters than this is treated as a paste
+pub const PASTE_DETECT_CHARS: usize = 64;
+
+/// True if typed `text` is more likely pasted than typed
+pub fn looks_like_paste(text: &str) -> bool {
+    text.contains('\n') || text.chars().nth(PASTE_DETECT_CHARS).is_some()
+}
+
+impl EditorBuffer {
+    /// Insert `text` verbatim, replacing the selection, as a single undo step
+    ///
+    /// Snapshot views and log buffers reject the paste and keep the caret and selection.
+    pub fn insert_pasted(&mut self, text: &str) {
+        if !self.allows_edit() {
+            return;
+        }
+        let text = text.replace("\r\n", "\n");
+        let (start, end) = match self.selection.take() {
+            Some(sel) => sel.normalized(),
+            None => ((self.cursor.row, self.cursor.col), (self.cursor.row, self.cursor.col)),
+        };
+        // The one state pushed here covers both removing the selection and inserting
+        self.push_undo();
+        self.replace_range(start, end, &text);
+        let (row, col) = start;
+        self.cursor.row = row + text.matches('\n').count();
+        self.cursor.col = match text.rsplit_once('\n') {
+            Some((_, last)) => last.chars().count(),
+            None => col + text.chars().count(),
+        };
+    }
+
+    /// Insert `text` as if it was typed: each line break continues lists like Enter does
+    /// and a single bracket or quote over a selection surrounds it
+    pub fn type_text(&mut self, text: &str) {
+        let mut chars = text.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if self.surround_selection(c) {
+                return;
+            }
+        }
+        for (index, segment) in text.replace("\r\n", "\n").split('\n').enumerate() {
+            if index > 0 && !self.continue_list() {
+                self.insert_newline();
+            }
+            if !segment.is_empty() {
+                self.insert_text(segment);
+            }
+        }
+    }
+
+    /// Insert typed `text`, guarding it as a paste when it looks like one
+    pub fn insert_typed(&mut self, text: &str) {
+        if self.config.paste_guard && looks_like_paste(text) {
+            self.insert_pasted(text);
+        } else {
+            self.type_text(text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corelogic::selection::Selection;
+    use crate::corelogic::undo::MAX_UNDO_STACK_SIZE;
+
+    #[test]
+    fn detects_multiline_and_long_input() {
+        assert!(!looks_like_paste("x"));
+        assert!(!looks_like_paste("你好"));
+        assert!(looks_like_paste("a\nb"));
+        assert!(looks_like_paste(&"x".repeat(PASTE_DETECT_CHARS + 1)));
+        assert!(!looks_like_paste(&"x".repeat(PASTE_DETECT_CHARS)));
+    }
+
+    #[test]
+    fn paste_over_a_selection_is_one_undo_step_with_a_full_history() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["abcdef".into()];
+        for _ in 0..MAX_UNDO_STACK_SIZE {
+            buffer.push_undo();
+        }
+        buffer.selection = Some(Selection { start_row: 0, start_col: 1, end_row: 0, end_col: 3 });
+        buffer.insert_pasted("X\r\nY");
+        assert_eq!(buffer.lines, vec!["aX", "Ydef"]);
+        assert_eq!((buffer.cursor.row, buffer.cursor.col), (1, 1));
+        buffer.undo();
+        assert_eq!(buffer.lines, vec!["abcdef"]);
+        assert_eq!(buffer.undo_stack.len(), MAX_UNDO_STACK_SIZE - 1);
+    }
+}
@@ -0,0 +1,212 @@
+//! Pointer (mouse) handling for EditorBuffer
+//!
+//! The single home of pointer logic: hit-testing screen positions, the `MouseState` of
+//! a press/drag and the click, drag and multi-click handlers the widget's
+//! `PointerHandler` forwards to. Every drag, whether it starts a selection or extends
+//! one with Shift, auto-scrolls the same way when the pointer leaves the text area.
+
+use super::buffer::EditorBuffer;
+
+/// Mouse interaction state for selection handling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseState {
+    /// No mouse interaction
+    #[default]
+    Idle,
+    /// Mouse is down and dragging to select
+    Selecting { start_row: usize, start_col: usize },
+    /// Mouse is down and dragging to extend selection
+    ExtendingSelection,
+}
+
+/// Rows to scroll for a drag at `y` beyond the text area `top..bottom`: negative above
+/// it, positive below it, one more for every line height further out
+pub fn drag_scroll_rows(y: f64, top: f64, bottom: f64, line_height: f64) -> isize {
+    if line_height <= 0.0 {
+        0
+    } else if y < top {
+        -(((top - y) / line_height).ceil() as isize)
+    } else if y >= bottom {
+        ((y - bottom) / line_height).floor() as isize + 1
+    } else {
+        0
+    }
+}
+
+impl EditorBuffer {
+    /// Convert screen coordinates to buffer position (row, col)
+    ///
+    /// Used by every pointer handler; columns come from the cached character edges of
+    /// the line (see `hittest`), so dragging along a long line stays cheap.
+    pub fn screen_to_buffer_position(&self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) -> (usize, usize) {
+        // Vertical columns: full-width CJK characters advance about two Latin cells
+        #[cfg(feature = "vertical-text")]
+        if self.vertical_mode {
+            return self.vertical_screen_to_buffer_position(x, y, line_height, 2.0 * char_width, self.viewport_text_right.get(), top_margin);
+        }
+        // Calculate row from y coordinate, accounting for the scrolled viewport
+        let row = self.row_at_display(self.display_row_at_y(y, top_margin, line_height));
+        
+        // Calculate column from x coordinate
+        let col = if row < self.lines.len() {
+            // Right-to-left rows run leftwards from the right edge of the text area
+            let text_right = self.viewport_text_right.get();
+            let offset = if text_right > 0.0 && self.is_rtl_row(row) { text_right - x } else { x - left_margin };
+            self.column_at_x(row, offset, char_width)
+        } else {
+            0
+        };
+        
+        (row, col)
+    }
+
+    /// Handle mouse click - sets cursor position and clears selection (unless Shift is held)
+    #[allow(clippy::too_many_arguments)]
+    pub fn handle_mouse_click(&mut self, x: f64, y: f64, shift_held: bool, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
+        let (row, col) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
+        
+        if shift_held && self.selection.is_some() {
+            // Extend existing selection
+            if let Some(sel) = &mut self.selection {
+                sel.end_row = row;
+                sel.end_col = col;
+                sel.clamp_to_buffer(&self.lines);
+            }
+        } else {
+            // Clear selection and set cursor position
+            self.selection = None;
+            self.cursor.row = row;
+            self.cursor.col = col;
+        }
+        
+        // Update mouse state
+        self.mouse_state = if shift_held {
+            MouseState::ExtendingSelection
+        } else {
+            MouseState::Selecting { start_row: row, start_col: col }
+        };
+    }
+
+    /// Handle mouse drag - creates or extends selection
+    ///
+    /// Dragging above or below the text area scrolls the view towards the pointer and
+    /// extends the selection to the edge row.
+    pub fn handle_mouse_drag(&mut self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
+        let y = if self.mouse_state == MouseState::Idle { y } else { self.drag_auto_scroll(y, line_height, top_margin) };
+        let (row, col) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
+        
+        match self.mouse_state {
+            MouseState::Selecting { start_row, start_col } => {
+                // Create new selection from start to current position
+                let mut sel = crate::corelogic::selection::Selection::new(start_row, start_col);
+                sel.end_row = row;
+                sel.end_col = col;
+                sel.clamp_to_buffer(&self.lines);
+                
+                // Only set selection if there's an actual area selected
+                if sel.is_active() {
+                    self.selection = Some(sel);
+                } else {
+                    self.selection = None;
+                }
+                
+                // Update cursor to current position
+                self.cursor.row = row;
+                self.cursor.col = col;
+            },
+            MouseState::ExtendingSelection => {
+                // Extend existing selection
+                if let Some(sel) = &mut self.selection {
+                    sel.end_row = row;
+                    sel.end_col = col;
+                    sel.clamp_to_buffer(&self.lines);
+                }
+                
+                // Update cursor to current position
+                self.cursor.row = row;
+                self.cursor.col = col;
+            },
+            MouseState::Idle => {
+                // Start new selection
+                self.mouse_state = MouseState::Selecting { start_row: row, start_col: col };
+            }
+        }
+    }
+
+    /// Handle mouse release - finalize selection
+    pub fn handle_mouse_release(&mut self) {
+        self.mouse_state = MouseState::Idle;
+    }
+
+    /// Handle double-click - select word at position
+    pub fn handle_double_click(&mut self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
+        let (row, col) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
+        
+        if row < self.lines.len() {
+            // Find word boundaries (word characters depend on the language)
+            if let Some((start_col, end_col)) = self.word_range_at(row, col) {
+                // Create selection for the word
+                let mut sel = crate::corelogic::selection::Selection::new(row, start_col);
+                sel.end_row = row;
+                sel.end_col = end_col;
+                self.selection = Some(sel);
+                
+                // Position cursor at end of selection
+                self.cursor.row = row;
+                self.cursor.col = end_col;
+            }
+        }
+        
+        self.mouse_state = MouseState::Idle;
+    }
+
+    /// Handle triple-click - select entire line
+    pub fn handle_triple_click(&mut self, x: f64, y: f64, line_height: f64, char_width: f64, left_margin: f64, top_margin: f64) {
+        let (row, _) = self.screen_to_buffer_position(x, y, line_height, char_width, left_margin, top_margin);
+        
+        if row < self.lines.len() {
+            // Select entire line
+            let mut sel = crate::corelogic::selection::Selection::new(row, 0);
+            sel.end_row = row;
+            sel.end_col = self.lines[row].chars().count();
+            self.selection = Some(sel);
+            
+            // Position cursor at end of line
+            self.cursor.row = row;
+            self.cursor.col = self.lines[row].chars().count();
+        }
+        
+        self.mouse_state = MouseState::Idle;
+    }
+
+    /// Scroll for a drag at `y` past the text area; returns `y` clamped into it
+    fn drag_auto_scroll(&mut self, y: f64, line_height: f64, top_margin: f64) -> f64 {
+        // Vertical columns scroll sideways; not handled yet
+        #[cfg(feature = "vertical-text")]
+        if self.vertical_mode {
+            return y;
+        }
+        let bottom = self.display_row_y(self.scroll_offset + self.viewport_rows().max(1), top_margin, line_height);
+        let rows = drag_scroll_rows(y, top_margin, bottom, line_height);
+        if rows != 0 {
+            let last = self.display_row_count().saturating_sub(1);
+            self.cancel_smooth_scroll();
+            self.scroll_offset = self.scroll_offset.saturating_add_signed(rows).min(last);
+        }
+        y.clamp(top_margin, bottom - line_height / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drag_scrolls_towards_the_pointer() {
+        assert_eq!(drag_scroll_rows(50.0, 10.0, 210.0, 20.0), 0);
+        assert_eq!(drag_scroll_rows(5.0, 10.0, 210.0, 20.0), -1);
+        assert_eq!(drag_scroll_rows(-35.0, 10.0, 210.0, 20.0), -3);
+        assert_eq!(drag_scroll_rows(210.0, 10.0, 210.0, 20.0), 1);
+        assert_eq!(drag_scroll_rows(255.0, 10.0, 210.0, 20.0), 3);
+    }
+}
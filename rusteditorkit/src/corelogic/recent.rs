@@ -0,0 +1,326 @@
+//! Recently opened files and their last positions
+//!
+//! A `RecentFiles` store is optional: hosts enable it with `set_recent_files`, usually
+//! with `RecentFiles::load_default()` (`$XDG_STATE_HOME/rusteditorkit/recent.ron`). The
+//! buffer then records every file it opens, remembers the cursor and scroll position
+//! when it moves to another file (or when the host calls `remember_position`, e.g. on
+//! quit) and restores that position when the file is opened again. Manual folds and
+//! bookmarks are remembered with it, anchored to line content (see `anchors`) so they
+//! survive modest edits made outside the editor. `entries()` lists the files newest
+//! first for "Open Recent" menus.
+//!
+//! Position, fold and bookmark updates stay in memory until the store is next saved:
+//! when a file is opened, forgotten or left (`remember_position`), on `save()` or when
+//! the store is dropped.
+//!
+//! Several editors may share the store file. Each save reads the file again and merges:
+//! the entries this store changed (or removed) since it last wrote win, every other entry
+//! is taken as found on disk.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use super::anchors::{LineAnchor, SavedFold};
+use super::buffer::EditorBuffer;
+use super::folding::FoldKind;
+use super::safesave::{write_atomically, BackupMode};
+
+/// Files kept in the store unless set otherwise
+pub const DEFAULT_MAX_RECENT_FILES: usize = 50;
+
+/// A remembered file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: String,
+    pub row: usize,
+    pub col: usize,
+    pub scroll_offset: usize,
+    /// Last opened, in seconds since the Unix epoch
+    pub opened: u64,
+    /// Manual folds when the file was last left
+    #[serde(default)]
+    pub folds: Vec<SavedFold>,
+    #[serde(default)]
+    pub bookmarks: Vec<LineAnchor>,
+}
+
+/// Recently opened files, newest first, persisted as RON
+#[derive(Debug, Clone, Default)]
+pub struct RecentFiles {
+    entries: Vec<RecentFile>,
+    max: usize,
+    /// Where the store is saved (in memory only when None)
+    file: Option<PathBuf>,
+    /// Paths whose entries changed here since the last save
+    changed: HashSet<String>,
+    /// Paths forgotten here since the last save
+    removed: HashSet<String>,
+}
+
+/// Key for `path`: absolute and with symlinks resolved where possible (URIs are kept)
+fn normalize(path: &str) -> String {
+    if path.contains("://") {
+        return path.to_string();
+    }
+    std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .map_or_else(|_| path.to_string(), |p| p.to_string_lossy().into_owned())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+impl RecentFiles {
+    /// Empty in-memory store
+    pub fn new(max: usize) -> Self {
+        Self { entries: Vec::new(), max, file: None, changed: HashSet::new(), removed: HashSet::new() }
+    }
+
+    /// Default location of the store file: `$XDG_STATE_HOME` (`~/.local/state`), or the
+    /// user data directory where there is no home directory (the temporary directory in
+    /// builds without the `gtk` feature)
+    pub fn default_path() -> PathBuf {
+        #[cfg(feature = "gtk")]
+        let fallback = glib::user_data_dir;
+        #[cfg(not(feature = "gtk"))]
+        let fallback = std::env::temp_dir;
+        let state_dir = std::env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state")))
+            .unwrap_or_else(fallback);
+        state_dir.join("rusteditorkit").join("recent.ron")
+    }
+
+    /// Load the store saved at `file` (empty if missing or unreadable); changes are saved back there
+    pub fn load(file: &Path) -> Self {
+        let mut store = Self::new(DEFAULT_MAX_RECENT_FILES);
+        store.entries = read_entries(file);
+        store.entries.truncate(store.max);
+        store.file = Some(file.to_path_buf());
+        store
+    }
+
+    /// Load the store from `default_path()`
+    pub fn load_default() -> Self {
+        Self::load(&Self::default_path())
+    }
+
+    /// Keep at most `max` files
+    pub fn set_max(&mut self, max: usize) {
+        self.max = max;
+        self.entries.truncate(max);
+    }
+
+    /// Remembered files, most recently opened first
+    pub fn entries(&self) -> &[RecentFile] {
+        &self.entries
+    }
+
+    /// Entry for `path`, if remembered
+    pub fn get(&self, path: &str) -> Option<&RecentFile> {
+        let key = normalize(path);
+        self.entries.iter().find(|entry| entry.path == key)
+    }
+
+    /// Record that `path` was opened, moving it to the front and keeping its position
+    pub fn record_open(&mut self, path: &str) {
+        let key = normalize(path);
+        let mut entry = match self.entries.iter().position(|entry| entry.path == key) {
+            Some(index) => self.entries.remove(index),
+            None => RecentFile { path: key, row: 0, col: 0, scroll_offset: 0, opened: 0, folds: Vec::new(), bookmarks: Vec::new() },
+        };
+        entry.opened = now();
+        self.changed.insert(entry.path.clone());
+        self.entries.insert(0, entry);
+        self.entries.truncate(self.max);
+        self.save();
+    }
+
+    /// Remember the position in `path` until the next save (ignored for files that are
+    /// not in the store)
+    pub fn record_position(&mut self, path: &str, row: usize, col: usize, scroll_offset: usize) {
+        self.update(path, |entry| (entry.row, entry.col, entry.scroll_offset) = (row, col, scroll_offset));
+    }
+
+    /// Remember the folds and bookmarks of `path` until the next save (ignored for files
+    /// that are not in the store)
+    pub fn record_markers(&mut self, path: &str, folds: Vec<SavedFold>, bookmarks: Vec<LineAnchor>) {
+        self.update(path, |entry| (entry.folds, entry.bookmarks) = (folds, bookmarks));
+    }
+
+    /// Change the entry for `path`, if there is one, leaving the write to the next save
+    fn update(&mut self, path: &str, change: impl FnOnce(&mut RecentFile)) {
+        let key = normalize(path);
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == key) {
+            change(entry);
+            self.changed.insert(key);
+        }
+    }
+
+    /// Forget `path`
+    pub fn remove(&mut self, path: &str) {
+        let key = normalize(path);
+        self.entries.retain(|entry| entry.path != key);
+        self.changed.remove(&key);
+        self.removed.insert(key);
+        self.save();
+    }
+
+    /// Forget all files
+    pub fn clear(&mut self) {
+        self.removed.extend(self.entries.drain(..).map(|entry| entry.path));
+        self.changed.clear();
+        self.save();
+    }
+
+    /// Merge with the store file as it is now and write the result back, if the store
+    /// has a file and changed since it last wrote
+    pub fn save(&mut self) {
+        let Some(file) = &self.file else { return };
+        if self.changed.is_empty() && self.removed.is_empty() {
+            return;
+        }
+        let mut entries: Vec<RecentFile> = self.entries.iter()
+            .filter(|entry| self.changed.contains(&entry.path))
+            .cloned()
+            .collect();
+        entries.extend(read_entries(file).into_iter()
+            .filter(|entry| !self.changed.contains(&entry.path) && !self.removed.contains(&entry.path)));
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.opened));
+        entries.truncate(self.max);
+        self.entries = entries;
+        let text = match ron::ser::to_string_pretty(&self.entries, ron::ser::PrettyConfig::default()) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("[ERROR] Failed to serialize recent files: {}", e);
+                return;
+            }
+        };
+        let written = file.parent().map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| write_atomically(file, text.as_bytes(), BackupMode::None));
+        match written {
+            Ok(()) => {
+                self.changed.clear();
+                self.removed.clear();
+            }
+            Err(e) => eprintln!("[ERROR] Failed to save recent files to '{}': {}", file.display(), e),
+        }
+    }
+}
+
+impl Drop for RecentFiles {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+/// Entries saved in `file` (none if missing or unreadable)
+fn read_entries(file: &Path) -> Vec<RecentFile> {
+    std::fs::read_to_string(file).ok()
+        .and_then(|text| ron::from_str::<Vec<RecentFile>>(&text).ok())
+        .unwrap_or_default()
+}
+
+impl EditorBuffer {
+    /// Enable (or with None, disable) the recent files store
+    pub fn set_recent_files(&mut self, store: Option<RecentFiles>) {
+        self.recent_files = store;
+    }
+
+    /// The recent files store, if enabled
+    pub fn recent_files(&self) -> Option<&RecentFiles> {
+        self.recent_files.as_ref()
+    }
+
+    /// Store the cursor and scroll position, manual folds and bookmarks of the current
+    /// file, and save the store
+    pub fn remember_position(&mut self) {
+        let (Some(store), Some(path)) = (self.recent_files.as_mut(), self.file_path.as_deref()) else {
+            return;
+        };
+        let folds = self.folds.iter()
+            .filter(|(_, fold)| fold.kind == FoldKind::Manual)
+            .map(|(&start, fold)| SavedFold::new(&self.lines, start, fold))
+            .collect();
+        let bookmarks = self.bookmarks.iter().map(|&row| LineAnchor::new(&self.lines, row)).collect();
+        let (row, col, scroll_offset) = (self.cursor.row, self.cursor.col, self.scroll_offset);
+        store.update(path, |entry| {
+            (entry.row, entry.col, entry.scroll_offset) = (row, col, scroll_offset);
+            (entry.folds, entry.bookmarks) = (folds, bookmarks);
+        });
+        store.save();
+    }
+
+    /// Record the file just loaded from `path` and move to its remembered position
+    pub(crate) fn restore_recent_position(&mut self, path: &str) {
+        let Some(store) = self.recent_files.as_mut() else { return };
+        let remembered = store.get(path).cloned();
+        store.record_open(path);
+        if let Some(entry) = remembered {
+            let (row, col) = super::recovery::clamp_position(&self.lines, entry.row, entry.col);
+            (self.cursor.row, self.cursor.col) = (row, col);
+            self.scroll_offset = entry.scroll_offset.min(self.lines.len().saturating_sub(1));
+            // Entries whose lines changed too much are dropped
+            self.folds.extend(entry.folds.iter().filter_map(|fold| fold.resolve(&self.lines)));
+            self.bookmarks.extend(entry.bookmarks.iter().filter_map(|anchor| anchor.resolve(&self.lines)));
+            self.reveal_cursor_row();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_round_trips_and_orders_by_open() {
+        let file = std::env::temp_dir().join(format!("rek-recent-{}", std::process::id())).join("recent.ron");
+        let mut store = RecentFiles::load(&file);
+        store.record_open("/tmp/a.txt");
+        store.record_open("/tmp/b.txt");
+        store.record_position("/tmp/a.txt", 12, 3, 5);
+        store.record_open("/tmp/a.txt");
+
+        let reloaded = RecentFiles::load(&file);
+        let paths: Vec<&str> = reloaded.entries().iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["/tmp/a.txt", "/tmp/b.txt"]);
+        let a = reloaded.get("/tmp/a.txt").unwrap();
+        assert_eq!((a.row, a.col, a.scroll_offset), (12, 3, 5));
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn positions_are_written_on_save_only() {
+        let file = std::env::temp_dir().join(format!("rek-recent-pending-{}", std::process::id())).join("recent.ron");
+        let mut store = RecentFiles::load(&file);
+        store.record_open("/tmp/a.txt");
+        store.record_position("/tmp/a.txt", 7, 2, 1);
+        let row = |file: &Path| RecentFiles::load(file).get("/tmp/a.txt").map(|entry| entry.row);
+        assert_eq!(row(&file), Some(0));
+        store.save();
+        assert_eq!(row(&file), Some(7));
+        store.record_position("/tmp/a.txt", 9, 0, 0);
+        drop(store);
+        assert_eq!(row(&file), Some(9));
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn stores_sharing_a_file_keep_each_others_entries() {
+        let file = std::env::temp_dir().join(format!("rek-recent-shared-{}", std::process::id())).join("recent.ron");
+        let mut first = RecentFiles::load(&file);
+        let mut second = RecentFiles::load(&file);
+        first.record_open("/tmp/a.txt");
+        second.record_open("/tmp/b.txt");
+        first.record_position("/tmp/a.txt", 4, 1, 0);
+        first.save();
+        second.remove("/tmp/a.txt");
+        first.record_open("/tmp/c.txt");
+
+        let paths: Vec<String> = RecentFiles::load(&file).entries().iter().map(|entry| entry.path.clone()).collect();
+        assert!(paths.contains(&"/tmp/b.txt".to_string()) && paths.contains(&"/tmp/c.txt".to_string()));
+        assert!(!paths.contains(&"/tmp/a.txt".to_string()));
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+}
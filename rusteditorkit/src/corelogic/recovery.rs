@@ -0,0 +1,73 @@
+//! Panic recovery for EditorBuffer
+//!
+//! The dispatcher and the draw functions run inside `catch_unwind`. After a command
+//! panics, `repair_state` brings the buffer back to a state every other command can
+//! rely on (non-empty lines, cursor and selections inside the text).
+
+use std::any::Any;
+use super::buffer::{EditorBuffer, MouseState};
+
+/// Human-readable message from a panic payload
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Clamp `(row, col)` into `lines` (`col` is a char index, like the cursor's)
+pub(crate) fn clamp_position(lines: &[String], row: usize, col: usize) -> (usize, usize) {
+    let row = row.min(lines.len().saturating_sub(1));
+    let len = lines.get(row).map_or(0, |line| line.chars().count());
+    (row, col.min(len))
+}
+
+impl EditorBuffer {
+    /// Restore the invariants other commands rely on after a failed command
+    pub fn repair_state(&mut self) {
+        // The failed command may have left per-line caches behind the text
+        self.text_replaced();
+        let (row, col) = clamp_position(&self.lines, self.cursor.row, self.cursor.col);
+        self.cursor.row = row;
+        self.cursor.col = col;
+
+        if let Some(sel) = self.selection.as_mut() {
+            (sel.start_row, sel.start_col) = clamp_position(&self.lines, sel.start_row, sel.start_col);
+            (sel.end_row, sel.end_col) = clamp_position(&self.lines, sel.end_row, sel.end_col);
+        }
+        let lines = &self.lines;
+        self.multi_cursors.retain(|&(r, _)| r < lines.len());
+        for cursor in self.multi_cursors.iter_mut() {
+            *cursor = clamp_position(lines, cursor.0, cursor.1);
+        }
+        self.multi_selections.clear();
+        self.mouse_state = MouseState::Idle;
+        self.scroll_offset = self.scroll_offset.min(self.lines.len().saturating_sub(1));
+        self.scroll_fraction = 0.0;
+        self.smooth_scroll = None;
+        self.focus_range = None;
+        self.line_flash = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_position_counts_chars_not_bytes() {
+        let lines = vec!["héllo".to_string(), "ab".to_string()];
+        assert_eq!(clamp_position(&lines, 9, 9), (1, 2));
+        assert_eq!(clamp_position(&lines, 0, 6), (0, 5));
+        assert_eq!(clamp_position(&[], 3, 3), (0, 0));
+    }
+
+    #[test]
+    fn panic_message_reads_str_and_string_payloads() {
+        let payload = std::panic::catch_unwind(|| panic!("boom {}", 1)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "boom 1");
+    }
+}
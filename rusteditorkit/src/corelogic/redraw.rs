@@ -0,0 +1,152 @@
+//! Redraw requests with per-layer dirty flags
+//!
+//! Code that changes what is on screen marks the layers it touched with `invalidate`
+//! (`request_redraw` marks all of them). The first mark after a frame asks the host for
+//! a new one through `redraw_callback`; later marks only add flags, so a command that
+//! invalidates several times still costs one `queue_draw`. The dispatcher holds requests
+//! back for the whole command with `begin_redraw_batch`/`end_redraw_batch`.
+//!
+//! The draw function takes the flags with `take_dirty_layers` and reuses the cached text
+//! and gutter layers that are still clean (see `render::invalidate::LayerCache`). A frame
+//! nobody asked for (a resize, a host calling `queue_draw` itself) redraws everything.
+
+use std::ops::{BitOr, BitOrAssign};
+use super::buffer::EditorBuffer;
+
+/// Set of view layers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RedrawLayers(u8);
+
+impl RedrawLayers {
+    pub const NONE: Self = Self(0);
+    /// Line text, fold summaries and missing glyph boxes
+    pub const TEXT: Self = Self(1);
+    /// Line numbers, fold markers, bookmarks, change bars and annotations
+    pub const GUTTER: Self = Self(1 << 1);
+    /// Selection highlight
+    pub const SELECTION: Self = Self(1 << 2);
+    /// The caret
+    pub const CURSOR: Self = Self(1 << 3);
+    /// Everything else drawn over the text area: active line, occurrences, line flash,
+    /// remote cursors, focus dimming, sticky scroll and the scroll map
+    pub const OVERLAY: Self = Self(1 << 4);
+    pub const ALL: Self = Self(0b1_1111);
+    /// What moving the caret or changing the selection touches
+    pub const CARET: Self = Self(Self::GUTTER.0 | Self::SELECTION.0 | Self::CURSOR.0 | Self::OVERLAY.0);
+
+    pub fn contains(self, layers: Self) -> bool {
+        self.0 & layers.0 == layers.0
+    }
+
+    pub fn intersects(self, layers: Self) -> bool {
+        self.0 & layers.0 != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for RedrawLayers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for RedrawLayers {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl EditorBuffer {
+    /// Mark `layers` for redrawing and ask the host for a frame, once per frame
+    pub fn invalidate(&self, layers: RedrawLayers) {
+        self.dirty_layers.set(self.dirty_layers.get() | layers);
+        if self.redraw_batch.get() == 0 {
+            self.flush_redraw();
+        }
+    }
+
+    fn flush_redraw(&self) {
+        if self.dirty_layers.get().is_empty() {
+            return;
+        }
+        if let Some(callback) = &self.redraw_callback {
+            if !self.redraw_queued.replace(true) {
+                callback();
+            }
+        }
+    }
+
+    /// Hold redraw requests back until the matching `end_redraw_batch` (batches nest)
+    pub fn begin_redraw_batch(&self) {
+        self.redraw_batch.set(self.redraw_batch.get() + 1);
+    }
+
+    /// Close a batch, asking for one frame if anything was invalidated during it
+    pub fn end_redraw_batch(&self) {
+        let depth = self.redraw_batch.get().saturating_sub(1);
+        self.redraw_batch.set(depth);
+        if depth == 0 {
+            self.flush_redraw();
+        }
+    }
+
+    /// Layers marked since the last frame, without clearing them
+    pub fn dirty_layers(&self) -> RedrawLayers {
+        self.dirty_layers.get()
+    }
+
+    /// Layers to redraw in the frame being drawn; clears them for the next one
+    ///
+    /// A frame drawn without any invalidation redraws everything.
+    pub fn take_dirty_layers(&self) -> RedrawLayers {
+        self.redraw_queued.set(false);
+        match self.dirty_layers.replace(RedrawLayers::NONE) {
+            RedrawLayers::NONE => RedrawLayers::ALL,
+            layers => layers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use super::*;
+
+    #[test]
+    fn requests_coalesce_into_one_frame() {
+        let mut buffer = EditorBuffer::new();
+        let frames = Rc::new(Cell::new(0));
+        let counter = frames.clone();
+        buffer.redraw_callback = Some(Box::new(move || counter.set(counter.get() + 1)));
+        // A new buffer starts with everything dirty for its first frame
+        assert_eq!(buffer.take_dirty_layers(), RedrawLayers::ALL);
+
+        buffer.invalidate(RedrawLayers::CURSOR);
+        buffer.invalidate(RedrawLayers::SELECTION);
+        assert_eq!(frames.get(), 1);
+        assert_eq!(buffer.take_dirty_layers(), RedrawLayers::SELECTION | RedrawLayers::CURSOR);
+
+        // A batch asks once, when it closes
+        buffer.begin_redraw_batch();
+        buffer.request_redraw();
+        buffer.invalidate(RedrawLayers::GUTTER);
+        assert_eq!(frames.get(), 1);
+        buffer.end_redraw_batch();
+        assert_eq!(frames.get(), 2);
+        assert!(buffer.take_dirty_layers().contains(RedrawLayers::TEXT));
+
+        // An empty batch asks for nothing; an unrequested frame redraws everything
+        buffer.begin_redraw_batch();
+        buffer.end_redraw_batch();
+        assert_eq!(frames.get(), 2);
+        assert_eq!(buffer.take_dirty_layers(), RedrawLayers::ALL);
+        assert!(RedrawLayers::CARET.intersects(RedrawLayers::GUTTER));
+        assert!(!RedrawLayers::CARET.contains(RedrawLayers::TEXT));
+    }
+}
@@ -0,0 +1,259 @@
+//! Hard line wrapping (reflow) for EditorBuffer
+//!
+//! This module implements the `ReflowParagraph` command (similar to Vim's `gq`):
+//! the paragraph under the cursor, or every paragraph in the selection, is re-wrapped
+//! to a configured column while keeping comment prefixes and list markers intact.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use super::buffer::EditorBuffer;
+
+/// Configuration for the reflow command
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReflowConfig {
+    /// Default column to wrap at (in characters, prefix included)
+    pub wrap_column: usize,
+    /// Per-language overrides keyed by language id (e.g. "rust" => 100)
+    pub language_columns: HashMap<String, usize>,
+    /// Comment prefixes recognized at the start of a line, longest first
+    pub comment_prefixes: Vec<String>,
+}
+
+impl Default for ReflowConfig {
+    fn default() -> Self {
+        Self {
+            wrap_column: 80,
+            language_columns: HashMap::new(),
+            comment_prefixes: vec![
+                "///".to_string(),
+                "//!".to_string(),
+                "//".to_string(),
+                "#".to_string(),
+            ],
+        }
+    }
+}
+
+/// Prefix information for a single line (indent, comment token and list marker)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LinePrefix {
+    /// Leading whitespace plus comment token (and the space after it)
+    lead: String,
+    /// List marker including its trailing space, e.g. "- " or "12. "
+    marker: String,
+    /// Remaining text after the prefix
+    body: String,
+}
+
+impl LinePrefix {
+    fn parse(line: &str, comment_prefixes: &[String]) -> Self {
+        let indent_len = line.len() - line.trim_start().len();
+        let mut lead_end = indent_len;
+        let rest = &line[indent_len..];
+        for prefix in comment_prefixes {
+            if !prefix.is_empty() && rest.starts_with(prefix.as_str()) {
+                lead_end += prefix.len();
+                if line[lead_end..].starts_with(' ') {
+                    lead_end += 1;
+                }
+                break;
+            }
+        }
+        let lead = line[..lead_end].to_string();
+        let after_lead = &line[lead_end..];
+        let marker_len = list_marker_len(after_lead);
+        Self {
+            lead,
+            marker: after_lead[..marker_len].to_string(),
+            body: after_lead[marker_len..].trim().to_string(),
+        }
+    }
+
+    /// Prefix used for continuation lines (marker replaced by spaces)
+    fn continuation(&self) -> String {
+        format!("{}{}", self.lead, " ".repeat(self.marker.chars().count()))
+    }
+}
+
+/// Returns the byte length of a list marker ("- ", "* ", "+ ", "1. ", "1) ") at the start of `text`
+fn list_marker_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 2 && matches!(bytes[0], b'-' | b'*' | b'+') && bytes[1] == b' ' {
+        return 2;
+    }
+    let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits > 0
+        && bytes.len() >= digits + 2
+        && matches!(bytes[digits], b'.' | b')')
+        && bytes[digits + 1] == b' '
+    {
+        return digits + 2;
+    }
+    0
+}
+
+/// Greedily wrap `words` so that no line exceeds `column` characters (unless a single word is longer)
+fn wrap_words(words: &[&str], first_prefix: &str, next_prefix: &str, column: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = first_prefix.to_string();
+    let mut current_len = first_prefix.chars().count();
+    let mut has_word = false;
+    for word in words {
+        let word_len = word.chars().count();
+        if has_word && current_len + 1 + word_len > column {
+            out.push(current);
+            current = next_prefix.to_string();
+            current_len = next_prefix.chars().count();
+            has_word = false;
+        }
+        if has_word {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.push_str(word);
+        current_len += word_len;
+        has_word = true;
+    }
+    out.push(current.trim_end().to_string());
+    out
+}
+
+/// Reflow a block of lines, treating blank lines and list items as paragraph boundaries
+pub fn reflow_lines(lines: &[String], column: usize, comment_prefixes: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let first = LinePrefix::parse(&lines[i], comment_prefixes);
+        if first.body.is_empty() && first.marker.is_empty() {
+            // Blank (or comment-only) line separates paragraphs and is kept as is
+            out.push(lines[i].clone());
+            i += 1;
+            continue;
+        }
+        let mut bodies = vec![first.body.clone()];
+        let mut j = i + 1;
+        while j < lines.len() {
+            let next = LinePrefix::parse(&lines[j], comment_prefixes);
+            if next.body.is_empty() || !next.marker.is_empty() || next.lead.trim() != first.lead.trim() {
+                break;
+            }
+            bodies.push(next.body);
+            j += 1;
+        }
+        let joined = bodies.join(" ");
+        let words: Vec<&str> = joined.split_whitespace().collect();
+        let first_prefix = format!("{}{}", first.lead, first.marker);
+        out.extend(wrap_words(&words, &first_prefix, &first.continuation(), column));
+        i = j;
+    }
+    out
+}
+
+impl EditorBuffer {
+    /// Column used by the reflow command for this buffer
+    ///
+    /// Resolution order: buffer override, per-language config, global config.
+    pub fn reflow_column(&self) -> usize {
+        if let Some(column) = self.reflow_column_override.or_else(|| self.modeline.as_ref().and_then(|modeline| modeline.text_width)) {
+            return column;
+        }
+        self.language
+            .as_ref()
+            .and_then(|lang| self.config.reflow.language_columns.get(lang).copied())
+            .unwrap_or(self.config.reflow.wrap_column)
+            .max(1)
+    }
+
+    /// Set (or clear) the buffer-local reflow column override
+    pub fn set_reflow_column(&mut self, column: Option<usize>) {
+        self.reflow_column_override = column;
+    }
+
+    /// Re-wrap the paragraph under the cursor, or all paragraphs touched by the selection
+    pub fn reflow_paragraph(&mut self) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let prefixes = self.config.reflow.comment_prefixes.clone();
+        let is_blank = |line: &str| {
+            let parsed = LinePrefix::parse(line, &prefixes);
+            parsed.body.is_empty() && parsed.marker.is_empty()
+        };
+
+        let (start_row, end_row) = match &self.selection {
+            Some(sel) if sel.is_active() => {
+                let ((start_row, _), (end_row, _)) = sel.normalized();
+                (start_row, end_row.min(self.lines.len() - 1))
+            }
+            _ => {
+                let row = self.cursor.row.min(self.lines.len() - 1);
+                if is_blank(&self.lines[row]) {
+                    return;
+                }
+                let mut start = row;
+                while start > 0
+                    && !is_blank(&self.lines[start - 1])
+                    && LinePrefix::parse(&self.lines[start], &prefixes).marker.is_empty()
+                {
+                    start -= 1;
+                }
+                let mut end = row;
+                while end + 1 < self.lines.len()
+                    && !is_blank(&self.lines[end + 1])
+                    && LinePrefix::parse(&self.lines[end + 1], &prefixes).marker.is_empty()
+                {
+                    end += 1;
+                }
+                (start, end)
+            }
+        };
+
+        let reflowed = reflow_lines(&self.lines[start_row..=end_row], self.reflow_column(), &prefixes);
+        if reflowed.as_slice() == &self.lines[start_row..=end_row] {
+            return;
+        }
+
+        self.push_undo();
+        let new_end = start_row + reflowed.len() - 1;
+        self.replace_rows(start_row..end_row + 1, reflowed);
+        self.selection = None;
+        self.cursor.row = new_end;
+        self.cursor.col = self.lines[new_end].chars().count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixes() -> Vec<String> {
+        ReflowConfig::default().comment_prefixes
+    }
+
+    #[test]
+    fn plain_paragraph_rewraps_to_the_width() {
+        let lines = vec!["one two three four five six".to_string()];
+        let out = reflow_lines(&lines, 10, &prefixes());
+        assert_eq!(out, vec!["one two", "three four", "five six"]);
+    }
+
+    #[test]
+    fn reflow_keeps_the_comment_prefix() {
+        let lines = vec![
+            "    // alpha beta".to_string(),
+            "    // gamma delta epsilon".to_string(),
+        ];
+        let out = reflow_lines(&lines, 20, &prefixes());
+        assert_eq!(out, vec!["    // alpha beta", "    // gamma delta", "    // epsilon"]);
+    }
+
+    #[test]
+    fn list_items_reflow_separately() {
+        let lines = vec![
+            "- first item text".to_string(),
+            "- second".to_string(),
+        ];
+        let out = reflow_lines(&lines, 10, &prefixes());
+        assert_eq!(out, vec!["- first", "  item", "  text", "- second"]);
+    }
+}
@@ -0,0 +1,72 @@
+//! Remote participants' cursors for collaborative editing overlays
+//!
+//! This is only the presentation half of collaboration: the host syncs the text and
+//! pushes every participant's caret (and optional selection anchor) with
+//! `set_remote_cursors`. Positions are (row, char column) in the current buffer and are
+//! clamped when drawn, so a stale update can never index past the text.
+
+use super::buffer::EditorBuffer;
+use super::color::Color;
+
+/// Caret and selection of one remote participant
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteCursor {
+    /// Caret position as (row, char column)
+    pub pos: (usize, usize),
+    /// Other end of the participant's selection, if any
+    pub anchor: Option<(usize, usize)>,
+    /// Caret, flag and selection tint
+    pub color: Color,
+    /// Name shown in the flag above the caret
+    pub label: String,
+}
+
+impl RemoteCursor {
+    pub fn new(pos: (usize, usize), color: Color, label: &str) -> Self {
+        Self { pos, anchor: None, color, label: label.to_string() }
+    }
+
+    /// Same cursor with a selection running from `anchor` to the caret
+    pub fn with_selection(mut self, anchor: (usize, usize)) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    /// Selection in document order, or `None` when empty
+    pub fn selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.anchor.filter(|&anchor| anchor != self.pos)?;
+        Some((anchor.min(self.pos), anchor.max(self.pos)))
+    }
+}
+
+impl EditorBuffer {
+    /// Replace the remote participants' cursors and redraw
+    pub fn set_remote_cursors(&mut self, cursors: Vec<RemoteCursor>) {
+        self.remote_cursors = cursors;
+        self.request_redraw();
+    }
+
+    /// Remove all remote cursors
+    pub fn clear_remote_cursors(&mut self) {
+        self.set_remote_cursors(Vec::new());
+    }
+
+    /// Remote cursors currently shown
+    pub fn remote_cursors(&self) -> &[RemoteCursor] {
+        &self.remote_cursors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_range_is_ordered_and_skips_empty() {
+        let caret = RemoteCursor::new((2, 4), Color::hex(0xe91e63), "Ana");
+        assert_eq!(caret.selection_range(), None);
+        assert_eq!(caret.clone().with_selection((2, 4)).selection_range(), None);
+        assert_eq!(caret.clone().with_selection((5, 0)).selection_range(), Some(((2, 4), (5, 0))));
+        assert_eq!(caret.with_selection((0, 9)).selection_range(), Some(((0, 9), (2, 4))));
+    }
+}
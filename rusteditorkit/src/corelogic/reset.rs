@@ -0,0 +1,100 @@
+//! Replacing the whole document
+//!
+//! `reset` swaps in new text in one step and drops everything tied to the old text:
+//! history, carets and selections, bookmarks, folds, diagnostics, snapshots and the
+//! per-row caches. Hosts should use it instead of assigning `lines` directly, which
+//! leaves that state pointing at rows that no longer mean anything. Opening a file and
+//! starting a new one clear the same state.
+
+use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+
+/// Lines of `content` (`\n` or `\r\n` separated; a trailing newline ends in an empty line)
+pub fn split_content(content: &str) -> Vec<String> {
+    content.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line).to_string()).collect()
+}
+
+impl EditorBuffer {
+    /// Replace all text with `content` and emit `EditorEvent::DocumentReset`
+    ///
+    /// The new text becomes the saved baseline (the buffer is unmodified) and the file path
+    /// is kept. The change feed is not given an op: `changes_since` returns None for older
+    /// revisions, so synchronization hosts resync from the full text.
+    pub fn reset(&mut self, content: &str) {
+        self.lines = split_content(content);
+        self.text_replaced();
+        self.clear_document_state();
+        self.set_saved_lines(self.lines.clone());
+        self.changes.revision += 1;
+        self.changes.log.clear();
+        self.events.emit(&EditorEvent::DocumentReset { lines: self.lines.len() });
+        self.request_redraw();
+    }
+
+    /// Drop carets, history, markers and caches tied to the previous text
+    pub(crate) fn clear_document_state(&mut self) {
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.cursor.row = 0;
+        self.cursor.col = 0;
+        self.scroll_offset = 0;
+        self.scroll_fraction = 0.0;
+        self.smooth_scroll = None;
+        self.selection = None;
+        self.multi_cursors.clear();
+        self.multi_selections.clear();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.diagnostics.clear();
+        self.bookmarks.clear();
+        self.snapshots.clear();
+        self.snapshot_view = None;
+        self.folds.clear();
+        self.snippet = None;
+        self.focus_range = None;
+        self.line_flash = None;
+        self.remote_cursors.clear();
+        self.paragraph_directions.clear();
+        self.decimal_regions.clear();
+        self.missing_glyphs.borrow_mut().clear();
+        self.invalidate_gutter_annotations();
+        self.last_statistics = None;
+        self.last_scope_path = None;
+        self.last_caret_context = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_unix_and_windows_line_ends() {
+        assert_eq!(split_content("a\r\nb\n"), vec!["a", "b", ""]);
+        assert_eq!(split_content(""), vec![""]);
+    }
+
+    #[test]
+    fn reset_drops_the_state_of_the_old_text() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["one".into(), "two".into(), "three".into(), String::new()];
+        buffer.cursor = crate::corelogic::EditorCursor::new(1, 0);
+        buffer.toggle_bookmark();
+        buffer.add_fold(0, 2, None);
+        buffer.cursor = crate::corelogic::EditorCursor::new(3, 0);
+        buffer.insert_snippet("${1:a} ${2:b}");
+        buffer.redo_stack = buffer.undo_stack.clone();
+        buffer.selection = Some(crate::corelogic::selection::Selection::new(0, 1));
+        assert!(buffer.can_undo() && buffer.can_redo() && buffer.in_snippet());
+
+        buffer.reset("new\ntext");
+        assert_eq!(buffer.lines, vec!["new", "text"]);
+        assert!(!buffer.can_undo() && !buffer.can_redo());
+        assert!(buffer.selection.is_none());
+        assert!(buffer.bookmarks.is_empty());
+        assert!(buffer.folds.is_empty());
+        assert!(!buffer.in_snippet());
+        assert_eq!((buffer.cursor.row, buffer.cursor.col), (0, 0));
+    }
+}
@@ -0,0 +1,192 @@
+//! Crash-safe saving
+//!
+//! With `config.atomic_save` (the default) a save writes a temporary file next to the
+//! target, flushes it to disk and renames it over the target, so a crash leaves either
+//! the old or the new file, never a truncated one. The target's permissions (and, where
+//! allowed, owner and group) carry over to the new file, and a symlinked target is
+//! written through the link. `config.backup_mode` optionally keeps the previous version
+//! as `name~` or as numbered `name.~N~` copies.
+//!
+//! When no temporary file can be created next to the target (a directory the user may
+//! not write to, holding a file they may), the file is overwritten in place instead.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use super::buffer::EditorBuffer;
+
+/// Backup copies kept when a file is overwritten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum BackupMode {
+    /// No backup
+    #[default]
+    None,
+    /// A single `name~` copy of the previous version
+    Tilde,
+    /// `name.~1~`, `name.~2~`, ... one copy per save
+    Numbered,
+}
+
+/// Path of the backup to write for `path`, given the files already in its directory
+pub fn backup_path(path: &Path, mode: BackupMode) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    match mode {
+        BackupMode::None => None,
+        BackupMode::Tilde => Some(path.with_file_name(format!("{}~", name))),
+        BackupMode::Numbered => {
+            let prefix = format!("{}.~", name);
+            let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            let last = fs::read_dir(dir).ok()?
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let file = entry.file_name().to_string_lossy().into_owned();
+                    file.strip_prefix(&prefix)?.strip_suffix('~')?.parse::<u32>().ok()
+                })
+                .max()
+                .unwrap_or(0);
+            Some(path.with_file_name(format!("{}{}~", prefix, last + 1)))
+        }
+    }
+}
+
+/// Write `bytes` to `path` through a synced temporary file in the same directory
+///
+/// Keeps a backup of the existing file first when `backup` is not `BackupMode::None`.
+/// Falls back to `write_in_place` when the temporary file cannot be created.
+pub fn write_atomically(path: &Path, bytes: &[u8], backup: BackupMode) -> io::Result<()> {
+    // Replace the file a symlink points to, not the link itself
+    let target = if fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink()) {
+        fs::canonicalize(path)?
+    } else {
+        path.to_path_buf()
+    };
+    let existing = fs::metadata(&target).ok();
+    if existing.is_some() {
+        if let Some(backup_path) = backup_path(&target, backup) {
+            fs::copy(&target, backup_path)?;
+        }
+    }
+
+    let name = target.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_string_lossy()
+        .into_owned();
+    let temp = target.with_file_name(format!(".{}.{}.tmp", name, std::process::id()));
+    let Ok(mut file) = OpenOptions::new().write(true).create_new(true).open(&temp) else {
+        return write_in_place(&target, bytes);
+    };
+    let written = (|| {
+        file.write_all(bytes)?;
+        if let Some(meta) = &existing {
+            file.set_permissions(meta.permissions())?;
+            preserve_owner(&temp, meta);
+        }
+        file.sync_all()?;
+        fs::rename(&temp, &target)
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+        return written;
+    }
+    sync_parent_dir(&target);
+    Ok(())
+}
+
+/// Overwrite `path` with `bytes` and sync it; not crash-safe, but keeps the file's
+/// permissions and owner since it stays the same file
+pub fn write_in_place(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+/// Give `path` the owner and group in `meta` (best effort: only root may change the owner)
+#[cfg(unix)]
+fn preserve_owner(path: &Path, meta: &fs::Metadata) {
+    use std::os::unix::fs::MetadataExt;
+    if std::os::unix::fs::chown(path, Some(meta.uid()), Some(meta.gid())).is_err() {
+        let _ = std::os::unix::fs::chown(path, None, Some(meta.gid()));
+    }
+}
+
+#[cfg(not(unix))]
+fn preserve_owner(_path: &Path, _meta: &fs::Metadata) {}
+
+/// Flush the rename to disk (a no-op where directories cannot be opened)
+fn sync_parent_dir(path: &Path) {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+impl EditorBuffer {
+    /// Write `bytes` to `path` using the configured save strategy
+    pub(crate) fn write_file_bytes(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        if self.config.atomic_save {
+            write_atomically(path, bytes, self.config.backup_mode)
+        } else {
+            if let Some(backup) = backup_path(path, self.config.backup_mode).filter(|_| path.exists()) {
+                fs::copy(path, backup)?;
+            }
+            fs::write(path, bytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_keeps_permissions_and_backups() {
+        let dir = std::env::temp_dir().join(format!("rek-safesave-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("notes.txt");
+        fs::write(&path, "one").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        write_atomically(&path, b"two", BackupMode::Numbered).unwrap();
+        write_atomically(&path, b"three", BackupMode::Numbered).unwrap();
+        write_atomically(&path, b"four", BackupMode::Tilde).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "four");
+        assert_eq!(fs::read_to_string(dir.join("notes.txt.~1~")).unwrap(), "one");
+        assert_eq!(fs::read_to_string(dir.join("notes.txt.~2~")).unwrap(), "two");
+        assert_eq!(fs::read_to_string(dir.join("notes.txt~")).unwrap(), "three");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o640);
+        }
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 4);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_is_written_in_place() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("rek-safesave-ro-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("notes.txt");
+        fs::write(&path, "one").unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o555)).unwrap();
+        // Root may write anywhere, so the directory only blocks the temp file for others
+        let blocked = File::create(dir.join("probe")).is_err();
+        let written = write_atomically(&path, b"two", BackupMode::None);
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        written.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two");
+        if blocked {
+            assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
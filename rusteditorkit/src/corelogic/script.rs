@@ -0,0 +1,170 @@
+//! Scripted editing without a window
+//!
+//! A script is a list of commands, one per line, run against a buffer in order:
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! select 3 10          # lines 3 to 10 (1-based, inclusive); "select all"; "select none"
+//! replace /foo/bar/    # replace all; any delimiter character works: replace |a/b|c|
+//! indent
+//! unindent
+//! sort                 # selected lines, or all lines without a selection
+//! ```
+//!
+//! It only needs the headless core, which is what the `rek-script` binary (feature
+//! `cli`) and tests use it for.
+
+use super::buffer::EditorBuffer;
+use super::selection::Selection;
+
+/// One script command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptCommand {
+    /// Select whole rows `first..=last` (0-based)
+    Select { first: usize, last: usize },
+    SelectAll,
+    SelectNone,
+    /// Replace every occurrence of `query`
+    Replace { query: String, replacement: String },
+    Indent,
+    Unindent,
+    /// Sort the selected rows (all rows without a selection)
+    Sort,
+}
+
+/// A script line that could not be parsed, or a command that could not run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    /// 1-based script line
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "script line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// `replace` argument `/query/replacement/` with any delimiter
+fn parse_replace(args: &str) -> Result<ScriptCommand, String> {
+    let mut chars = args.chars();
+    let delimiter = chars.next().ok_or("replace needs /query/replacement/")?;
+    let parts: Vec<&str> = chars.as_str().split(delimiter).collect();
+    match parts.as_slice() {
+        [query, replacement, ""] if !query.is_empty() => Ok(ScriptCommand::Replace {
+            query: query.to_string(),
+            replacement: replacement.to_string(),
+        }),
+        _ => Err(format!("replace needs {0}query{0}replacement{0}", delimiter)),
+    }
+}
+
+fn parse_select(args: &str) -> Result<ScriptCommand, String> {
+    let row = |n: &str| n.parse::<usize>().ok().filter(|&n| n > 0).map(|n| n - 1)
+        .ok_or_else(|| format!("'{}' is not a line number", n));
+    match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["all"] => Ok(ScriptCommand::SelectAll),
+        ["none"] => Ok(ScriptCommand::SelectNone),
+        [line] => row(line).map(|first| ScriptCommand::Select { first, last: first }),
+        [first, last] => {
+            let (first, last) = (row(first)?, row(last)?);
+            Ok(ScriptCommand::Select { first: first.min(last), last: first.max(last) })
+        }
+        _ => Err("select needs a line, a line range, 'all' or 'none'".to_string()),
+    }
+}
+
+/// Parse the commands of `script`
+pub fn parse_script(script: &str) -> Result<Vec<(usize, ScriptCommand)>, ScriptError> {
+    let mut commands = Vec::new();
+    for (index, text) in script.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let (name, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        // Trailing comments are allowed except after replace, whose text may contain '#'
+        let plain_args = args.split('#').next().unwrap_or("").trim();
+        let command = match name {
+            "select" => parse_select(plain_args),
+            "replace" => parse_replace(args.trim()),
+            "indent" => Ok(ScriptCommand::Indent),
+            "unindent" => Ok(ScriptCommand::Unindent),
+            "sort" => Ok(ScriptCommand::Sort),
+            _ => Err(format!("unknown command '{}'", name)),
+        };
+        commands.push((line, command.map_err(|message| ScriptError { line, message })?));
+    }
+    Ok(commands)
+}
+
+impl EditorBuffer {
+    /// Sort the selected rows, or all rows without a selection, as one undo step
+    pub fn sort_lines(&mut self) {
+        let (first, last) = match &self.selection {
+            Some(sel) => {
+                let ((first, _), (last, _)) = sel.normalized();
+                (first, last.min(self.lines.len() - 1))
+            }
+            None => (0, self.lines.len() - 1),
+        };
+        let mut sorted = self.lines[first..=last].to_vec();
+        sorted.sort();
+        self.push_undo();
+        self.replace_rows(first..last + 1, sorted);
+        self.request_redraw();
+    }
+
+    /// Run parsed script commands in order, stopping at the first that fails
+    pub fn run_script(&mut self, commands: &[(usize, ScriptCommand)]) -> Result<(), ScriptError> {
+        for (line, command) in commands {
+            match command {
+                ScriptCommand::Select { first, last } => {
+                    if *last >= self.lines.len() {
+                        return Err(ScriptError {
+                            line: *line,
+                            message: format!("line {} is past the end ({} lines)", last + 1, self.lines.len()),
+                        });
+                    }
+                    let mut sel = Selection::new(*first, 0);
+                    sel.set(*first, 0, *last, self.lines[*last].chars().count());
+                    self.selection = Some(sel);
+                    (self.cursor.row, self.cursor.col) = (*first, 0);
+                }
+                ScriptCommand::SelectAll => self.select_all(),
+                ScriptCommand::SelectNone => self.selection = None,
+                ScriptCommand::Replace { query, replacement } => {
+                    self.replace_all(query, replacement);
+                }
+                ScriptCommand::Indent => self.indent(),
+                ScriptCommand::Unindent => self.unindent(),
+                ScriptCommand::Sort => self.sort_lines(),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_reports_bad_lines() {
+        let script = "# tidy\nselect 4 2\nreplace |a/b|c#d|\n\nsort  # trailing comment\nselect all";
+        let commands: Vec<ScriptCommand> = parse_script(script).unwrap().into_iter().map(|(_, c)| c).collect();
+        assert_eq!(commands, vec![
+            ScriptCommand::Select { first: 1, last: 3 },
+            ScriptCommand::Replace { query: "a/b".to_string(), replacement: "c#d".to_string() },
+            ScriptCommand::Sort,
+            ScriptCommand::SelectAll,
+        ]);
+        assert_eq!(parse_script("indent\nfrobnicate").unwrap_err().line, 2);
+        assert!(parse_script("replace /a/").is_err());
+        assert!(parse_script("select 0").is_err());
+    }
+}
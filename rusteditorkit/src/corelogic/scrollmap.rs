@@ -0,0 +1,213 @@
+//! Annotated scroll map for EditorBuffer
+//!
+//! Collects the rows worth marking along the scroll track (search matches, diagnostics,
+//! lines changed since the last save, bookmarks) and maps between track coordinates and
+//! buffer rows. Drawing lives in `render::scrollmap`.
+//!
+//! Search matches are looked up once per text revision and query, and modified rows
+//! once per text revision and save, not on every frame.
+
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use super::buffer::EditorBuffer;
+use super::snapshots::diff_hunks;
+
+/// Source of a scroll map mark, in drawing order (later kinds paint over earlier ones)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollMarkKind {
+    Modified,
+    SearchMatch,
+    Bookmark,
+    Warning,
+    Error,
+}
+
+/// A single mark on the scroll track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollMark {
+    pub row: usize,
+    pub kind: ScrollMarkKind,
+}
+
+/// Rows holding matches of `query`, as of text revision `revision`
+#[derive(Debug, Clone)]
+pub struct SearchMarkCache {
+    revision: u64,
+    query: String,
+    rows: Vec<usize>,
+}
+
+/// Rows changed since the last save, as of text revision `revision`
+#[derive(Debug, Clone)]
+pub struct ModifiedRowsCache {
+    revision: u64,
+    rows: Rc<BTreeSet<usize>>,
+}
+
+/// Rows of `lines` that differ from `saved`, line by line
+///
+/// Inserted and rewritten rows are marked; where lines were only removed, the row now
+/// standing in their place is.
+pub fn changed_rows(saved: &[String], lines: &[String]) -> BTreeSet<usize> {
+    let last = lines.len().saturating_sub(1);
+    diff_hunks(saved, lines).iter()
+        .flat_map(|hunk| if hunk.new_lines.is_empty() {
+            let row = hunk.new_start.min(last);
+            row..row + 1
+        } else {
+            hunk.new_range()
+        })
+        .collect()
+}
+
+impl EditorBuffer {
+    /// Set (or clear) the query whose matches are marked in the scroll map
+    pub fn set_search_query(&mut self, query: Option<&str>) {
+        self.search_query = query.filter(|q| !q.is_empty()).map(str::to_string);
+    }
+
+    /// Make `lines` the saved text that modified rows are measured against
+    pub(crate) fn set_saved_lines(&mut self, lines: Vec<String>) {
+        self.saved_lines = lines;
+        self.modified_rows_cache.replace(None);
+    }
+
+    /// Rows changed since the buffer was last opened, saved or reset with `new_file`,
+    /// compared again only after the text or the saved text changed
+    pub fn modified_rows(&self) -> Rc<BTreeSet<usize>> {
+        if self.saved_lines.is_empty() {
+            return Rc::default();
+        }
+        if let Some(cache) = self.modified_rows_cache.borrow().as_ref().filter(|cache| cache.revision == self.text_revision) {
+            return cache.rows.clone();
+        }
+        let rows = Rc::new(changed_rows(&self.saved_lines, &self.lines));
+        self.modified_rows_cache.replace(Some(ModifiedRowsCache { revision: self.text_revision, rows: rows.clone() }));
+        rows
+    }
+
+    /// All scroll map marks, sorted by kind so higher-priority marks are drawn last
+    ///
+    /// Log buffers only grow, so they get no modified-line marks.
+    pub fn scroll_marks(&self) -> Vec<ScrollMark> {
+        let modified = if self.is_log_buffer() { Rc::default() } else { self.modified_rows() };
+        let mut marks: Vec<ScrollMark> = modified.iter()
+            .map(|&row| ScrollMark { row, kind: ScrollMarkKind::Modified })
+            .collect();
+        marks.extend(self.search_match_rows().into_iter().map(|row| ScrollMark { row, kind: ScrollMarkKind::SearchMatch }));
+        marks.extend(self.bookmarks.iter().map(|&row| ScrollMark { row, kind: ScrollMarkKind::Bookmark }));
+        for (row, _, kind) in &self.diagnostics {
+            let kind = kind.to_lowercase();
+            let kind = if kind.contains("err") {
+                ScrollMarkKind::Error
+            } else if kind.contains("warn") {
+                ScrollMarkKind::Warning
+            } else {
+                continue;
+            };
+            marks.push(ScrollMark { row: *row, kind });
+        }
+        marks.retain(|m| m.row < self.lines.len());
+        marks.sort_by_key(|m| m.kind);
+        marks
+    }
+
+    /// Rows with matches of the search query, found again only after the text or the
+    /// query changed
+    fn search_match_rows(&self) -> Vec<usize> {
+        let Some(query) = &self.search_query else {
+            return Vec::new();
+        };
+        let fresh = |cache: &SearchMarkCache| cache.revision == self.text_revision && &cache.query == query;
+        if let Some(cache) = self.search_mark_cache.borrow().as_ref().filter(|cache| fresh(cache)) {
+            return cache.rows.clone();
+        }
+        let mut rows: Vec<usize> = self.find_all(query).into_iter().map(|m| m.row).collect();
+        rows.dedup();
+        self.search_mark_cache.replace(Some(SearchMarkCache {
+            revision: self.text_revision,
+            query: query.clone(),
+            rows: rows.clone(),
+        }));
+        rows
+    }
+
+    /// Vertical position of `row` on a scroll track of `track_height` pixels
+    pub fn scroll_map_y(&self, row: usize, track_height: f64) -> f64 {
+        let rows = self.lines.len().max(1) as f64;
+        row as f64 / rows * track_height
+    }
+
+    /// Row to jump to when the scroll track is clicked at `y`
+    ///
+    /// Snaps to a mark within a few pixels of the click so small marks are easy to hit,
+    /// otherwise returns the row proportional to the click position.
+    pub fn scroll_map_row_at(&self, y: f64, track_height: f64) -> usize {
+        const SNAP_PX: f64 = 4.0;
+        let nearest = self.scroll_marks().into_iter()
+            .map(|m| (m.row, (self.scroll_map_y(m.row, track_height) - y).abs()))
+            .filter(|&(_, dist)| dist <= SNAP_PX)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((row, _)) = nearest {
+            return row;
+        }
+        let rows = self.lines.len().max(1);
+        ((y.max(0.0) / track_height.max(1.0)) * rows as f64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn changed_rows_marks_each_changed_line() {
+        let saved = lines("a\nb\nc\nd");
+        let rows = |text: &str| changed_rows(&saved, &lines(text)).into_iter().collect::<Vec<_>>();
+        assert_eq!(rows("a\nB\nc\nd"), [1]);
+        assert_eq!(rows("a\nb\nx\ny\nc\nd"), [2, 3]);
+        assert_eq!(rows("a\nd"), [1]);
+        assert_eq!(rows("a\nb\nc\nd\ne"), [4]);
+        // Separate edits stay separate
+        assert_eq!(rows("A\nb\nc\nD"), [0, 3]);
+    }
+
+    #[test]
+    fn modified_rows_follow_edits_and_saves() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = lines("a\nb\nc");
+        buffer.set_saved_lines(buffer.lines.clone());
+        assert!(buffer.modified_rows().is_empty());
+        buffer.replace_range((2, 0), (2, 0), "x");
+        assert_eq!(buffer.modified_rows().iter().copied().collect::<Vec<_>>(), [2]);
+        buffer.set_saved_lines(buffer.lines.clone());
+        assert!(buffer.modified_rows().is_empty());
+    }
+
+    #[test]
+    fn search_marks_follow_edits() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = lines("foo\nbar\nfoo bar foo");
+        buffer.set_search_query(Some("foo"));
+        assert_eq!(buffer.search_match_rows(), [0, 2]);
+        buffer.replace_rows(1..2, vec!["foo".into()]);
+        assert_eq!(buffer.search_match_rows(), [0, 1, 2]);
+        buffer.set_search_query(Some("bar"));
+        assert_eq!(buffer.search_match_rows(), [2]);
+    }
+
+    #[test]
+    fn bookmarks_move_with_the_text() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = lines("a\nb\nc\nd");
+        buffer.bookmarks.extend([1, 3]);
+        buffer.replace_range((0, 1), (0, 1), "\nx");
+        assert_eq!(buffer.bookmarks.iter().copied().collect::<Vec<_>>(), [2, 4]);
+        buffer.replace_rows(2..3, Vec::new());
+        assert_eq!(buffer.bookmarks.iter().copied().collect::<Vec<_>>(), [3]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Search and replace functionality for EditorBuffer
 //!
-//! This module contains text search, find/replace, and match highlighting operations.
+//! This module contains text search, find/replace, and match highlighting operations,
+//! plus occurrence counts and replace-all previews for confirmation UIs.
 
 use super::buffer::EditorBuffer;
 
@@ -12,6 +13,36 @@ pub struct SearchMatch {
     pub length: usize,
 }
 
+/// One replacement `replace_all` would make, for confirmation dialogs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacePreview {
+    pub row: usize,
+    /// Byte range of the occurrence in the line
+    pub range: std::ops::Range<usize>,
+    /// Text of the occurrence
+    pub before: String,
+    /// Text replacing it
+    pub after: String,
+    /// The whole line before replacing, for diff-style context
+    pub line: String,
+}
+
+/// Replacements of non-overlapping `query` occurrences in the line at `row` (as `str::replace` makes them)
+pub fn preview_line_replacements(row: usize, line: &str, query: &str, replacement: &str) -> Vec<ReplacePreview> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    line.match_indices(query)
+        .map(|(col, found)| ReplacePreview {
+            row,
+            range: col..col + found.len(),
+            before: found.to_string(),
+            after: replacement.to_string(),
+            line: line.to_string(),
+        })
+        .collect()
+}
+
 impl EditorBuffer {
     /// Find the next occurrence of a string, returns (row, col) or None
     pub fn find_next(&self, query: &str, from: Option<(usize, usize)>) -> Option<(usize, usize)> {
@@ -99,8 +130,9 @@ impl EditorBuffer {
     pub fn replace_next(&mut self, query: &str, replacement: &str, from: Option<(usize, usize)>) -> bool {
         if let Some((row, col)) = self.find_next(query, from) {
             self.push_undo();
-            let line = &mut self.lines[row];
+            let mut line = self.lines[row].clone();
             line.replace_range(col..col + query.len(), replacement);
+            self.replace_rows(row..row + 1, vec![line]);
             self.cursor.row = row;
             self.cursor.col = col + replacement.len();
             println!("[DEBUG] Replaced '{}' with '{}' at ({}, {})", query, replacement, row, col);
@@ -109,6 +141,36 @@ impl EditorBuffer {
         false
     }
 
+    /// Every replacement `replace_all(query, replacement)` would make, without changing the buffer
+    pub fn replace_all_preview(&self, query: &str, replacement: &str) -> Vec<ReplacePreview> {
+        self.lines.iter()
+            .enumerate()
+            .flat_map(|(row, line)| preview_line_replacements(row, line, query, replacement))
+            .collect()
+    }
+
+    /// Number of non-overlapping occurrences of `query`, the count `replace_all` would replace
+    pub fn count_occurrences(&self, query: &str) -> usize {
+        if query.is_empty() {
+            return 0;
+        }
+        self.lines.iter().map(|line| line.matches(query).count()).sum()
+    }
+
+    /// Text whose occurrences are counted: the selection when it is within one line, else the search query
+    pub fn occurrence_query(&self) -> Option<String> {
+        let selected = self.selection.as_ref()
+            .filter(|sel| sel.is_active() && sel.start_row == sel.end_row)
+            .and_then(|_| self.get_selected_text())
+            .filter(|text| !text.is_empty());
+        selected.or_else(|| self.search_query.clone())
+    }
+
+    /// Occurrences of the selected text or active search ("N matches"), None when neither exists
+    pub fn occurrence_count(&self) -> Option<usize> {
+        self.occurrence_query().map(|query| self.count_occurrences(&query))
+    }
+
     /// Replace all occurrences of a string
     pub fn replace_all(&mut self, query: &str, replacement: &str) -> usize {
         if query.is_empty() {
@@ -118,12 +180,15 @@ impl EditorBuffer {
         self.push_undo();
         let mut count = 0;
         
-        for line in &mut self.lines {
+        for row in 0..self.lines.len() {
+            let line = &self.lines[row];
             let new_line = line.replace(query, replacement);
             if new_line != *line {
                 let replacements = line.matches(query).count();
-                count += replacements;
-                *line = new_line;
+                // Read-only buffers reject the edit: nothing was replaced
+                if self.replace_rows(row..row + 1, vec![new_line]).is_some() {
+                    count += replacements;
+                }
             }
         }
         
@@ -145,6 +210,11 @@ impl EditorBuffer {
             search_match.col + search_match.length
         );
         self.selection = Some(sel);
+        let scroll_before = self.scroll_position();
+        self.cancel_smooth_scroll();
+        self.ensure_cursor_visible();
+        self.animate_scroll_from(scroll_before);
+        self.flash_line(search_match.row);
         
         println!("[DEBUG] Jumped to search match at ({}, {})", search_match.row, search_match.col);
     }
@@ -185,3 +255,36 @@ impl EditorBuffer {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previews_non_overlapping_replacements() {
+        let preview = preview_line_replacements(3, "aaa ba", "aa", "x");
+        assert_eq!(preview, vec![ReplacePreview {
+            row: 3,
+            range: 0..2,
+            before: "aa".to_string(),
+            after: "x".to_string(),
+            line: "aaa ba".to_string(),
+        }]);
+        assert_eq!(preview_line_replacements(0, "é é", "é", "e").iter().map(|p| p.range.clone()).collect::<Vec<_>>(), vec![0..2, 3..5]);
+        assert!(preview_line_replacements(0, "abc", "", "x").is_empty());
+    }
+
+    #[test]
+    fn replace_all_counts_only_what_it_replaced() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["a a".to_string(), "b a".to_string()];
+        assert_eq!(buffer.replace_all("a", "x"), 3);
+        assert_eq!(buffer.lines, ["x x", "b x"]);
+
+        let mut log = EditorBuffer::new();
+        log.set_buffer_kind(crate::corelogic::logview::BufferKind::Log);
+        log.append_lines_streaming(["a a", "b a"]);
+        assert_eq!(log.replace_all("a", "x"), 0);
+        assert!(log.lines.ends_with(&["a a".to_string(), "b a".to_string()]));
+    }
+}
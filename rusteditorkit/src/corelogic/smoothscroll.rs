@@ -0,0 +1,137 @@
+//! Animated scrolling for EditorBuffer
+//!
+//! The scroll position is `scroll_offset` plus a fractional `scroll_fraction` of a row.
+//! An active `SmoothScroll` is advanced from the GTK frame clock by the widget (like the
+//! line flash), and any user input cancels it, leaving the view on the nearest row.
+
+use serde::Deserialize;
+use super::buffer::EditorBuffer;
+
+/// Easing curve of scroll animations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum ScrollEasing {
+    Linear,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl ScrollEasing {
+    /// Map linear progress `t` (0.0 - 1.0) to eased progress
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ScrollEasing::Linear => t,
+            ScrollEasing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            ScrollEasing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// A running scroll animation between two row positions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothScroll {
+    pub from: f64,
+    pub to: f64,
+    /// Frame time (microseconds) of the first frame, set on the first tick
+    pub start_time: Option<i64>,
+}
+
+impl EditorBuffer {
+    /// Scroll position in rows, including the fraction of a partially scrolled row
+    pub fn scroll_position(&self) -> f64 {
+        self.scroll_offset as f64 + self.scroll_fraction
+    }
+
+    fn set_scroll_position(&mut self, position: f64) {
+        let max = self.display_row_count().saturating_sub(1) as f64;
+        let position = position.clamp(0.0, max);
+        self.scroll_offset = position.floor() as usize;
+        self.scroll_fraction = position - position.floor();
+    }
+
+    /// True while a scroll animation is running
+    pub fn is_smooth_scrolling(&self) -> bool {
+        self.smooth_scroll.is_some()
+    }
+
+    /// Scroll so `row` is the first visible row, animated when smooth scrolling is enabled
+    pub fn smooth_scroll_to(&mut self, row: usize) {
+        self.animate_scroll(self.scroll_position(), self.display_row(row) as f64);
+    }
+
+    /// Scroll by `rows` (may be fractional or negative), animated when smooth scrolling is enabled
+    pub fn smooth_scroll_by(&mut self, rows: f64) {
+        // Chain onto a running animation instead of restarting from the current frame
+        let base = self.smooth_scroll.map_or(self.scroll_position(), |scroll| scroll.to);
+        self.animate_scroll(self.scroll_position(), base + rows);
+    }
+
+    /// Animate from `from` to the current scroll position after a jump (search, goto)
+    pub fn animate_scroll_from(&mut self, from: f64) {
+        let to = self.scroll_position();
+        self.animate_scroll(from, to);
+    }
+
+    fn animate_scroll(&mut self, from: f64, to: f64) {
+        let max = self.lines.len().saturating_sub(1) as f64;
+        let to = to.clamp(0.0, max);
+        if !self.config.smooth_scrolling || self.config.smooth_scroll_duration_ms == 0 || (to - from).abs() < f64::EPSILON {
+            self.smooth_scroll = None;
+            self.set_scroll_position(to);
+        } else {
+            self.set_scroll_position(from);
+            self.smooth_scroll = Some(SmoothScroll { from, to, start_time: None });
+        }
+        self.request_redraw();
+    }
+
+    /// Stop a running animation on the nearest whole row (called on user input)
+    pub fn cancel_smooth_scroll(&mut self) {
+        if self.smooth_scroll.take().is_some() {
+            let position = self.scroll_position().round();
+            self.set_scroll_position(position);
+            self.request_redraw();
+        }
+    }
+
+    /// Advance the animation to `frame_time` (microseconds); returns false once it is over
+    pub fn advance_smooth_scroll(&mut self, frame_time: i64) -> bool {
+        let Some(scroll) = self.smooth_scroll.as_mut() else {
+            return false;
+        };
+        let start = *scroll.start_time.get_or_insert(frame_time);
+        let duration = self.config.smooth_scroll_duration_ms.max(1) as f64 * 1000.0;
+        let t = (frame_time - start) as f64 / duration;
+        let (from, to) = (scroll.from, scroll.to);
+        let position = from + (to - from) * self.config.smooth_scroll_easing.apply(t);
+        if t >= 1.0 {
+            self.smooth_scroll = None;
+            self.set_scroll_position(to);
+            return false;
+        }
+        self.set_scroll_position(position);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_and_end_in_place() {
+        for easing in [ScrollEasing::Linear, ScrollEasing::EaseOut, ScrollEasing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-9);
+            assert!(easing.apply(0.3) < easing.apply(0.6));
+        }
+        assert!(ScrollEasing::EaseOut.apply(0.5) > 0.5);
+    }
+}
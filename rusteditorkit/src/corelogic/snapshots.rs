@@ -0,0 +1,248 @@
+//! Named buffer snapshots, diffs against them and a read-only snapshot view
+//!
+//! Snapshots are plain copies of the lines, taken manually with `take_snapshot` or
+//! automatically on every save (under `SAVE_SNAPSHOT`). `diff_against_snapshot` returns
+//! line hunks for "review changes" panels, and `view_snapshot` shows a snapshot in the
+//! editor itself: the buffer's own text is stashed and restored by `close_snapshot_view`,
+//! and editing commands are rejected until then.
+
+use super::buffer::{EditorBuffer, EditorCursor};
+use super::selection::Selection;
+
+/// Name of the snapshot taken automatically on save (replaced by each save)
+pub const SAVE_SNAPSHOT: &str = "saved";
+
+/// Snapshots kept per buffer; the oldest manual snapshot is dropped beyond this
+pub const MAX_SNAPSHOTS: usize = 20;
+
+/// Above this many line comparisons the changed region is reported as a single hunk
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// A named copy of the buffer text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub name: String,
+    pub lines: Vec<String>,
+    pub created: std::time::SystemTime,
+}
+
+/// One contiguous difference: `old_lines` at `old_start` became `new_lines` at `new_start`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: Vec<String>,
+    pub new_start: usize,
+    pub new_lines: Vec<String>,
+}
+
+impl DiffHunk {
+    /// Rows of the snapshot covered by this hunk
+    pub fn old_range(&self) -> std::ops::Range<usize> {
+        self.old_start..self.old_start + self.old_lines.len()
+    }
+
+    /// Rows of the current buffer covered by this hunk
+    pub fn new_range(&self) -> std::ops::Range<usize> {
+        self.new_start..self.new_start + self.new_lines.len()
+    }
+}
+
+/// The buffer's own state while a snapshot is displayed
+#[derive(Debug, Clone)]
+pub struct SnapshotView {
+    pub name: String,
+    pub lines: Vec<String>,
+    pub cursor: EditorCursor,
+    pub selection: Option<Selection>,
+    pub scroll_offset: usize,
+}
+
+/// Line hunks turning `old` into `new`, in order
+///
+/// Uses a longest-common-subsequence table over the region between the common prefix and
+/// suffix; very large regions fall back to one hunk spanning the whole region.
+pub fn diff_hunks(old: &[String], new: &[String]) -> Vec<DiffHunk> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old.iter().rev().zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+    if old_mid.is_empty() && new_mid.is_empty() {
+        return Vec::new();
+    }
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_DIFF_CELLS {
+        return vec![DiffHunk {
+            old_start: prefix,
+            old_lines: old_mid.to_vec(),
+            new_start: prefix,
+            new_lines: new_mid.to_vec(),
+        }];
+    }
+
+    // lcs[i][j] = length of the LCS of old_mid[i..] and new_mid[j..]
+    let (n, m) = (old_mid.len(), new_mid.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let mut current: Option<DiffHunk> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_mid[i] == new_mid[j] {
+            hunks.extend(current.take());
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let hunk = current.get_or_insert_with(|| DiffHunk {
+            old_start: prefix + i,
+            old_lines: Vec::new(),
+            new_start: prefix + j,
+            new_lines: Vec::new(),
+        });
+        if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            hunk.new_lines.push(new_mid[j].clone());
+            j += 1;
+        } else {
+            hunk.old_lines.push(old_mid[i].clone());
+            i += 1;
+        }
+    }
+    hunks.extend(current);
+    hunks
+}
+
+impl EditorBuffer {
+    /// Store the current text under `name`, replacing an older snapshot of the same name
+    pub fn take_snapshot(&mut self, name: &str) {
+        let lines = self.snapshot_view.as_ref().map_or(&self.lines, |view| &view.lines).clone();
+        self.store_snapshot(name, lines);
+    }
+
+    /// Store `lines` as the snapshot called `name`
+    pub(crate) fn store_snapshot(&mut self, name: &str, lines: Vec<String>) {
+        self.snapshots.retain(|snapshot| snapshot.name != name);
+        self.snapshots.push(Snapshot { name: name.to_string(), lines, created: std::time::SystemTime::now() });
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            if let Some(oldest) = self.snapshots.iter().position(|snapshot| snapshot.name != SAVE_SNAPSHOT) {
+                self.snapshots.remove(oldest);
+            }
+        }
+    }
+
+    /// All snapshots, oldest first
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    /// Snapshot called `name`
+    pub fn snapshot(&self, name: &str) -> Option<&Snapshot> {
+        self.snapshots.iter().find(|snapshot| snapshot.name == name)
+    }
+
+    /// Delete the snapshot called `name`, returns false if there was none
+    pub fn remove_snapshot(&mut self, name: &str) -> bool {
+        let before = self.snapshots.len();
+        self.snapshots.retain(|snapshot| snapshot.name != name);
+        self.snapshots.len() != before
+    }
+
+    /// Hunks from the snapshot called `name` to the current text
+    pub fn diff_against_snapshot(&self, name: &str) -> Option<Vec<DiffHunk>> {
+        let current = self.snapshot_view.as_ref().map_or(&self.lines, |view| &view.lines);
+        self.snapshot(name).map(|snapshot| diff_hunks(&snapshot.lines, current))
+    }
+
+    /// Show the snapshot called `name` read-only until `close_snapshot_view`
+    ///
+    /// Returns false (and changes nothing) if no such snapshot exists.
+    pub fn view_snapshot(&mut self, name: &str) -> bool {
+        let Some(lines) = self.snapshot(name).map(|snapshot| snapshot.lines.clone()) else {
+            return false;
+        };
+        self.close_snapshot_view();
+        self.snapshot_view = Some(SnapshotView {
+            name: name.to_string(),
+            lines: std::mem::replace(&mut self.lines, lines),
+            cursor: self.cursor,
+            selection: self.selection.take(),
+            scroll_offset: self.scroll_offset,
+        });
+        self.text_replaced();
+        self.cursor = EditorCursor::default();
+        self.scroll_offset = 0;
+        true
+    }
+
+    /// Return to the buffer's own text after `view_snapshot`
+    pub fn close_snapshot_view(&mut self) {
+        if let Some(view) = self.snapshot_view.take() {
+            self.lines = view.lines;
+            self.text_replaced();
+            self.cursor = view.cursor;
+            self.selection = view.selection;
+            self.scroll_offset = view.scroll_offset;
+        }
+    }
+
+    /// Name of the snapshot being viewed, if any
+    pub fn viewed_snapshot(&self) -> Option<&str> {
+        self.snapshot_view.as_ref().map(|view| view.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn diff_hunks_reports_separate_changes() {
+        let old = lines("a\nb\nc\nd\ne");
+        assert!(diff_hunks(&old, &old).is_empty());
+        let hunks = diff_hunks(&old, &lines("a\nB\nc\nd\nx\ne"));
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_range(), hunks[0].new_range()), (1..2, 1..2));
+        assert_eq!(hunks[0].new_lines, vec!["B"]);
+        assert_eq!((hunks[1].old_range(), hunks[1].new_range()), (4..4, 4..5));
+        let hunks = diff_hunks(&old, &lines("a\ne"));
+        assert_eq!(hunks, vec![DiffHunk { old_start: 1, old_lines: lines("b\nc\nd"), new_start: 1, new_lines: vec![] }]);
+    }
+
+    #[test]
+    fn snapshot_view_rejects_edits_and_records_nothing() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = lines("one\ntwo");
+        buffer.set_change_feed_enabled(true);
+        buffer.take_snapshot("v1");
+        buffer.replace_rows(1..2, vec!["TWO".into()]);
+        let revision = buffer.revision();
+        assert!(buffer.view_snapshot("v1"));
+        let cursor = buffer.cursor;
+        buffer.insert_text("typed\nmore");
+        buffer.insert_newline();
+        buffer.duplicate_line();
+        buffer.insert_pasted("pasted\nlines");
+        assert_eq!(buffer.cursor, cursor);
+        buffer.paste_text("pasted");
+        buffer.undo();
+        assert_eq!(buffer.lines, lines("one\ntwo"));
+        buffer.close_snapshot_view();
+        assert_eq!(buffer.lines, lines("one\nTWO"));
+        assert_eq!(buffer.revision(), revision);
+    }
+}
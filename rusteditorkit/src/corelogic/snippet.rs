@@ -0,0 +1,275 @@
+//! Snippets with fill-in fields
+//!
+//! Snippet text marks fields as `$1`, `${2:default}` and the final cursor position as
+//! `$0` (`\$` is a literal dollar). `insert_snippet` inserts the text and selects the
+//! first field; Tab moves to the next field (Escape or moving past the last one ends the
+//! session, leaving the cursor at `$0`). Field positions are kept relative to the end of
+//! their line and of the document, so typing into earlier fields does not move later ones.
+//!
+//! The session also ends when the caret leaves the field being filled in, or when the
+//! text changes anywhere else: later fields would no longer be where they are tracked.
+
+use super::buffer::EditorBuffer;
+use super::edit::RowEdit;
+use super::selection::Selection;
+
+/// A field of parsed snippet text, in (row, col) relative to the snippet start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetField {
+    /// Tab order; 0 is the final cursor position
+    pub index: usize,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+/// Append `c` to `text`, advancing the (row, col) position
+fn push(text: &mut String, c: char, row: &mut usize, col: &mut usize) {
+    text.push(c);
+    if c == '\n' {
+        (*row, *col) = (*row + 1, 0);
+    } else {
+        *col += 1;
+    }
+}
+
+/// Snippet text with the field markers removed, and its fields in tab order ($0 last)
+pub fn parse_snippet(snippet: &str) -> (String, Vec<SnippetField>) {
+    let chars: Vec<char> = snippet.chars().collect();
+    let mut text = String::new();
+    let mut fields = Vec::new();
+    let (mut row, mut col) = (0, 0);
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && chars.get(i + 1) == Some(&'$') {
+            push(&mut text, '$', &mut row, &mut col);
+            i += 2;
+            continue;
+        }
+        if c == '$' {
+            let braced = chars.get(i + 1) == Some(&'{');
+            let digits_from = i + 1 + usize::from(braced);
+            let digits = chars[digits_from.min(chars.len())..].iter().take_while(|c| c.is_ascii_digit()).count();
+            if digits > 0 {
+                let index: usize = chars[digits_from..digits_from + digits].iter().collect::<String>().parse().unwrap_or(0);
+                let mut next = digits_from + digits;
+                let start = (row, col);
+                if braced {
+                    if chars.get(next) == Some(&':') {
+                        next += 1;
+                        while next < chars.len() && chars[next] != '}' {
+                            push(&mut text, chars[next], &mut row, &mut col);
+                            next += 1;
+                        }
+                    }
+                    if chars.get(next) != Some(&'}') {
+                        // Unterminated field: keep the text as written
+                        text.push_str(&chars[i..next.min(chars.len())].iter().collect::<String>());
+                        i = next;
+                        continue;
+                    }
+                    next += 1;
+                }
+                fields.push(SnippetField { index, start, end: (row, col) });
+                i = next;
+                continue;
+            }
+        }
+        push(&mut text, c, &mut row, &mut col);
+        i += 1;
+    }
+    // Tab order, with $0 last; a repeated index keeps its first occurrence
+    fields.sort_by_key(|field| if field.index == 0 { usize::MAX } else { field.index });
+    fields.dedup_by_key(|field| field.index);
+    (text, fields)
+}
+
+/// A field of an active snippet: its ends as (rows from the last line, chars from the line end)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrackedField {
+    start: (usize, usize),
+    end: (usize, usize),
+    final_cursor: bool,
+}
+
+/// Fields still to visit in an inserted snippet
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnippetSession {
+    fields: Vec<TrackedField>,
+    /// The field being filled in: its start as a (row, col) position and its end like
+    /// `TrackedField::end`, so typing inside it moves neither
+    current: Option<((usize, usize), (usize, usize))>,
+}
+
+impl EditorBuffer {
+    /// Insert `snippet` at the cursor (replacing the selection) and select its first field
+    ///
+    /// Snapshot views and log buffers reject the snippet and keep the caret and selection.
+    pub fn insert_snippet(&mut self, snippet: &str) {
+        if !self.allows_edit() {
+            return;
+        }
+        let (text, fields) = parse_snippet(snippet);
+        self.delete_selection();
+        self.push_undo();
+        // Where the text goes in, clamped to the text as `replace_range` clamps it
+        let row = self.cursor.row.min(self.lines.len() - 1);
+        let origin = (row, self.cursor.col.min(self.lines[row].chars().count()));
+        if self.replace_range(origin, origin, &text).is_none() {
+            return;
+        }
+        let rows: Vec<&str> = text.split('\n').collect();
+        self.cursor.row = origin.0 + rows.len() - 1;
+        self.cursor.col = if rows.len() > 1 {
+            rows[rows.len() - 1].chars().count()
+        } else {
+            origin.1 + text.chars().count()
+        };
+        let from_end = |(row, col): (usize, usize)| {
+            let (row, col) = if row == 0 { (origin.0, origin.1 + col) } else { (origin.0 + row, col) };
+            (self.lines.len() - 1 - row, self.lines[row].chars().count() - col)
+        };
+        let tracked = fields.iter()
+            .map(|field| TrackedField {
+                start: from_end(field.start),
+                end: from_end(field.end),
+                final_cursor: field.index == 0,
+            })
+            .collect();
+        self.snippet = Some(SnippetSession { fields: tracked, current: None });
+        if !self.next_snippet_field() {
+            self.request_redraw();
+        }
+    }
+
+    /// True while a snippet has fields left to visit
+    pub fn in_snippet(&self) -> bool {
+        self.snippet.is_some()
+    }
+
+    /// Select the next snippet field; returns false (ending the session) when none is left
+    pub fn next_snippet_field(&mut self) -> bool {
+        let Some(session) = self.snippet.as_mut() else { return false };
+        if session.fields.is_empty() {
+            self.snippet = None;
+            return false;
+        }
+        let field = session.fields.remove(0);
+        if session.fields.is_empty() {
+            self.snippet = None;
+        }
+        let last = self.lines.len() - 1;
+        let position = |(rows, cols): (usize, usize)| {
+            let row = last.saturating_sub(rows);
+            (row, self.lines[row].chars().count().saturating_sub(cols))
+        };
+        let ((start_row, start_col), (end_row, end_col)) = (position(field.start), position(field.end));
+        if let Some(session) = self.snippet.as_mut() {
+            session.current = Some(((start_row, start_col), field.end));
+        }
+        self.cursor.row = end_row;
+        self.cursor.col = end_col;
+        self.selection = ((start_row, start_col) < (end_row, end_col)).then(|| {
+            let mut sel = Selection::new(start_row, start_col);
+            sel.set(start_row, start_col, end_row, end_col);
+            sel
+        });
+        if field.final_cursor {
+            self.snippet = None;
+        }
+        self.request_redraw();
+        true
+    }
+
+    /// Stop visiting snippet fields
+    pub fn end_snippet(&mut self) {
+        self.snippet = None;
+    }
+
+    /// Start and end position of the field being filled in
+    fn current_snippet_field(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (start, (rows, cols)) = self.snippet.as_ref()?.current?;
+        let row = (self.lines.len() - 1).checked_sub(rows)?;
+        let end = (row, self.lines[row].chars().count().checked_sub(cols)?);
+        (start <= end).then_some((start, end))
+    }
+
+    /// End the snippet session if the caret left the field being filled in
+    pub(crate) fn end_snippet_if_caret_left(&mut self) {
+        if self.snippet.is_none() {
+            return;
+        }
+        let caret = (self.cursor.row, self.cursor.col);
+        if !self.current_snippet_field().is_some_and(|(start, end)| start <= caret && caret <= end) {
+            self.snippet = None;
+        }
+    }
+
+    /// Follow an edit of the text: the session survives only edits inside the field
+    /// being filled in (rows it had or gained)
+    pub(crate) fn snippet_rows_replaced(&mut self, edit: &RowEdit) {
+        if self.snippet.is_none() {
+            return;
+        }
+        let inside = self.current_snippet_field().is_some_and(|((start_row, _), (end_row, _))| {
+            start_row <= edit.start && edit.start + edit.new_rows <= end_row + 1
+        });
+        if !inside {
+            self.snippet = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_defaults_and_escapes() {
+        let (text, fields) = parse_snippet("fn ${1:name}() {\n    $0\n}\\$ ${2:x}$1");
+        assert_eq!(text, "fn name() {\n    \n}$ x");
+        assert_eq!(fields, vec![
+            SnippetField { index: 1, start: (0, 3), end: (0, 7) },
+            SnippetField { index: 2, start: (2, 3), end: (2, 4) },
+            SnippetField { index: 0, start: (1, 4), end: (1, 4) },
+        ]);
+        assert_eq!(parse_snippet("cost: $ 5 ${3").0, "cost: $ 5 ${3");
+    }
+
+    #[test]
+    fn session_ends_when_leaving_the_field() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["top".into(), String::new()];
+        buffer.cursor = crate::corelogic::EditorCursor::new(1, 0);
+        buffer.insert_snippet("let ${1:x} = ${2:y};$0");
+        // Typing into the field keeps the session
+        buffer.insert_text("total");
+        buffer.end_snippet_if_caret_left();
+        assert!(buffer.in_snippet());
+        assert!(buffer.next_snippet_field());
+        assert_eq!(buffer.selection.as_ref().map(|sel| sel.normalized()), Some(((1, 12), (1, 13))));
+        assert!(buffer.in_snippet());
+        // The caret moving off the field ends it
+        buffer.cursor.col = 2;
+        buffer.end_snippet_if_caret_left();
+        assert!(!buffer.in_snippet());
+        // So does an edit elsewhere
+        buffer.insert_snippet("${1:a}$2");
+        assert!(buffer.in_snippet());
+        buffer.replace_range((0, 0), (0, 0), "x");
+        assert!(!buffer.in_snippet());
+    }
+
+    #[test]
+    fn snapshot_view_rejects_snippets() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["one".into(), "two".into()];
+        buffer.take_snapshot("v1");
+        assert!(buffer.view_snapshot("v1"));
+        buffer.cursor = crate::corelogic::EditorCursor::new(1, 3);
+        buffer.insert_snippet("fn ${1:name}() {\n    $0\n}");
+        assert_eq!(buffer.lines, ["one", "two"]);
+        assert_eq!(buffer.cursor, crate::corelogic::EditorCursor::new(1, 3));
+        assert!(!buffer.in_snippet());
+    }
+}
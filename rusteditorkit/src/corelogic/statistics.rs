@@ -0,0 +1,192 @@
+//! Document statistics for EditorBuffer
+//!
+//! Word counts, page counts and reading time estimates for writing-oriented hosts.
+//! Statistics are refreshed after each dispatched command and published through
+//! `EditorEvent::StatisticsChanged` when they change.
+//!
+//! While someone listens, the counts are kept per line and updated from the rows each
+//! edit replaced, so a refresh does not walk the whole document.
+
+use super::buffer::EditorBuffer;
+use super::edit::RowEdit;
+use super::events::EditorEvent;
+
+/// Snapshot of the buffer's word and page statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentStatistics {
+    pub words: usize,
+    pub characters: usize,
+    pub lines: usize,
+    pub paragraphs: usize,
+    /// Total pages in A4 mode (`None` when A4 mode is off)
+    pub page_count: Option<usize>,
+    /// 1-based page containing the cursor in A4 mode
+    pub current_page: Option<usize>,
+    /// Estimated reading time in minutes, based on `words_per_minute`
+    pub reading_time_minutes: f64,
+}
+
+impl DocumentStatistics {
+    /// Human readable page position, e.g. "Page 3 of 12"
+    pub fn page_label(&self) -> Option<String> {
+        match (self.current_page, self.page_count) {
+            (Some(current), Some(total)) => Some(format!("Page {} of {}", current, total)),
+            _ => None,
+        }
+    }
+
+    /// Reading time rounded up to whole minutes (at least 1 for non-empty text)
+    pub fn reading_time_rounded(&self) -> usize {
+        if self.words == 0 {
+            0
+        } else {
+            (self.reading_time_minutes.ceil() as usize).max(1)
+        }
+    }
+}
+
+/// Counts of one line
+#[derive(Debug, Clone, Copy, Default)]
+struct LineCounts {
+    words: usize,
+    characters: usize,
+    blank: bool,
+}
+
+impl LineCounts {
+    fn of(line: &str) -> Self {
+        Self {
+            words: line.split_whitespace().count(),
+            characters: line.chars().count(),
+            blank: line.trim().is_empty(),
+        }
+    }
+}
+
+/// Per-line counts and their totals, kept up to date edit by edit once built
+#[derive(Debug, Clone, Default)]
+pub struct StatisticsCache {
+    rows: Vec<LineCounts>,
+    words: usize,
+    characters: usize,
+    paragraphs: usize,
+}
+
+impl StatisticsCache {
+    /// Count `lines` from scratch
+    fn build(lines: &[String]) -> Self {
+        let rows: Vec<LineCounts> = lines.iter().map(|line| LineCounts::of(line)).collect();
+        let mut cache = Self {
+            words: rows.iter().map(|row| row.words).sum(),
+            characters: rows.iter().map(|row| row.characters).sum(),
+            paragraphs: 0,
+            rows,
+        };
+        cache.paragraphs = cache.paragraph_starts(0..cache.rows.len());
+        cache
+    }
+
+    fn is_built(&self) -> bool {
+        !self.rows.is_empty()
+    }
+
+    /// Forget the counts; they are rebuilt on the next refresh
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Non-blank rows in `rows` that follow a blank row (or the start)
+    fn paragraph_starts(&self, rows: std::ops::Range<usize>) -> usize {
+        rows.filter(|&row| !self.rows[row].blank && (row == 0 || self.rows[row - 1].blank)).count()
+    }
+
+    /// Update the counts for `edit`; `lines` is the text after it
+    ///
+    /// Only the replaced rows are counted again. A paragraph start can also appear or
+    /// vanish on the row right after them, so that row is checked on both sides.
+    pub(crate) fn rows_replaced(&mut self, edit: &RowEdit, old_len: usize, lines: &[String]) {
+        if !self.is_built() {
+            return;
+        }
+        if self.rows.len() != old_len {
+            self.clear();
+            return;
+        }
+        let old_end = edit.start + edit.old_rows;
+        self.paragraphs -= self.paragraph_starts(edit.start..(old_end + 1).min(old_len));
+        for row in &self.rows[edit.start..old_end] {
+            self.words -= row.words;
+            self.characters -= row.characters;
+        }
+        let new_end = edit.start + edit.new_rows;
+        let counted: Vec<LineCounts> = lines[edit.start..new_end].iter().map(|line| LineCounts::of(line)).collect();
+        for row in &counted {
+            self.words += row.words;
+            self.characters += row.characters;
+        }
+        self.rows.splice(edit.start..old_end, counted);
+        self.paragraphs += self.paragraph_starts(edit.start..(new_end + 1).min(lines.len()));
+    }
+}
+
+impl EditorBuffer {
+    /// Compute word, paragraph, page and reading time statistics for the whole buffer
+    ///
+    /// Uses the per-line counts when they are built, else counts every line.
+    pub fn document_statistics(&self) -> DocumentStatistics {
+        let counted;
+        let cache = if self.statistics_cache.is_built() {
+            &self.statistics_cache
+        } else {
+            counted = StatisticsCache::build(&self.lines);
+            &counted
+        };
+        let (words, characters, paragraphs) = (cache.words, cache.characters, cache.paragraphs);
+        let wpm = self.config.words_per_minute.max(1) as f64;
+        DocumentStatistics {
+            words,
+            characters,
+            lines: self.lines.len(),
+            paragraphs,
+            page_count: self.page_count(),
+            current_page: self.current_page(),
+            reading_time_minutes: words as f64 / wpm,
+        }
+    }
+
+    /// Recompute statistics and emit `StatisticsChanged` if they differ from the last snapshot
+    ///
+    /// Does nothing when no event listeners are connected, so plain code editing pays no cost.
+    pub fn refresh_statistics(&mut self) {
+        if !self.events.has_listeners() {
+            return;
+        }
+        if !self.statistics_cache.is_built() {
+            self.statistics_cache = StatisticsCache::build(&self.lines);
+        }
+        let stats = self.document_statistics();
+        if self.last_statistics.as_ref() != Some(&stats) {
+            self.last_statistics = Some(stats.clone());
+            self.events.emit(&EditorEvent::StatisticsChanged(stats));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_follow_edits_without_recounting() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = ["one two", "", "three"].iter().map(|s| s.to_string()).collect();
+        buffer.statistics_cache = StatisticsCache::build(&buffer.lines);
+        buffer.replace_range((1, 0), (1, 0), "four");
+        buffer.replace_range((0, 7), (0, 7), "\n\nfive six");
+        buffer.replace_rows(4..5, Vec::new());
+        let incremental = buffer.document_statistics();
+        buffer.statistics_cache.clear();
+        assert_eq!(incremental, buffer.document_statistics());
+        assert_eq!((incremental.words, incremental.paragraphs), (5, 2));
+    }
+}
@@ -0,0 +1,144 @@
+//! Streaming content for pager-like hosts (pipes, growing log files)
+//!
+//! `append_lines_streaming` adds lines at the end without touching the undo history.
+//! With follow-tail on, the view sticks to the bottom as lines arrive; `stream_max_lines`
+//! bounds memory by trimming the oldest lines, shifting the carets, selections,
+//! bookmarks, folds, diagnostics and remote cursors along with the text.
+
+use super::buffer::EditorBuffer;
+
+impl EditorBuffer {
+    /// Append `lines` at the end of the buffer, then trim to `stream_max_lines`
+    ///
+    /// A fresh buffer's single empty line is replaced rather than kept above the stream.
+    pub fn append_lines_streaming<I, S>(&mut self, lines: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut incoming = lines.into_iter().map(Into::into).peekable();
+        if incoming.peek().is_none() {
+            return;
+        }
+        let fresh = self.lines.len() == 1 && self.lines[0].is_empty();
+        let appended_from = if fresh { 0 } else { self.lines.len() };
+        // Streaming is how log buffers grow, the one edit they accept
+        self.append_rows(appended_from, incoming.collect());
+        if self.is_log_buffer() {
+            self.fold_repeated_lines(appended_from);
+        }
+
+        let cap = self.config.stream_max_lines;
+        if cap > 0 && self.lines.len() > cap {
+            self.trim_top_lines(self.lines.len() - cap);
+        }
+        if self.follow_tail {
+            self.scroll_to_tail();
+        }
+        self.request_redraw();
+    }
+
+    /// Stick the view to the last line while streaming (and jump there now)
+    pub fn set_follow_tail(&mut self, follow: bool) {
+        self.follow_tail = follow;
+        if follow {
+            self.scroll_to_tail();
+            self.request_redraw();
+        }
+    }
+
+    /// True while the view follows streamed content
+    pub fn is_following_tail(&self) -> bool {
+        self.follow_tail
+    }
+
+    /// Scroll so the last line sits at the bottom of the viewport
+    fn scroll_to_tail(&mut self) {
+        self.cancel_smooth_scroll();
+        self.scroll_offset = self.display_row_count().saturating_sub(self.viewport_rows().max(1));
+    }
+
+    /// Drop the first `count` lines, moving positions that refer to later lines up
+    fn trim_top_lines(&mut self, count: usize) {
+        let count = count.min(self.lines.len().saturating_sub(1));
+        if count == 0 {
+            return;
+        }
+        // The scroll position counts displayed rows, some of the trimmed ones may be folded away
+        let trimmed_rows = self.display_row(count);
+        self.trim_top_rows(count);
+        // Positions in the trimmed lines move to the start of the text; bookmarks and folds moved with the edit
+        let shift = |(row, col): (usize, usize)| if row < count { (0, 0) } else { (row - count, col) };
+        (self.cursor.row, self.cursor.col) = shift((self.cursor.row, self.cursor.col));
+        if let Some(sel) = &mut self.selection {
+            (sel.start_row, sel.start_col) = shift((sel.start_row, sel.start_col));
+            (sel.end_row, sel.end_col) = shift((sel.end_row, sel.end_col));
+        }
+        if self.selection.as_ref().is_some_and(|sel| !sel.is_active()) {
+            self.selection = None;
+        }
+        for caret in &mut self.multi_cursors {
+            *caret = shift(*caret);
+        }
+        self.multi_cursors.dedup();
+        for (start, end) in &mut self.multi_selections {
+            *start = start.map(shift);
+            *end = end.map(shift);
+        }
+        self.multi_selections.retain(|(start, end)| start != end);
+        for remote in &mut self.remote_cursors {
+            remote.pos = shift(remote.pos);
+            remote.anchor = remote.anchor.map(shift);
+        }
+        // Diagnostics on trimmed lines go with them
+        self.diagnostics.retain(|(row, _, _)| *row >= count);
+        for (row, _, _) in &mut self.diagnostics {
+            *row -= count;
+        }
+        self.scroll_offset = self.scroll_offset.saturating_sub(trimmed_rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corelogic::selection::Selection;
+
+    #[test]
+    fn trimming_moves_every_position_with_the_text() {
+        let mut buffer = EditorBuffer::new();
+        buffer.config.stream_max_lines = 4;
+        buffer.append_lines_streaming(["a", "b", "c", "d"]);
+        buffer.cursor.row = 3;
+        buffer.selection = Some(Selection { start_row: 2, start_col: 0, end_row: 3, end_col: 1 });
+        buffer.multi_cursors = vec![(0, 1), (2, 1)];
+        buffer.multi_selections = vec![(Some((2, 0)), Some((3, 1)))];
+        buffer.bookmarks.extend([1, 3]);
+        buffer.diagnostics = vec![(0, "gone".into(), "error".into()), (3, "kept".into(), "warning".into())];
+        buffer.append_lines_streaming(["e", "f"]);
+        assert_eq!(buffer.lines, ["c", "d", "e", "f"]);
+        assert_eq!(buffer.cursor.row, 1);
+        let sel = buffer.selection.as_ref().unwrap();
+        assert_eq!((sel.start_row, sel.end_row), (0, 1));
+        assert_eq!(buffer.multi_cursors, vec![(0, 0), (0, 1)]);
+        assert_eq!(buffer.multi_selections, vec![(Some((0, 0)), Some((1, 1)))]);
+        assert_eq!(buffer.bookmarks.iter().copied().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(buffer.diagnostics, vec![(1, "kept".to_string(), "warning".to_string())]);
+    }
+
+    #[test]
+    fn log_buffers_only_grow_by_streaming() {
+        let mut buffer = EditorBuffer::new();
+        buffer.set_buffer_kind(crate::corelogic::logview::BufferKind::Log);
+        buffer.append_lines_streaming(["INFO one", "INFO two"]);
+        assert_eq!(buffer.replace_range((0, 0), (0, 4), "WARN"), None);
+        assert_eq!(buffer.replace_rows(0..1, Vec::new()), None);
+        let cursor = buffer.cursor;
+        buffer.insert_text("typed");
+        buffer.insert_newline();
+        assert_eq!(buffer.cursor, cursor);
+        buffer.config.stream_max_lines = 2;
+        buffer.append_lines_streaming(["INFO three"]);
+        assert_eq!(buffer.lines, ["INFO two", "INFO three"]);
+    }
+}
@@ -0,0 +1,306 @@
+//! Surround and unsurround for EditorBuffer
+//!
+//! `surround_selection` wraps every selection (the main one and `multi_selections`) in a
+//! bracket or quote pair and keeps the inner text selected; typing a pair character over
+//! a selection does the same. `unsurround` removes the innermost pair enclosing each
+//! selection or cursor. Both are a single undo step.
+
+use super::buffer::EditorBuffer;
+use super::selection::Selection;
+
+type Position = (usize, usize);
+
+/// Opening and closing character of the pair `c` belongs to
+pub fn surround_pair(c: char) -> Option<(char, char)> {
+    match c {
+        '(' | ')' => Some(('(', ')')),
+        '[' | ']' => Some(('[', ']')),
+        '{' | '}' => Some(('{', '}')),
+        '<' | '>' => Some(('<', '>')),
+        '"' | '\'' | '`' => Some((c, c)),
+        _ => None,
+    }
+}
+
+/// What a position is, for deciding which side of an inserted character it ends up on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Anchor {
+    /// Start of a wrapped range: moves past its own opening character
+    Start,
+    /// End of a wrapped range: stays before its own closing character
+    End,
+    /// Any other cursor: moves past a closing character at its column
+    Caret,
+}
+
+/// Where `pos` ends up after `ranges` were wrapped in single-character pairs
+fn shift_for_surround(pos: Position, ranges: &[(Position, Position)], anchor: Anchor) -> Position {
+    let inserted = ranges.iter()
+        .map(|&(start, end)| {
+            let open = start.0 == pos.0 && (start.1 < pos.1 || (anchor == Anchor::Start && start == pos));
+            let close = end.0 == pos.0 && (end.1 < pos.1 || (end.1 == pos.1 && anchor != Anchor::End));
+            usize::from(open) + usize::from(close)
+        })
+        .sum::<usize>();
+    (pos.0, pos.1 + inserted)
+}
+
+/// Where `pos` ends up after the characters at `removed` were deleted
+fn shift_for_removal(pos: Position, removed: &[Position]) -> Position {
+    let before = removed.iter().filter(|&&(row, col)| row == pos.0 && col < pos.1).count();
+    (pos.0, pos.1 - before)
+}
+
+/// Innermost pair enclosing `start..end` on `lines`, as the positions of its two characters
+///
+/// Brackets may span lines; quotes must be on the selection's own line and pair up when
+/// read from the start of it, so the gap between two quoted strings is not mistaken for one.
+pub fn enclosing_pair(lines: &[String], start: Position, end: Position) -> Option<(Position, Position)> {
+    let char_at = |(row, col): Position| lines.get(row).and_then(|line| line.chars().nth(col));
+    // A bracket pair right around the selection wins (quotes only count when they pair up)
+    if start.1 > 0 {
+        let (open, close) = ((start.0, start.1 - 1), end);
+        if let (Some(a), Some(b)) = (char_at(open), char_at(close)) {
+            if surround_pair(a).is_some_and(|(o, c)| o != c && o == a && c == b) {
+                return Some((open, close));
+            }
+        }
+    }
+    let bracket = enclosing_bracket(lines, start, end);
+    let quote = enclosing_quote(lines, start, end);
+    // The candidate opening closest to the selection is the innermost one
+    match (bracket, quote) {
+        (Some(b), Some(q)) => Some(if q.0 > b.0 { q } else { b }),
+        (b, q) => b.or(q),
+    }
+}
+
+fn enclosing_bracket(lines: &[String], start: Position, end: Position) -> Option<(Position, Position)> {
+    let rows: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    // Walk back from the start for an unmatched opening bracket
+    let mut depth: Vec<char> = Vec::new();
+    let mut open = None;
+    'back: for row in (0..=start.0).rev() {
+        let upto = if row == start.0 { start.1.min(rows[row].len()) } else { rows[row].len() };
+        for col in (0..upto).rev() {
+            let c = rows[row][col];
+            match c {
+                ')' | ']' | '}' => depth.push(c),
+                '(' | '[' | '{' => {
+                    let close = surround_pair(c)?.1;
+                    if depth.last() == Some(&close) {
+                        depth.pop();
+                    } else {
+                        open = Some(((row, col), close));
+                        break 'back;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let ((open_pos, close_char), open_char) = open.map(|(pos, close)| ((pos, close), rows[pos.0][pos.1]))?;
+    // Walk forward from the end for its closing bracket
+    let mut nested = 0usize;
+    for (row, chars) in rows.iter().enumerate().skip(end.0) {
+        let from = if row == end.0 { end.1 } else { 0 };
+        for (col, &c) in chars.iter().enumerate().skip(from) {
+            if c == open_char {
+                nested += 1;
+            } else if c == close_char {
+                if nested == 0 {
+                    return Some((open_pos, (row, col)));
+                }
+                nested -= 1;
+            }
+        }
+    }
+    None
+}
+
+/// Columns of the balanced quote pairs of a line, read from its start
+///
+/// A backslash escapes the next character, and other quote characters inside a quoted
+/// run are part of its text.
+fn quote_pairs(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    let mut open: Option<usize> = None;
+    let mut escaped = false;
+    for (col, &c) in chars.iter().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '"' | '\'' | '`' => match open {
+                Some(start) if chars[start] == c => {
+                    pairs.push((start, col));
+                    open = None;
+                }
+                Some(_) => {}
+                None => open = Some(col),
+            },
+            _ => {}
+        }
+    }
+    pairs
+}
+
+fn enclosing_quote(lines: &[String], start: Position, end: Position) -> Option<(Position, Position)> {
+    if start.0 != end.0 {
+        return None;
+    }
+    let chars: Vec<char> = lines.get(start.0)?.chars().collect();
+    quote_pairs(&chars).into_iter()
+        .find(|&(open, close)| open < start.1 && close >= end.1)
+        .map(|(open, close)| ((start.0, open), (start.0, close)))
+}
+
+impl EditorBuffer {
+    /// Selections to act on, normalized: the main one first, then `multi_selections`
+    fn surround_targets(&self) -> Vec<(Position, Position)> {
+        let mut targets: Vec<(Position, Position)> = Vec::new();
+        if let Some(sel) = self.selection.as_ref().filter(|sel| sel.is_active()) {
+            targets.push(sel.normalized());
+        }
+        for &(start, end) in &self.multi_selections {
+            if let (Some(start), Some(end)) = (start, end) {
+                let range = if start <= end { (start, end) } else { (end, start) };
+                if range.0 != range.1 && !targets.contains(&range) {
+                    targets.push(range);
+                }
+            }
+        }
+        targets
+    }
+
+    /// Wrap every selection in the pair `c` belongs to, keeping the inner text selected
+    ///
+    /// Returns false (and changes nothing) when nothing is selected or `c` is not a pair character.
+    pub fn surround_selection(&mut self, c: char) -> bool {
+        let Some((open, close)) = surround_pair(c) else {
+            return false;
+        };
+        let targets = self.surround_targets();
+        if targets.is_empty() {
+            return false;
+        }
+        self.push_undo();
+        let mut inserts: Vec<(Position, bool)> = targets.iter()
+            .flat_map(|&(start, end)| [(start, true), (end, false)])
+            .collect();
+        // Back to front so earlier positions stay valid; an opening character goes in
+        // first so a closing one at the same column ends up before it
+        inserts.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+        for ((row, col), is_open) in inserts {
+            let pair = if is_open { open } else { close };
+            self.replace_range((row, col), (row, col), pair.encode_utf8(&mut [0; 4]));
+        }
+
+        let shifted: Vec<(Position, Position)> = targets.iter()
+            .map(|&(start, end)| (shift_for_surround(start, &targets, Anchor::Start), shift_for_surround(end, &targets, Anchor::End)))
+            .collect();
+        let main_selected = self.selection.as_ref().is_some_and(|sel| sel.is_active());
+        if main_selected {
+            let (start, end) = shifted[0];
+            let mut sel = Selection::new(start.0, start.1);
+            sel.set(start.0, start.1, end.0, end.1);
+            self.selection = Some(sel);
+            (self.cursor.row, self.cursor.col) = end;
+        } else {
+            let cursor = shift_for_surround((self.cursor.row, self.cursor.col), &targets, Anchor::Caret);
+            (self.cursor.row, self.cursor.col) = cursor;
+        }
+        self.multi_selections = shifted.iter().skip(usize::from(main_selected))
+            .map(|&(start, end)| (Some(start), Some(end)))
+            .collect();
+        for cursor in self.multi_cursors.iter_mut() {
+            *cursor = shift_for_surround(*cursor, &targets, Anchor::Caret);
+        }
+        true
+    }
+
+    /// Remove the innermost bracket or quote pair around each selection and cursor
+    ///
+    /// Returns false (and changes nothing) when no pair encloses any of them.
+    pub fn unsurround(&mut self) -> bool {
+        let mut ranges = self.surround_targets();
+        if !self.selection.as_ref().is_some_and(|sel| sel.is_active()) {
+            ranges.insert(0, ((self.cursor.row, self.cursor.col), (self.cursor.row, self.cursor.col)));
+        }
+        ranges.extend(self.multi_cursors.iter().map(|&pos| (pos, pos)));
+        let mut removed: Vec<Position> = ranges.iter()
+            .filter_map(|&(start, end)| enclosing_pair(&self.lines, start, end))
+            .flat_map(|(open, close)| [open, close])
+            .collect();
+        if removed.is_empty() {
+            return false;
+        }
+        removed.sort();
+        removed.dedup();
+        self.push_undo();
+        for &(row, col) in removed.iter().rev() {
+            self.replace_range((row, col), (row, col + 1), "");
+        }
+
+        let shift = |pos: Position| shift_for_removal(pos, &removed);
+        (self.cursor.row, self.cursor.col) = shift((self.cursor.row, self.cursor.col));
+        if let Some(sel) = &mut self.selection {
+            (sel.start_row, sel.start_col) = shift((sel.start_row, sel.start_col));
+            (sel.end_row, sel.end_col) = shift((sel.end_row, sel.end_col));
+        }
+        for (start, end) in self.multi_selections.iter_mut() {
+            *start = start.map(shift);
+            *end = end.map(shift);
+        }
+        for cursor in self.multi_cursors.iter_mut() {
+            *cursor = shift(*cursor);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn surround_shifts_adjacent_ranges() {
+        let ranges = [((0, 0), (0, 3)), ((0, 3), (0, 5)), ((1, 2), (1, 4))];
+        // "abcde" -> "(abc)(de)"
+        assert_eq!(shift_for_surround((0, 0), &ranges, Anchor::Start), (0, 1));
+        assert_eq!(shift_for_surround((0, 3), &ranges, Anchor::End), (0, 4));
+        assert_eq!(shift_for_surround((0, 3), &ranges, Anchor::Start), (0, 6));
+        assert_eq!(shift_for_surround((0, 5), &ranges, Anchor::End), (0, 8));
+        assert_eq!(shift_for_surround((0, 5), &ranges, Anchor::Caret), (0, 9));
+        assert_eq!(shift_for_surround((1, 0), &ranges, Anchor::Caret), (1, 0));
+    }
+
+    #[test]
+    fn enclosing_pair_finds_innermost() {
+        let text = lines("call(a, \"b c\", [d])\nx");
+        assert_eq!(enclosing_pair(&text, (0, 10), (0, 10)), Some(((0, 8), (0, 12))));
+        assert_eq!(enclosing_pair(&text, (0, 16), (0, 17)), Some(((0, 15), (0, 17))));
+        assert_eq!(enclosing_pair(&text, (0, 5), (0, 6)), Some(((0, 4), (0, 18))));
+        assert_eq!(enclosing_pair(&text, (1, 0), (1, 1)), None);
+        let nested = lines("{\n  (a)\n}");
+        assert_eq!(enclosing_pair(&nested, (1, 0), (1, 0)), Some(((0, 0), (2, 0))));
+    }
+
+    #[test]
+    fn quotes_pair_up_from_the_line_start() {
+        let text = lines("f(\"a\", x, \"b\")");
+        // Between two strings: the call's parentheses, not the closing and opening quotes
+        assert_eq!(enclosing_pair(&text, (0, 7), (0, 7)), Some(((0, 1), (0, 13))));
+        assert_eq!(enclosing_pair(&text, (0, 5), (0, 10)), Some(((0, 1), (0, 13))));
+        assert_eq!(enclosing_pair(&text, (0, 11), (0, 11)), Some(((0, 10), (0, 12))));
+        let escaped = lines(r#"s = "say \"hi\" it's" + x"#);
+        assert_eq!(enclosing_pair(&escaped, (0, 12), (0, 12)), Some(((0, 4), (0, 20))));
+        assert_eq!(enclosing_pair(&escaped, (0, 25), (0, 25)), None);
+    }
+}
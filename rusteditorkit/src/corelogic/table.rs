@@ -0,0 +1,328 @@
+//! Markdown table editing helpers for EditorBuffer
+//!
+//! Tables are detected from the line structure: a block of consecutive lines starting with `|`
+//! whose second line is a `|---|` separator row under the header. Tab and Shift+Tab only
+//! move between cells in Markdown and prose buffers, so a code line starting with a
+//! closure like `|x|` still indents.
+//! Formatting re-pads cells so the pipes line up, honoring `:---`, `:---:` and `---:` alignment
+//! markers. All commands apply a single range edit with one undo step.
+
+use super::buffer::EditorBuffer;
+
+/// Column alignment taken from the separator row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// True if the line is part of a Markdown table (starts with a pipe)
+pub fn is_table_line(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+/// Split a table line into trimmed cell texts (escaped `\|` stays inside the cell)
+pub fn split_cells(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let inner = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let inner = if inner.ends_with('|') && !inner.ends_with("\\|") {
+        &inner[..inner.len() - 1]
+    } else {
+        inner
+    };
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in inner.chars() {
+        if c == '|' && !escaped {
+            cells.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+/// True if every cell is a separator cell like `---`, `:--` or `:-:`
+fn is_separator_row(cells: &[String]) -> bool {
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+fn alignment(cell: &str) -> Align {
+    match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Align::Center,
+        (false, true) => Align::Right,
+        _ => Align::Left,
+    }
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = width.saturating_sub(text.chars().count());
+    match align {
+        Align::Left => format!("{}{}", text, " ".repeat(fill)),
+        Align::Right => format!("{}{}", " ".repeat(fill), text),
+        Align::Center => format!("{}{}{}", " ".repeat(fill / 2), text, " ".repeat(fill - fill / 2)),
+    }
+}
+
+/// Re-pad all rows of a table so that the pipes are aligned
+pub fn format_table(lines: &[String]) -> Vec<String> {
+    let indent: String = lines.first()
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).collect())
+        .unwrap_or_default();
+    let rows: Vec<Vec<String>> = lines.iter().map(|line| split_cells(line)).collect();
+    let columns = rows.iter().map(|cells| cells.len()).max().unwrap_or(0);
+    let separator = rows.iter().position(|cells| is_separator_row(cells));
+    let aligns: Vec<Align> = (0..columns)
+        .map(|col| {
+            separator
+                .and_then(|row| rows[row].get(col))
+                .map(|cell| alignment(cell))
+                .unwrap_or(Align::Left)
+        })
+        .collect();
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            rows.iter().enumerate()
+                .filter(|(row, _)| Some(*row) != separator)
+                .filter_map(|(_, cells)| cells.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+
+    rows.iter().enumerate().map(|(row, cells)| {
+        let formatted: Vec<String> = (0..columns).map(|col| {
+            let width = widths[col];
+            if Some(row) == separator {
+                let (left, right) = match aligns[col] {
+                    Align::Left => (cells.get(col).is_some_and(|c| c.starts_with(':')), false),
+                    Align::Center => (true, true),
+                    Align::Right => (false, true),
+                };
+                let dashes = width - left as usize - right as usize;
+                format!("{}{}{}", if left { ":" } else { "" }, "-".repeat(dashes), if right { ":" } else { "" })
+            } else {
+                pad(cells.get(col).map(String::as_str).unwrap_or(""), width, aligns[col])
+            }
+        }).collect();
+        format!("{}| {} |", indent, formatted.join(" | "))
+    }).collect()
+}
+
+/// Index of the cell containing char column `col` in a table line
+pub fn cell_index_at(line: &str, col: usize) -> usize {
+    let mut pipes: usize = 0;
+    let mut escaped = false;
+    for c in line.chars().take(col) {
+        if c == '|' && !escaped {
+            pipes += 1;
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    pipes.saturating_sub(1)
+}
+
+/// Char column where the content of cell `index` starts in a table line
+pub fn cell_start_col(line: &str, index: usize) -> usize {
+    let mut pipes: usize = 0;
+    let mut escaped = false;
+    for (i, c) in line.chars().enumerate() {
+        if c == '|' && !escaped {
+            if pipes == index {
+                // Skip the pipe and the padding space after it
+                return (i + 2).min(line.chars().count());
+            }
+            pipes += 1;
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    line.chars().count()
+}
+
+impl EditorBuffer {
+    /// Row range `(start, end)` of the table containing `row`, if any
+    pub fn table_range(&self, row: usize) -> Option<(usize, usize)> {
+        if !self.lines.get(row).is_some_and(|line| is_table_line(line)) {
+            return None;
+        }
+        let mut start = row;
+        while start > 0 && is_table_line(&self.lines[start - 1]) {
+            start -= 1;
+        }
+        let mut end = row;
+        while end + 1 < self.lines.len() && is_table_line(&self.lines[end + 1]) {
+            end += 1;
+        }
+        // A header row followed by a separator row, or it is just lines starting with a pipe
+        if end == start || !is_separator_row(&split_cells(&self.lines[start + 1])) {
+            return None;
+        }
+        Some((start, end))
+    }
+
+    /// True if the cursor is inside a Markdown table of a Markdown or prose buffer
+    pub fn in_table(&self) -> bool {
+        self.prose_mode_active() && self.table_range(self.cursor.row).is_some()
+    }
+
+    /// Replace the table `start..=end` with `rows` (formatted) as one undo step, then place
+    /// the cursor at the start of cell `cell` in `target_row`
+    fn apply_table_edit(&mut self, start: usize, end: usize, rows: Vec<String>, target_row: usize, cell: usize) {
+        let formatted = format_table(&rows);
+        self.push_undo();
+        let new_end = start + formatted.len() - 1;
+        self.replace_rows(start..end + 1, formatted);
+        self.selection = None;
+        self.cursor.row = target_row.min(new_end);
+        self.cursor.col = cell_start_col(&self.lines[self.cursor.row], cell);
+    }
+
+    /// Index of the separator row within the table `start..=end`
+    fn table_separator(&self, start: usize, end: usize) -> Option<usize> {
+        (start..=end).find(|&row| is_separator_row(&split_cells(&self.lines[row])))
+    }
+
+    /// Align the pipes of the table under the cursor, keeping the cursor in its cell
+    pub fn format_table_at_cursor(&mut self) {
+        let Some((start, end)) = self.table_range(self.cursor.row) else { return };
+        let rows = self.lines[start..=end].to_vec();
+        if format_table(&rows) == rows {
+            return;
+        }
+        let cell = cell_index_at(&self.lines[self.cursor.row], self.cursor.col);
+        self.apply_table_edit(start, end, rows, self.cursor.row, cell);
+    }
+
+    /// Move to the next cell (formatting the table); appends a row after the last cell
+    pub fn table_next_cell(&mut self) {
+        let Some((start, end)) = self.table_range(self.cursor.row) else { return };
+        let separator = self.table_separator(start, end);
+        let mut rows = self.lines[start..=end].to_vec();
+        let columns = rows.iter().map(|line| split_cells(line).len()).max().unwrap_or(1);
+        let cell = cell_index_at(&self.lines[self.cursor.row], self.cursor.col);
+        let (mut row, mut cell) = if cell + 1 < columns {
+            (self.cursor.row, cell + 1)
+        } else {
+            (self.cursor.row + 1, 0)
+        };
+        if Some(row) == separator {
+            row += 1;
+        }
+        if row > end {
+            rows.push("|".repeat(columns + 1));
+            cell = 0;
+        }
+        self.apply_table_edit(start, end, rows, row, cell);
+    }
+
+    /// Move to the previous cell (formatting the table)
+    pub fn table_prev_cell(&mut self) {
+        let Some((start, end)) = self.table_range(self.cursor.row) else { return };
+        let separator = self.table_separator(start, end);
+        let rows = self.lines[start..=end].to_vec();
+        let columns = rows.iter().map(|line| split_cells(line).len()).max().unwrap_or(1);
+        let cell = cell_index_at(&self.lines[self.cursor.row], self.cursor.col);
+        let (mut row, mut cell) = if cell > 0 {
+            (self.cursor.row, cell - 1)
+        } else if self.cursor.row > start {
+            (self.cursor.row - 1, columns - 1)
+        } else {
+            (start, 0)
+        };
+        if Some(row) == separator {
+            if row > start {
+                row -= 1;
+            } else {
+                row += 1;
+                cell = 0;
+            }
+        }
+        self.apply_table_edit(start, end, rows, row, cell);
+    }
+
+    /// Insert an empty row below the cursor row (below the separator when on the header)
+    pub fn insert_table_row(&mut self) {
+        let Some((start, end)) = self.table_range(self.cursor.row) else { return };
+        let separator = self.table_separator(start, end);
+        let mut rows = self.lines[start..=end].to_vec();
+        let columns = rows.iter().map(|line| split_cells(line).len()).max().unwrap_or(1);
+        let mut insert_at = self.cursor.row + 1;
+        if separator == Some(insert_at) {
+            insert_at += 1;
+        }
+        rows.insert(insert_at - start, "|".repeat(columns + 1));
+        self.apply_table_edit(start, end, rows, insert_at, 0);
+    }
+
+    /// Insert an empty column after the cell under the cursor
+    pub fn insert_table_column(&mut self) {
+        let Some((start, end)) = self.table_range(self.cursor.row) else { return };
+        let separator = self.table_separator(start, end);
+        let cell = cell_index_at(&self.lines[self.cursor.row], self.cursor.col);
+        let indent: String = self.lines[start].chars().take_while(|c| c.is_whitespace()).collect();
+        let rows: Vec<String> = (start..=end).map(|row| {
+            let mut cells = split_cells(&self.lines[row]);
+            let at = (cell + 1).min(cells.len());
+            cells.insert(at, if Some(row) == separator { "---".to_string() } else { String::new() });
+            format!("{}| {} |", indent, cells.join(" | "))
+        }).collect();
+        self.apply_table_edit(start, end, rows, self.cursor.row, cell + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn format_table_aligns_pipes() {
+        let table = lines(&["| a | long header |", "|:-|--:|", "| xyz | 1 |"]);
+        assert_eq!(format_table(&table), lines(&[
+            "| a   | long header |",
+            "| :-- | ----------: |",
+            "| xyz |           1 |",
+        ]));
+    }
+
+    #[test]
+    fn cells_are_found_by_column_and_escaped_pipes_kept() {
+        let line = "| a   | bb  |";
+        assert_eq!(cell_index_at(line, 2), 0);
+        assert_eq!(cell_index_at(line, 8), 1);
+        assert_eq!(cell_start_col(line, 1), 8);
+        assert_eq!(split_cells("| a \\| b | c |"), vec!["a \\| b", "c"]);
+    }
+
+    #[test]
+    fn tab_indents_lines_that_only_start_with_a_pipe() {
+        let mut buffer = EditorBuffer::new();
+        buffer.language = Some("markdown".to_string());
+        buffer.lines = lines(&["| a | b |", "|---|---|", "| 1 | 2 |", "", "|x| x + 1"]);
+        buffer.cursor = crate::corelogic::EditorCursor::new(2, 0);
+        assert!(buffer.in_table());
+        buffer.cursor = crate::corelogic::EditorCursor::new(4, 0);
+        assert!(!buffer.in_table());
+
+        // A closure in a code buffer, even with a separator-like line under it
+        buffer.language = Some("rust".to_string());
+        buffer.lines = lines(&["|x| x + 1", "|-|"]);
+        buffer.cursor = crate::corelogic::EditorCursor::new(0, 0);
+        buffer.handle_editor_action(crate::keybinds::EditorAction::Indent);
+        assert_eq!(buffer.lines[0], "    |x| x + 1");
+    }
+}
@@ -0,0 +1,114 @@
+//! Opt-in session telemetry for EditorBuffer
+//!
+//! Nothing is recorded until the host installs a sink with `set_telemetry_sink`. Metrics
+//! are anonymized counters only (keystrokes, per-action usage, undo/redo frequency); no
+//! text, file names or positions are ever included. The crate performs no I/O itself,
+//! the sink decides what to do with the events.
+
+use std::collections::BTreeMap;
+use super::buffer::EditorBuffer;
+use crate::keybinds::EditorAction;
+
+/// Aggregated counters for the current editing session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionMetrics {
+    /// Characters typed plus non-text commands executed
+    pub keystrokes: u64,
+    /// Number of times each action was executed, keyed by action name
+    pub command_counts: BTreeMap<String, u64>,
+    pub undos: u64,
+    pub redos: u64,
+}
+
+/// Events delivered to the telemetry sink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelemetryEvent {
+    /// An action was executed (the action name only, never its parameters)
+    CommandExecuted(String),
+    /// Periodic snapshot of the session counters, also sent by `flush_telemetry`
+    Summary(SessionMetrics),
+}
+
+/// Host-supplied receiver for telemetry events
+pub type TelemetrySink = Box<dyn Fn(&TelemetryEvent)>;
+
+/// Telemetry state owned by the buffer
+#[derive(Default)]
+pub struct Telemetry {
+    sink: Option<TelemetrySink>,
+    metrics: SessionMetrics,
+    summary_interval: u64,
+    commands_since_summary: u64,
+}
+
+impl Telemetry {
+    /// True once a sink has been installed
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// Current session counters
+    pub fn metrics(&self) -> &SessionMetrics {
+        &self.metrics
+    }
+
+    /// Count one executed action; `typed_chars` is the length of inserted text, if any
+    pub fn record(&mut self, action: EditorAction, typed_chars: Option<usize>) {
+        let Some(sink) = &self.sink else {
+            return;
+        };
+        let name = format!("{:?}", action);
+        self.metrics.keystrokes += typed_chars.map_or(1, |n| n as u64);
+        *self.metrics.command_counts.entry(name.clone()).or_insert(0) += 1;
+        match action {
+            EditorAction::Undo => self.metrics.undos += 1,
+            EditorAction::Redo => self.metrics.redos += 1,
+            _ => {}
+        }
+        sink(&TelemetryEvent::CommandExecuted(name));
+
+        self.commands_since_summary += 1;
+        if self.summary_interval > 0 && self.commands_since_summary >= self.summary_interval {
+            self.flush();
+        }
+    }
+
+    /// Send a summary of the current counters to the sink
+    pub fn flush(&mut self) {
+        if let Some(sink) = &self.sink {
+            sink(&TelemetryEvent::Summary(self.metrics.clone()));
+        }
+        self.commands_since_summary = 0;
+    }
+}
+
+impl std::fmt::Debug for Telemetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Telemetry")
+            .field("enabled", &self.is_enabled())
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+impl EditorBuffer {
+    /// Opt in to telemetry: `sink` receives events, with a summary every `summary_interval`
+    /// commands (0 disables periodic summaries; call `flush_telemetry` instead)
+    pub fn set_telemetry_sink<F: Fn(&TelemetryEvent) + 'static>(&mut self, sink: F, summary_interval: u64) {
+        self.telemetry = Telemetry {
+            sink: Some(Box::new(sink)),
+            summary_interval,
+            ..Telemetry::default()
+        };
+    }
+
+    /// Opt out: drop the sink and reset the counters
+    pub fn clear_telemetry_sink(&mut self) {
+        self.telemetry = Telemetry::default();
+    }
+
+    /// Send the current session summary to the sink (e.g. when the document is closed)
+    pub fn flush_telemetry(&mut self) {
+        self.telemetry.flush();
+    }
+}
@@ -0,0 +1,56 @@
+//! File templates for new buffers
+//!
+//! Hosts register named templates with `register_template`; `NewFileFromTemplate(name)`
+//! starts a new file with the template content. The content is snippet text (see
+//! `snippet`), so `${1:Title}` style fields are selected one after another with Tab and
+//! `$0` marks where the cursor ends up.
+
+use super::buffer::EditorBuffer;
+
+/// A named starting point for new files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTemplate {
+    pub name: String,
+    /// Snippet text inserted into the new buffer
+    pub content: String,
+}
+
+impl FileTemplate {
+    pub fn new(name: &str, content: &str) -> Self {
+        Self { name: name.to_string(), content: content.to_string() }
+    }
+}
+
+impl EditorBuffer {
+    /// Add a template, replacing any template with the same name
+    pub fn register_template(&mut self, template: FileTemplate) {
+        match self.templates.iter_mut().find(|existing| existing.name == template.name) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    /// Remove the template called `name`; returns whether it existed
+    pub fn unregister_template(&mut self, name: &str) -> bool {
+        let before = self.templates.len();
+        self.templates.retain(|template| template.name != name);
+        self.templates.len() != before
+    }
+
+    /// Names of the registered templates, in registration order
+    pub fn template_names(&self) -> Vec<&str> {
+        self.templates.iter().map(|template| template.name.as_str()).collect()
+    }
+
+    /// Start a new file filled from the template called `name`; false if there is none
+    pub fn new_file_from_template(&mut self, name: &str) -> bool {
+        let Some(content) = self.templates.iter().find(|template| template.name == name).map(|t| t.content.clone()) else {
+            return false;
+        };
+        self.new_file();
+        self.insert_snippet(&content);
+        // The template is the starting point, not an undoable edit
+        self.undo_stack.clear();
+        true
+    }
+}
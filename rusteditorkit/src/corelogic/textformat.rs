@@ -0,0 +1,222 @@
+//! On-disk text format of a buffer: encoding, byte order mark and line endings
+//!
+//! Files are decoded into `lines` without any of these, so the buffer keeps the format
+//! it found in `text_format` and saving encodes the lines back into it. Byte offsets
+//! shown for the file (the gutter's offset mode) are measured in that format, through a
+//! prefix-sum table of line start offsets that the edit path cuts back to the first
+//! changed row.
+
+use super::buffer::EditorBuffer;
+use super::edit::RowEdit;
+
+/// Character encoding of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// Line separator of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// How the text of a file is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    /// Whether the file starts with a byte order mark
+    pub bom: bool,
+    pub line_ending: LineEnding,
+    /// Whether the last line ends with a line ending too
+    pub final_newline: bool,
+}
+
+impl TextFormat {
+    /// Decode file bytes: UTF-8 (with or without BOM) or BOM-marked UTF-16
+    ///
+    /// The line ending is the one ending the first line (LF for single-line files).
+    /// The text comes back as found; a line ending after the last line is recorded in
+    /// `final_newline` so `encode` writes it again.
+    pub fn decode(bytes: &[u8]) -> Option<(String, TextFormat)> {
+        let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+            if !bytes.len().is_multiple_of(2) {
+                return None;
+            }
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| from([pair[0], pair[1]])).collect();
+            String::from_utf16(&units).ok()
+        };
+        let (text, encoding, bom) = match bytes {
+            [0xEF, 0xBB, 0xBF, rest @ ..] => (String::from_utf8(rest.to_vec()).ok()?, TextEncoding::Utf8, true),
+            [0xFF, 0xFE, rest @ ..] => (utf16(rest, u16::from_le_bytes)?, TextEncoding::Utf16Le, true),
+            [0xFE, 0xFF, rest @ ..] => (utf16(rest, u16::from_be_bytes)?, TextEncoding::Utf16Be, true),
+            _ => (String::from_utf8(bytes.to_vec()).ok()?, TextEncoding::Utf8, false),
+        };
+        let line_ending = match text.find('\n') {
+            Some(end) if text[..end].ends_with('\r') => LineEnding::CrLf,
+            _ => LineEnding::Lf,
+        };
+        let final_newline = text.ends_with('\n');
+        Some((text, TextFormat { encoding, bom, line_ending, final_newline }))
+    }
+
+    /// Join `lines` with the line ending and encode them, byte order mark first and a
+    /// line ending last when the file had one
+    pub fn encode(&self, lines: &[String]) -> Vec<u8> {
+        let mut text = lines.join(self.line_ending.as_str());
+        if self.final_newline {
+            text.push_str(self.line_ending.as_str());
+        }
+        let mut bytes = Vec::with_capacity(self.bom_len() + self.encoded_len(&text));
+        match self.encoding {
+            TextEncoding::Utf8 => {
+                if self.bom {
+                    bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+                }
+                bytes.extend_from_slice(text.as_bytes());
+            }
+            TextEncoding::Utf16Le => {
+                if self.bom {
+                    bytes.extend_from_slice(&[0xFF, 0xFE]);
+                }
+                bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+            TextEncoding::Utf16Be => {
+                if self.bom {
+                    bytes.extend_from_slice(&[0xFE, 0xFF]);
+                }
+                bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            }
+        }
+        bytes
+    }
+
+    /// Size of one code unit in bytes
+    fn unit_size(&self) -> usize {
+        match self.encoding {
+            TextEncoding::Utf8 => 1,
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => 2,
+        }
+    }
+
+    /// Bytes taken by the byte order mark
+    pub fn bom_len(&self) -> usize {
+        match (self.bom, self.encoding) {
+            (false, _) => 0,
+            (true, TextEncoding::Utf8) => 3,
+            (true, TextEncoding::Utf16Le | TextEncoding::Utf16Be) => 2,
+        }
+    }
+
+    /// Bytes `text` takes in this encoding
+    pub fn encoded_len(&self, text: &str) -> usize {
+        match self.encoding {
+            TextEncoding::Utf8 => text.len(),
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => text.encode_utf16().count() * 2,
+        }
+    }
+
+    /// Bytes taken by one line separator
+    pub fn line_ending_len(&self) -> usize {
+        self.line_ending.as_str().len() * self.unit_size()
+    }
+}
+
+/// Start offsets of the lines in the encoded file, built up to the rows asked for
+#[derive(Debug, Default)]
+pub struct LineOffsets {
+    /// `starts[row]` is the byte offset of `row`; valid for the rows it holds
+    starts: Vec<usize>,
+    format: TextFormat,
+}
+
+impl LineOffsets {
+    /// Forget every offset
+    pub fn clear(&mut self) {
+        self.starts.clear();
+    }
+
+    /// Follow an edit of the text: offsets up to the first changed row stay valid
+    pub fn rows_replaced(&mut self, edit: &RowEdit) {
+        self.starts.truncate(edit.start + 1);
+    }
+}
+
+impl EditorBuffer {
+    /// Byte offset of the start of `row` in the file as saved in the buffer's
+    /// `text_format` (encoding, BOM and line endings included)
+    ///
+    /// Rows past the end give the start of the last line.
+    pub fn line_start_offset(&self, row: usize) -> usize {
+        let mut offsets = self.line_offsets.borrow_mut();
+        if offsets.format != self.text_format {
+            offsets.starts.clear();
+            offsets.format = self.text_format;
+        }
+        if offsets.starts.is_empty() {
+            offsets.starts.push(self.text_format.bom_len());
+        }
+        let row = row.min(self.lines.len().saturating_sub(1));
+        while offsets.starts.len() <= row {
+            let prev = offsets.starts.len() - 1;
+            let start = offsets.starts[prev] + self.text_format.encoded_len(&self.lines[prev]) + self.text_format.line_ending_len();
+            offsets.starts.push(start);
+        }
+        offsets.starts[row]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_detects_the_format() {
+        let (text, format) = TextFormat::decode(b"\xEF\xBB\xBFa\r\nb").unwrap();
+        assert_eq!(text, "a\r\nb");
+        assert_eq!(format, TextFormat { encoding: TextEncoding::Utf8, bom: true, line_ending: LineEnding::CrLf, final_newline: false });
+        let (text, format) = TextFormat::decode(b"\xFF\xFEa\x00\n\x00").unwrap();
+        assert_eq!(text, "a\n");
+        assert_eq!((format.encoding, format.line_ending, format.final_newline), (TextEncoding::Utf16Le, LineEnding::Lf, true));
+        assert_eq!(TextFormat::decode(b"plain").unwrap().1, TextFormat::default());
+    }
+
+    #[test]
+    fn encode_writes_the_decoded_format_back() {
+        for bytes in [&b"\xEF\xBB\xBFa\r\nb"[..], b"\xFF\xFEa\x00\r\x00\n\x00\xE9\x00", b"\xFE\xFF\x00a\x00\n\x00b", b"a\nb", b"a\r\nb\r\n", b"a\n\n"] {
+            let (text, format) = TextFormat::decode(bytes).unwrap();
+            let lines: Vec<String> = text.lines().map(str::to_string).collect();
+            assert_eq!(format.encode(&lines), bytes);
+        }
+    }
+
+    #[test]
+    fn offsets_count_the_encoded_file() {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec!["ab".into(), "é".into(), "c".into()];
+        assert_eq!(buffer.line_start_offset(2), 6);
+        buffer.text_format = TextFormat { encoding: TextEncoding::Utf8, bom: true, line_ending: LineEnding::CrLf, final_newline: false };
+        assert_eq!(buffer.line_start_offset(0), 3);
+        assert_eq!(buffer.line_start_offset(2), 3 + 4 + 4);
+        buffer.text_format = TextFormat { encoding: TextEncoding::Utf16Le, bom: true, line_ending: LineEnding::CrLf, final_newline: false };
+        assert_eq!(buffer.line_start_offset(1), 2 + 4 + 4);
+        // Edits drop the offsets from the changed row on
+        buffer.replace_range((1, 0), (1, 0), "xy");
+        assert_eq!(buffer.line_start_offset(2), 2 + 8 + 6 + 4);
+        assert_eq!(buffer.line_start_offset(9), 2 + 8 + 6 + 4);
+    }
+}
@@ -0,0 +1,121 @@
+//! Display titles for tabs and windows
+//!
+//! `display_title()` describes the buffer the way tab strips and window titles show it:
+//! the file name (or "untitled-N"), the directory relative to the workspace root set
+//! with `set_workspace_root`, and the modified/read-only state. `refresh_title` emits
+//! `EditorEvent::TitleChanged` whenever any of that changes.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+
+static NEXT_UNTITLED: AtomicUsize = AtomicUsize::new(1);
+
+/// Next number for an untitled buffer, unique within the process
+pub fn next_untitled_number() -> usize {
+    NEXT_UNTITLED.fetch_add(1, Ordering::Relaxed)
+}
+
+/// What a tab or window title shows for a buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayTitle {
+    /// File name, or "untitled-N" for unsaved buffers
+    pub name: String,
+    /// Directory of the file relative to the workspace root ("~"-abbreviated outside of it);
+    /// None without a workspace root, for untitled buffers and for files in the root itself
+    pub directory: Option<String>,
+    pub modified: bool,
+    pub read_only: bool,
+}
+
+/// "main.rs — src — modified"
+impl fmt::Display for DisplayTitle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(directory) = &self.directory {
+            write!(f, " — {}", directory)?;
+        }
+        if self.modified {
+            write!(f, " — modified")?;
+        }
+        if self.read_only {
+            write!(f, " — read-only")?;
+        }
+        Ok(())
+    }
+}
+
+/// Directory shown for `path`: relative to `root` inside it, otherwise with the home directory as "~"
+pub fn display_directory(path: &str, root: &Path) -> Option<String> {
+    if path.contains("://") {
+        return None;
+    }
+    let absolute = std::path::absolute(path).ok()?;
+    let parent = absolute.parent()?;
+    if let Ok(relative) = parent.strip_prefix(root) {
+        return (!relative.as_os_str().is_empty()).then(|| relative.to_string_lossy().into_owned());
+    }
+    // Without GTK the home directory comes from $HOME alone
+    #[cfg(feature = "gtk")]
+    let home = Some(glib::home_dir());
+    #[cfg(not(feature = "gtk"))]
+    let home = std::env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from);
+    Some(match home.as_deref().and_then(|home| parent.strip_prefix(home).ok()) {
+        Some(relative) if relative.as_os_str().is_empty() => "~".to_string(),
+        Some(relative) => Path::new("~").join(relative).to_string_lossy().into_owned(),
+        None => parent.to_string_lossy().into_owned(),
+    })
+}
+
+impl EditorBuffer {
+    /// Root that `DisplayTitle::directory` is relative to (None hides the directory)
+    pub fn set_workspace_root(&mut self, root: Option<PathBuf>) {
+        self.workspace_root = root.map(|root| std::path::absolute(&root).unwrap_or(root));
+        self.refresh_title();
+    }
+
+    /// Title of the buffer for tabs and window titles
+    pub fn display_title(&self) -> DisplayTitle {
+        let (name, directory) = match self.file_path.as_deref() {
+            Some(path) => {
+                let name = Path::new(path).file_name().map_or_else(|| path.to_string(), |n| n.to_string_lossy().into_owned());
+                let directory = self.workspace_root.as_deref().and_then(|root| display_directory(path, root));
+                (name, directory)
+            }
+            None => (format!("untitled-{}", self.untitled_number), None),
+        };
+        DisplayTitle { name, directory, modified: self.is_modified(), read_only: self.file_read_only }
+    }
+
+    /// Recompute the title and emit `TitleChanged` if it differs from the last one
+    ///
+    /// Does nothing when no event listeners are connected.
+    pub fn refresh_title(&mut self) {
+        if !self.events.has_listeners() {
+            return;
+        }
+        let title = self.display_title();
+        if self.last_title.as_ref() != Some(&title) {
+            self.last_title = Some(title.clone());
+            self.events.emit(&EditorEvent::TitleChanged(title));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_text_and_directory() {
+        let title = DisplayTitle { name: "untitled-1".to_string(), directory: None, modified: true, read_only: false };
+        assert_eq!(title.to_string(), "untitled-1 — modified");
+        let root = Path::new("/work/project");
+        assert_eq!(display_directory("/work/project/src/main.rs", root).as_deref(), Some("src"));
+        assert_eq!(display_directory("/work/project/README.md", root), None);
+        assert_eq!(display_directory("/etc/hosts", root).as_deref(), Some("/etc"));
+        assert_eq!(display_directory("sftp://host/a.txt", root), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Open/save transform hooks (e.g. decryption and encryption)
+//!
+//! A host registers a `FileTransform` for a path pattern such as `*.age` or
+//! `/home/*/secrets/*`. Opening a matching file reads the raw bytes and runs `decode`
+//! in memory; saving runs `encode` on the text and writes only its output, so the
+//! plaintext never reaches the disk. The first matching transform wins.
+
+use super::buffer::EditorBuffer;
+
+/// Host callback turning file bytes into other bytes; gets the path and returns an error message on failure
+pub type TransformFn = Box<dyn Fn(&str, Vec<u8>) -> Result<Vec<u8>, String>>;
+
+/// A pair of byte transforms applied to files whose path matches `pattern`
+pub struct FileTransform {
+    /// `*` matches any run of characters, `?` a single one; patterns without `/` match the file name
+    pub pattern: String,
+    /// Applied to the bytes read from disk (e.g. decrypt)
+    pub decode: TransformFn,
+    /// Applied to the text bytes before writing (e.g. encrypt)
+    pub encode: TransformFn,
+}
+
+impl FileTransform {
+    pub fn new(pattern: &str, decode: TransformFn, encode: TransformFn) -> Self {
+        Self { pattern: pattern.to_string(), decode, encode }
+    }
+
+    /// True if this transform applies to `path`
+    pub fn matches(&self, path: &str) -> bool {
+        let target = if self.pattern.contains('/') {
+            path
+        } else {
+            path.rsplit('/').next().unwrap_or(path)
+        };
+        wildcard_match(&self.pattern, target)
+    }
+}
+
+impl std::fmt::Debug for FileTransform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileTransform").field("pattern", &self.pattern).finish()
+    }
+}
+
+/// Errors from opening or saving a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileError {
+    /// Reading or writing the file failed
+    Io { path: String, message: String },
+    /// The (decoded) content is neither UTF-8 nor UTF-16 with a byte order mark
+    InvalidUtf8 { path: String },
+    /// The location is on a volume that is not mounted and no mount operation was given
+    NotMounted { path: String },
+    /// The open transform rejected the file (e.g. wrong key)
+    DecodeFailed { path: String, message: String },
+    /// The save transform failed; nothing was written
+    EncodeFailed { path: String, message: String },
+    /// A snapshot is shown read-only and must be closed before saving
+    SnapshotView { name: String },
+    /// The file may not be read or written with the user's permissions (EACCES); saving
+    /// can be retried through `EditorBuffer::save_file_elevated`
+    PermissionDenied { path: String },
+}
+
+impl FileError {
+    /// Error for a failed read or write of `path`
+    pub fn from_io(path: &str, error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => FileError::PermissionDenied { path: path.to_string() },
+            _ => FileError::Io { path: path.to_string(), message: error.to_string() },
+        }
+    }
+
+    /// Error for a failed GIO read or write of `location`
+    #[cfg(feature = "gtk")]
+    pub fn from_gio(location: &str, error: &glib::Error) -> Self {
+        if error.matches(gio::IOErrorEnum::PermissionDenied) {
+            FileError::PermissionDenied { path: location.to_string() }
+        } else if error.matches(gio::IOErrorEnum::NotMounted) {
+            FileError::NotMounted { path: location.to_string() }
+        } else {
+            FileError::Io { path: location.to_string(), message: error.to_string() }
+        }
+    }
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Io { path, message } => write!(f, "{}: {}", path, message),
+            FileError::InvalidUtf8 { path } => write!(f, "{}: content is not valid UTF-8 or UTF-16 text", path),
+            FileError::NotMounted { path } => write!(f, "{}: location is not mounted", path),
+            FileError::DecodeFailed { path, message } => write!(f, "{}: open transform failed: {}", path, message),
+            FileError::EncodeFailed { path, message } => write!(f, "{}: save transform failed: {}", path, message),
+            FileError::SnapshotView { name } => write!(f, "snapshot '{}' is being viewed; close the view before saving", name),
+            FileError::PermissionDenied { path } => write!(f, "{}: permission denied", path),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?` any one character
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl EditorBuffer {
+    /// Register open/save transforms for files matching `transform.pattern`
+    pub fn register_file_transform(&mut self, transform: FileTransform) {
+        self.file_transforms.push(transform);
+    }
+
+    /// Remove all transforms registered for `pattern`
+    pub fn unregister_file_transform(&mut self, pattern: &str) {
+        self.file_transforms.retain(|transform| transform.pattern != pattern);
+    }
+
+    /// Transform applying to `path`, if any
+    pub fn file_transform_for(&self, path: &str) -> Option<&FileTransform> {
+        self.file_transforms.iter().find(|transform| transform.matches(path))
+    }
+
+    /// Turn raw file bytes into lines and the format they were stored in, running the
+    /// transform registered for `path` first
+    pub(crate) fn decode_file_bytes(&self, path: &str, bytes: Vec<u8>) -> Result<(Vec<String>, super::textformat::TextFormat), FileError> {
+        let bytes = match self.file_transform_for(path) {
+            Some(transform) => (transform.decode)(path, bytes)
+                .map_err(|message| FileError::DecodeFailed { path: path.to_string(), message })?,
+            None => bytes,
+        };
+        let (text, format) = super::textformat::TextFormat::decode(&bytes).ok_or_else(|| FileError::InvalidUtf8 { path: path.to_string() })?;
+        Ok((text.lines().map(str::to_string).collect(), format))
+    }
+
+    /// Bytes to write for the current lines in the buffer's `text_format`, encoded by the
+    /// transform registered for `path`
+    pub(crate) fn encode_file_bytes(&self, path: &str) -> Result<Vec<u8>, FileError> {
+        let bytes = self.text_format.encode(&self.lines);
+        match self.file_transform_for(path) {
+            Some(transform) => (transform.encode)(path, bytes)
+                .map_err(|message| FileError::EncodeFailed { path: path.to_string(), message }),
+            None => Ok(bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_patterns_match_names_and_paths() {
+        assert!(wildcard_match("*.age", "notes.md.age"));
+        assert!(!wildcard_match("*.age", "notes.md"));
+        assert!(wildcard_match("secret?.txt", "secret1.txt"));
+        assert!(wildcard_match("a*b*c", "axxbyyc"));
+        assert!(!wildcard_match("a*b*c", "axxbyy"));
+
+        let identity: fn() -> TransformFn = || Box::new(|_, bytes| Ok(bytes));
+        let by_name = FileTransform::new("*.gpg", identity(), identity());
+        assert!(by_name.matches("/home/me/diary.gpg"));
+        let by_path = FileTransform::new("/vault/*", identity(), identity());
+        assert!(by_path.matches("/vault/a/b.txt"));
+        assert!(!by_path.matches("/home/vault/b.txt"));
+    }
+
+    #[test]
+    fn dispatcher_keeps_the_file_error() {
+        use crate::corelogic::dispatcher::{CommandDispatcher, CommandError, CommandParams};
+        use crate::keybinds::editor_action::EditorAction;
+        let path = std::env::temp_dir().join(format!("rek-transform-{}.age", std::process::id()));
+        std::fs::write(&path, b"sealed").unwrap();
+        let path = path.to_string_lossy().into_owned();
+        let mut buffer = EditorBuffer::new();
+        buffer.register_file_transform(FileTransform::new("*.age", Box::new(|_, _| Err("wrong key".into())), Box::new(|_, bytes| Ok(bytes))));
+        let result = CommandDispatcher::new().execute(&mut buffer, EditorAction::OpenFile, CommandParams::FilePath(path.clone()));
+        let _ = std::fs::remove_file(&path);
+        match result {
+            Err(CommandError::FileError(FileError::DecodeFailed { message, .. })) => assert_eq!(message, "wrong key"),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
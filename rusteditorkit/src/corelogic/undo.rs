@@ -3,6 +3,10 @@
 //! This module contains all undo/redo stack management and state operations.
 
 use super::buffer::{EditorBuffer, EditorCursor};
+use super::cursor::PreferredColumn;
+
+/// Maximum number of undo states kept, older states are dropped
+pub const MAX_UNDO_STACK_SIZE: usize = 100;
 
 /// State for undo/redo selection and cursor
 #[derive(Clone, Debug)]
@@ -10,21 +14,31 @@ pub struct BufferState {
     pub lines: Vec<String>,
     pub selection: Option<crate::corelogic::selection::Selection>,
     pub cursor: EditorCursor,
+    /// Column Up/Down were aiming for when the state was saved
+    pub preferred_col: Option<PreferredColumn>,
 }
 
 impl EditorBuffer {
     /// Push current buffer state to undo stack and clear redo stack
+    ///
+    /// Called before every edit, so it also drops the preferred column: the next Up/Down
+    /// starts from where the edit leaves the caret.
     pub fn push_undo(&mut self) {
+        let preferred_col = self.preferred_col.take();
+        // Log buffers and snapshot views keep no history
+        if !self.allows_edit() {
+            return;
+        }
         let state = BufferState {
             lines: self.lines.clone(),
             selection: self.selection.clone(),
             cursor: self.cursor,
+            preferred_col,
         };
         self.undo_stack.push(state);
         self.redo_stack.clear();
         
         // Limit undo stack size to prevent memory issues
-        const MAX_UNDO_STACK_SIZE: usize = 100;
         if self.undo_stack.len() > MAX_UNDO_STACK_SIZE {
             self.undo_stack.remove(0);
         }
@@ -32,17 +46,22 @@ impl EditorBuffer {
 
     /// Undo last buffer state
     pub fn undo(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
         if let Some(prev) = self.undo_stack.pop() {
             let current_state = BufferState {
                 lines: self.lines.clone(),
                 selection: self.selection.clone(),
                 cursor: self.cursor,
+                preferred_col: self.preferred_col,
             };
             self.redo_stack.push(current_state);
             
-            self.lines = prev.lines;
+            self.set_lines(prev.lines);
             self.selection = prev.selection;
             self.cursor = prev.cursor;
+            self.preferred_col = prev.preferred_col;
             
             println!("[DEBUG] Undo applied - cursor: {:?}", self.cursor);
         }
@@ -50,17 +69,22 @@ impl EditorBuffer {
 
     /// Redo last buffer state
     pub fn redo(&mut self) {
+        if !self.allows_edit() {
+            return;
+        }
         if let Some(next) = self.redo_stack.pop() {
             let current_state = BufferState {
                 lines: self.lines.clone(),
                 selection: self.selection.clone(),
                 cursor: self.cursor,
+                preferred_col: self.preferred_col,
             };
             self.undo_stack.push(current_state);
             
-            self.lines = next.lines;
+            self.set_lines(next.lines);
             self.selection = next.selection;
             self.cursor = next.cursor;
+            self.preferred_col = next.preferred_col;
             
             println!("[DEBUG] Redo applied - cursor: {:?}", self.cursor);
         }
@@ -103,3 +127,55 @@ impl EditorBuffer {
         self.redo_stack.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer() -> EditorBuffer {
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = ["let total = compute();", "}", "", "println!(\"{total}\");"].iter().map(|s| s.to_string()).collect();
+        buffer.cursor = EditorCursor::new(0, 12);
+        buffer
+    }
+
+    #[test]
+    fn undo_restores_the_preferred_column() {
+        let mut buffer = buffer();
+        buffer.move_down();
+        assert_eq!(buffer.cursor, EditorCursor::new(1, 1));
+        buffer.insert_text(";");
+        buffer.undo();
+        // Back where Down left the caret, still aiming for column 12
+        buffer.move_down();
+        buffer.move_down();
+        assert_eq!(buffer.cursor, EditorCursor::new(3, 12));
+
+        // Redo brings back the caret after the edit, which had no preferred column
+        buffer.move_up();
+        buffer.move_up();
+        buffer.redo();
+        assert_eq!(buffer.cursor, EditorCursor::new(1, 2));
+        buffer.move_down();
+        buffer.move_down();
+        assert_eq!(buffer.cursor, EditorCursor::new(3, 2));
+    }
+
+    #[test]
+    fn edits_and_horizontal_moves_reset_the_preferred_column() {
+        let mut buffer = buffer();
+        buffer.move_down();
+        buffer.insert_text(";");
+        buffer.move_down();
+        assert_eq!(buffer.cursor, EditorCursor::new(2, 0));
+        buffer.move_down();
+        assert_eq!(buffer.cursor, EditorCursor::new(3, 2));
+
+        // Down lands at the end of "};"; Left then drops the aim for column 12
+        buffer.cursor = EditorCursor::new(0, 12);
+        buffer.move_down();
+        buffer.move_left();
+        buffer.move_up();
+        assert_eq!(buffer.cursor, EditorCursor::new(0, 1));
+    }
+}
@@ -0,0 +1,72 @@
+//! Experimental vertical writing mode for EditorBuffer (feature `vertical-text`)
+//!
+//! Each buffer line becomes a column running top-to-bottom, and columns advance from
+//! right to left as in traditional CJK typesetting. `scroll_offset` is the first visible
+//! column. Arrow keys are remapped so they move the caret the way they point on screen.
+
+use super::buffer::EditorBuffer;
+use crate::keybinds::EditorAction;
+
+impl EditorBuffer {
+    /// True if the buffer is laid out in vertical columns
+    pub fn is_vertical_mode(&self) -> bool {
+        self.vertical_mode
+    }
+
+    /// Switch vertical writing on or off
+    pub fn set_vertical_mode(&mut self, vertical: bool) {
+        self.vertical_mode = vertical;
+        self.scroll_offset = self.scroll_offset.min(self.lines.len().saturating_sub(1));
+        self.request_redraw();
+    }
+
+    /// Map arrow keys to their on-screen meaning in vertical mode
+    ///
+    /// Down/Up move along the column (next/previous character); Left moves to the next
+    /// line (the column to the left) and Right to the previous one.
+    pub fn vertical_action(&self, action: EditorAction) -> EditorAction {
+        if !self.vertical_mode {
+            return action;
+        }
+        match action {
+            EditorAction::MoveCursorDown => EditorAction::MoveCursorRight,
+            EditorAction::MoveCursorUp => EditorAction::MoveCursorLeft,
+            EditorAction::MoveCursorLeft => EditorAction::MoveCursorDown,
+            EditorAction::MoveCursorRight => EditorAction::MoveCursorUp,
+            EditorAction::SelectDown => EditorAction::SelectRight,
+            EditorAction::SelectUp => EditorAction::SelectLeft,
+            EditorAction::SelectLeft => EditorAction::SelectDown,
+            EditorAction::SelectRight => EditorAction::SelectUp,
+            other => other,
+        }
+    }
+
+    /// Buffer position under `(x, y)` in vertical mode
+    ///
+    /// `column_width` is the line height and `char_advance` the vertical advance of one
+    /// character; `right` is the x position of the first visible column's right edge.
+    pub fn vertical_screen_to_buffer_position(&self, x: f64, y: f64, column_width: f64, char_advance: f64, right: f64, top: f64) -> (usize, usize) {
+        let row = ((right - x) / column_width).max(0.0) as usize + self.scroll_offset;
+        let row = row.min(self.lines.len().saturating_sub(1));
+        let col = ((y - top) / char_advance).max(0.0) as usize;
+        let col = col.min(self.lines.get(row).map_or(0, |line| line.chars().count()));
+        (row, col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrows_follow_the_columns() {
+        let mut buf = EditorBuffer::new();
+        buf.lines = vec!["縦書き".into(), "日本語".into()];
+        assert_eq!(buf.vertical_action(EditorAction::MoveCursorDown), EditorAction::MoveCursorDown);
+        buf.set_vertical_mode(true);
+        assert_eq!(buf.vertical_action(EditorAction::MoveCursorDown), EditorAction::MoveCursorRight);
+        assert_eq!(buf.vertical_action(EditorAction::MoveCursorLeft), EditorAction::MoveCursorDown);
+        // Second column from the right, third character down
+        assert_eq!(buf.vertical_screen_to_buffer_position(150.0, 45.0, 20.0, 16.0, 180.0, 5.0), (1, 2));
+    }
+}
@@ -0,0 +1,120 @@
+//! Viewport scrolling for EditorBuffer
+//!
+//! `scroll_offset` is the first visible row, counted in displayed rows when folds are
+//! collapsed (see `folding`). The draw function records how many rows fit in the widget
+//! (`viewport_rows`), and `ensure_cursor_visible` scrolls after each command: minimally
+//! in normal mode, or keeping the caret line centered in typewriter mode. With
+//! `config.scroll_margin` (vim's `scrolloff`) that many rows of context stay visible
+//! above and below the caret, except at the start and end of the buffer.
+
+use super::buffer::EditorBuffer;
+
+/// Rows assumed per page before the first draw
+const DEFAULT_PAGE_ROWS: usize = 25;
+
+/// Scroll offset keeping display `row` visible with `margin` rows around it
+///
+/// `rows` is the viewport height and `last` the last display row. The margin shrinks in
+/// viewports too small to honour it on both sides.
+pub fn scroll_offset_for_row(offset: usize, row: usize, rows: usize, margin: usize, last: usize) -> usize {
+    let margin = margin.min(rows.saturating_sub(1) / 2);
+    let top = row.saturating_sub(margin);
+    let bottom = (row + margin).min(last);
+    if top < offset {
+        top
+    } else if bottom >= offset + rows {
+        bottom + 1 - rows
+    } else {
+        offset
+    }
+}
+
+impl EditorBuffer {
+    /// Number of rows visible in the widget (0 until the first draw)
+    pub fn viewport_rows(&self) -> usize {
+        self.viewport_rows.get()
+    }
+
+    /// Record the number of visible rows (called from the draw function)
+    pub fn set_viewport_rows(&self, rows: usize) {
+        self.viewport_rows.set(rows);
+    }
+
+    /// Line height in pixels of the last frame, or the configured estimate before the first draw
+    pub fn viewport_line_height(&self) -> f64 {
+        match self.viewport_line_height.get() {
+            h if h > 0.0 => h,
+            _ => self.unified_line_height() * self.zoom,
+        }
+    }
+
+    /// Record the measured line height (called from the draw function)
+    pub fn set_viewport_line_height(&self, line_height: f64) {
+        self.viewport_line_height.set(line_height);
+    }
+
+    /// Record where the text area ends, used to hit-test right-to-left rows
+    pub fn set_viewport_text_right(&self, x: f64) {
+        self.viewport_text_right.set(x);
+    }
+
+    /// Record the left edge of the text area and the advance of one cell (called from
+    /// the draw function)
+    pub fn set_viewport_text_geometry(&self, text_left: f64, char_width: f64) {
+        self.viewport_text_geometry.set(Some((text_left, char_width)));
+    }
+
+    /// Record the average advance of the gutter font (called from the draw function)
+    pub fn set_viewport_gutter_char_width(&self, char_width: f64) {
+        self.viewport_gutter_char_width.set(char_width);
+    }
+
+    /// Scroll so the cursor row is visible (centered when typewriter scrolling is on)
+    pub fn ensure_cursor_visible(&mut self) {
+        let rows = self.viewport_rows();
+        // A running animation is already bringing the target into view
+        if rows == 0 || self.is_smooth_scrolling() {
+            return;
+        }
+        let row = self.display_row(self.cursor.row.min(self.lines.len().saturating_sub(1)));
+        if self.config.typewriter_scrolling {
+            self.scroll_offset = row.saturating_sub(rows / 2);
+        } else {
+            let last = self.display_row_count().saturating_sub(1);
+            self.scroll_offset = scroll_offset_for_row(self.scroll_offset, row, rows, self.config.scroll_margin, last);
+        }
+    }
+
+    /// Rows moved by Page Up/Down: the viewport height less the scroll margin, so the
+    /// margin rows stay on screen as context
+    pub fn page_rows(&self) -> usize {
+        match self.viewport_rows() {
+            0 => DEFAULT_PAGE_ROWS,
+            rows => rows.saturating_sub(self.config.scroll_margin.min(rows.saturating_sub(1) / 2)).max(1),
+        }
+    }
+
+    /// Scroll by `delta` rows without moving the cursor, clamped to the buffer
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.display_row_count().saturating_sub(1);
+        self.scroll_offset = self.scroll_offset.saturating_add_signed(delta).min(max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn margin_keeps_context_rows() {
+        // 10 visible rows, margin 3, 100 rows in the buffer
+        assert_eq!(scroll_offset_for_row(0, 7, 10, 3, 99), 1);
+        assert_eq!(scroll_offset_for_row(20, 22, 10, 3, 99), 19);
+        assert_eq!(scroll_offset_for_row(20, 25, 10, 3, 99), 20);
+        // No margin past the buffer ends
+        assert_eq!(scroll_offset_for_row(90, 99, 10, 3, 99), 90);
+        assert_eq!(scroll_offset_for_row(5, 1, 10, 3, 99), 0);
+        // A margin too large for the viewport is reduced to half of it
+        assert_eq!(scroll_offset_for_row(0, 10, 4, 5, 99), 8);
+    }
+}
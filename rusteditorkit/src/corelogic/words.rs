@@ -0,0 +1,217 @@
+//! Word boundaries for EditorBuffer
+//!
+//! A word is a run of letters, digits and the buffer's extra word characters: `_` by
+//! default, overridden per language in `config.language_word_chars` (e.g. `-` for CSS
+//! and Lisp, `$` for shell and JavaScript). Double-click selection, Ctrl+arrow movement,
+//! word deletion and the occurrence highlighter all use the same rule.
+
+use super::buffer::EditorBuffer;
+use super::selection::Selection;
+
+/// True if `c` belongs to a word, given the extra word characters
+pub fn is_word_char(c: char, extra: &str) -> bool {
+    c.is_alphanumeric() || extra.contains(c)
+}
+
+/// Column range of the word containing (or starting at) `col`
+pub fn word_range(chars: &[char], col: usize, extra: &str) -> Option<(usize, usize)> {
+    if !chars.get(col).is_some_and(|&c| is_word_char(c, extra)) {
+        return None;
+    }
+    let start = chars[..col].iter().rposition(|&c| !is_word_char(c, extra)).map_or(0, |i| i + 1);
+    let end = chars[col..].iter().position(|&c| !is_word_char(c, extra)).map_or(chars.len(), |i| col + i);
+    Some((start, end))
+}
+
+/// Column after the next word (or run of punctuation) right of `col`, skipping whitespace first
+pub fn next_word_boundary(chars: &[char], col: usize, extra: &str) -> usize {
+    let mut col = col;
+    while col < chars.len() && chars[col].is_whitespace() {
+        col += 1;
+    }
+    let in_word = chars.get(col).is_some_and(|&c| is_word_char(c, extra));
+    while col < chars.len() && !chars[col].is_whitespace() && is_word_char(chars[col], extra) == in_word {
+        col += 1;
+    }
+    col
+}
+
+/// Column where the word (or run of punctuation) left of `col` starts, skipping whitespace first
+pub fn prev_word_boundary(chars: &[char], col: usize, extra: &str) -> usize {
+    let mut col = col.min(chars.len());
+    while col > 0 && chars[col - 1].is_whitespace() {
+        col -= 1;
+    }
+    let in_word = col > 0 && is_word_char(chars[col - 1], extra);
+    while col > 0 && !chars[col - 1].is_whitespace() && is_word_char(chars[col - 1], extra) == in_word {
+        col -= 1;
+    }
+    col
+}
+
+impl EditorBuffer {
+    /// Characters besides letters and digits that belong to words in this buffer's language
+    pub fn word_chars(&self) -> &str {
+        self.language.as_ref()
+            .and_then(|lang| self.config.language_word_chars.get(lang))
+            .unwrap_or(&self.config.word_chars)
+    }
+
+    /// Column range of the word at (row, col)
+    pub fn word_range_at(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let chars: Vec<char> = self.lines.get(row)?.chars().collect();
+        word_range(&chars, col, self.word_chars())
+    }
+
+    /// Position one word left of the cursor (the end of the previous line at column 0)
+    fn word_left_position(&self) -> (usize, usize) {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        if col == 0 {
+            return match row.checked_sub(1) {
+                Some(prev) => (prev, self.lines[prev].chars().count()),
+                None => (0, 0),
+            };
+        }
+        let chars: Vec<char> = self.lines[row].chars().collect();
+        (row, prev_word_boundary(&chars, col, self.word_chars()))
+    }
+
+    /// Position one word right of the cursor (the start of the next line at the line end)
+    fn word_right_position(&self) -> (usize, usize) {
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let chars: Vec<char> = self.lines[row].chars().collect();
+        if col >= chars.len() {
+            return if row + 1 < self.lines.len() { (row + 1, 0) } else { (row, chars.len()) };
+        }
+        (row, next_word_boundary(&chars, col, self.word_chars()))
+    }
+
+    /// Move the cursor to the start of the previous word (Ctrl+Left)
+    pub fn move_word_left(&mut self) {
+        self.clear_selection();
+        (self.cursor.row, self.cursor.col) = self.word_left_position();
+    }
+
+    /// Move the cursor past the end of the next word (Ctrl+Right)
+    pub fn move_word_right(&mut self) {
+        self.clear_selection();
+        (self.cursor.row, self.cursor.col) = self.word_right_position();
+    }
+
+    /// Start or extend the selection one word to the left
+    pub fn select_word_left(&mut self) {
+        let target = self.word_left_position();
+        self.extend_selection_to(target);
+    }
+
+    /// Start or extend the selection one word to the right
+    pub fn select_word_right(&mut self) {
+        let target = self.word_right_position();
+        self.extend_selection_to(target);
+    }
+
+    /// Move the cursor to `(row, col)`, moving the selection end along
+    fn extend_selection_to(&mut self, (row, col): (usize, usize)) {
+        let anchor = self.selection.as_ref().map_or((self.cursor.row, self.cursor.col), |sel| (sel.start_row, sel.start_col));
+        self.cursor.row = row;
+        self.cursor.col = col;
+        self.selection = (anchor != (row, col)).then(|| {
+            let mut sel = Selection::new(anchor.0, anchor.1);
+            sel.set(anchor.0, anchor.1, row, col);
+            sel
+        });
+    }
+
+    /// Delete from the cursor back to the start of the previous word (Ctrl+Backspace)
+    pub fn delete_word_left(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let target = self.word_left_position();
+        self.delete_to(target);
+    }
+
+    /// Delete from the cursor to the end of the next word (Ctrl+Delete)
+    pub fn delete_word_right(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let target = self.word_right_position();
+        self.delete_to(target);
+    }
+
+    /// Delete the text between the cursor and `target` as one undo step
+    fn delete_to(&mut self, target: (usize, usize)) {
+        let cursor = (self.cursor.row, self.cursor.col);
+        if target == cursor {
+            return;
+        }
+        let mut sel = Selection::new(cursor.0, cursor.1);
+        sel.set(cursor.0, cursor.1, target.0, target.1);
+        self.selection = Some(sel);
+        self.delete_selection();
+    }
+
+    /// Whole-word occurrences of the word under the cursor in `rows`, as (row, start col, end col)
+    ///
+    /// Empty while a selection exists or the cursor is not on a word.
+    pub fn word_occurrences(&self, rows: impl IntoIterator<Item = usize>) -> Vec<(usize, usize, usize)> {
+        if self.selection.is_some() {
+            return Vec::new();
+        }
+        let extra = self.word_chars();
+        let Some(line) = self.lines.get(self.cursor.row) else {
+            return Vec::new();
+        };
+        let chars: Vec<char> = line.chars().collect();
+        // The caret right after a word still counts as on it
+        let range = word_range(&chars, self.cursor.col, extra)
+            .or_else(|| self.cursor.col.checked_sub(1).and_then(|col| word_range(&chars, col, extra)));
+        let Some((start, end)) = range else {
+            return Vec::new();
+        };
+        let word = &chars[start..end];
+        let mut found = Vec::new();
+        for row in rows {
+            let Some(line) = self.lines.get(row) else { continue };
+            let chars: Vec<char> = line.chars().collect();
+            let mut col = 0;
+            while col + word.len() <= chars.len() {
+                let bounded = (col == 0 || !is_word_char(chars[col - 1], extra))
+                    && chars.get(col + word.len()).is_none_or(|&c| !is_word_char(c, extra));
+                if bounded && chars[col..col + word.len()] == *word {
+                    found.push((row, col, col + word.len()));
+                    col += word.len();
+                } else {
+                    col += 1;
+                }
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn extra_word_chars_change_boundaries() {
+        let css = chars("  margin-top: 4px;");
+        assert_eq!(word_range(&css, 5, "_"), Some((2, 8)));
+        assert_eq!(word_range(&css, 5, "_-"), Some((2, 12)));
+        assert_eq!(word_range(&css, 12, "_-"), None);
+        assert_eq!(next_word_boundary(&css, 0, "_-"), 12);
+        assert_eq!(next_word_boundary(&css, 12, "_-"), 13);
+        assert_eq!(prev_word_boundary(&css, 12, "_"), 9);
+
+        let shell = chars("echo $HOME_DIR");
+        assert_eq!(word_range(&shell, 7, "_$"), Some((5, 14)));
+        assert_eq!(prev_word_boundary(&shell, 14, "_"), 6);
+        assert_eq!(prev_word_boundary(&shell, 14, "_$"), 5);
+    }
+}
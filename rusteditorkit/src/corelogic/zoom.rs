@@ -0,0 +1,61 @@
+//! Runtime text zoom for EditorBuffer
+//!
+//! The zoom factor multiplies the configured font sizes when rendering. It belongs to
+//! the view, not the config: `config.font.font_size` is never modified, so saving the
+//! config does not persist a temporary zoom.
+
+use super::buffer::EditorBuffer;
+use super::events::EditorEvent;
+
+/// Smallest and largest zoom factors
+pub const ZOOM_MIN: f64 = 0.5;
+pub const ZOOM_MAX: f64 = 3.0;
+/// Step used by ZoomIn/ZoomOut and Ctrl+scroll
+pub const ZOOM_STEP: f64 = 0.1;
+
+impl EditorBuffer {
+    /// Current zoom factor (1.0 = configured size)
+    pub fn zoom_level(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Zoom as a percentage label for status bars, e.g. "120%"
+    pub fn zoom_label(&self) -> String {
+        format!("{:.0}%", self.zoom * 100.0)
+    }
+
+    /// Set the zoom factor (clamped), publishing `EditorEvent::ZoomChanged` when it changes
+    pub fn set_zoom(&mut self, zoom: f64) {
+        // Round to whole percent so repeated steps do not accumulate float error
+        let zoom = (zoom.clamp(ZOOM_MIN, ZOOM_MAX) * 100.0).round() / 100.0;
+        if zoom == self.zoom {
+            return;
+        }
+        self.zoom = zoom;
+        self.events.emit(&EditorEvent::ZoomChanged(zoom));
+        self.request_redraw();
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.set_zoom(self.zoom + ZOOM_STEP);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.set_zoom(self.zoom - ZOOM_STEP);
+    }
+
+    /// Back to the configured font size
+    pub fn reset_zoom(&mut self) {
+        self.set_zoom(1.0);
+    }
+
+    /// Text font size after zoom, used by the render pipeline
+    pub fn zoomed_font_size(&self) -> f64 {
+        self.config.font.font_size() * self.zoom
+    }
+
+    /// Gutter font size after zoom
+    pub fn zoomed_gutter_font_size(&self) -> f64 {
+        self.config.gutter.font_size.max(8) as f64 * self.zoom
+    }
+}
@@ -133,6 +133,7 @@ pub fn find_font(name: &str) -> Option<FontHandle> {
 }
 
 /// Open a font file using font-kit for cross-platform support
+#[cfg(feature = "gtk")]
 pub fn open_font(path: &str) -> Result<FontHandle, String> {
     use font_kit::sources::fs::FsSource;
     use font_kit::handle::Handle;
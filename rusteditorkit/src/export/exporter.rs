@@ -0,0 +1,30 @@
+//! Pluggable exporters for styled documents
+//!
+//! An `Exporter` turns a `StyledDocument` into file bytes. Hosts can implement the trait
+//! for additional formats and use `EditorBuffer::export_with` to write them.
+
+use crate::corelogic::EditorBuffer;
+use super::styled::{ExportScope, StyledDocument};
+
+/// A document format the editor can export to
+pub trait Exporter {
+    /// Human readable format name, e.g. "Rich Text Format"
+    fn name(&self) -> &str;
+    /// File extension without the leading dot, e.g. "rtf"
+    fn extension(&self) -> &str;
+    /// Serialize the styled document
+    fn export(&self, doc: &StyledDocument) -> Result<Vec<u8>, String>;
+}
+
+impl EditorBuffer {
+    /// Export the buffer or selection with `exporter` and write the result to `path`
+    pub fn export_with(&self, exporter: &dyn Exporter, scope: ExportScope, path: &str) -> Result<(), String> {
+        let doc = self.styled_document(scope);
+        let bytes = exporter.export(&doc)?;
+        std::fs::write(path, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        if self.debug_mode {
+            println!("[DEBUG] Exported {}: {} ({} lines)", exporter.name(), path, doc.lines.len());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+//! Header and footer fields for exported/printed pages
+//!
+//! Each header/footer has left, center and right fields. Fields may contain tokens:
+//! `%filename`, `%path`, `%page`, `%pages`, `%date`, `%time` and `%%` for a literal percent.
+
+use serde::Deserialize;
+
+/// Left/center/right fields of a page header or footer
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HeaderFooterConfig {
+    pub toggle: bool,
+    pub left: String,
+    pub center: String,
+    pub right: String,
+}
+
+impl HeaderFooterConfig {
+    /// True if the header/footer is enabled and has at least one non-empty field
+    pub fn is_visible(&self) -> bool {
+        self.toggle && !(self.left.is_empty() && self.center.is_empty() && self.right.is_empty())
+    }
+}
+
+/// Values substituted for header/footer tokens on a given page
+#[derive(Debug, Clone, Default)]
+pub struct PageTokens {
+    pub filename: String,
+    pub path: String,
+    /// 1-based page number
+    pub page: usize,
+    pub pages: usize,
+    pub date: String,
+    pub time: String,
+}
+
+impl PageTokens {
+    /// Build tokens for a document, filling date and time from the local clock
+    #[cfg(feature = "gtk")]
+    pub fn new(path: Option<&str>, pages: usize, date_format: &str) -> Self {
+        let filename = path
+            .and_then(|p| std::path::Path::new(p).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_string());
+        let now = glib::DateTime::now_local().ok();
+        let format_now = |fmt: &str| {
+            now.as_ref()
+                .and_then(|dt| dt.format(fmt).ok())
+                .map(|s| s.to_string())
+                .unwrap_or_default()
+        };
+        Self {
+            filename,
+            path: path.unwrap_or_default().to_string(),
+            page: 1,
+            pages,
+            date: format_now(date_format),
+            time: format_now("%H:%M"),
+        }
+    }
+
+    /// Tokens for a specific page (1-based)
+    pub fn for_page(&self, page: usize) -> Self {
+        Self { page, ..self.clone() }
+    }
+
+    /// Replace all tokens in `template`; unknown tokens are kept verbatim
+    pub fn expand(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(idx) = rest.find('%') {
+            out.push_str(&rest[..idx]);
+            let tail = &rest[idx + 1..];
+            let tokens: [(&str, String); 7] = [
+                ("%", "%".to_string()),
+                ("filename", self.filename.clone()),
+                ("pages", self.pages.to_string()),
+                ("page", self.page.to_string()),
+                ("path", self.path.clone()),
+                ("date", self.date.clone()),
+                ("time", self.time.clone()),
+            ];
+            match tokens.iter().find(|(name, _)| tail.starts_with(name)) {
+                Some((name, value)) => {
+                    out.push_str(value);
+                    rest = &tail[name.len()..];
+                }
+                None => {
+                    out.push('%');
+                    rest = tail;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_expand_and_unknown_ones_stay() {
+        let tokens = PageTokens {
+            filename: "notes.md".to_string(),
+            path: "/tmp/notes.md".to_string(),
+            page: 3,
+            pages: 12,
+            date: "2025-01-01".to_string(),
+            time: "12:00".to_string(),
+        };
+        assert_eq!(tokens.expand("Page %page of %pages"), "Page 3 of 12");
+        assert_eq!(tokens.expand("%filename - %date"), "notes.md - 2025-01-01");
+        assert_eq!(tokens.expand("100%% %unknown"), "100% %unknown");
+    }
+}
@@ -0,0 +1,66 @@
+//! HTML exporter with syntax highlight colors and the editor font
+
+use super::exporter::Exporter;
+use super::styled::StyledDocument;
+
+/// Exports styled documents as a standalone HTML page
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmlExporter;
+
+/// Escape HTML special characters
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render the document as a `<pre>` fragment with inline styles
+///
+/// Inline styles (rather than a stylesheet) survive pasting into rich-text targets.
+pub fn html_fragment(doc: &StyledDocument) -> String {
+    let (br, bg, bb) = doc.background;
+    let mut out = format!(
+        "<pre style=\"font-family: '{}', monospace; font-size: {}pt; background-color: #{:02x}{:02x}{:02x};\">",
+        escape_html(&doc.font_name), doc.font_size, br, bg, bb
+    );
+    for (i, spans) in doc.lines.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for span in spans {
+            let (r, g, b) = span.color;
+            let mut style = format!("color: #{:02x}{:02x}{:02x};", r, g, b);
+            if span.bold {
+                style.push_str(" font-weight: bold;");
+            }
+            if span.italic {
+                style.push_str(" font-style: italic;");
+            }
+            if span.underline {
+                style.push_str(" text-decoration: underline;");
+            }
+            out.push_str(&format!("<span style=\"{}\">{}</span>", style, escape_html(&span.text)));
+        }
+    }
+    out.push_str("</pre>");
+    out
+}
+
+impl Exporter for HtmlExporter {
+    fn name(&self) -> &str {
+        "HTML"
+    }
+
+    fn extension(&self) -> &str {
+        "html"
+    }
+
+    fn export(&self, doc: &StyledDocument) -> Result<Vec<u8>, String> {
+        let html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"></head>\n<body>\n{}\n</body>\n</html>\n",
+            html_fragment(doc)
+        );
+        Ok(html.into_bytes())
+    }
+}
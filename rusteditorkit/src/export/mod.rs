@@ -0,0 +1,70 @@
+//! Export and print subsystem for RustEditorKit
+//!
+//! - `page`: headless pagination and page rendering onto any cairo context
+//! - `header_footer`: header/footer fields and token expansion (%filename, %page, ...)
+//! - `pdf`: PDF export built on the page renderer
+//! - `print`: GtkPrintOperation integration (print dialog and preview)
+//! - `styled`, `exporter`: syntax-highlighted document model and the pluggable `Exporter` trait
+//! - `html`, `rtf`, `odt`: rich text exporters for pasting styled code into word processors
+
+pub mod header_footer;
+#[cfg(feature = "gtk")]
+pub mod page;
+#[cfg(feature = "gtk")]
+pub mod pdf;
+#[cfg(feature = "gtk")]
+pub mod print;
+pub mod styled;
+pub mod exporter;
+pub mod html;
+pub mod rtf;
+pub mod odt;
+
+use serde::Deserialize;
+use crate::corelogic::color::{Color, ThemeColor};
+pub use header_footer::{HeaderFooterConfig, PageTokens};
+#[cfg(feature = "gtk")]
+pub use page::{PageLayout, PageLine, render_page};
+#[cfg(feature = "gtk")]
+pub use pdf::export_pdf;
+#[cfg(feature = "gtk")]
+pub use print::{build_print_operation, render_print_page};
+pub use styled::{ExportScope, StyledDocument, StyledSpan};
+pub use exporter::Exporter;
+pub use html::{html_fragment, HtmlExporter};
+pub use rtf::RtfExporter;
+pub use odt::OdtExporter;
+
+/// Configuration for exported and printed pages
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportConfig {
+    pub header: HeaderFooterConfig,
+    pub footer: HeaderFooterConfig,
+    /// Font size (points) used for header and footer text
+    pub header_footer_font_size: f64,
+    pub header_footer_color: ThemeColor,
+    /// strftime-style format used for the %date token
+    pub date_format: String,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            header: HeaderFooterConfig {
+                toggle: true,
+                left: "%filename".to_string(),
+                center: String::new(),
+                right: "%date".to_string(),
+            },
+            footer: HeaderFooterConfig {
+                toggle: true,
+                left: String::new(),
+                center: "Page %page of %pages".to_string(),
+                right: String::new(),
+            },
+            header_footer_font_size: 9.0,
+            header_footer_color: Color::hex(0x555555).into(),
+            date_format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! ODT (OpenDocument Text) exporter with syntax highlight colors
+//!
+//! Writes a minimal ODF package: an uncompressed zip holding `mimetype`, `content.xml`,
+//! `styles.xml` and the manifest. Each distinct span style becomes an automatic text style.
+
+use super::exporter::Exporter;
+use super::styled::{StyledDocument, StyledSpan};
+
+/// Exports styled documents as OpenDocument Text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OdtExporter;
+
+/// Escape XML special characters
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Encode span text, preserving runs of spaces and tabs the way ODF requires
+///
+/// ODF readers drop a literal space at the start of a paragraph or after another space,
+/// even across spans, so spaces starting a span are all written as `<text:s/>`.
+fn encode_odf_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut spaces = 0;
+    let flush = |out: &mut String, spaces: &mut usize| {
+        match *spaces {
+            0 => {}
+            n if out.is_empty() => out.push_str(&space_element(n)),
+            1 => out.push(' '),
+            n => {
+                out.push(' ');
+                out.push_str(&space_element(n - 1));
+            }
+        }
+        *spaces = 0;
+    };
+    for c in text.chars() {
+        match c {
+            ' ' => spaces += 1,
+            '\t' => {
+                flush(&mut out, &mut spaces);
+                out.push_str("<text:tab/>");
+            }
+            c => {
+                flush(&mut out, &mut spaces);
+                out.push_str(&escape_xml(&c.to_string()));
+            }
+        }
+    }
+    flush(&mut out, &mut spaces);
+    out
+}
+
+/// `<text:s/>` standing for `count` spaces
+fn space_element(count: usize) -> String {
+    if count == 1 {
+        "<text:s/>".to_string()
+    } else {
+        format!("<text:s text:c=\"{}\"/>", count)
+    }
+}
+
+/// Style key for a span (color and font flags)
+fn style_key(span: &StyledSpan) -> ((u8, u8, u8), bool, bool, bool) {
+    (span.color, span.bold, span.italic, span.underline)
+}
+
+/// Minimal writer for an uncompressed ("stored") zip archive
+struct StoredZip {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl StoredZip {
+    fn new() -> Self {
+        Self { data: Vec::new(), central: Vec::new(), entries: 0 }
+    }
+
+    fn add(&mut self, name: &str, contents: &[u8]) {
+        let crc = crc32fast::hash(contents);
+        let offset = self.data.len() as u32;
+        let size = contents.len() as u32;
+        let name_len = name.len() as u16;
+        // Fixed DOS date 1980-01-01 00:00
+        let (time, date) = (0u16, 0x21u16);
+
+        self.data.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        for value in [20u16, 0, 0, time, date] {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [crc, size, size] {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [name_len, 0] {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(contents);
+
+        self.central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        for value in [20u16, 20, 0, 0, time, date] {
+            self.central.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [crc, size, size] {
+            self.central.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [name_len, 0, 0, 0, 0] {
+            self.central.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [0u32, offset] {
+            self.central.extend_from_slice(&value.to_le_bytes());
+        }
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.data.extend_from_slice(&self.central);
+        self.data.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        for value in [0u16, 0, self.entries, self.entries] {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [central_size, central_offset] {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data
+    }
+}
+
+const MANIFEST_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">
+ <manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.text"/>
+ <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+ <manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="text/xml"/>
+</manifest:manifest>
+"#;
+
+const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-styles xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" office:version="1.2"/>
+"#;
+
+impl OdtExporter {
+    fn content_xml(&self, doc: &StyledDocument) -> String {
+        let mut styles = Vec::new();
+        for span in doc.lines.iter().flatten() {
+            let key = style_key(span);
+            if !styles.contains(&key) {
+                styles.push(key);
+            }
+        }
+        let font = escape_xml(&doc.font_name);
+        let (br, bg, bb) = doc.background;
+
+        let mut out = String::new();
+        out.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" office:version="1.2">
+<office:font-face-decls>"#);
+        out.push_str(&format!("<style:font-face style:name=\"{0}\" svg:font-family=\"'{0}'\" style:font-pitch=\"fixed\" xmlns:svg=\"urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0\"/>", font));
+        out.push_str("</office:font-face-decls>\n<office:automatic-styles>");
+        out.push_str(&format!(
+            "<style:style style:name=\"P1\" style:family=\"paragraph\"><style:paragraph-properties fo:background-color=\"#{:02x}{:02x}{:02x}\" fo:margin-top=\"0cm\" fo:margin-bottom=\"0cm\"/><style:text-properties style:font-name=\"{}\" fo:font-size=\"{}pt\"/></style:style>",
+            br, bg, bb, font, doc.font_size
+        ));
+        for (i, ((r, g, b), bold, italic, underline)) in styles.iter().enumerate() {
+            out.push_str(&format!("<style:style style:name=\"T{}\" style:family=\"text\"><style:text-properties fo:color=\"#{:02x}{:02x}{:02x}\"", i + 1, r, g, b));
+            if *bold {
+                out.push_str(" fo:font-weight=\"bold\"");
+            }
+            if *italic {
+                out.push_str(" fo:font-style=\"italic\"");
+            }
+            if *underline {
+                out.push_str(" style:text-underline-style=\"solid\"");
+            }
+            out.push_str("/></style:style>");
+        }
+        out.push_str("</office:automatic-styles>\n<office:body><office:text>\n");
+        for spans in &doc.lines {
+            out.push_str("<text:p text:style-name=\"P1\">");
+            for span in spans {
+                let index = styles.iter().position(|key| *key == style_key(span)).unwrap_or(0) + 1;
+                out.push_str(&format!("<text:span text:style-name=\"T{}\">{}</text:span>", index, encode_odf_text(&span.text)));
+            }
+            out.push_str("</text:p>\n");
+        }
+        out.push_str("</office:text></office:body>\n</office:document-content>\n");
+        out
+    }
+}
+
+impl Exporter for OdtExporter {
+    fn name(&self) -> &str {
+        "OpenDocument Text"
+    }
+
+    fn extension(&self) -> &str {
+        "odt"
+    }
+
+    fn export(&self, doc: &StyledDocument) -> Result<Vec<u8>, String> {
+        let mut zip = StoredZip::new();
+        // The mimetype entry must come first and be stored uncompressed
+        zip.add("mimetype", b"application/vnd.oasis.opendocument.text");
+        zip.add("content.xml", self.content_xml(doc).as_bytes());
+        zip.add("styles.xml", STYLES_XML.as_bytes());
+        zip.add("META-INF/manifest.xml", MANIFEST_XML.as_bytes());
+        Ok(zip.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_keeps_leading_and_repeated_spaces() {
+        assert_eq!(encode_odf_text("    x"), "<text:s text:c=\"4\"/>x");
+        assert_eq!(encode_odf_text(" a  b\t<c>"), "<text:s/>a <text:s/>b<text:tab/>&lt;c&gt;");
+        assert_eq!(encode_odf_text("a b"), "a b");
+    }
+
+    #[test]
+    fn export_writes_an_odf_package() {
+        let doc = StyledDocument {
+            lines: vec![vec![StyledSpan { text: "  fn".to_string(), color: (255, 0, 0), bold: true, italic: false, underline: false }]],
+            font_name: "Mono".to_string(),
+            font_size: 11.0,
+            background: (0, 0, 0),
+        };
+        let bytes = OdtExporter.export(&doc).unwrap();
+        // The mimetype entry comes first, stored, right after its 30 byte local header
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        assert_eq!(&bytes[30..38], b"mimetype");
+        assert!(bytes.windows(39).any(|w| w == b"application/vnd.oasis.opendocument.text"));
+        let content = OdtExporter.content_xml(&doc);
+        assert!(content.contains("fo:color=\"#ff0000\" fo:font-weight=\"bold\""));
+        assert!(content.contains("<text:span text:style-name=\"T1\"><text:s text:c=\"2\"/>fn</text:span>"));
+    }
+}
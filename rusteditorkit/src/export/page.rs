@@ -0,0 +1,211 @@
+//! Headless page layout and rendering for export and print
+//!
+//! Pages are laid out in 96-DPI pixel units on an A4 sheet using the configured margins.
+//! `render_page` draws onto any cairo context, so the same code backs PDF export and
+//! print preview; callers scale the context to their device units. Text and fills go
+//! through `DrawingBackend`; only images still need cairo.
+
+use gtk4::cairo::Context;
+use gtk4::pango;
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::layout::{cm_to_px, paginate};
+use crate::render::backend::{DrawingBackend, Rgba};
+use crate::render::layout::FontMetrics;
+use super::header_footer::PageTokens;
+
+/// A4 page width in pixels at 96 DPI
+pub const A4_WIDTH_PX: f64 = 21.0 * 96.0 / 2.54;
+/// A4 page height in pixels at 96 DPI
+pub const A4_HEIGHT_PX: f64 = 29.7 * 96.0 / 2.54;
+
+/// One laid out line of a page
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageLine {
+    /// A visual (hard-wrapped) text line
+    Text(String),
+    /// An image scaled to fit the text area, occupying `lines` visual lines
+    Image { path: String, width: f64, height: f64, lines: usize },
+}
+
+/// Buffer content split into printable pages
+#[derive(Debug, Clone)]
+pub struct PageLayout {
+    pub page_width: f64,
+    pub page_height: f64,
+    /// Left, right, top, bottom margins in pixels
+    pub margins: (f64, f64, f64, f64),
+    pub line_height: f64,
+    /// Vertical offset of the text within a line (centers the glyphs in `line_height`)
+    pub text_offset: f64,
+    pub font_desc: pango::FontDescription,
+    /// Visual lines for each page
+    pub pages: Vec<Vec<PageLine>>,
+}
+
+impl PageLayout {
+    /// Measure the buffer font on `ctx` and paginate the buffer content
+    pub fn new(buf: &EditorBuffer, ctx: &Context) -> Self {
+        let font_string = format!("{} {}", buf.config.font.font_name(), buf.config.font.font_size());
+        let font_desc = pango::FontDescription::from_string(&font_string);
+        let metrics = FontMetrics::calculate(ctx, &font_desc);
+        let line_height = metrics.height.max(buf.config.font.font_line_height());
+        let text_offset = (line_height - metrics.height) / 2.0;
+        let page_metrics = buf.page_metrics_with(line_height, metrics.average_char_width);
+
+        let wrapped: Vec<Vec<PageLine>> = buf.lines.iter().enumerate()
+            .map(|(row, line)| match buf.image_size_for_row(row, &page_metrics) {
+                Some((width, height)) => {
+                    let path = crate::corelogic::images::parse_image_markup(line)
+                        .map(|(_, path)| buf.resolve_image_path(&path))
+                        .unwrap_or_default();
+                    let lines = crate::corelogic::images::image_line_count(height, &page_metrics);
+                    vec![PageLine::Image { path, width, height, lines }]
+                }
+                None => wrap_line(line, page_metrics.chars_per_line).into_iter().map(PageLine::Text).collect(),
+            })
+            .collect();
+        let rows: Vec<(usize, bool)> = buf.lines.iter().zip(&wrapped)
+            .map(|(line, parts)| {
+                let height = parts.iter().map(|part| match part {
+                    PageLine::Image { lines, .. } => *lines,
+                    PageLine::Text(_) => 1,
+                }).sum();
+                (height, line.trim().is_empty())
+            })
+            .collect();
+        let breaks = paginate(&rows, page_metrics.lines_per_page, buf.config.widow_lines, buf.config.orphan_lines);
+
+        let mut pages = Vec::with_capacity(breaks.len() + 1);
+        let mut start = 0;
+        for end in breaks.into_iter().chain(std::iter::once(wrapped.len())) {
+            pages.push(wrapped[start..end].iter().flatten().cloned().collect());
+            start = end;
+        }
+
+        Self {
+            page_width: A4_WIDTH_PX,
+            page_height: A4_HEIGHT_PX,
+            margins: (
+                cm_to_px(buf.config.left_margin_cm),
+                cm_to_px(buf.config.right_margin_cm),
+                cm_to_px(buf.config.top_margin_cm),
+                cm_to_px(buf.config.bottom_margin_cm),
+            ),
+            line_height,
+            text_offset,
+            font_desc,
+            pages,
+        }
+    }
+
+    /// Number of pages (at least 1)
+    pub fn page_count(&self) -> usize {
+        self.pages.len().max(1)
+    }
+}
+
+/// Hard-wrap a line into chunks of at most `width` characters
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars.chunks(width.max(1)).map(|chunk| chunk.iter().collect()).collect()
+}
+
+/// Draw a single line of text with its left edge at `x` and its top at `y`
+fn draw_text(backend: &dyn DrawingBackend, text: &str, font_desc: &pango::FontDescription, x: f64, y: f64, color: Rgba) {
+    let run = backend.create_text_run();
+    run.set_font_description(Some(font_desc));
+    run.set_text(text);
+    backend.draw_text_run(&run, x, y, color);
+}
+
+/// Draw the image at `path` scaled into the given box (nothing is drawn if it fails to load)
+fn draw_image(ctx: &Context, path: &str, x: f64, y: f64, width: f64, height: f64) {
+    use gtk4::gdk::prelude::GdkCairoContextExt;
+    let Ok(pixbuf) = gtk4::gdk_pixbuf::Pixbuf::from_file(path) else {
+        eprintln!("[ERROR] Failed to load image '{}'", path);
+        return;
+    };
+    if pixbuf.width() <= 0 || pixbuf.height() <= 0 {
+        return;
+    }
+    let _ = ctx.save();
+    ctx.translate(x, y);
+    ctx.scale(width / pixbuf.width() as f64, height / pixbuf.height() as f64);
+    ctx.set_source_pixbuf(&pixbuf, 0.0, 0.0);
+    let _ = ctx.paint();
+    let _ = ctx.restore();
+}
+
+/// Draw left/center/right fields in the band starting at `y`
+fn draw_fields(backend: &dyn DrawingBackend, layout: &PageLayout, fields: [&str; 3], font_desc: &pango::FontDescription, y: f64, color: Rgba) {
+    let (left, right, _, _) = layout.margins;
+    let [left_text, center_text, right_text] = fields;
+    let measure = |text: &str| {
+        let pl = backend.create_text_run();
+        pl.set_font_description(Some(font_desc));
+        pl.set_text(text);
+        pl.extents().1.width() as f64 / pango::SCALE as f64
+    };
+    if !left_text.is_empty() {
+        draw_text(backend, left_text, font_desc, left, y, color);
+    }
+    if !center_text.is_empty() {
+        let x = left + (layout.page_width - left - right - measure(center_text)) / 2.0;
+        draw_text(backend, center_text, font_desc, x, y, color);
+    }
+    if !right_text.is_empty() {
+        let x = layout.page_width - right - measure(right_text);
+        draw_text(backend, right_text, font_desc, x, y, color);
+    }
+}
+
+/// Render one page (body, header and footer) onto `ctx` in 96-DPI pixel units
+pub fn render_page(buf: &EditorBuffer, ctx: &Context, layout: &PageLayout, page_index: usize, tokens: &PageTokens) {
+    let (left, _, top, bottom) = layout.margins;
+    let export_cfg = &buf.config.export;
+
+    // Page background
+    ctx.fill_rect(0.0, 0.0, layout.page_width, layout.page_height, (1.0, 1.0, 1.0, 1.0));
+
+    // Body text
+    let color = buf.config.color(buf.config.font.font_color()).rgba();
+    if let Some(lines) = layout.pages.get(page_index) {
+        let mut y = top;
+        for line in lines {
+            match line {
+                PageLine::Text(text) => {
+                    draw_text(ctx, text, &layout.font_desc, left, y + layout.text_offset, color);
+                    y += layout.line_height;
+                }
+                PageLine::Image { path, width, height, lines } => {
+                    draw_image(ctx, path, left, y, *width, *height);
+                    y += *lines as f64 * layout.line_height;
+                }
+            }
+        }
+    }
+
+    // Header and footer, centered in the top and bottom margins
+    let hf_font = pango::FontDescription::from_string(&format!(
+        "{} {}",
+        buf.config.font.font_name(),
+        export_cfg.header_footer_font_size
+    ));
+    let hf_height = export_cfg.header_footer_font_size * 96.0 / 72.0;
+    let page_tokens = tokens.for_page(page_index + 1);
+    let color = buf.config.color(&export_cfg.header_footer_color).rgba();
+    if export_cfg.header.is_visible() {
+        let cfg = &export_cfg.header;
+        let fields = [page_tokens.expand(&cfg.left), page_tokens.expand(&cfg.center), page_tokens.expand(&cfg.right)];
+        draw_fields(ctx, layout, [&fields[0], &fields[1], &fields[2]], &hf_font, (top - hf_height) / 2.0, color);
+    }
+    if export_cfg.footer.is_visible() {
+        let cfg = &export_cfg.footer;
+        let fields = [page_tokens.expand(&cfg.left), page_tokens.expand(&cfg.center), page_tokens.expand(&cfg.right)];
+        let y = layout.page_height - bottom + (bottom - hf_height) / 2.0;
+        draw_fields(ctx, layout, [&fields[0], &fields[1], &fields[2]], &hf_font, y, color);
+    }
+}
@@ -0,0 +1,30 @@
+//! PDF export for EditorBuffer
+//!
+//! Renders every page through `page::render_page` onto an A4 cairo PDF surface.
+
+use gtk4::cairo::{self, Context};
+use crate::corelogic::EditorBuffer;
+use super::header_footer::PageTokens;
+use super::page::{render_page, PageLayout};
+
+/// Points per 96-DPI pixel (PDF user space is in points)
+const PX_TO_PT: f64 = 72.0 / 96.0;
+
+/// Export the buffer to a PDF file at `path`, one A4 page per laid out page
+pub fn export_pdf(buf: &EditorBuffer, path: &str) -> Result<(), String> {
+    let width_pt = super::page::A4_WIDTH_PX * PX_TO_PT;
+    let height_pt = super::page::A4_HEIGHT_PX * PX_TO_PT;
+    let surface = cairo::PdfSurface::new(width_pt, height_pt, path)
+        .map_err(|e| format!("Failed to create PDF surface: {}", e))?;
+    let ctx = Context::new(&surface).map_err(|e| format!("Failed to create cairo context: {}", e))?;
+    ctx.scale(PX_TO_PT, PX_TO_PT);
+
+    let layout = PageLayout::new(buf, &ctx);
+    let tokens = PageTokens::new(buf.file_path.as_deref(), layout.page_count(), &buf.config.export.date_format);
+    for page in 0..layout.page_count() {
+        render_page(buf, &ctx, &layout, page, &tokens);
+        ctx.show_page().map_err(|e| format!("Failed to emit PDF page: {}", e))?;
+    }
+    surface.finish();
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Printing via GtkPrintOperation
+//!
+//! Pagination comes from `page::PageLayout`, and each page is drawn through the same
+//! `page::render_page` used by PDF export. `render_print_page` is the headless entry point
+//! for drawing a page onto a printer (or preview) context.
+
+use gtk4::prelude::*;
+use gtk4::{PageSetup, PaperSize, PrintContext, PrintOperation};
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::corelogic::EditorBuffer;
+use super::header_footer::PageTokens;
+use super::page::{render_page, PageLayout};
+
+/// Points per 96-DPI pixel; the operation uses `Unit::Points`
+const PX_TO_PT: f64 = 72.0 / 96.0;
+
+/// Run `f` with the print context's cairo context scaled to 96-DPI pixels
+fn with_px_context<T>(print_ctx: &PrintContext, f: impl FnOnce(&gtk4::cairo::Context) -> T) -> T {
+    let ctx = print_ctx.cairo_context();
+    ctx.save().ok();
+    ctx.scale(PX_TO_PT, PX_TO_PT);
+    let result = f(&ctx);
+    ctx.restore().ok();
+    result
+}
+
+/// Lay out the buffer for the given print context
+pub fn print_layout(buf: &EditorBuffer, print_ctx: &PrintContext) -> PageLayout {
+    with_px_context(print_ctx, |ctx| PageLayout::new(buf, ctx))
+}
+
+/// Render one page onto a printer or preview context (headless, no dialog involved)
+pub fn render_print_page(buf: &EditorBuffer, print_ctx: &PrintContext, layout: &PageLayout, page_index: usize, tokens: &PageTokens) {
+    with_px_context(print_ctx, |ctx| render_page(buf, ctx, layout, page_index, tokens));
+}
+
+/// Build a print operation for the buffer on A4 paper
+///
+/// Pages are laid out in `begin-print` once the printer context is known, so the
+/// page count reflects the printer's font metrics. Margins are drawn by the page renderer.
+pub fn build_print_operation(buffer: Rc<RefCell<EditorBuffer>>) -> PrintOperation {
+    let operation = PrintOperation::new();
+    let page_setup = PageSetup::new();
+    page_setup.set_paper_size(&PaperSize::new(Some(gtk4::PAPER_NAME_A4)));
+    operation.set_default_page_setup(Some(&page_setup));
+    operation.set_use_full_page(true);
+    operation.set_unit(gtk4::Unit::Points);
+    {
+        let buf = buffer.borrow();
+        let job_name = buf.file_path.as_deref()
+            .and_then(|p| std::path::Path::new(p).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_string());
+        operation.set_job_name(&job_name);
+    }
+
+    let state: Rc<RefCell<Option<(PageLayout, PageTokens)>>> = Rc::new(RefCell::new(None));
+
+    let buffer_begin = buffer.clone();
+    let state_begin = state.clone();
+    operation.connect_begin_print(move |op, print_ctx| {
+        let buf = buffer_begin.borrow();
+        let layout = print_layout(&buf, print_ctx);
+        let tokens = PageTokens::new(buf.file_path.as_deref(), layout.page_count(), &buf.config.export.date_format);
+        op.set_n_pages(layout.page_count() as i32);
+        *state_begin.borrow_mut() = Some((layout, tokens));
+    });
+
+    let state_draw = state.clone();
+    operation.connect_draw_page(move |_, print_ctx, page_nr| {
+        if let Some((layout, tokens)) = state_draw.borrow().as_ref() {
+            let buf = buffer.borrow();
+            render_print_page(&buf, print_ctx, layout, page_nr.max(0) as usize, tokens);
+        }
+    });
+
+    operation.connect_end_print(move |_, _| {
+        state.borrow_mut().take();
+    });
+
+    operation
+}
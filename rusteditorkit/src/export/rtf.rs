@@ -0,0 +1,108 @@
+//! RTF exporter with syntax highlight colors
+
+use super::exporter::Exporter;
+use super::styled::StyledDocument;
+
+/// Exports styled documents as Rich Text Format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RtfExporter;
+
+/// Escape text for an RTF body (control characters and non-ASCII as \uN?)
+fn escape_rtf(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\t' => out.push_str("\\tab "),
+            c if c.is_ascii() => out.push(c),
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    out
+}
+
+impl Exporter for RtfExporter {
+    fn name(&self) -> &str {
+        "Rich Text Format"
+    }
+
+    fn extension(&self) -> &str {
+        "rtf"
+    }
+
+    fn export(&self, doc: &StyledDocument) -> Result<Vec<u8>, String> {
+        let colors = doc.colors();
+        let mut out = String::new();
+        out.push_str("{\\rtf1\\ansi\\deff0");
+        out.push_str(&format!("{{\\fonttbl{{\\f0\\fmodern {};}}}}", escape_rtf(&doc.font_name)));
+        out.push_str("{\\colortbl;");
+        for (r, g, b) in &colors {
+            out.push_str(&format!("\\red{}\\green{}\\blue{};", r, g, b));
+        }
+        out.push_str("}\n");
+        // RTF font sizes are in half-points
+        out.push_str(&format!("\\f0\\fs{} ", (doc.font_size * 2.0).round() as i64));
+        for (i, spans) in doc.lines.iter().enumerate() {
+            if i > 0 {
+                out.push_str("\\line\n");
+            }
+            for span in spans {
+                let index = colors.iter().position(|c| *c == span.color).map(|i| i + 1).unwrap_or(0);
+                out.push_str(&format!("{{\\cf{}", index));
+                if span.bold {
+                    out.push_str("\\b");
+                }
+                if span.italic {
+                    out.push_str("\\i");
+                }
+                if span.underline {
+                    out.push_str("\\ul");
+                }
+                out.push(' ');
+                out.push_str(&escape_rtf(&span.text));
+                out.push('}');
+            }
+        }
+        out.push_str("\n}");
+        Ok(out.into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::styled::StyledSpan;
+
+    fn span(text: &str, color: (u8, u8, u8), bold: bool) -> StyledSpan {
+        StyledSpan { text: text.to_string(), color, bold, italic: false, underline: false }
+    }
+
+    #[test]
+    fn escape_covers_braces_tabs_and_unicode() {
+        assert_eq!(escape_rtf("a{b}\\c\td"), "a\\{b\\}\\\\c\\tab d");
+        assert_eq!(escape_rtf("é😀"), "\\u233?\\u-10179?\\u-8704?");
+    }
+
+    #[test]
+    fn export_uses_a_color_table_and_line_breaks() {
+        let doc = StyledDocument {
+            lines: vec![vec![span("fn", (0, 0, 255), true), span(" main", (0, 0, 0), false)], vec![span("}", (0, 0, 255), false)]],
+            font_name: "Mono".to_string(),
+            font_size: 10.5,
+            background: (255, 255, 255),
+        };
+        let rtf = String::from_utf8(RtfExporter.export(&doc).unwrap()).unwrap();
+        assert!(rtf.starts_with("{\\rtf1\\ansi"));
+        assert!(rtf.contains("{\\colortbl;\\red0\\green0\\blue255;\\red0\\green0\\blue0;}"));
+        assert!(rtf.contains("\\fs21 {\\cf1\\b fn}{\\cf2  main}\\line\n{\\cf1 \\}}"));
+        assert!(rtf.ends_with("\n}"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Syntax-highlighted document model shared by the rich text exporters
+//!
+//! The buffer (or selection) is highlighted with syntect using the buffer's theme and
+//! language, producing colored spans that each exporter serializes in its own format.
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::FontStyle;
+use crate::corelogic::EditorBuffer;
+
+/// A run of text sharing one style
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledSpan {
+    pub text: String,
+    /// Foreground color as (r, g, b)
+    pub color: (u8, u8, u8),
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// Highlighted lines plus the font and background used to present them
+#[derive(Debug, Clone)]
+pub struct StyledDocument {
+    pub lines: Vec<Vec<StyledSpan>>,
+    pub font_name: String,
+    /// Font size in points
+    pub font_size: f64,
+    /// Background color as (r, g, b)
+    pub background: (u8, u8, u8),
+}
+
+impl StyledDocument {
+    /// Plain text content of the document
+    pub fn plain_text(&self) -> String {
+        self.lines.iter()
+            .map(|spans| spans.iter().map(|span| span.text.as_str()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Distinct span colors in order of first use (for formats with a color table)
+    pub fn colors(&self) -> Vec<(u8, u8, u8)> {
+        let mut colors = Vec::new();
+        for span in self.lines.iter().flatten() {
+            if !colors.contains(&span.color) {
+                colors.push(span.color);
+            }
+        }
+        colors
+    }
+}
+
+/// Which part of the buffer to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportScope {
+    /// The whole buffer
+    Buffer,
+    /// The active selection, or the whole buffer when nothing is selected
+    Selection,
+}
+
+impl EditorBuffer {
+    /// Highlight the requested scope into a `StyledDocument`
+    pub fn styled_document(&self, scope: ExportScope) -> StyledDocument {
+        let has_selection = self.selection.as_ref().is_some_and(|sel| sel.is_active());
+        let lines: Vec<String> = if scope == ExportScope::Selection && has_selection {
+            self.copy().split('\n').map(|line| line.to_string()).collect()
+        } else {
+            self.lines.clone()
+        };
+        self.styled_lines(&lines)
+    }
+
+    /// Highlight arbitrary lines using the buffer's language, theme and font
+    pub fn styled_lines(&self, lines: &[String]) -> StyledDocument {
+        let syntax = self.language.as_deref()
+            .and_then(|lang| self.syntax_set.find_syntax_by_token(lang))
+            .or_else(|| {
+                self.file_path.as_deref()
+                    .and_then(|p| std::path::Path::new(p).extension())
+                    .and_then(|ext| self.syntax_set.find_syntax_by_extension(&ext.to_string_lossy()))
+            })
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
+
+        let default_fg = self.theme.settings.foreground
+            .map(|c| (c.r, c.g, c.b))
+            .unwrap_or((0, 0, 0));
+        let styled_lines = lines.iter().map(|line| {
+            let with_newline = format!("{}\n", line);
+            match highlighter.highlight_line(&with_newline, &self.syntax_set) {
+                Ok(ranges) => ranges.into_iter()
+                    .map(|(style, text)| StyledSpan {
+                        text: text.trim_end_matches('\n').to_string(),
+                        color: (style.foreground.r, style.foreground.g, style.foreground.b),
+                        bold: style.font_style.contains(FontStyle::BOLD),
+                        italic: style.font_style.contains(FontStyle::ITALIC),
+                        underline: style.font_style.contains(FontStyle::UNDERLINE),
+                    })
+                    .filter(|span| !span.text.is_empty())
+                    .collect(),
+                Err(_) => vec![StyledSpan {
+                    text: line.clone(),
+                    color: default_fg,
+                    bold: false,
+                    italic: false,
+                    underline: false,
+                }],
+            }
+        }).collect();
+
+        StyledDocument {
+            lines: styled_lines,
+            font_name: self.config.font.font_name().to_string(),
+            font_size: self.config.font.font_size(),
+            background: self.theme.settings.background
+                .map(|c| (c.r, c.g, c.b))
+                .unwrap_or((255, 255, 255)),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 // Indentation and commenting logic for EditorBuffer
-use crate::core::EditorBuffer;
+use crate::corelogic::buffer::EditorBuffer;
 
 impl EditorBuffer {
     /// Auto-indent current line (simple: match previous line's indent)
@@ -7,16 +7,17 @@ impl EditorBuffer {
         if self.cursor.row > 0 {
             let prev = &self.lines[self.cursor.row - 1];
             let indent: String = prev.chars().take_while(|c| c.is_whitespace()).collect();
-            let line = &mut self.lines[self.cursor.row];
-            let rest = line.trim_start().to_string();
-            *line = format!("{}{}", indent, rest);
+            let row = self.cursor.row;
+            let line = format!("{}{}", indent, self.lines[row].trim_start());
+            self.replace_rows(row..row + 1, vec![line]);
             self.cursor.col = indent.len();
         }
     }
 
     /// Comment/uncomment current line (simple: add/remove //)
     pub fn toggle_comment(&mut self) {
-        let line = &mut self.lines[self.cursor.row];
+        let row = self.cursor.row;
+        let mut line = self.lines[row].clone();
         if line.trim_start().starts_with("//") {
             if let Some(idx) = line.find("//") {
                 line.replace_range(idx..idx+2, "");
@@ -25,5 +26,6 @@ impl EditorBuffer {
             let idx = line.chars().take_while(|c| c.is_whitespace()).count();
             line.insert_str(idx, "//");
         }
+        self.replace_rows(row..row + 1, vec![line]);
     }
 }
@@ -1,5 +1,5 @@
 /// Enum of all editor actions that can be triggered by keybindings
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum EditorAction {
     // Navigation
     MoveCursorLeft,
@@ -12,12 +12,16 @@ pub enum EditorAction {
     MoveCursorEnd,         // Alias for end of line
     MoveCursorPageUp,
     MoveCursorPageDown,
+    MoveWordLeft,          // Start of the previous word
+    MoveWordRight,         // End of the next word
     // Selection
     SelectLeft,
     SelectRight,
     SelectUp,
     SelectDown,
     SelectAll,
+    SelectWordLeft,
+    SelectWordRight,
     // Editing
     CopySelection,
     CutSelection,
@@ -26,8 +30,20 @@ pub enum EditorAction {
     DeleteRight,
     Backspace,             // Delete character before cursor
     Delete,                // Delete character at cursor
+    DeleteWordLeft,        // Delete back to the start of the previous word
+    DeleteWordRight,       // Delete to the end of the next word
     InsertText,            // Insert text at cursor
     InsertNewline,         // Insert newline
+    SurroundSelection,     // Wrap the selection(s) in the bracket/quote pair given as Text
+    UnSurround,            // Remove the bracket/quote pair around the selection(s) or cursor(s)
+    FillColumn,            // Write the Text parameter on every row of the column block
+    FillColumnSequence,    // Number the rows of the column block (from the Text parameter, default 1)
+    AlignSelection,        // Line up the Text parameter (a character or regex) across the selected lines
+    ReflowParagraph,       // Re-wrap paragraph/selection to the configured column
+    FormatTable,           // Align the pipes of the Markdown table under the cursor
+    InsertTableRow,        // Insert an empty row below the current table row
+    InsertTableColumn,     // Insert an empty column after the current table cell
+    ToggleCheckbox,        // Flip "- [ ]" / "- [x]" on the current or selected lines
     Undo,
     Redo,
     // Indentation and Tabulation
@@ -42,6 +58,7 @@ pub enum EditorAction {
     CloseOverlay,
     // File Operations
     NewFile,
+    NewFileFromTemplate,
     OpenFile,
     SaveFile,
     SaveAs,
@@ -51,6 +68,18 @@ pub enum EditorAction {
     Replace,
     // Layout and View
     ToggleA4Mode,          // Toggle A4 page mode
+    NextPage,              // Jump to the start of the next A4 page
+    PrevPage,              // Jump to the start of the previous A4 page
+    NextHeading,           // Jump to the next outline entry (heading or symbol)
+    PrevHeading,           // Jump to the previous outline entry
+    ToggleBookmark,        // Add/remove a bookmark on the cursor line
+    NextBookmark,          // Jump to the next bookmark (wraps)
+    PrevBookmark,          // Jump to the previous bookmark (wraps)
+    ToggleFold,            // Collapse/expand the fold around the cursor line
+    ZoomIn,                // Enlarge text for this view (not saved to config)
+    ZoomOut,               // Shrink text for this view
+    ZoomReset,             // Back to the configured font size
+    SetTextDirection,      // Cycle the cursor paragraph's direction: Auto, LTR, RTL
     // Multi-cursor
     AddCursor,             // Add cursor at position
 }
@@ -66,6 +95,7 @@ pub struct KeyCombo {
 
 impl KeyCombo {
     /// Converts a GTK keyval and modifier state to a KeyCombo for keymap matching
+    #[cfg(feature = "gtk")]
     pub fn from_gtk_event(keyval: u32, state: gtk4::gdk::ModifierType) -> Self {
         // Recognize fallback keyvals for common keys
         let key = match keyval {
@@ -7,15 +7,31 @@ pub fn linux_keymap() -> HashMap<EditorAction, KeyCombo> {
     // === Navigation ===
     map.insert(MoveCursorLeft, KeyCombo::new("Left", false, false, false));
     map.insert(MoveCursorRight, KeyCombo::new("Right", false, false, false));
+    map.insert(MoveWordLeft, KeyCombo::new("Left", true, false, false));
+    map.insert(MoveWordRight, KeyCombo::new("Right", true, false, false));
     map.insert(MoveCursorUp, KeyCombo::new("Up", false, false, false));
     map.insert(MoveCursorDown, KeyCombo::new("Down", false, false, false));
     map.insert(MoveCursorStartOfLine, KeyCombo::new("Home", false, false, false));
     map.insert(MoveCursorEndOfLine, KeyCombo::new("End", false, false, false));
     map.insert(MoveCursorPageUp, KeyCombo::new("PageUp", false, false, false));
     map.insert(MoveCursorPageDown, KeyCombo::new("PageDown", false, false, false));
+    map.insert(PrevPage, KeyCombo::new("PageUp", false, false, true));
+    map.insert(NextPage, KeyCombo::new("PageDown", false, false, true));
+    map.insert(PrevHeading, KeyCombo::new("Up", true, false, true));
+    map.insert(NextHeading, KeyCombo::new("Down", true, false, true));
+    map.insert(ToggleBookmark, KeyCombo::new("F2", true, false, false));
+    map.insert(NextBookmark, KeyCombo::new("F2", false, false, false));
+    map.insert(PrevBookmark, KeyCombo::new("F2", false, true, false));
+    map.insert(ToggleFold, KeyCombo::new("bracketleft", true, false, false));
+    map.insert(ZoomIn, KeyCombo::new("equal", true, false, false));
+    map.insert(ZoomOut, KeyCombo::new("minus", true, false, false));
+    map.insert(ZoomReset, KeyCombo::new("0", true, false, false));
+    map.insert(SetTextDirection, KeyCombo::new("D", true, true, false));
     // === Selection ===
     map.insert(SelectLeft, KeyCombo::new("Left", false, true, false));
     map.insert(SelectRight, KeyCombo::new("Right", false, true, false));
+    map.insert(SelectWordLeft, KeyCombo::new("Left", true, true, false));
+    map.insert(SelectWordRight, KeyCombo::new("Right", true, true, false));
     map.insert(SelectUp, KeyCombo::new("Up", false, true, false));
     map.insert(SelectDown, KeyCombo::new("Down", false, true, false));
     map.insert(SelectAll, KeyCombo::new("a", true, false, false));
@@ -25,8 +41,17 @@ pub fn linux_keymap() -> HashMap<EditorAction, KeyCombo> {
     map.insert(PasteClipboard, KeyCombo::new("v", true, false, false));
     map.insert(DeleteLeft, KeyCombo::new("Backspace", false, false, false));
     map.insert(DeleteRight, KeyCombo::new("Delete", false, false, false));
+    map.insert(DeleteWordLeft, KeyCombo::new("Backspace", true, false, false));
+    map.insert(DeleteWordRight, KeyCombo::new("Delete", true, false, false));
     map.insert(Undo, KeyCombo::new("z", true, false, false));
     map.insert(Redo, KeyCombo::new("y", true, false, false));
+    map.insert(ReflowParagraph, KeyCombo::new("q", false, false, true));
+    map.insert(FormatTable, KeyCombo::new("t", false, false, true));
+    map.insert(InsertTableRow, KeyCombo::new("R", false, true, true));
+    map.insert(InsertTableColumn, KeyCombo::new("C", false, true, true));
+    map.insert(UnSurround, KeyCombo::new("U", false, true, true));
+    map.insert(FillColumnSequence, KeyCombo::new("N", false, true, true));
+    map.insert(ToggleCheckbox, KeyCombo::new("Return", true, false, false));
     // === Indentation and Tabulation ===
     map.insert(Indent, KeyCombo::new("Tab", false, false, false));
     map.insert(Unindent, KeyCombo::new("Tab", false, true, false));
@@ -2,4 +2,6 @@ pub mod editor_action;
 pub mod linux;
 pub mod win;
 pub mod mac;
+pub mod shortcuts;
 pub use crate::keybinds::editor_action::{EditorAction, KeyCombo};
+pub use crate::keybinds::shortcuts::{list_shortcuts, ShortcutInfo, ShortcutStyle};
@@ -0,0 +1,130 @@
+//! Keymap introspection: human-readable shortcut labels for menus and cheatsheets
+
+use super::editor_action::{EditorAction, KeyCombo};
+use std::collections::HashMap;
+
+/// How modifier keys are written in a shortcut label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutStyle {
+    /// "Ctrl+Shift+S"
+    Text,
+    /// "⌘⇧S" (macOS, where the keymap's ctrl flag stands for Command)
+    MacSymbols,
+}
+
+impl ShortcutStyle {
+    /// Style matching the platform the crate was built for
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            ShortcutStyle::MacSymbols
+        } else {
+            ShortcutStyle::Text
+        }
+    }
+}
+
+/// One registered shortcut, ready to display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutInfo {
+    pub action: EditorAction,
+    pub combo: KeyCombo,
+    /// Formatted shortcut, e.g. "Ctrl+Shift+S"
+    pub label: String,
+}
+
+/// Display name of a GDK key name
+fn key_label(key: &str, style: ShortcutStyle) -> String {
+    let mac = style == ShortcutStyle::MacSymbols;
+    let label = match key {
+        "Return" if mac => "↩",
+        "Return" => "Enter",
+        "BackSpace" | "Backspace" if mac => "⌫",
+        "BackSpace" | "Backspace" => "Backspace",
+        "Delete" if mac => "⌦",
+        "Escape" if mac => "⎋",
+        "Escape" => "Esc",
+        "Tab" if mac => "⇥",
+        "Left" if mac => "←",
+        "Right" if mac => "→",
+        "Up" if mac => "↑",
+        "Down" if mac => "↓",
+        "PageUp" | "Page_Up" if mac => "⇞",
+        "PageUp" | "Page_Up" => "Page Up",
+        "PageDown" | "Page_Down" if mac => "⇟",
+        "PageDown" | "Page_Down" => "Page Down",
+        "Home" if mac => "↖",
+        "End" if mac => "↘",
+        "space" => "Space",
+        // Letters are shown uppercase whether or not the keymap stores the shifted keyval
+        _ if key.chars().count() == 1 => return key.to_uppercase(),
+        _ => key,
+    };
+    label.to_string()
+}
+
+impl KeyCombo {
+    /// Shortcut label in the given style, e.g. "Ctrl+Alt+Down" or "⌘⌥↓"
+    pub fn format(&self, style: ShortcutStyle) -> String {
+        let key = key_label(self.key, style);
+        match style {
+            ShortcutStyle::Text => {
+                let mut parts: Vec<&str> = Vec::new();
+                if self.ctrl { parts.push("Ctrl"); }
+                if self.alt { parts.push("Alt"); }
+                if self.shift { parts.push("Shift"); }
+                parts.push(&key);
+                parts.join("+")
+            }
+            ShortcutStyle::MacSymbols => {
+                let mut label = String::new();
+                if self.ctrl { label.push('⌘'); }
+                if self.shift { label.push('⇧'); }
+                if self.alt { label.push('⌥'); }
+                label.push_str(&key);
+                label
+            }
+        }
+    }
+
+    /// Shortcut label for the current platform
+    pub fn label(&self) -> String {
+        self.format(ShortcutStyle::current())
+    }
+}
+
+/// All actions in `keymap` with their formatted shortcuts, in `EditorAction` declaration order
+///
+/// Pass the keymap the widget actually uses (`EditorWidget::keymap`) so user overrides
+/// are reflected.
+pub fn list_shortcuts(keymap: &HashMap<EditorAction, KeyCombo>, style: ShortcutStyle) -> Vec<ShortcutInfo> {
+    let mut shortcuts: Vec<ShortcutInfo> = keymap.iter()
+        .map(|(&action, combo)| ShortcutInfo { action, combo: combo.clone(), label: combo.format(style) })
+        .collect();
+    shortcuts.sort_by_key(|s| s.action);
+    shortcuts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_text_and_mac_labels() {
+        let save_as = KeyCombo::new("s", true, true, false);
+        assert_eq!(save_as.format(ShortcutStyle::Text), "Ctrl+Shift+S");
+        assert_eq!(save_as.format(ShortcutStyle::MacSymbols), "⌘⇧S");
+        let heading = KeyCombo::new("Down", true, false, true);
+        assert_eq!(heading.format(ShortcutStyle::Text), "Ctrl+Alt+Down");
+        assert_eq!(heading.format(ShortcutStyle::MacSymbols), "⌘⌥↓");
+        assert_eq!(KeyCombo::new("PageDown", false, false, true).format(ShortcutStyle::Text), "Alt+Page Down");
+    }
+
+    #[test]
+    fn lists_shortcuts_in_action_order() {
+        let mut keymap = HashMap::new();
+        keymap.insert(EditorAction::Redo, KeyCombo::new("y", true, false, false));
+        keymap.insert(EditorAction::MoveCursorLeft, KeyCombo::new("Left", false, false, false));
+        let labels: Vec<String> = list_shortcuts(&keymap, ShortcutStyle::Text).into_iter().map(|s| s.label).collect();
+        assert_eq!(labels, vec!["Left".to_string(), "Ctrl+Y".to_string()]);
+    }
+}
@@ -1,32 +1,58 @@
-// Editor logic library entry point
-// Exposes all editor modules for use as a library
+//! Editor logic library entry point
+//!
+//! The supported API is the `prelude`, re-exported here at the crate root. The modules
+//! below stay public so existing code keeps building, but they are implementation
+//! details: hidden from the documentation and free to change between releases.
+
+pub mod prelude;
+pub use prelude::*;
 
 // Core logic modules (centralized)
+#[doc(hidden)]
 pub mod corelogic;
 
 // UI and platform modules
+#[doc(hidden)]
 pub mod keybinds;
+#[doc(hidden)]
 pub mod crossplatform;
 
+#[cfg(feature = "gtk")]
+#[doc(hidden)]
 pub mod widget;
+#[cfg(feature = "gtk")]
+#[doc(hidden)]
 pub mod imcontext;
 
 // Legacy modules (will be deprecated)
+#[cfg(feature = "gtk")]
+#[doc(hidden)]
+#[deprecated(since = "0.0.1", note = "use rusteditorkit::prelude")]
 pub mod core; // Legacy core, will be removed
+#[doc(hidden)]
 pub mod multicursor; // Will be merged into corelogic
+#[doc(hidden)]
 pub mod bracket; // Will be merged into corelogic
+#[doc(hidden)]
 pub mod indent; // Will be merged into corelogic
 
+#[doc(hidden)]
 pub mod config {
     pub mod configuration;
     pub mod api_config_loader;
     pub mod editor_config_loader;
 }
 
+#[cfg(feature = "gtk")]
+#[doc(hidden)]
 pub mod render;
-pub use render::render_editor;
+#[doc(hidden)]
+pub mod export;
 
-// Re-export the main types from the new centralized structure
-pub use corelogic::{EditorBuffer, EditorCursor};
-pub use corelogic::{CommandDispatcher, CommandError, CommandParams, CommandResult};
-pub use config::configuration::EditorConfig;
+// Deprecated names of the crate root, kept until the next breaking release
+#[deprecated(since = "0.0.1", note = "use rusteditorkit::Buffer")]
+pub type EditorBuffer = corelogic::EditorBuffer;
+#[deprecated(since = "0.0.1", note = "use rusteditorkit::Cursor")]
+pub type EditorCursor = corelogic::EditorCursor;
+#[deprecated(since = "0.0.1", note = "use rusteditorkit::Config")]
+pub type EditorConfig = config::configuration::EditorConfig;
@@ -1,7 +1,7 @@
 // Multi-cursor and multi-selection logic for EditorBuffer
 // This module provides functions for managing multiple cursors and selections.
 
-use crate::core::EditorBuffer;
+use crate::corelogic::buffer::EditorBuffer;
 
 impl EditorBuffer {
     /// Add a new cursor at (row, col)
@@ -0,0 +1,35 @@
+//! The supported public API
+//!
+//! `use rusteditorkit::prelude::*;` brings in everything a host needs, and these names
+//! (also re-exported at the crate root) follow semver:
+//!
+//! - `Editor`: the GTK editor widget
+//! - `EditorView`: the editor as a `GtkWidget` subclass, for UI files and relm4
+//! - `Buffer`: the text, cursor, selections and editing operations behind it
+//! - `View`: geometry of a drawn view (row positions, line heights, gutter columns)
+//! - `Command`: an editor action, run through the `CommandDispatcher`
+//! - `Config`: the editor configuration
+//! - `Color`, `ThemeColor`, `ColorScheme`: colors and the color settings of the config
+//! - `EditorEvent`, `EventBus`, `ListenerId`: change notifications
+//! - `HeadlessEditor`: drives the widget input logic without a display, for tests
+//!
+//! `Editor`, `EditorView`, `View` and `HeadlessEditor` need the default `gtk` feature.
+//!
+//! The modules behind them (`corelogic`, `render`, `widget`, ...) are hidden from the
+//! documentation and may change in any release.
+
+pub use crate::config::configuration::EditorConfig as Config;
+pub use crate::corelogic::color::{Color, ColorScheme, ThemeColor};
+pub use crate::corelogic::dispatcher::{CommandDispatcher, CommandError, CommandParams, CommandResult};
+pub use crate::corelogic::events::{EditorEvent, EventBus, ListenerId};
+pub use crate::corelogic::selection::Selection;
+pub use crate::corelogic::{EditorBuffer as Buffer, EditorCursor as Cursor};
+pub use crate::keybinds::{EditorAction as Command, KeyCombo};
+#[cfg(feature = "gtk")]
+pub use crate::render::layout::LayoutMetrics as View;
+#[cfg(feature = "gtk")]
+pub use crate::render::render_editor;
+#[cfg(feature = "gtk")]
+pub use crate::widget::headless::HeadlessEditor;
+#[cfg(feature = "gtk")]
+pub use crate::widget::{EditorView, EditorWidget as Editor};
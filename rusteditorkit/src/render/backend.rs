@@ -0,0 +1,149 @@
+//! Drawing backend for the render layers
+//!
+//! Layers put pixels on screen through `DrawingBackend`: filled (optionally rounded)
+//! rectangles, text runs already shaped by Pango, images and rectangular clips. Shaping
+//! and measuring stay with Pango whatever the backend, so the layout logic
+//! (`LayoutMetrics`, column positions) is shared, and a backend only has to draw what it
+//! is given. A cairo `Context` is one, covering the widget and plain cairo surfaces (PDF
+//! export, printing); a GPU canvas such as femtovg would implement the same trait.
+//!
+//! Lines are drawn as thin rectangles, so the trait needs no stroking. Only the
+//! experimental vertical text layer still draws on a cairo `Context` directly, as it
+//! lays its columns out in a rotated space.
+
+use gtk4::cairo::Context;
+use gtk4::gdk::prelude::GdkCairoContextExt;
+use gtk4::gdk_pixbuf::Pixbuf;
+use gtk4::pango;
+
+/// Red, green, blue and alpha, each 0.0-1.0 (as returned by `Color::rgba`)
+pub type Rgba = (f64, f64, f64, f64);
+
+/// What a render target must provide
+pub trait DrawingBackend {
+    /// Fill a rectangle with `color`
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64, color: Rgba);
+
+    /// Fill a rectangle with corners rounded to `radius` with `color`
+    fn fill_rounded_rect(&self, x: f64, y: f64, width: f64, height: f64, radius: f64, color: Rgba);
+
+    /// New Pango layout to shape a text run for this backend
+    fn create_text_run(&self) -> pango::Layout;
+
+    /// Draw a shaped text run with its top-left corner at (`x`, `y`)
+    fn draw_text_run(&self, run: &pango::Layout, x: f64, y: f64, color: Rgba);
+
+    /// Draw `image` at its own size with its top-left corner at (`x`, `y`)
+    fn draw_image(&self, image: &Pixbuf, x: f64, y: f64);
+
+    /// Restrict drawing to a rectangle until the matching `pop_clip`
+    fn push_clip(&self, x: f64, y: f64, width: f64, height: f64);
+
+    /// Undo the last `push_clip`
+    fn pop_clip(&self);
+}
+
+impl DrawingBackend for Context {
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64, (r, g, b, a): Rgba) {
+        self.set_source_rgba(r, g, b, a);
+        self.rectangle(x, y, width, height);
+        let _ = self.fill();
+    }
+
+    fn fill_rounded_rect(&self, x: f64, y: f64, width: f64, height: f64, radius: f64, (r, g, b, a): Rgba) {
+        use std::f64::consts::{FRAC_PI_2, PI};
+        let radius = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+        self.set_source_rgba(r, g, b, a);
+        self.new_sub_path();
+        self.arc(x + width - radius, y + radius, radius, -FRAC_PI_2, 0.0);
+        self.arc(x + width - radius, y + height - radius, radius, 0.0, FRAC_PI_2);
+        self.arc(x + radius, y + height - radius, radius, FRAC_PI_2, PI);
+        self.arc(x + radius, y + radius, radius, PI, 3.0 * FRAC_PI_2);
+        self.close_path();
+        let _ = self.fill();
+    }
+
+    fn create_text_run(&self) -> pango::Layout {
+        pangocairo::functions::create_layout(self)
+    }
+
+    fn draw_text_run(&self, run: &pango::Layout, x: f64, y: f64, (r, g, b, a): Rgba) {
+        self.set_source_rgba(r, g, b, a);
+        self.move_to(x, y);
+        pangocairo::functions::show_layout(self, run);
+    }
+
+    fn draw_image(&self, image: &Pixbuf, x: f64, y: f64) {
+        let _ = self.save();
+        self.set_source_pixbuf(image, x, y);
+        let _ = self.paint();
+        let _ = self.restore();
+    }
+
+    fn push_clip(&self, x: f64, y: f64, width: f64, height: f64) {
+        let _ = self.save();
+        self.rectangle(x, y, width, height);
+        self.clip();
+    }
+
+    fn pop_clip(&self) {
+        let _ = self.restore();
+    }
+}
+
+/// Outline of a rectangle, `thickness` wide on the inside of its edges
+pub fn fill_rect_outline(backend: &dyn DrawingBackend, x: f64, y: f64, width: f64, height: f64, thickness: f64, color: Rgba) {
+    backend.fill_rect(x, y, width, thickness, color);
+    backend.fill_rect(x, y + height - thickness, width, thickness, color);
+    backend.fill_rect(x, y + thickness, thickness, height - 2.0 * thickness, color);
+    backend.fill_rect(x + width - thickness, y + thickness, thickness, height - 2.0 * thickness, color);
+}
+
+/// Horizontal dashed line `thickness` high from `x0` to `x1` at `y`
+pub fn fill_dashed_hline(backend: &dyn DrawingBackend, x0: f64, x1: f64, y: f64, (dash, gap): (f64, f64), thickness: f64, color: Rgba) {
+    let mut x = x0;
+    while x < x1 {
+        backend.fill_rect(x, y, dash.min(x1 - x), thickness, color);
+        x += dash + gap;
+    }
+}
+
+/// Backend for tests that records rectangles instead of drawing them
+#[cfg(test)]
+pub(crate) struct Recorder {
+    pub rects: std::cell::RefCell<Vec<(f64, f64, f64, f64)>>,
+    /// Context text runs are shaped on, on a 1x1 image surface as in the snapshot backend
+    shaping: Context,
+}
+
+#[cfg(test)]
+impl Recorder {
+    pub fn new() -> Self {
+        let surface = gtk4::cairo::ImageSurface::create(gtk4::cairo::Format::ARgb32, 1, 1).expect("1x1 image surface");
+        let shaping = Context::new(&surface).expect("context on an image surface");
+        Self { rects: Default::default(), shaping }
+    }
+}
+
+#[cfg(test)]
+impl DrawingBackend for Recorder {
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64, _color: Rgba) {
+        self.rects.borrow_mut().push((x, y, width, height));
+    }
+
+    fn fill_rounded_rect(&self, x: f64, y: f64, width: f64, height: f64, _radius: f64, color: Rgba) {
+        self.fill_rect(x, y, width, height, color);
+    }
+
+    fn create_text_run(&self) -> pango::Layout {
+        pangocairo::functions::create_layout(&self.shaping)
+    }
+
+    fn draw_text_run(&self, _run: &pango::Layout, _x: f64, _y: f64, _color: Rgba) {}
+
+    fn draw_image(&self, _image: &Pixbuf, _x: f64, _y: f64) {}
+
+    fn push_clip(&self, _x: f64, _y: f64, _width: f64, _height: f64) {}
+
+    fn pop_clip(&self) {}
+}
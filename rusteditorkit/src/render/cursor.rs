@@ -1,14 +1,21 @@
 //! Renders the caret, handles blinking and movement
-use gtk4::cairo::Context;
 use gtk4::pango;
 use crate::corelogic::EditorBuffer;
-use crate::render::layout::LayoutMetrics;
-use crate::corelogic::gutter::parse_color;
+use crate::render::backend::DrawingBackend;
+use crate::render::layout::{column_width, column_x, LayoutMetrics};
+
+/// Draws the cursor on the caret row, laying that row out again
+pub fn render_caret(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics) {
+    let row = rkit.cursor.row.min(rkit.lines.len().saturating_sub(1));
+    // Laid out like the text layer draws the row, tab stops included
+    let pango_layout = crate::render::layout::row_layout(rkit, backend, layout, row);
+    render_cursor_layer(rkit, backend, &pango_layout, layout, layout.row_y(row));
+}
 
 /// Draws the cursor with exact alignment to text baseline
 pub fn render_cursor_layer(
     rkit: &EditorBuffer,
-    ctx: &Context,
+    backend: &dyn DrawingBackend,
     text_layout: &pango::Layout,
     layout: &LayoutMetrics,
     y_line: f64
@@ -18,12 +25,17 @@ pub fn render_cursor_layer(
     if !cursor_state.is_cursor_visible() {
         return;
     }
-    let (r, g, b, a) = parse_color(&cursor_cfg.cursor_color);
-    ctx.set_source_rgba(r, g, b, a);
-    let col = rkit.cursor.col.min(rkit.lines[rkit.cursor.row].chars().count());
-    // Unicode fallback: Pango handles multi-byte, so just document
-    let cursor_rect = text_layout.index_to_pos(col as i32);
-    let cursor_x = layout.text_left_offset + (cursor_rect.x() as f64) / (pango::SCALE as f64);
+    let color = rkit.config.color(&cursor_cfg.cursor_color).rgba();
+    let line = &rkit.lines[rkit.cursor.row];
+    let col = rkit.cursor.col.min(line.chars().count());
+    // Leading edge of the cluster at the caret; block and underline carets cover the whole
+    // cluster (both cells of a wide character), or one average cell at the end of the line
+    let cursor_x = column_x(text_layout, line, col, layout);
+    let cell = match column_width(text_layout, line, col) {
+        width if width != 0.0 => width,
+        _ => layout.text_metrics.average_char_width,
+    };
+    let cell_left = cursor_x.min(cursor_x + cell);
     let y_baseline = y_line + layout.text_metrics.baseline_offset;
     let cursor_y = y_baseline + cursor_cfg.cursor_padding_y;
     let text_height = layout.text_metrics.height;
@@ -31,50 +43,54 @@ pub fn render_cursor_layer(
     // println!("text_height = {}, gutter_height = {}, line_height = {}", text_height, layout.gutter_metrics.height, line_height);
     match cursor_cfg.cursor_type.as_str() {
         "bar" => {
-            ctx.rectangle(
-                cursor_x - cursor_cfg.cursor_padding_x, 
-                cursor_y, 
-                cursor_cfg.cursor_thickness, 
-                text_height
+            backend.fill_rect(
+                cursor_x - cursor_cfg.cursor_padding_x,
+                cursor_y,
+                cursor_cfg.cursor_thickness,
+                text_height,
+                color
             );
         },
         "block" => {
             if cursor_cfg.cursor_roundness > 0.0 {
-                let _ = ctx.save();
-                ctx.arc(
-                    cursor_x + (rkit.config.font.font_size() / 2.0),
-                    cursor_y + (text_height / 2.0),
-                    rkit.config.font.font_size() / 2.0,
-                    0.0,
-                    std::f64::consts::PI * 2.0
+                // A dot one font size across, centered on the cell
+                let diameter = rkit.zoomed_font_size();
+                backend.fill_rounded_rect(
+                    cell_left + (cell.abs() - diameter) / 2.0,
+                    cursor_y + (text_height - diameter) / 2.0,
+                    diameter,
+                    diameter,
+                    diameter / 2.0,
+                    color
                 );
-                let _ = ctx.restore();
             } else {
-                ctx.rectangle(
-                    cursor_x - cursor_cfg.cursor_padding_x,
+                backend.fill_rect(
+                    cell_left - cursor_cfg.cursor_padding_x,
                     cursor_y,
-                    rkit.config.font.font_size() + 2.0 * cursor_cfg.cursor_padding_x,
-                    text_height
+                    cell.abs() + 2.0 * cursor_cfg.cursor_padding_x,
+                    text_height,
+                    color
                 );
             }
         },
         "underline" => {
             let underline_y = cursor_y + text_height - cursor_cfg.cursor_thickness;
-            ctx.rectangle(
-                cursor_x - cursor_cfg.cursor_padding_x,
+            backend.fill_rect(
+                cell_left - cursor_cfg.cursor_padding_x,
                 underline_y,
-                rkit.config.font.font_size() + 2.0 * cursor_cfg.cursor_padding_x,
-                cursor_cfg.cursor_thickness
+                cell.abs() + 2.0 * cursor_cfg.cursor_padding_x,
+                cursor_cfg.cursor_thickness,
+                color
             );
         },
         _ => {
-            ctx.rectangle(
+            backend.fill_rect(
                 cursor_x - cursor_cfg.cursor_padding_x,
                 cursor_y,
                 cursor_cfg.cursor_thickness,
-                text_height
+                text_height,
+                color
             );
         }
     }
-    ctx.fill().unwrap_or(());
 }
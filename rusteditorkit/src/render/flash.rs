@@ -0,0 +1,18 @@
+//! Line flash rendering: fading background on the row the caret jumped to
+
+use crate::corelogic::EditorBuffer;
+use crate::render::layout::LayoutMetrics;
+use crate::render::backend::DrawingBackend;
+
+/// Draws the line flash background, fading out as the flash progresses
+pub fn render_line_flash_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
+    let Some(flash) = buf.line_flash else {
+        return;
+    };
+    let (r, g, b, a) = buf.config.color(&buf.config.line_flash_color).rgba();
+    // Ease out so the color lingers briefly before fading
+    let alpha = a * (1.0 - flash.progress * flash.progress);
+    let y_line = layout.row_y(flash.row);
+    let y = y_line + layout.text_metrics.baseline_offset;
+    backend.fill_rect(0.0, y, width as f64, layout.row_height(flash.row), (r, g, b, alpha));
+}
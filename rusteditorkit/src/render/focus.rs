@@ -0,0 +1,40 @@
+//! Zen mode: dims text outside the focused line, sentence or paragraph
+use crate::corelogic::EditorBuffer;
+use crate::render::backend::DrawingBackend;
+use crate::render::layout::LayoutMetrics;
+use crate::render::selection::{calculate_column_x_position, fill_row_span};
+
+/// Draws a translucent background-colored veil over text outside the focus region
+///
+/// Uses the region cached by `EditorBuffer::refresh_focus`, so cursor moves within the
+/// same sentence/paragraph do not change what is drawn.
+pub fn render_focus_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
+    if !buf.config.zen_mode || buf.lines.is_empty() {
+        return;
+    }
+    let ((start_row, start_col), (end_row, end_col)) = buf.focus_range
+        .unwrap_or_else(|| buf.compute_focus_range());
+    let (r, g, b, _) = buf.config.color(&buf.config.editor_bg_color).rgba();
+    let veil = (r, g, b, buf.config.zen_dim_opacity.clamp(0.0, 1.0));
+    let left = layout.text_left_offset;
+    let right = width as f64;
+    let row_y = |row: usize| layout.row_y(row);
+
+    // Whole rows above and below the focus region
+    let above_top = row_y(0).max(layout.viewport_top);
+    backend.fill_rect(left, above_top, right - left, (row_y(start_row) - above_top).max(0.0), veil);
+    let below_top = row_y(end_row + 1);
+    backend.fill_rect(left, below_top, right - left, (row_y(buf.lines.len()) - below_top).max(0.0), veil);
+
+    // Partial rows: text before the start and after the end of the region (mirrored for RTL rows)
+    let rtl_rows = buf.rtl_rows();
+    let edges = |rtl: bool| if rtl { (right, left) } else { (left, right) };
+    let rtl = rtl_rows[start_row];
+    let start_x = calculate_column_x_position(buf, backend, start_row, start_col, layout);
+    fill_row_span(backend, edges(rtl).0, start_x, row_y(start_row), layout.row_height(start_row), veil);
+    if end_row < buf.lines.len() {
+        let rtl = rtl_rows[end_row];
+        let end_x = calculate_column_x_position(buf, backend, end_row, end_col, layout);
+        fill_row_span(backend, end_x, edges(rtl).1, row_y(end_row), layout.row_height(end_row), veil);
+    }
+}
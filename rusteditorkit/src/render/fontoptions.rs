@@ -0,0 +1,53 @@
+//! Cairo font options applied to the Pango layouts of the render pipeline
+use gtk4::cairo::{self, Context};
+use gtk4::pango;
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::fontoptions::{FontRenderOptions, HintLevel, SubpixelOrder};
+use crate::render::backend::DrawingBackend;
+
+/// Translate the resolved options into cairo font options
+pub fn cairo_font_options(opts: &FontRenderOptions) -> Option<cairo::FontOptions> {
+    let mut fo = cairo::FontOptions::new().ok()?;
+    fo.set_antialias(match (opts.antialias, opts.subpixel) {
+        (false, _) => cairo::Antialias::None,
+        (true, SubpixelOrder::None) => cairo::Antialias::Gray,
+        (true, _) => cairo::Antialias::Subpixel,
+    });
+    fo.set_subpixel_order(match opts.subpixel {
+        SubpixelOrder::None => cairo::SubpixelOrder::Default,
+        SubpixelOrder::Rgb => cairo::SubpixelOrder::Rgb,
+        SubpixelOrder::Bgr => cairo::SubpixelOrder::Bgr,
+        SubpixelOrder::Vrgb => cairo::SubpixelOrder::Vrgb,
+        SubpixelOrder::Vbgr => cairo::SubpixelOrder::Vbgr,
+    });
+    fo.set_hint_style(match opts.hinting {
+        HintLevel::None => cairo::HintStyle::None,
+        HintLevel::Slight => cairo::HintStyle::Slight,
+        HintLevel::Medium => cairo::HintStyle::Medium,
+        HintLevel::Full => cairo::HintStyle::Full,
+    });
+    fo.set_hint_metrics(if opts.hinting == HintLevel::None {
+        cairo::HintMetrics::Off
+    } else {
+        cairo::HintMetrics::On
+    });
+    Some(fo)
+}
+
+/// Create a Pango layout for `ctx` with the buffer's font rendering options applied
+pub fn create_layout(rkit: &EditorBuffer, ctx: &Context) -> pango::Layout {
+    apply_font_options(rkit, pangocairo::functions::create_layout(ctx))
+}
+
+/// Create a text run for `backend` with the buffer's font rendering options applied
+pub fn create_text_run(rkit: &EditorBuffer, backend: &dyn DrawingBackend) -> pango::Layout {
+    apply_font_options(rkit, backend.create_text_run())
+}
+
+fn apply_font_options(rkit: &EditorBuffer, layout: pango::Layout) -> pango::Layout {
+    if let Some(fo) = cairo_font_options(&rkit.font_render_options()) {
+        pangocairo::functions::context_set_font_options(&layout.context(), Some(&fo));
+        layout.context_changed();
+    }
+    layout
+}
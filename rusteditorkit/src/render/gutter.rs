@@ -1,25 +1,152 @@
-//! Handles line numbers, breakpoints, and fold markers
-use gtk4::cairo::Context;
+//! Handles line numbers, markers, fold markers, change bars and annotations
+//!
+//! Each gutter column (see `corelogic::gutter::GutterColumn`) has its own draw function
+//! and only draws inside the span `LayoutMetrics::gutter_columns` gives it.
+use gtk4::pango;
 use crate::corelogic::EditorBuffer;
+use crate::corelogic::gutter::{GutterColumn, GutterColumnSpan, LineNumberMode};
+use crate::render::backend::DrawingBackend;
 use crate::render::layout::LayoutMetrics;
-use crate::corelogic::gutter::render_gutter;
 
-/// Draws the gutter (line numbers, markers, etc.)
-pub fn render_gutter_layer(rkit: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics, height: i32) {
-    let gutter_cfg = &rkit.config.gutter;
-    if !gutter_cfg.toggle {
+/// Draws the gutter (background, border and every column)
+pub fn render_gutter_layer(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, height: i32) {
+    if !rkit.config.gutter.toggle || layout.gutter_columns.is_empty() {
         return;
     }
-    // Use the same font as the text area for alignment
-    render_gutter(
-        rkit,
-        ctx,
-        height,
-        gutter_cfg,
-        rkit.lines.len(),
-        rkit.cursor.row,
-        layout.line_height,
-        layout.top_offset,
-        layout,
-    );
+    render_gutter_background(rkit, backend, layout, height);
+    for span in &layout.gutter_columns {
+        match span.column {
+            GutterColumn::Markers => render_marker_column(rkit, backend, layout, span),
+            GutterColumn::LineNumbers => render_line_number_column(rkit, backend, layout, span),
+            GutterColumn::Folding => render_folding_column(rkit, backend, layout, span),
+            GutterColumn::ChangeBars => render_change_bar_column(rkit, backend, layout, span),
+            GutterColumn::Annotations => render_annotation_column(rkit, backend, layout, span, height),
+        }
+    }
+}
+
+/// Background and border behind all columns except the annotations, which have their own
+fn render_gutter_background(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, height: i32) {
+    let cfg = &rkit.config.gutter;
+    let base = layout.gutter_columns.iter().filter(|span| span.column != GutterColumn::Annotations);
+    let Some((left, right)) = base.fold(None, |bounds: Option<(f64, f64)>, span| {
+        let (left, right) = bounds.unwrap_or((span.x, span.x + span.width));
+        Some((left.min(span.x), right.max(span.x + span.width)))
+    }) else {
+        return;
+    };
+    backend.fill_rect(left, 0.0, right - left, height as f64, rkit.config.color(&cfg.bg_color).rgba());
+
+    if cfg.border.toggle {
+        // Border on the text side: right, or left when the view is mirrored, centered on the edge
+        let border_x = if rkit.view_is_rtl() { left } else { right };
+        let border_width = cfg.border.width as f64;
+        backend.fill_rect(border_x - border_width / 2.0, 0.0, border_width, height as f64, rkit.config.color(&cfg.border.color).rgba());
+    }
+}
+
+/// Pango layout in the gutter font
+fn gutter_layout(rkit: &EditorBuffer, backend: &dyn DrawingBackend) -> pango::Layout {
+    let pango_layout = crate::render::fontoptions::create_text_run(rkit, backend);
+    // Always use the same font as in font config.ron for gutter font
+    let font_desc = crate::render::layout::font_description(rkit, rkit.zoomed_gutter_font_size());
+    pango_layout.set_font_description(Some(&font_desc));
+    pango_layout.context().set_round_glyph_positions(true);
+    pango_layout
+}
+
+/// Bookmark and diagnostic markers
+fn render_marker_column(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, span: &GutterColumnSpan) {
+    let cfg = &rkit.config.gutter.markers;
+    let pango_layout = gutter_layout(rkit, backend);
+    pango_layout.set_text(&cfg.icon_char);
+    let icon_width = pango_layout.pixel_size().0 as f64;
+    let color = rkit.config.color(&cfg.color).rgba();
+    for row in layout.visible_rows(rkit) {
+        let marked = rkit.bookmarks.contains(&row) || rkit.diagnostics.iter().any(|(r, _, _)| *r == row);
+        if !marked {
+            continue;
+        }
+        let x = span.x + (span.width - icon_width) / 2.0;
+        backend.draw_text_run(&pango_layout, x, layout.row_y(row) + layout.gutter_metrics.baseline_offset, color);
+    }
+}
+
+/// Line numbers (or line start offsets), aligned per `line_numbers.align`
+fn render_line_number_column(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, span: &GutterColumnSpan) {
+    let cfg = &rkit.config.gutter;
+    let pango_layout = gutter_layout(rkit, backend);
+    let mode = rkit.line_number_mode();
+    let padding = cfg.line_numbers.padding as f64;
+    for row in layout.visible_rows(rkit) {
+        // ...highlight is now drawn in render/highlight.rs...
+        let color = if row == rkit.cursor.row {
+            &cfg.active_line.line_number_color
+        } else {
+            &cfg.line_numbers.color
+        };
+        let offset = if mode == LineNumberMode::Line { 0 } else { rkit.line_start_offset(row) };
+        pango_layout.set_text(&mode.label(row, offset));
+        let text_width = pango_layout.pixel_size().0 as f64;
+        let x = match cfg.line_numbers.align.as_str() {
+            "left" => padding,
+            "center" => (span.width - text_width) / 2.0,
+            _ => span.width - text_width - padding,
+        };
+        // Align gutter number to text baseline using gutter_metrics.baseline_offset from LayoutMetrics
+        backend.draw_text_run(&pango_layout, span.x + x, layout.row_y(row) + layout.gutter_metrics.baseline_offset, rkit.config.color(color).rgba());
+    }
+}
+
+/// Fold header markers: collapsed or expanded
+fn render_folding_column(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, span: &GutterColumnSpan) {
+    if rkit.folds.is_empty() {
+        return;
+    }
+    let pango_layout = gutter_layout(rkit, backend);
+    let color = rkit.config.color(&rkit.config.gutter.line_numbers.color).rgba();
+    for row in layout.visible_rows(rkit) {
+        let Some(fold) = rkit.folds.get(&row) else {
+            continue;
+        };
+        pango_layout.set_text(if fold.collapsed { "\u{25b8}" } else { "\u{25be}" });
+        let text_width = pango_layout.pixel_size().0 as f64;
+        let x = span.x + (span.width - text_width) / 2.0;
+        backend.draw_text_run(&pango_layout, x, layout.row_y(row) + layout.gutter_metrics.baseline_offset, color);
+    }
+}
+
+/// Bars beside lines changed since the last save
+fn render_change_bar_column(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, span: &GutterColumnSpan) {
+    let modified = rkit.modified_rows();
+    if modified.is_empty() {
+        return;
+    }
+    let color = rkit.config.color(&rkit.config.gutter.change_bars.color).rgba();
+    for row in layout.visible_rows(rkit).into_iter().filter(|row| modified.contains(row)) {
+        backend.fill_rect(span.x, layout.row_y(row), span.width, layout.row_height(row), color);
+    }
+}
+
+/// Draws the host annotation column between the other columns and the text
+fn render_annotation_column(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, span: &GutterColumnSpan, height: i32) {
+    let cfg = &rkit.config.gutter.annotations;
+    backend.fill_rect(span.x, 0.0, span.width, height as f64, rkit.config.color(&cfg.bg_color).rgba());
+
+    let color = rkit.config.color(&cfg.color).rgba();
+    let pango_layout = crate::render::fontoptions::create_text_run(rkit, backend);
+    pango_layout.set_font_description(Some(&layout.gutter_metrics.font_desc));
+    pango_layout.set_width((span.width - 2.0 * cfg.padding as f64).max(0.0) as i32 * pango::SCALE);
+    pango_layout.set_ellipsize(pango::EllipsizeMode::End);
+
+    // Only the visible rows
+    let annotations = rkit.gutter_annotations();
+    for row in layout.visible_rows(rkit) {
+        let Some(text) = annotations.get(row).filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        pango_layout.set_text(text);
+        let y = layout.row_y(row) + layout.gutter_metrics.baseline_offset;
+        backend.draw_text_run(&pango_layout, span.x + cfg.padding as f64, y, color);
+    }
 }
@@ -3,26 +3,24 @@
 
 use crate::corelogic::EditorBuffer;
 use crate::render::layout::LayoutMetrics;
-use cairo::Context;
+use crate::render::backend::DrawingBackend;
 
 /// Draws the active line highlight if enabled in config
 ///
 /// # Arguments
 /// * `buf` - EditorBuffer reference
-/// * `ctx` - Cairo context
+/// * `backend` - Drawing backend
 /// * `layout` - LayoutMetrics for positioning
 /// * `width` - Total editor width
-pub fn render_highlight_layer(buf: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics, width: i32) {
+pub fn render_highlight_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
     let gutter_config = buf.config.gutter();
-    let line_height = layout.line_height;
     let row = buf.cursor.row.min(buf.lines.len().saturating_sub(1));
-    let y_line = layout.top_offset + line_height * row as f64;
+    let line_height = layout.row_height(row);
+    let y_line = layout.row_y(row);
     let y_baseline = y_line + layout.text_metrics.baseline_offset;
     if gutter_config.active_line.highlight_toggle {
         let highlight_color = &gutter_config.active_line.highlight_color;
-        let (hr, hg, hb, _) = crate::corelogic::gutter::parse_color(highlight_color);
-        ctx.set_source_rgba(hr, hg, hb, gutter_config.active_line.highlight_opacity);
-        ctx.rectangle(0.0, y_baseline, width as f64, line_height);
-        ctx.fill().unwrap();
+        let (hr, hg, hb, _) = buf.config.color(highlight_color).rgba();
+        backend.fill_rect(0.0, y_baseline, width as f64, line_height, (hr, hg, hb, gutter_config.active_line.highlight_opacity));
     }
 }
@@ -0,0 +1,23 @@
+//! Draws inline image placeholders in A4 (document) mode
+use crate::corelogic::EditorBuffer;
+use crate::render::backend::DrawingBackend;
+use crate::render::layout::LayoutMetrics;
+
+/// Draws a framed thumbnail for every image anchor row, scaled into the row height
+///
+/// The full-size image is reserved in the page layout and drawn by export/print;
+/// the editor keeps uniform row heights, so only a placeholder is shown here.
+pub fn render_image_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
+    if !buf.config.a4_mode {
+        return;
+    }
+    let (r, g, b, a) = buf.config.color(&buf.config.page_break_color).rgba();
+    for anchor in buf.image_anchors().into_iter().filter(|anchor| !layout.hidden_rows.is_hidden(anchor.row)) {
+        let y = layout.row_y(anchor.row);
+        let x = layout.text_left_offset;
+        backend.fill_rect(x, y, width as f64 - x, layout.line_height, (r, g, b, a * 0.15));
+        if let Some(pixbuf) = buf.image_thumbnails.thumbnail(&anchor.path, layout.line_height as i32) {
+            backend.draw_image(&pixbuf, width as f64 - pixbuf.width() as f64 - 4.0, y);
+        }
+    }
+}
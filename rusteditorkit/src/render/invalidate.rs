@@ -1,2 +1,105 @@
-//! Dirty region tracking and optimized partial redraw logic
-// Stub for future implementation
+//! Cached layers for partial redraws
+//!
+//! Text and gutter are the expensive layers (every line is shaped by Pango), so
+//! `LayerCache` keeps each in an offscreen surface and replays it while the buffer has
+//! not invalidated it (`RedrawLayers::TEXT`, `RedrawLayers::GUTTER`) and the view
+//! geometry it was drawn for (size, scroll position, line height) still holds. The other
+//! layers are cheap and drawn every frame.
+//!
+//! Code that changes the buffer behind its methods (e.g. writing `lines` directly) has
+//! to call `request_redraw` for the cached layers to notice.
+
+use std::cell::RefCell;
+use gtk4::cairo::{Content, Context, Surface};
+use crate::corelogic::{EditorBuffer, RedrawLayers};
+use crate::render::layout::LayoutMetrics;
+
+/// View geometry a layer was drawn for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LayerKey {
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) top_offset: f64,
+    pub(crate) line_height: f64,
+    pub(crate) text_left_offset: f64,
+    pub(crate) text_right_offset: f64,
+}
+
+impl LayerKey {
+    pub(crate) fn new(layout: &LayoutMetrics, width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            top_offset: layout.top_offset,
+            line_height: layout.line_height,
+            text_left_offset: layout.text_left_offset,
+            text_right_offset: layout.text_right_offset,
+        }
+    }
+
+    /// Same geometry, possibly scrolled to another position
+    pub(crate) fn same_apart_from_scroll(&self, other: &Self) -> bool {
+        Self { top_offset: 0.0, ..*self } == Self { top_offset: 0.0, ..*other }
+    }
+}
+
+type CachedLayer = RefCell<Option<(LayerKey, Surface)>>;
+
+/// Offscreen copies of the text and gutter layers, kept across frames
+#[derive(Default)]
+pub struct LayerCache {
+    text: CachedLayer,
+    gutter: CachedLayer,
+}
+
+impl LayerCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the cached layers, so the next frame draws them again
+    pub fn clear(&self) {
+        self.text.replace(None);
+        self.gutter.replace(None);
+    }
+
+    /// Draw the text layer, replaying the cached one unless `dirty` has `TEXT`
+    pub fn text_layer(&self, rkit: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics, (width, height): (i32, i32), dirty: RedrawLayers) {
+        paint_cached(&self.text, ctx, LayerKey::new(layout, width, height), dirty.contains(RedrawLayers::TEXT), |target| {
+            crate::render::text::render_text_layer(rkit, target, layout);
+        });
+    }
+
+    /// Draw the gutter layer, replaying the cached one unless `dirty` has `GUTTER`
+    pub fn gutter_layer(&self, rkit: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics, (width, height): (i32, i32), dirty: RedrawLayers) {
+        paint_cached(&self.gutter, ctx, LayerKey::new(layout, width, height), dirty.contains(RedrawLayers::GUTTER), |target| {
+            crate::render::gutter::render_gutter_layer(rkit, target, layout, height);
+        });
+    }
+}
+
+/// Paint the layer in `slot` onto `ctx`, drawing it afresh with `draw` when it is dirty,
+/// missing or drawn for another geometry
+fn paint_cached(slot: &CachedLayer, ctx: &Context, key: LayerKey, dirty: bool, draw: impl FnOnce(&Context)) {
+    let mut slot = slot.borrow_mut();
+    if dirty || slot.as_ref().is_none_or(|(cached, _)| *cached != key) {
+        // Left empty if drawing panics, so a half-drawn layer is never replayed
+        *slot = None;
+        let Some((surface, target)) = ctx.target().create_similar(Content::ColorAlpha, key.width, key.height).ok()
+            .and_then(|surface| Context::new(&surface).ok().map(|target| (surface, target)))
+        else {
+            draw(ctx);
+            return;
+        };
+        draw(&target);
+        drop(target);
+        *slot = Some((key, surface));
+    }
+    if let Some((_, surface)) = slot.as_ref() {
+        let _ = ctx.save();
+        if ctx.set_source_surface(surface, 0.0, 0.0).is_ok() {
+            let _ = ctx.paint();
+        }
+        let _ = ctx.restore();
+    }
+}
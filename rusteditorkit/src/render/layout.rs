@@ -2,6 +2,10 @@
 use gtk4::cairo::Context;
 use gtk4::pango;
 use crate::corelogic::EditorBuffer;
+use crate::corelogic::folding::HiddenRows;
+use crate::corelogic::gutter::GutterColumnSpan;
+use crate::corelogic::lineheights::RowOffsets;
+use crate::render::backend::DrawingBackend;
 
 #[derive(Debug, Clone)]
 pub struct FontMetrics {
@@ -18,12 +22,31 @@ pub struct LayoutMetrics {
     pub text_metrics: FontMetrics,
     pub gutter_metrics: FontMetrics,
     pub text_left_offset: f64,
+    /// Y position of row 0 (negative once the view is scrolled)
     pub top_offset: f64,
+    /// Y position of the top of the visible text area
+    pub viewport_top: f64,
+    /// Extra advance of each space on top of `text_metrics.average_char_width`
+    pub word_spacing: f64,
+    /// X position where the text area ends (right-to-left paragraphs align to it)
+    pub text_right_offset: f64,
+    /// Visible gutter columns (mirrored to the right side of the view when it is RTL)
+    pub gutter_columns: Vec<GutterColumnSpan>,
+    /// Rows inside collapsed folds, which take no vertical space
+    pub hidden_rows: HiddenRows,
+    /// Y position where the visible text area ends
+    pub viewport_bottom: f64,
+    /// Tops of the rows on screen when the host overrides line heights
+    pub row_offsets: Option<RowOffsets>,
 }
 
 impl FontMetrics {
     pub fn calculate(ctx: &Context, font_desc: &pango::FontDescription) -> Self {
-        let layout = pangocairo::functions::create_layout(ctx);
+        Self::measure(pangocairo::functions::create_layout(ctx), font_desc)
+    }
+
+    /// Measure with an already prepared layout (e.g. one carrying font rendering options)
+    pub fn measure(layout: pango::Layout, font_desc: &pango::FontDescription) -> Self {
         layout.set_font_description(Some(font_desc));
         layout.set_text("Hg");
         let baseline = layout.baseline() as f64 / pango::SCALE as f64;
@@ -45,31 +68,240 @@ impl FontMetrics {
 }
 
 impl LayoutMetrics {
-    pub fn calculate(rkit: &EditorBuffer, ctx: &Context) -> Self {
+    /// Metrics with fixed values instead of measured fonts, for headless tests
+    ///
+    /// Every character is `char_width` wide, rows are `line_height` tall and the view is
+    /// not scrolled nor bounded below.
+    pub fn synthetic(line_height: f64, char_width: f64, text_left_offset: f64, viewport_top: f64, view_width: f64) -> Self {
+        let font = FontMetrics {
+            font_desc: pango::FontDescription::new(),
+            baseline: line_height * 0.8,
+            height: line_height,
+            baseline_offset: line_height * 0.8,
+            average_char_width: char_width,
+        };
+        Self {
+            line_height,
+            text_metrics: font.clone(),
+            gutter_metrics: font,
+            text_left_offset,
+            top_offset: viewport_top,
+            viewport_top,
+            word_spacing: 0.0,
+            text_right_offset: view_width,
+            gutter_columns: Vec::new(),
+            hidden_rows: HiddenRows::default(),
+            viewport_bottom: f64::INFINITY,
+            row_offsets: None,
+        }
+    }
+
+    pub fn calculate(rkit: &EditorBuffer, ctx: &Context, width: i32, height: i32) -> Self {
         let font_cfg = &rkit.config.font;
-        let font_string = format!("{} {}", font_cfg.font_name(), font_cfg.font_size());
-        let text_font_desc = pango::FontDescription::from_string(&font_string);
+        let text_font_desc = font_description(rkit, rkit.zoomed_font_size());
         // Gutter uses the same font as text for alignment
         let gutter_font_desc = text_font_desc.clone();
-        let mut text_metrics = FontMetrics::calculate(ctx, &text_font_desc);
-        let mut gutter_metrics = FontMetrics::calculate(ctx, &gutter_font_desc);
+        let mut text_metrics = FontMetrics::measure(crate::render::fontoptions::create_layout(rkit, ctx), &text_font_desc);
+        let mut gutter_metrics = FontMetrics::measure(crate::render::fontoptions::create_layout(rkit, ctx), &gutter_font_desc);
         let line_height = text_metrics.height
             .max(gutter_metrics.height)
-            .max(font_cfg.font_line_height());
+            .max(font_cfg.font_line_height() * rkit.zoom_level());
         text_metrics.baseline_offset = (line_height - text_metrics.height) / 2.0 + text_metrics.baseline;
+        // Letter spacing is applied as a Pango attribute to every text character
+        text_metrics.average_char_width += rkit.letter_spacing_px();
         gutter_metrics.baseline_offset = (line_height - gutter_metrics.height) / 2.0 + gutter_metrics.baseline;
-        let text_left_offset = if rkit.config.gutter.toggle {
-            rkit.config.gutter.ltr_width as f64 + rkit.config.margin_left
+        let scroll_map_width = if rkit.config.scroll_map { rkit.config.scroll_map_width } else { 0.0 };
+        let view_right = width as f64 - scroll_map_width;
+        let gutter_columns = rkit.gutter_columns(gutter_metrics.average_char_width, view_right);
+        let gutter_width: f64 = gutter_columns.iter().map(|span| span.width).sum();
+        // An RTL view mirrors the gutter (annotations stay next to the text) and the margins
+        let (text_left_offset, text_right_offset) = if rkit.view_is_rtl() {
+            (rkit.config.margin_right, view_right - gutter_width - rkit.config.margin_left)
         } else {
-            rkit.config.margin_left
+            (gutter_width + rkit.config.margin_left, view_right - rkit.config.margin_right)
         };
-        let top_offset = rkit.config.margin_top;
+        let text_right_offset = text_right_offset.max(text_left_offset);
+        let viewport_top = rkit.config.margin_top;
+        let top_offset = viewport_top - rkit.scroll_position() * line_height;
+        let viewport_bottom = height as f64;
+        // The partly scrolled first row moves by a fraction of its own height
+        let first_height = rkit.row_height(rkit.row_at_display(rkit.scroll_offset), line_height);
+        let row_offsets = rkit.row_offsets(viewport_top - rkit.scroll_fraction * first_height, viewport_bottom, line_height);
         Self {
             line_height,
             text_metrics,
             gutter_metrics,
             text_left_offset,
             top_offset,
+            viewport_top,
+            word_spacing: rkit.word_spacing_px(),
+            text_right_offset,
+            gutter_columns,
+            hidden_rows: rkit.hidden_rows(),
+            viewport_bottom,
+            row_offsets,
+        }
+    }
+
+    /// Y position of the top of buffer row `row` (a folded row shares its fold header's)
+    pub fn row_y(&self, row: usize) -> f64 {
+        let display = self.hidden_rows.display_row(row);
+        match &self.row_offsets {
+            Some(offsets) => offsets.top(display),
+            None => self.top_offset + display as f64 * self.line_height,
+        }
+    }
+
+    /// Height of buffer row `row`, `line_height` unless the host overrides it
+    pub fn row_height(&self, row: usize) -> f64 {
+        match &self.row_offsets {
+            Some(offsets) => offsets.height(self.hidden_rows.display_row(row)),
+            None => self.line_height,
+        }
+    }
+
+    /// Number of whole rows that fit in the text area
+    pub fn rows_fitting(&self) -> usize {
+        match &self.row_offsets {
+            Some(offsets) => offsets.rows_fitting(self.viewport_bottom),
+            None => ((self.viewport_bottom - self.viewport_top) / self.line_height).max(1.0) as usize,
+        }
+    }
+
+    /// Display row at the top of the text area
+    fn first_visible_display_row(&self) -> usize {
+        match &self.row_offsets {
+            Some(offsets) => offsets.display_row_at(self.viewport_top),
+            None => ((self.viewport_top - self.top_offset) / self.line_height).floor().max(0.0) as usize,
+        }
+    }
+
+    /// Buffer row at the top of the text area (partly scrolled out or not)
+    pub fn first_visible_row(&self) -> usize {
+        self.hidden_rows.row_at_display(self.first_visible_display_row())
+    }
+
+    /// Buffer rows on screen, top to bottom, skipping folded rows
+    pub fn visible_rows(&self, rkit: &EditorBuffer) -> Vec<usize> {
+        let first = self.first_visible_display_row();
+        let end = match &self.row_offsets {
+            Some(offsets) => offsets.first + offsets.tops.len(),
+            None => first + rkit.viewport_rows() + 1,
+        };
+        (first..end)
+            .map(|display| self.hidden_rows.row_at_display(display))
+            .take_while(|&row| row < rkit.lines.len())
+            .collect()
+    }
+}
+
+/// Font description for `size` using the configured font and its fallback chain
+pub fn font_description(rkit: &EditorBuffer, size: f64) -> pango::FontDescription {
+    let font_cfg = &rkit.config.font;
+    let mut desc = pango::FontDescription::from_string(&format!("{} {}", font_cfg.font_name(), size));
+    desc.set_family(&font_cfg.font_family_chain());
+    desc
+}
+
+/// Letter and word spacing attributes for a text line
+///
+/// Pango's `set_spacing` is inter-line spacing; horizontal spacing needs attributes.
+/// Spaces get the letter spacing plus the word spacing.
+pub fn spacing_attributes(rkit: &EditorBuffer, line: &str) -> pango::AttrList {
+    let attrs = pango::AttrList::new();
+    let letter = rkit.letter_spacing_px();
+    let word = rkit.word_spacing_px();
+    if letter != 0.0 {
+        attrs.insert(pango::AttrInt::new_letter_spacing((letter * pango::SCALE as f64) as i32));
+    }
+    if word != 0.0 {
+        let spacing = ((letter + word) * pango::SCALE as f64) as i32;
+        for (index, _) in line.match_indices(' ') {
+            let mut attr = pango::AttrInt::new_letter_spacing(spacing);
+            attr.set_start_index(index as u32);
+            attr.set_end_index(index as u32 + 1);
+            attrs.insert(attr);
         }
     }
+    attrs
+}
+
+/// Measure the buffer's decimal tab stops with the text font, unless they are up to date
+///
+/// `measuring` makes the layout to measure with; it is only called when measuring.
+pub fn refresh_decimal_tabs(rkit: &EditorBuffer, measuring: impl FnOnce() -> pango::Layout, layout: &LayoutMetrics) {
+    let font_desc = &layout.text_metrics.font_desc;
+    let measured_with = format!("{} {} {}", font_desc, rkit.letter_spacing_px(), rkit.word_spacing_px());
+    if !rkit.decimal_tabs_stale(&measured_with) {
+        return;
+    }
+    let pango_layout = measuring();
+    pango_layout.set_font_description(Some(font_desc));
+    let measure = |text: &str| {
+        pango_layout.set_text(text);
+        pango_layout.set_attributes(Some(&spacing_attributes(rkit, text)));
+        pango_layout.extents().1.width() as f64 / pango::SCALE as f64
+    };
+    // At least two spaces between adjacent cells
+    let gap = measure("  ");
+    rkit.refresh_decimal_tabs(&measured_with, measure, gap);
+}
+
+/// Set `stops` (pixels from the start of the text) as the tab stops of `pango_layout`
+pub fn apply_tab_stops(pango_layout: &pango::Layout, stops: &[f64]) {
+    if stops.is_empty() {
+        return;
+    }
+    let mut tabs = pango::TabArray::new(stops.len() as i32, true);
+    for (index, &x) in stops.iter().enumerate() {
+        tabs.set_tab(index as i32, pango::TabAlign::Left, x.round() as i32);
+    }
+    pango_layout.set_tabs(Some(&tabs));
+}
+
+/// Pango layout of buffer row `row` as the text layer lays it out, for measuring positions
+pub fn row_layout(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, row: usize) -> pango::Layout {
+    let line = rkit.lines.get(row).map_or("", String::as_str);
+    let pango_layout = crate::render::fontoptions::create_text_run(rkit, backend);
+    pango_layout.set_text(line);
+    pango_layout.set_font_description(Some(&crate::render::text::text_font_description(rkit, layout)));
+    pango_layout.set_attributes(Some(&spacing_attributes(rkit, line)));
+    if !rkit.is_log_buffer() {
+        refresh_decimal_tabs(rkit, || crate::render::fontoptions::create_text_run(rkit, backend), layout);
+        apply_tab_stops(&pango_layout, &rkit.tab_stops(row));
+    }
+    apply_direction(&pango_layout, rkit.is_rtl_row(row), layout);
+    pango_layout
+}
+
+/// X position of the caret before char column `col` of `line`, laid out in `pango_layout`
+///
+/// Uses the extents of the cluster at that column, so wide CJK characters and emoji
+/// get their shaped width; columns inside a multi-codepoint cluster share its edge.
+pub fn column_x(pango_layout: &pango::Layout, line: &str, col: usize, layout: &LayoutMetrics) -> f64 {
+    layout.text_left_offset + pango_layout.index_to_pos(byte_index(line, col)).x() as f64 / pango::SCALE as f64
+}
+
+/// Width of the cluster at char column `col` of `line` (negative in right-to-left runs,
+/// 0 past the end of the line)
+pub fn column_width(pango_layout: &pango::Layout, line: &str, col: usize) -> f64 {
+    pango_layout.index_to_pos(byte_index(line, col)).width() as f64 / pango::SCALE as f64
+}
+
+fn byte_index(line: &str, col: usize) -> i32 {
+    line.char_indices().nth(col).map_or(line.len(), |(index, _)| index) as i32
+}
+
+/// Lay out a row in its resolved base direction
+///
+/// Right-to-left rows get an explicit base direction and are right-aligned in the text area.
+pub fn apply_direction(pango_layout: &pango::Layout, rtl: bool, layout: &LayoutMetrics) {
+    pango_layout.set_auto_dir(false);
+    pango_layout.context().set_base_dir(if rtl { pango::Direction::Rtl } else { pango::Direction::Ltr });
+    pango_layout.context_changed();
+    if rtl {
+        let width = (layout.text_right_offset - layout.text_left_offset).max(0.0);
+        pango_layout.set_width((width * pango::SCALE as f64) as i32);
+        pango_layout.set_alignment(pango::Alignment::Right);
+    }
 }
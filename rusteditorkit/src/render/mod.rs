@@ -4,14 +4,64 @@ use crate::corelogic::buffer::EditorBuffer;
 
 /// Main rendering entry point with layered architecture
 pub fn render_editor(rkit: &EditorBuffer, ctx: &Context, width: i32, height: i32) {
-    let layout = LayoutMetrics::calculate(rkit, ctx);
-    background::render_background_layer(rkit, ctx, width, height);
-    gutter::render_gutter_layer(rkit, ctx, &layout, height);
-    highlight::render_highlight_layer(rkit, ctx, &layout, width);
-    selection::render_selection_layer(rkit, ctx, &layout, width);
-    text::render_text_layer(rkit, ctx, &layout);
+    render_frame(rkit, ctx, width, height, None);
 }
 
+/// Like `render_editor`, replaying the layers of `cache` that `rkit` has not invalidated
+/// since the previous frame
+pub fn render_editor_cached(rkit: &EditorBuffer, ctx: &Context, width: i32, height: i32, cache: &LayerCache) {
+    render_frame(rkit, ctx, width, height, Some(cache));
+}
+
+fn render_frame(rkit: &EditorBuffer, ctx: &Context, width: i32, height: i32, cache: Option<&LayerCache>) {
+    let dirty = rkit.take_dirty_layers();
+    // A panicking layer skips the rest of this frame instead of aborting the host
+    let drawn = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let layout = frame_layout(rkit, ctx, width, height);
+        background::render_background_layer(rkit, ctx, width, height);
+        #[cfg(feature = "vertical-text")]
+        if rkit.is_vertical_mode() {
+            vertical::render_vertical_text_layer(rkit, ctx, &layout, height);
+            scrollmap::render_scroll_map_layer(rkit, ctx, width, height);
+            return;
+        }
+        match cache {
+            Some(cache) => cache.gutter_layer(rkit, ctx, &layout, (width, height), dirty),
+            None => gutter::render_gutter_layer(rkit, ctx, &layout, height),
+        }
+        highlight::render_highlight_layer(rkit, ctx, &layout, width);
+        flash::render_line_flash_layer(rkit, ctx, &layout, width);
+        pagebreak::render_page_break_layer(rkit, ctx, &layout, width);
+        images::render_image_layer(rkit, ctx, &layout, width);
+        selection::render_selection_layer(rkit, ctx, &layout, width);
+        occurrences::render_occurrence_layer(rkit, ctx, &layout);
+        match cache {
+            Some(cache) => cache.text_layer(rkit, ctx, &layout, (width, height), dirty),
+            None => text::render_text_layer(rkit, ctx, &layout),
+        }
+        remote::render_remote_cursor_layer(rkit, ctx, &layout);
+        focus::render_focus_layer(rkit, ctx, &layout, width);
+        sticky::render_sticky_scroll_layer(rkit, ctx, &layout, width);
+        scrollmap::render_scroll_map_layer(rkit, ctx, width, height);
+        cursor::render_caret(rkit, ctx, &layout);
+    }));
+    if let Err(payload) = drawn {
+        eprintln!("[ERROR] Rendering panicked: {}", crate::corelogic::recovery::panic_message(payload.as_ref()));
+    }
+}
+
+/// Measure the view for a frame and tell the buffer what fits in it
+fn frame_layout(rkit: &EditorBuffer, ctx: &Context, width: i32, height: i32) -> LayoutMetrics {
+    let layout = LayoutMetrics::calculate(rkit, ctx, width, height);
+    rkit.set_viewport_rows(layout.rows_fitting());
+    rkit.set_viewport_line_height(layout.line_height);
+    rkit.set_viewport_text_right(layout.text_right_offset);
+    rkit.set_viewport_text_geometry(layout.text_left_offset, layout.text_metrics.average_char_width);
+    rkit.set_viewport_gutter_char_width(layout.gutter_metrics.average_char_width);
+    layout
+}
+
+pub mod backend;
 pub mod background;
 pub mod gutter;
 pub mod text;
@@ -22,6 +72,18 @@ pub mod cache;
 pub mod invalidate;
 pub mod highlight;
 pub mod selection;
+pub mod pagebreak;
+pub mod images;
+pub mod sticky;
+pub mod focus;
+pub mod flash;
+pub mod scrollmap;
+pub mod fontoptions;
+pub mod remote;
+pub mod occurrences;
+pub mod snapshot;
+#[cfg(feature = "vertical-text")]
+pub mod vertical;
 
 // Publicly re-export main types and entry points
 pub use background::render_background_layer;
@@ -29,4 +91,12 @@ pub use gutter::render_gutter_layer;
 pub use text::render_text_layer;
 pub use cursor::render_cursor_layer;
 pub use layout::{LayoutMetrics, FontMetrics};
-pub use selection::render_selection_layer;
\ No newline at end of file
+pub use invalidate::LayerCache;
+pub use snapshot::{render_editor_snapshot, NodeCache};
+pub use selection::render_selection_layer;
+pub use pagebreak::render_page_break_layer;
+pub use images::render_image_layer;
+pub use sticky::render_sticky_scroll_layer;
+pub use focus::render_focus_layer;
+pub use flash::render_line_flash_layer;
+pub use scrollmap::render_scroll_map_layer;
\ No newline at end of file
@@ -0,0 +1,24 @@
+//! Occurrence highlighting: other places the word under the caret appears
+use crate::corelogic::EditorBuffer;
+use crate::render::backend::DrawingBackend;
+use crate::render::layout::{column_x, row_layout, LayoutMetrics};
+use crate::render::selection::fill_row_span;
+
+/// Draws a background behind every whole-word match of the word under the caret on screen
+pub fn render_occurrence_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics) {
+    if !buf.config.occurrence_highlight {
+        return;
+    }
+    let occurrences = buf.word_occurrences(layout.visible_rows(buf));
+    // A word that appears only under the caret is not worth marking
+    if occurrences.len() < 2 {
+        return;
+    }
+    let color = buf.config.color(&buf.config.occurrence_highlight_color).rgba();
+    for (row, start, end) in occurrences {
+        let row_layout = row_layout(buf, backend, layout, row);
+        let x0 = column_x(&row_layout, &buf.lines[row], start, layout);
+        let x1 = column_x(&row_layout, &buf.lines[row], end, layout);
+        fill_row_span(backend, x0, x1, layout.row_y(row), layout.row_height(row), color);
+    }
+}
@@ -0,0 +1,70 @@
+//! Draws page break separators at computed A4 page boundaries
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::layout::cm_to_px;
+use crate::render::backend::{fill_dashed_hline, DrawingBackend};
+use crate::render::layout::LayoutMetrics;
+
+/// Draws a dashed separator above every row that starts a new page (A4 mode only)
+pub fn render_page_break_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
+    if !buf.config.a4_mode || !buf.config.show_page_breaks {
+        return;
+    }
+    let color = buf.config.color(&buf.config.page_break_color).rgba();
+    for row in buf.page_breaks().into_iter().filter(|&row| !layout.hidden_rows.is_hidden(row)) {
+        // Snap to the pixel grid so the 1px line stays crisp
+        let y = layout.row_y(row).round();
+        fill_dashed_hline(backend, layout.text_left_offset, width as f64, y, (4.0, 3.0), 1.0, color);
+    }
+}
+
+/// Render A4 page boundary and margin guides, centered in a `width` x `height` view
+pub fn render_a4_boundary(buffer: &EditorBuffer, backend: &dyn DrawingBackend, width: i32, height: i32) {
+    if !buffer.config.a4_mode {
+        return;
+    }
+    let (page_width, page_height) = (cm_to_px(21.0), cm_to_px(29.7));
+    let left = cm_to_px(buffer.config.left_margin_cm);
+    let right = cm_to_px(buffer.config.right_margin_cm);
+    let top = cm_to_px(buffer.config.top_margin_cm);
+    let bottom = cm_to_px(buffer.config.bottom_margin_cm);
+    let x = (width as f64 - page_width) / 2.0;
+    let y = (height as f64 - page_height) / 2.0;
+
+    // Page boundary, 2px light gray
+    let gray = (0.8, 0.8, 0.8, 1.0);
+    backend.fill_rect(x - 1.0, y - 1.0, page_width + 2.0, 2.0, gray);
+    backend.fill_rect(x - 1.0, y + page_height - 1.0, page_width + 2.0, 2.0, gray);
+    backend.fill_rect(x - 1.0, y - 1.0, 2.0, page_height + 2.0, gray);
+    backend.fill_rect(x + page_width - 1.0, y - 1.0, 2.0, page_height + 2.0, gray);
+
+    // Margin guides, 1px semi-transparent blue
+    let blue = (0.2, 0.6, 0.9, 0.5);
+    backend.fill_rect(x, y + top - 0.5, page_width, 1.0, blue);
+    backend.fill_rect(x, y + page_height - bottom - 0.5, page_width, 1.0, blue);
+    backend.fill_rect(x + left - 0.5, y, 1.0, page_height, blue);
+    backend.fill_rect(x + page_width - right - 0.5, y, 1.0, page_height, blue);
+
+    // Text area
+    backend.fill_rect(x + left, y + top, page_width - left - right, page_height - top - bottom, (0.9, 0.9, 0.9, 0.3));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::backend::Recorder;
+
+    #[test]
+    fn layers_draw_through_any_backend() {
+        let recorder = Recorder::new();
+        fill_dashed_hline(&recorder, 10.0, 25.0, 4.0, (4.0, 3.0), 1.0, (0.0, 0.0, 0.0, 1.0));
+        assert_eq!(*recorder.rects.borrow(), vec![(10.0, 4.0, 4.0, 1.0), (17.0, 4.0, 4.0, 1.0), (24.0, 4.0, 1.0, 1.0)]);
+
+        let recorder = Recorder::new();
+        let mut buffer = EditorBuffer::new();
+        render_a4_boundary(&buffer, &recorder, 1000, 1200);
+        assert!(recorder.rects.borrow().is_empty());
+        buffer.config.a4_mode = true;
+        render_a4_boundary(&buffer, &recorder, 1000, 1200);
+        assert_eq!(recorder.rects.borrow().len(), 9);
+    }
+}
@@ -0,0 +1,62 @@
+//! Collaborative overlay: remote carets, selections and name flags
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::recovery::clamp_position;
+use crate::render::backend::DrawingBackend;
+use crate::render::layout::LayoutMetrics;
+use crate::render::selection::{calculate_column_x_position, fill_row_span};
+
+/// Opacity of remote selections over the participant's color
+const SELECTION_ALPHA: f64 = 0.25;
+
+/// Draws every remote participant's selection, caret and name flag
+pub fn render_remote_cursor_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics) {
+    if buf.remote_cursors.is_empty() || buf.lines.is_empty() {
+        return;
+    }
+    let row_y = |row: usize| layout.row_y(row);
+    let x_at = |(row, col): (usize, usize)| {
+        calculate_column_x_position(buf, backend, row, col, layout)
+    };
+
+    for remote in &buf.remote_cursors {
+        let (r, g, b, _) = remote.color.rgba();
+
+        if let Some((start, end)) = remote.selection_range() {
+            let start = clamp_position(&buf.lines, start.0, start.1);
+            let end = clamp_position(&buf.lines, end.0, end.1);
+            for row in (start.0..=end.0).filter(|&row| !layout.hidden_rows.is_hidden(row)) {
+                let line_len = buf.lines[row].chars().count();
+                let from = if row == start.0 { start.1 } else { 0 };
+                let to = if row == end.0 { end.1 } else { line_len };
+                fill_row_span(backend, x_at((row, from)), x_at((row, to)), row_y(row), layout.line_height, (r, g, b, SELECTION_ALPHA));
+            }
+        }
+
+        let (row, col) = clamp_position(&buf.lines, remote.pos.0, remote.pos.1);
+        let x = x_at((row, col));
+        let y = row_y(row);
+        if y + layout.line_height < layout.viewport_top {
+            continue;
+        }
+        backend.fill_rect(x - 1.0, y, 2.0, layout.line_height, (r, g, b, 1.0));
+        render_name_flag(buf, backend, layout, &remote.label, x, y, (r, g, b));
+    }
+}
+
+/// Small colored tag with the participant's name, sitting on top of the caret
+fn render_name_flag(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, label: &str, x: f64, y: f64, (r, g, b): (f64, f64, f64)) {
+    if label.is_empty() {
+        return;
+    }
+    let pango_layout = crate::render::fontoptions::create_text_run(buf, backend);
+    let mut font_desc = layout.gutter_metrics.font_desc.clone();
+    font_desc.set_size((font_desc.size() as f64 * 0.75) as i32);
+    pango_layout.set_font_description(Some(&font_desc));
+    pango_layout.set_text(label);
+    let (w, h) = pango_layout.pixel_size();
+    let (w, h) = (w as f64 + 6.0, h as f64 + 2.0);
+    // Above the caret, or inside the row when that would leave the viewport
+    let top = if y - h >= layout.viewport_top { y - h } else { y };
+    backend.fill_rect(x - 1.0, top, w, h, (r, g, b, 1.0));
+    backend.draw_text_run(&pango_layout, x + 2.0, top + 1.0, (1.0, 1.0, 1.0, 1.0));
+}
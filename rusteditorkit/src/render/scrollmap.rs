@@ -0,0 +1,63 @@
+//! Scroll map rendering: track along the right edge with the visible-range thumb and
+//! colored marks for modified lines, search matches, bookmarks and diagnostics
+
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::scrollmap::ScrollMarkKind;
+use crate::render::backend::DrawingBackend;
+
+/// Draws the scroll map at the right edge of the editor
+pub fn render_scroll_map_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, width: i32, height: i32) {
+    if !buf.config.scroll_map || buf.lines.is_empty() {
+        return;
+    }
+    let cfg = &buf.config;
+    let track_width = cfg.scroll_map_width;
+    let track_height = height as f64;
+    let x = width as f64 - track_width;
+
+    // Track and thumb for the visible rows
+    let (r, g, b, _) = buf.config.color(&cfg.scroll_map_track_color).rgba();
+    backend.fill_rect(x, 0.0, track_width, track_height, (r, g, b, 0.5));
+    let thumb_top = buf.scroll_map_y(buf.scroll_offset, track_height);
+    let thumb_bottom = buf.scroll_map_y(buf.scroll_offset + buf.viewport_rows().max(1), track_height);
+    let thumb_height = (thumb_bottom - thumb_top).max(4.0).min(track_height);
+    backend.fill_rect(x, thumb_top, track_width, thumb_height, (r * 0.8, g * 0.8, b * 0.8, 0.6));
+
+    // Marks: modified lines in a narrow left lane, everything else across the track
+    for mark in buf.scroll_marks() {
+        let color = match mark.kind {
+            ScrollMarkKind::Modified => &cfg.scroll_map_modified_color,
+            ScrollMarkKind::SearchMatch => &cfg.scroll_map_search_color,
+            ScrollMarkKind::Bookmark => &cfg.scroll_map_bookmark_color,
+            ScrollMarkKind::Warning => &cfg.scroll_map_warning_color,
+            ScrollMarkKind::Error => &cfg.scroll_map_error_color,
+        };
+        let y = buf.scroll_map_y(mark.row, track_height);
+        let (mark_x, mark_width) = match mark.kind {
+            ScrollMarkKind::Modified => (x, track_width / 3.0),
+            _ => (x + track_width / 3.0, track_width * 2.0 / 3.0),
+        };
+        backend.fill_rect(mark_x, y, mark_width, 2.0, buf.config.color(color).rgba());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::backend::Recorder;
+
+    #[test]
+    fn map_draws_the_track_thumb_and_marks() {
+        let recorder = Recorder::new();
+        let mut buffer = EditorBuffer::new();
+        buffer.lines = vec![String::new(); 10];
+        render_scroll_map_layer(&buffer, &recorder, 1000, 1200);
+        assert!(recorder.rects.borrow().is_empty());
+
+        // Track, thumb over the first row, then a bookmark halfway down
+        buffer.config.scroll_map = true;
+        buffer.bookmarks.insert(5);
+        render_scroll_map_layer(&buffer, &recorder, 1000, 1200);
+        assert_eq!(*recorder.rects.borrow(), vec![(988.0, 0.0, 12.0, 1200.0), (988.0, 0.0, 12.0, 120.0), (992.0, 600.0, 8.0, 2.0)]);
+    }
+}
@@ -2,18 +2,17 @@
 //! This module draws text selection backgrounds using unified line height and selection config
 
 use crate::corelogic::EditorBuffer;
-use crate::render::layout::LayoutMetrics;
-use crate::corelogic::gutter::parse_color;
-use cairo::Context;
+use crate::render::backend::{DrawingBackend, Rgba};
+use crate::render::layout::{column_x, row_layout, LayoutMetrics};
 
 /// Draws text selection backgrounds if a selection exists
 ///
 /// # Arguments
 /// * `buf` - EditorBuffer reference containing selection state
-/// * `ctx` - Cairo context for drawing
+/// * `backend` - Drawing backend
 /// * `layout` - LayoutMetrics for positioning calculations
 /// * `width` - Total editor width for full-line selections
-pub fn render_selection_layer(buf: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics, width: i32) {
+pub fn render_selection_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
     // Early return if no selection exists
     let selection = match &buf.selection {
         Some(sel) => sel,
@@ -34,11 +33,11 @@ pub fn render_selection_layer(buf: &EditorBuffer, ctx: &Context, layout: &Layout
 
     // Parse selection background color from config
     let bg_color = &selection_config.selection_bg_color;
-    let (r, g, b, _) = parse_color(bg_color);
+    let (r, g, b, _) = buf.config.color(bg_color).rgba();
     let opacity = selection_config.selection_opacity;
     
     println!("[SELECTION RENDER DEBUG] Color: r={}, g={}, b={}, opacity={}", r, g, b, opacity);
-    ctx.set_source_rgba(r, g, b, opacity);
+    let color = (r, g, b, opacity);
 
     // Get normalized selection coordinates
     let ((start_row, start_col), (end_row, end_col)) = selection.normalized();
@@ -48,16 +47,15 @@ pub fn render_selection_layer(buf: &EditorBuffer, ctx: &Context, layout: &Layout
     if start_row == end_row {
                 // Single-line selection
         println!("[SELECTION RENDER DEBUG] Single-line selection");
-        render_single_line_selection_coords(ctx, start_row, start_col, end_col, layout, buf);
+        render_single_line_selection_coords(backend, color, start_row, start_col, end_col, layout, buf);
     } else {
         // Multi-line selection
         render_multi_line_selection_coords(
-            ctx, 
-            start_row,
-            start_col,
-            end_row,
-            end_col,
-            layout, 
+            backend,
+            color,
+            (start_row, start_col),
+            (end_row, end_col),
+            layout,
             buf,
             width
         );
@@ -66,7 +64,8 @@ pub fn render_selection_layer(buf: &EditorBuffer, ctx: &Context, layout: &Layout
 
 /// Renders selection on a single line
 fn render_single_line_selection_coords(
-    ctx: &Context,
+    backend: &dyn DrawingBackend,
+    color: Rgba,
     row: usize,
     start_col: usize,
     end_col: usize,
@@ -87,17 +86,17 @@ fn render_single_line_selection_coords(
     println!("[SELECTION RENDER DEBUG] Line: '{}', clamped start_col={}, end_col={}", line, start_col, end_col);
     
     // Calculate pixel positions for start and end columns
-    let start_x = calculate_column_x_position(line, start_col, layout);
-    let end_x = calculate_column_x_position(line, end_col, layout);
+    let row_layout = row_layout(buf, backend, layout, row);
+    let start_x = column_x(&row_layout, line, start_col, layout);
+    let end_x = column_x(&row_layout, line, end_col, layout);
     
-    let y_line = layout.top_offset + row as f64 * layout.line_height;
-    let selection_width = end_x - start_x;
+    let y_line = layout.row_y(row);
+    let selection_width = (end_x - start_x).abs();
     
     println!("[SELECTION RENDER DEBUG] Positions: start_x={}, end_x={}, y_line={}, width={}", start_x, end_x, y_line, selection_width);
     
     if selection_width > 0.0 {
-        ctx.rectangle(start_x, y_line, selection_width, layout.line_height);
-        ctx.fill().unwrap();
+        fill_row_span(backend, start_x, end_x, y_line, layout.row_height(row), color);
         println!("[SELECTION RENDER DEBUG] Rectangle drawn and filled");
     } else {
         println!("[SELECTION RENDER DEBUG] Selection width <= 0, not drawing");
@@ -106,68 +105,72 @@ fn render_single_line_selection_coords(
 
 /// Renders selection spanning multiple lines
 fn render_multi_line_selection_coords(
-    ctx: &Context,
-    start_row: usize,
-    start_col: usize,
-    end_row: usize,
-    end_col: usize,
+    backend: &dyn DrawingBackend,
+    color: Rgba,
+    (start_row, start_col): (usize, usize),
+    (end_row, end_col): (usize, usize),
     layout: &LayoutMetrics,
     buf: &EditorBuffer,
     width: i32,
 ) {
     let text_left_offset = layout.text_left_offset;
     let right_edge = width as f64;
+    let rtl_rows = buf.rtl_rows();
     
     for row in start_row..=end_row {
         if row >= buf.lines.len() {
             break;
         }
+        if layout.hidden_rows.is_hidden(row) {
+            continue;
+        }
         
         let line = &buf.lines[row];
-        let y_line = layout.top_offset + row as f64 * layout.line_height;
+        let y_line = layout.row_y(row);
+        // Where the line starts and where the selection runs out to, mirrored for RTL rows
+        let rtl = rtl_rows.get(row).copied().unwrap_or(false);
+        let (line_start_x, line_end_x) = if rtl {
+            (layout.text_right_offset, text_left_offset)
+        } else {
+            (text_left_offset, right_edge)
+        };
         
         if row == start_row {
             // First line: from start_col to end of line
             let start_col = start_col.min(line.chars().count());
-            let start_x = calculate_column_x_position(line, start_col, layout);
-            let width = right_edge - start_x;
-            
-            if width > 0.0 {
-                ctx.rectangle(start_x, y_line, width, layout.line_height);
-                ctx.fill().unwrap();
-            }
+            let start_x = calculate_column_x_position(buf, backend, row, start_col, layout);
+            fill_row_span(backend, start_x, line_end_x, y_line, layout.row_height(row), color);
         } else if row == end_row {
             // Last line: from start of line to end_col
             let end_col = end_col.min(line.chars().count());
-            let end_x = calculate_column_x_position(line, end_col, layout);
-            let width = end_x - text_left_offset;
-            
-            if width > 0.0 {
-                ctx.rectangle(text_left_offset, y_line, width, layout.line_height);
-                ctx.fill().unwrap();
-            }
+            let end_x = calculate_column_x_position(buf, backend, row, end_col, layout);
+            fill_row_span(backend, line_start_x, end_x, y_line, layout.row_height(row), color);
         } else {
             // Middle lines: select entire line
-            let line_width = right_edge - text_left_offset;
-            ctx.rectangle(text_left_offset, y_line, line_width, layout.line_height);
-            ctx.fill().unwrap();
+            fill_row_span(backend, line_start_x, line_end_x, y_line, layout.row_height(row), color);
         }
     }
 }
 
-/// Calculates the X pixel position for a given column in a line
-/// This accounts for character width variations and Unicode characters
-fn calculate_column_x_position(
-    _line: &str,
+/// Calculates the X pixel position of char column `col` of buffer row `row`
+///
+/// Measured on the row's Pango layout (see `layout::column_x`), so wide characters,
+/// emoji and right-to-left rows line up with the text layer.
+pub(crate) fn calculate_column_x_position(
+    buf: &EditorBuffer,
+    backend: &dyn DrawingBackend,
+    row: usize,
     col: usize,
     layout: &LayoutMetrics,
 ) -> f64 {
-    if col == 0 {
-        return layout.text_left_offset;
-    }
+    let line = buf.lines.get(row).map_or("", String::as_str);
+    column_x(&row_layout(buf, backend, layout, row), line, col, layout)
+}
 
-    // For now, use a simple approximation based on average character width
-    // This should be replaced with proper Pango text measurement in production
-    let char_width = layout.text_metrics.average_char_width;
-    layout.text_left_offset + (col as f64 * char_width)
+/// Fills the span between two x positions (in either order) of a row with `color`
+pub(crate) fn fill_row_span(backend: &dyn DrawingBackend, x0: f64, x1: f64, y_line: f64, line_height: f64, color: Rgba) {
+    let (left, right) = (x0.min(x1), x0.max(x1));
+    if right > left {
+        backend.fill_rect(left, y_line, right - left, line_height, color);
+    }
 }
@@ -0,0 +1,182 @@
+//! Experimental render path building GTK render nodes
+//!
+//! `render_editor_snapshot` draws a frame into a `GtkSnapshot` instead of a cairo
+//! context, so GSK can composite it on the GPU. Every layer draws through
+//! `DrawingBackend`, so a frame is made of color, text and texture nodes. The vertical
+//! text mode still draws with cairo, into a single cairo node.
+//!
+//! `NodeCache` keeps the text and gutter nodes across frames like `LayerCache` keeps
+//! their surfaces. A text node holds every line of the document (log buffers only their
+//! visible rows), so while nothing depends on the scroll position it is replayed
+//! shifted when the view scrolls instead of being shaped again.
+//!
+//! `EditorView` uses this path when its `snapshot-rendering` property is set.
+
+use std::cell::RefCell;
+use gtk4::cairo::{Context, Format, ImageSurface};
+use gtk4::prelude::*;
+use gtk4::gdk_pixbuf::Pixbuf;
+use gtk4::{gdk, graphene, gsk, pango};
+use crate::corelogic::{EditorBuffer, RedrawLayers};
+use crate::render::backend::{DrawingBackend, Rgba};
+use crate::render::invalidate::LayerKey;
+use crate::render::layout::LayoutMetrics;
+use crate::render::{background, cursor, flash, focus, gutter, highlight, images, occurrences, pagebreak, remote, scrollmap, selection, sticky, text};
+
+/// A `GtkSnapshot` as a drawing backend
+///
+/// Text runs are shaped with `shaping`, the cairo context the frame is measured with,
+/// so glyph positions match the `LayoutMetrics` of the frame.
+pub struct SnapshotBackend<'a> {
+    snapshot: &'a gtk4::Snapshot,
+    shaping: &'a Context,
+}
+
+impl<'a> SnapshotBackend<'a> {
+    pub fn new(snapshot: &'a gtk4::Snapshot, shaping: &'a Context) -> Self {
+        Self { snapshot, shaping }
+    }
+}
+
+fn rgba((r, g, b, a): Rgba) -> gdk::RGBA {
+    gdk::RGBA::new(r as f32, g as f32, b as f32, a as f32)
+}
+
+fn rect(x: f64, y: f64, width: f64, height: f64) -> graphene::Rect {
+    graphene::Rect::new(x as f32, y as f32, width as f32, height as f32)
+}
+
+impl DrawingBackend for SnapshotBackend<'_> {
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64, color: Rgba) {
+        self.snapshot.append_color(&rgba(color), &rect(x, y, width, height));
+    }
+
+    fn fill_rounded_rect(&self, x: f64, y: f64, width: f64, height: f64, radius: f64, color: Rgba) {
+        let radius = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+        self.snapshot.push_rounded_clip(&gsk::RoundedRect::from_rect(rect(x, y, width, height), radius as f32));
+        self.snapshot.append_color(&rgba(color), &rect(x, y, width, height));
+        self.snapshot.pop();
+    }
+
+    fn create_text_run(&self) -> pango::Layout {
+        pangocairo::functions::create_layout(self.shaping)
+    }
+
+    fn draw_text_run(&self, run: &pango::Layout, x: f64, y: f64, color: Rgba) {
+        self.snapshot.save();
+        self.snapshot.translate(&graphene::Point::new(x as f32, y as f32));
+        self.snapshot.append_layout(run, &rgba(color));
+        self.snapshot.restore();
+    }
+
+    fn draw_image(&self, image: &Pixbuf, x: f64, y: f64) {
+        let texture = gdk::Texture::for_pixbuf(image);
+        self.snapshot.append_texture(&texture, &rect(x, y, image.width() as f64, image.height() as f64));
+    }
+
+    fn push_clip(&self, x: f64, y: f64, width: f64, height: f64) {
+        self.snapshot.push_clip(&rect(x, y, width, height));
+    }
+
+    fn pop_clip(&self) {
+        self.snapshot.pop();
+    }
+}
+
+type CachedNode = RefCell<Option<(LayerKey, gsk::RenderNode)>>;
+
+/// Text and gutter render nodes, kept across frames
+#[derive(Default)]
+pub struct NodeCache {
+    text: CachedNode,
+    gutter: CachedNode,
+}
+
+impl NodeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the cached nodes, so the next frame builds them again
+    pub fn clear(&self) {
+        self.text.replace(None);
+        self.gutter.replace(None);
+    }
+}
+
+/// Draw a frame of `rkit` into `snapshot`
+pub fn render_editor_snapshot(rkit: &EditorBuffer, snapshot: &gtk4::Snapshot, width: i32, height: i32, cache: &NodeCache) {
+    #[cfg(feature = "vertical-text")]
+    if rkit.is_vertical_mode() {
+        let bounds = rect(0.0, 0.0, width as f64, height as f64);
+        super::render_editor(rkit, &snapshot.append_cairo(&bounds), width, height);
+        return;
+    }
+    let dirty = rkit.take_dirty_layers();
+    let Some(shaping) = ImageSurface::create(Format::ARgb32, 1, 1).ok().and_then(|surface| Context::new(&surface).ok()) else {
+        return;
+    };
+    // Built apart and appended whole, so a panicking layer leaves no half-built frame
+    let frame = gtk4::Snapshot::new();
+    let drawn = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let layout = super::frame_layout(rkit, &shaping, width, height);
+        let backend = SnapshotBackend::new(&frame, &shaping);
+        background::render_background_layer(rkit, &backend, width, height);
+        append_cached(&cache.gutter, &frame, LayerKey::new(&layout, width, height), dirty.contains(RedrawLayers::GUTTER), false, |layer| {
+            gutter::render_gutter_layer(rkit, &SnapshotBackend::new(layer, &shaping), &layout, height);
+        });
+        highlight::render_highlight_layer(rkit, &backend, &layout, width);
+        flash::render_line_flash_layer(rkit, &backend, &layout, width);
+        pagebreak::render_page_break_layer(rkit, &backend, &layout, width);
+        images::render_image_layer(rkit, &backend, &layout, width);
+        selection::render_selection_layer(rkit, &backend, &layout, width);
+        occurrences::render_occurrence_layer(rkit, &backend, &layout);
+        append_cached(&cache.text, &frame, LayerKey::new(&layout, width, height), dirty.contains(RedrawLayers::TEXT), text_scrolls_as_one(rkit, &layout), |layer| {
+            text::render_text_layer(rkit, &SnapshotBackend::new(layer, &shaping), &layout);
+        });
+        remote::render_remote_cursor_layer(rkit, &backend, &layout);
+        focus::render_focus_layer(rkit, &backend, &layout, width);
+        sticky::render_sticky_scroll_layer(rkit, &backend, &layout, width);
+        scrollmap::render_scroll_map_layer(rkit, &backend, width, height);
+        cursor::render_caret(rkit, &backend, &layout);
+    }));
+    match drawn {
+        Ok(()) => {
+            if let Some(node) = frame.to_node() {
+                snapshot.append_node(&node);
+            }
+        }
+        Err(payload) => eprintln!("[ERROR] Rendering panicked: {}", crate::corelogic::recovery::panic_message(payload.as_ref())),
+    }
+}
+
+/// Whether the text layer only moves when the view scrolls: every line is drawn, at
+/// a fixed height, and nothing is styled by what is on screen
+fn text_scrolls_as_one(rkit: &EditorBuffer, layout: &LayoutMetrics) -> bool {
+    !rkit.is_log_buffer() && layout.row_offsets.is_none() && rkit.active_highlight_rules().is_empty()
+}
+
+/// Append the node in `slot` to `snapshot`, building it afresh with `draw` when it is
+/// dirty, missing or built for another geometry
+///
+/// With `scrollable` a node built at another scroll position is shifted into place.
+fn append_cached(slot: &CachedNode, snapshot: &gtk4::Snapshot, key: LayerKey, dirty: bool, scrollable: bool, draw: impl FnOnce(&gtk4::Snapshot)) {
+    let mut slot = slot.borrow_mut();
+    let reusable = |cached: &LayerKey| if scrollable { cached.same_apart_from_scroll(&key) } else { *cached == key };
+    if dirty || !slot.as_ref().is_some_and(|(cached, _)| reusable(cached)) {
+        *slot = None;
+        let layer = gtk4::Snapshot::new();
+        draw(&layer);
+        match layer.to_node() {
+            Some(node) => *slot = Some((key, node)),
+            // Nothing drawn
+            None => return,
+        }
+    }
+    if let Some((cached, node)) = slot.as_ref() {
+        snapshot.save();
+        snapshot.translate(&graphene::Point::new(0.0, (key.top_offset - cached.top_offset) as f32));
+        snapshot.append_node(node);
+        snapshot.restore();
+    }
+}
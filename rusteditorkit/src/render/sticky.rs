@@ -0,0 +1,71 @@
+//! Sticky scroll: pins the enclosing heading/scope lines to the top of the viewport
+//!
+//! The first visible row comes from the frame's layout, so the layer follows whatever put
+//! the view there (scrolling, folds, custom line heights).
+use gtk4::pango;
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::outline::{enclosing_path, OutlineEntry};
+use crate::render::backend::DrawingBackend;
+use crate::render::layout::LayoutMetrics;
+
+/// Outline entries enclosing `first_visible` that start above it, outermost first
+///
+/// At most `max_lines` are kept, dropping the outermost ones.
+pub fn pinned_entries(outline: &[OutlineEntry], first_visible: usize, max_lines: usize) -> Vec<OutlineEntry> {
+    let mut pinned: Vec<_> = enclosing_path(outline, first_visible)
+        .into_iter()
+        .filter(|entry| entry.row < first_visible)
+        .collect();
+    let skip = pinned.len().saturating_sub(max_lines);
+    pinned.drain(..skip);
+    pinned
+}
+
+/// Draws the outline entries enclosing the first visible row that have scrolled out of view
+pub fn render_sticky_scroll_layer(buf: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics, width: i32) {
+    if !buf.config.sticky_scroll {
+        return;
+    }
+    let pinned = pinned_entries(&buf.outline(), layout.first_visible_row(), buf.config.sticky_scroll_max_lines);
+    if pinned.is_empty() {
+        return;
+    }
+
+    let height = pinned.len() as f64 * layout.line_height;
+    backend.fill_rect(0.0, layout.viewport_top, width as f64, height, buf.config.color(&buf.config.sticky_scroll_bg_color).rgba());
+
+    let text_color = buf.text_color();
+    for (i, entry) in pinned.iter().enumerate() {
+        let Some(line) = buf.lines.get(entry.row) else { continue };
+        let pango_layout = crate::render::fontoptions::create_text_run(buf, backend);
+        pango_layout.set_text(line);
+        pango_layout.set_attributes(Some(&crate::render::layout::spacing_attributes(buf, line)));
+        pango_layout.set_font_description(Some(&layout.text_metrics.font_desc));
+        pango_layout.set_height((layout.line_height * pango::SCALE as f64) as i32);
+        crate::render::layout::apply_direction(&pango_layout, buf.is_rtl_row(entry.row), layout);
+        let y_line = layout.viewport_top + i as f64 * layout.line_height;
+        backend.draw_text_run(&pango_layout, layout.text_left_offset, y_line + layout.text_metrics.baseline_offset, text_color);
+    }
+
+    // Separator below the pinned lines, on whole pixels so it stays crisp
+    let y = (layout.viewport_top + height).round();
+    backend.fill_rect(0.0, y, width as f64, 1.0, buf.config.color(&buf.config.page_break_color).rgba());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corelogic::outline::{build_outline_tree, markdown_headings};
+
+    #[test]
+    fn pins_enclosing_entries_above_the_first_visible_row() {
+        let lines: Vec<String> = ["# A", "a", "## B", "b", "### C", "c", "## D"].iter().map(|s| s.to_string()).collect();
+        let outline = build_outline_tree(markdown_headings(&lines));
+        let titles = |first, max| pinned_entries(&outline, first, max).into_iter().map(|e| e.title).collect::<Vec<_>>();
+        assert_eq!(titles(5, 3), ["A", "B", "C"]);
+        assert_eq!(titles(5, 2), ["B", "C"]);
+        // A heading on the first visible row is on screen already
+        assert_eq!(titles(4, 3), ["A", "B"]);
+        assert!(titles(0, 3).is_empty());
+    }
+}
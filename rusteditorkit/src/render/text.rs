@@ -1,31 +1,146 @@
 //! Pango-based text layout and rendering
-use gtk4::cairo::Context;
 use gtk4::pango;
 use crate::corelogic::EditorBuffer;
+use crate::render::backend::{fill_rect_outline, DrawingBackend};
 use crate::render::layout::LayoutMetrics;
-use crate::corelogic::gutter::parse_color;
+use glib::translate::IntoGlib;
 
 /// Draws the text content layer
-pub fn render_text_layer(rkit: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics) {
-    let font_cfg = &rkit.config.font;
-    let char_spacing = font_cfg.font_character_spacing();
-    let font_color = font_cfg.font_color();
-    let (r, g, b, a) = parse_color(font_color);
-    for (i, line) in rkit.lines.iter().enumerate() {
-        let pango_layout = pangocairo::functions::create_layout(ctx);
+pub fn render_text_layer(rkit: &EditorBuffer, backend: &dyn DrawingBackend, layout: &LayoutMetrics) {
+    let (r, g, b, a) = rkit.text_color();
+    let font_desc = text_font_description(rkit, layout);
+    let log = rkit.is_log_buffer();
+    if !log {
+        crate::render::layout::refresh_decimal_tabs(rkit, || crate::render::fontoptions::create_text_run(rkit, backend), layout);
+    }
+    let rtl_rows = rkit.rtl_rows();
+    let highlight_rules = rkit.active_highlight_rules();
+    let mut drawn_rows = rkit.drawn_rows.borrow_mut();
+    drawn_rows.begin(rkit.text_revision);
+    // Log buffers can hold millions of lines: only lay out the rows on screen
+    let rows = if log {
+        layout.visible_rows(rkit)
+    } else {
+        (0..rkit.lines.len()).filter(|&row| !layout.hidden_rows.is_hidden(row)).collect()
+    };
+    for i in rows {
+        let line = &rkit.lines[i];
+        let pango_layout = crate::render::fontoptions::create_text_run(rkit, backend);
         pango_layout.set_text(line);
-        pango_layout.set_font_description(Some(&layout.text_metrics.font_desc));
-        pango_layout.set_spacing(char_spacing as i32);
+        pango_layout.set_font_description(Some(&font_desc));
+        let attrs = crate::render::layout::spacing_attributes(rkit, line);
+        let y_line = layout.row_y(i);
+        let visible = y_line + layout.row_height(i) >= layout.viewport_top
+            && y_line <= layout.viewport_bottom;
+        if !highlight_rules.is_empty() && visible {
+            add_highlight_rule_attributes(rkit, &attrs, line, &highlight_rules);
+        }
+        pango_layout.set_attributes(Some(&attrs));
         pango_layout.set_height((layout.line_height * pango::SCALE as f64) as i32);
+        if !log {
+            crate::render::layout::apply_tab_stops(&pango_layout, &rkit.tab_stops(i));
+        }
+        crate::render::layout::apply_direction(&pango_layout, rtl_rows[i], layout);
         let context = pango_layout.context();
         context.set_round_glyph_positions(true);
-        let y_line = layout.top_offset + i as f64 * layout.line_height;
         let y_baseline = y_line + layout.text_metrics.baseline_offset;
-        ctx.set_source_rgba(r, g, b, a);
-        ctx.move_to(layout.text_left_offset, y_baseline);
-        pangocairo::functions::show_layout(ctx, &pango_layout);
-        if i == rkit.cursor.row {
-            crate::render::cursor::render_cursor_layer(rkit, ctx, &pango_layout, layout, y_line);
+        let color = match if log { rkit.log_line_color(line) } else { None } {
+            Some(color) => color.rgba(),
+            None => (r, g, b, a),
+        };
+        backend.draw_text_run(&pango_layout, layout.text_left_offset, y_baseline, color);
+        let rtl_width = rtl_rows[i].then(|| (layout.text_right_offset - layout.text_left_offset).max(0.0));
+        drawn_rows.record(i, &pango_layout, rtl_width);
+        if let Some(summary) = rkit.folds.get(&i).filter(|fold| fold.collapsed).and_then(|fold| fold.summary.as_deref()) {
+            render_fold_summary(rkit, backend, &pango_layout, layout, summary, y_baseline, rtl_rows[i]);
+        }
+        let missing = pango_layout.unknown_glyphs_count().max(0) as usize;
+        rkit.report_missing_glyphs(i, missing);
+        if missing > 0 && rkit.config.missing_glyph_indicator {
+            render_missing_glyph_boxes(rkit, backend, &pango_layout, layout, y_line);
+        }
+    }
+}
+
+/// Text font, made heavier by `text_weight_boost` through the variable font "wght" axis
+pub(crate) fn text_font_description(rkit: &EditorBuffer, layout: &LayoutMetrics) -> pango::FontDescription {
+    let mut font_desc = layout.text_metrics.font_desc.clone();
+    let boost = rkit.text_weight_boost();
+    if boost > 0 {
+        let weight = font_desc.weight().into_glib() + i32::from(boost);
+        font_desc.set_variations(Some(&format!("wght={}", weight.min(1000))));
+    }
+    font_desc
+}
+
+/// Draw a collapsed fold's summary, dimmed, after the end of its header row
+fn render_fold_summary(rkit: &EditorBuffer, backend: &dyn DrawingBackend, line_layout: &pango::Layout, layout: &LayoutMetrics, summary: &str, y_baseline: f64, rtl: bool) {
+    let pango_layout = crate::render::fontoptions::create_text_run(rkit, backend);
+    pango_layout.set_font_description(Some(&layout.text_metrics.font_desc));
+    pango_layout.set_text(&format!("\u{22ef} {}", summary));
+    let scale = pango::SCALE as f64;
+    let line_extents = line_layout.extents().1;
+    let width = pango_layout.extents().1.width() as f64 / scale;
+    let gap = 2.0 * layout.text_metrics.average_char_width;
+    let x = if rtl {
+        layout.text_left_offset + line_extents.x() as f64 / scale - gap - width
+    } else {
+        layout.text_left_offset + (line_extents.x() + line_extents.width()) as f64 / scale + gap
+    };
+    let (r, g, b, a) = rkit.text_color();
+    backend.draw_text_run(&pango_layout, x, y_baseline, (r, g, b, a * 0.5));
+}
+
+/// Style the matches of the user highlight rules on `line`
+fn add_highlight_rule_attributes(rkit: &EditorBuffer, attrs: &pango::AttrList, line: &str, rules: &[&crate::corelogic::highlightrules::CompiledHighlightRule]) {
+    let to_u16 = |channel: f64| (channel.clamp(0.0, 1.0) * 65535.0) as u16;
+    for (start, end, index) in crate::corelogic::highlightrules::highlight_spans(line, rules) {
+        let rule = &rkit.config.highlight_rules[index];
+        let mut span_attrs: Vec<pango::Attribute> = Vec::new();
+        if let Some(color) = &rule.foreground {
+            let (r, g, b, _) = rkit.config.color(color).rgba();
+            span_attrs.push(pango::AttrColor::new_foreground(to_u16(r), to_u16(g), to_u16(b)).into());
+        }
+        if let Some(color) = &rule.background {
+            let (r, g, b, _) = rkit.config.color(color).rgba();
+            span_attrs.push(pango::AttrColor::new_background(to_u16(r), to_u16(g), to_u16(b)).into());
+        }
+        if rule.bold {
+            span_attrs.push(pango::AttrInt::new_weight(pango::Weight::Bold).into());
+        }
+        if rule.italic {
+            span_attrs.push(pango::AttrInt::new_style(pango::Style::Italic).into());
+        }
+        if rule.underline {
+            span_attrs.push(pango::AttrInt::new_underline(pango::Underline::Single).into());
+        }
+        for mut attr in span_attrs {
+            attr.set_start_index(start as u32);
+            attr.set_end_index(end as u32);
+            attrs.insert(attr);
+        }
+    }
+}
+
+/// Outline each glyph Pango had to draw as a hex box so missing fonts stand out
+fn render_missing_glyph_boxes(rkit: &EditorBuffer, backend: &dyn DrawingBackend, pango_layout: &pango::Layout, layout: &LayoutMetrics, y_line: f64) {
+    let color = rkit.config.color(&rkit.config.missing_glyph_color).rgba();
+    let scale = pango::SCALE as f64;
+    let mut iter = pango_layout.iter();
+    loop {
+        if let Some(run) = iter.run_readonly() {
+            let (_, logical) = iter.run_extents();
+            let mut x = layout.text_left_offset + logical.x() as f64 / scale;
+            for info in run.glyph_string().glyph_info() {
+                let width = info.geometry().width() as f64 / scale;
+                if info.glyph() & pango::GLYPH_UNKNOWN_FLAG != 0 {
+                    fill_rect_outline(backend, x, y_line + 1.0, width.max(2.0), (layout.line_height - 2.0).max(2.0), 1.0, color);
+                }
+                x += width;
+            }
+        }
+        if !iter.next_run() {
+            break;
         }
     }
 }
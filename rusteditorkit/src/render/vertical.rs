@@ -0,0 +1,68 @@
+//! Experimental vertical text layer (feature `vertical-text`)
+//!
+//! Lines are drawn as columns from the right edge of the text area leftwards. Each
+//! column is a regular Pango layout drawn in a cairo space rotated by 90 degrees with
+//! automatic gravity, so CJK glyphs stay upright while Latin runs are turned sideways.
+use gtk4::cairo::Context;
+use gtk4::pango;
+use crate::corelogic::EditorBuffer;
+use crate::render::layout::LayoutMetrics;
+
+/// Draws text, selection and caret in vertical columns
+pub fn render_vertical_text_layer(rkit: &EditorBuffer, ctx: &Context, layout: &LayoutMetrics, height: i32) {
+    let column_width = layout.line_height;
+    let right = layout.text_right_offset;
+    let columns = (((right - layout.text_left_offset) / column_width).max(1.0)) as usize;
+    rkit.set_viewport_rows(columns);
+    let (r, g, b, a) = rkit.text_color();
+    let selection = rkit.selection.as_ref().filter(|sel| sel.is_active()).map(|sel| sel.normalized());
+    let (sr, sg, sb, _) = rkit.config.color(&rkit.config.selection().selection_bg_color).rgba();
+    let text_height = (height as f64 - layout.viewport_top).max(0.0);
+
+    for (i, row) in (rkit.scroll_offset..rkit.lines.len()).take(columns).enumerate() {
+        let line = &rkit.lines[row];
+        let x_right = right - i as f64 * column_width;
+        let _ = ctx.save();
+        // Rotate so the layout's x axis runs down the screen and its y axis to the left
+        ctx.translate(x_right, layout.viewport_top);
+        ctx.rotate(std::f64::consts::FRAC_PI_2);
+        let pango_layout = crate::render::fontoptions::create_layout(rkit, ctx);
+        pango_layout.context().set_base_gravity(pango::Gravity::Auto);
+        pango_layout.context().set_gravity_hint(pango::GravityHint::Strong);
+        pango_layout.context_changed();
+        pango_layout.set_font_description(Some(&layout.text_metrics.font_desc));
+        pango_layout.set_text(line);
+        pango_layout.set_width((text_height * pango::SCALE as f64) as i32);
+
+        // Selection on this column
+        if let Some(((start_row, start_col), (end_row, end_col))) = selection {
+            if (start_row..=end_row).contains(&row) {
+                let from = if row == start_row { char_x(&pango_layout, line, start_col) } else { 0.0 };
+                let to = if row == end_row { char_x(&pango_layout, line, end_col) } else { text_height };
+                ctx.set_source_rgba(sr, sg, sb, rkit.config.selection().selection_opacity);
+                ctx.rectangle(from, 0.0, (to - from).max(0.0), column_width);
+                let _ = ctx.fill();
+            }
+        }
+
+        ctx.set_source_rgba(r, g, b, a);
+        ctx.move_to(0.0, 0.0);
+        pangocairo::functions::show_layout(ctx, &pango_layout);
+
+        // Caret: a bar across the column before the cursor character
+        if row == rkit.cursor.row && rkit.cursor_state.is_cursor_visible() {
+            let (cr, cg, cb, ca) = rkit.config.color(&rkit.config.cursor.cursor_color).rgba();
+            ctx.set_source_rgba(cr, cg, cb, ca);
+            let thickness = rkit.config.cursor.cursor_thickness.max(1.0);
+            ctx.rectangle(char_x(&pango_layout, line, rkit.cursor.col), 0.0, thickness, column_width);
+            let _ = ctx.fill();
+        }
+        let _ = ctx.restore();
+    }
+}
+
+/// Offset along the column of char index `col`
+fn char_x(pango_layout: &pango::Layout, line: &str, col: usize) -> f64 {
+    let byte = line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+    pango_layout.index_to_pos(byte as i32).x() as f64 / pango::SCALE as f64
+}
@@ -1,6 +1,8 @@
 //! Configuration management for the EditorWidget
 //! Handles loading and applying config files
 
+use gtk4::gio;
+use gtk4::gio::prelude::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 use crate::corelogic::EditorBuffer;
@@ -21,7 +23,7 @@ impl ConfigManager {
                     }
                     // Remove the first line if there is more than one line
                     if buf.lines.len() > 1 {
-                        buf.lines.remove(0);
+                        buf.replace_rows(0..1, Vec::new());
                     }
                 }
                 buffer.borrow().request_redraw();
@@ -29,8 +31,7 @@ impl ConfigManager {
             Err(e) => {
                 {
                     let mut buf = buffer.borrow_mut();
-                    buf.lines.clear();
-                    buf.lines.push(e.clone());
+                    buf.set_lines(vec![e.clone()]);
                     if buf.debug_mode {
                         println!("[DEBUG] Config load failed: {}", e);
                     }
@@ -39,4 +40,27 @@ impl ConfigManager {
             }
         }
     }
+
+    /// Re-apply the config file whenever it changes on disk (live reload)
+    ///
+    /// Keep the returned monitor alive for as long as reloading should happen. A file that
+    /// fails to parse is reported and the previous config stays in effect.
+    pub fn watch_config_file(buffer: &Rc<RefCell<EditorBuffer>>, path: &str) -> Result<gio::FileMonitor, glib::Error> {
+        let monitor = gio::File::for_path(path).monitor_file(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)?;
+        let buffer = buffer.clone();
+        let path = path.to_string();
+        monitor.connect_changed(move |_, _, _, event| {
+            if !matches!(event, gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::Created | gio::FileMonitorEvent::MovedIn | gio::FileMonitorEvent::Renamed) {
+                return;
+            }
+            match crate::config::editor_config_loader::load_widget_config(&path) {
+                Ok(config) => {
+                    buffer.borrow_mut().apply_config(config);
+                    buffer.borrow().request_redraw();
+                }
+                Err(e) => eprintln!("[ERROR] Config reload from '{}' failed: {}", path, e),
+            }
+        });
+        Ok(monitor)
+    }
 }
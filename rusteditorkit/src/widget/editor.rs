@@ -7,9 +7,8 @@ use gtk4::DrawingArea;
 use glib::ControlFlow;
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::corelogic::EditorBuffer;
+use crate::corelogic::{EditorBuffer, RedrawLayers};
 use crate::imcontext::EditorIMContext;
-use crate::render::layout::LayoutMetrics;
 
 /// Main editor widget struct that uses the modular render system
 pub struct EditorWidget {
@@ -18,6 +17,14 @@ pub struct EditorWidget {
     pub im_context: EditorIMContext,
     pub blink_source_id: Rc<RefCell<Option<glib::SourceId>>>,
     pub keymap: std::collections::HashMap<EditorAction, KeyCombo>,
+    frame_ticks: Rc<FrameTicks>,
+}
+
+/// Frame clock ticks currently driving an animation
+#[derive(Default)]
+struct FrameTicks {
+    flash: std::cell::Cell<bool>,
+    scroll: std::cell::Cell<bool>,
 }
 
 impl EditorWidget {
@@ -40,10 +47,9 @@ impl EditorWidget {
         // If blinking is disabled, ensure cursor is visible and redraw
         if !buf.config.cursor.cursor_blink {
             buf.cursor_state.visible = true;
-            self.drawing_area.queue_draw();
+            buf.invalidate(RedrawLayers::CURSOR);
             return;
         }
-        let drawing_area = self.drawing_area.clone();
         let buffer_clone = self.buffer.clone();
         let blink_source_id = self.blink_source_id.clone();
         let id = glib::timeout_add_local(std::time::Duration::from_millis(buf.config.cursor.cursor_blink_rate), move || {
@@ -51,7 +57,7 @@ impl EditorWidget {
             // Always use latest config
             buf.cursor_state_mut().tick_blink();
             buf.cursor_state_mut().check_restore_after_typing();
-            drawing_area.queue_draw();
+            buf.invalidate(RedrawLayers::CURSOR);
             // If blink is disabled, stop timer
             if !buf.config.cursor.cursor_blink {
                 return ControlFlow::Break;
@@ -90,26 +96,26 @@ impl EditorWidget {
         let im_context = EditorIMContext::new(move |text| {
             println!("IMContext commit: {}", text);
             let mut buf = buffer_clone.borrow_mut();
-            for c in text.chars() {
-                let row = buf.cursor.row;
-                let col = buf.cursor.col;
-                if row < buf.lines.len() {
-                    let line = &mut buf.lines[row];
-                    let byte_idx = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
-                    line.insert(byte_idx, c);
-                    buf.cursor.col += 1;
-                }
-            }
-            buf.request_redraw();
+            crate::widget::input::InputHandler::commit_text(&mut buf, &text);
         });
 
         // Cursor blinking logic is now managed only by update_cursor_config after config is loaded
 
-        let widget = Self { buffer, drawing_area, im_context, blink_source_id, keymap };
+        let widget = Self { buffer, drawing_area, im_context, blink_source_id, keymap, frame_ticks: Rc::default() };
         widget.update_cursor_config();
         widget
     }
 
+    /// Shortcuts of this widget's keymap (including overrides) formatted for the current platform
+    pub fn shortcuts(&self) -> Vec<crate::keybinds::ShortcutInfo> {
+        crate::keybinds::list_shortcuts(&self.keymap, crate::keybinds::ShortcutStyle::current())
+    }
+
+    /// Override (or add) the key combination bound to `action` (call before `connect_signals`)
+    pub fn set_keybinding(&mut self, action: EditorAction, combo: KeyCombo) {
+        self.keymap.insert(action, combo);
+    }
+
     /// Get a reference to the buffer (for integration/testing)
     pub fn buffer(&self) -> Rc<RefCell<EditorBuffer>> {
         self.buffer.clone()
@@ -123,27 +129,84 @@ impl EditorWidget {
     /// Connect the draw signal using the modular render system
     pub fn connect_draw_signal(&self) {
         let buffer = self.buffer.clone();
-        self.drawing_area.set_draw_func(move |_area, ctx, width, height| {
-            let buf = buffer.borrow();
-            let layout = LayoutMetrics::calculate(&buf, ctx);
-            crate::render::background::render_background_layer(&buf, ctx, width, height);
-            crate::render::gutter::render_gutter_layer(&buf, ctx, &layout, height);
-            crate::render::highlight::render_highlight_layer(&buf, ctx, &layout, width);
-            crate::render::selection::render_selection_layer(&buf, ctx, &layout, width);
-            crate::render::text::render_text_layer(&buf, ctx, &layout);
-
-            // Cursor rendering
-            let font_cfg = &buf.config.font;
-            let font_string = format!("{} {}", font_cfg.font_name(), font_cfg.font_size());
-            let font_desc = gtk4::pango::FontDescription::from_string(&font_string);
-            let pango_layout = pangocairo::functions::create_layout(ctx);
-            pango_layout.set_font_description(Some(&font_desc));
-            let row = buf.cursor.row.min(buf.lines.len().saturating_sub(1));
-            let line_text = buf.lines.get(row).cloned().unwrap_or_default();
-            pango_layout.set_text(&line_text);
-            let y_line = layout.top_offset + layout.line_height * row as f64;
-            crate::render::cursor::render_cursor_layer(&buf, ctx, &pango_layout, &layout, y_line);
+        let ticks = self.frame_ticks.clone();
+        let layers = crate::render::LayerCache::new();
+        self.drawing_area.set_draw_func(move |area, ctx, width, height| {
+            Self::start_frame_ticks(area, &buffer, &ticks);
+            crate::render::render_editor_cached(&buffer.borrow(), ctx, width, height, &layers);
         });
     }
 
+    /// Start the frame clock ticks of running animations (line flash, smooth scrolling);
+    /// the draw function calls this, and so must any other code drawing the buffer
+    pub(crate) fn start_animation_ticks(&self) {
+        Self::start_frame_ticks(&self.drawing_area, &self.buffer, &self.frame_ticks);
+    }
+
+    fn start_frame_ticks(area: &DrawingArea, buffer: &Rc<RefCell<EditorBuffer>>, ticks: &Rc<FrameTicks>) {
+        let buf = buffer.borrow();
+        if buf.line_flash.is_some() && !ticks.flash.get() {
+            Self::start_line_flash_tick(area, buffer.clone(), ticks.clone());
+        }
+        if buf.is_smooth_scrolling() && !ticks.scroll.get() {
+            Self::start_smooth_scroll_tick(area, buffer.clone(), ticks.clone());
+        }
+    }
+
+    /// Animate scrolling so `line` becomes the first visible row
+    ///
+    /// Jumps immediately when `smooth_scrolling` is disabled; any key or pointer input cancels it.
+    pub fn smooth_scroll_to(&self, line: usize) {
+        self.buffer.borrow_mut().smooth_scroll_to(line);
+    }
+
+    /// Animate scrolling by `px` pixels (negative scrolls up)
+    pub fn smooth_scroll_by(&self, px: f64) {
+        let mut buf = self.buffer.borrow_mut();
+        let rows = px / buf.viewport_line_height();
+        buf.smooth_scroll_by(rows);
+    }
+
+    /// Show collaborators' carets, selections and name flags
+    ///
+    /// Positions are not adjusted by local edits; re-send them whenever the shared text changes.
+    pub fn set_remote_cursors(&self, cursors: Vec<crate::corelogic::remote::RemoteCursor>) {
+        self.buffer.borrow_mut().set_remote_cursors(cursors);
+    }
+
+    /// Lay the text out in vertical columns (experimental, feature `vertical-text`)
+    #[cfg(feature = "vertical-text")]
+    pub fn set_vertical_mode(&self, vertical: bool) {
+        self.buffer.borrow_mut().set_vertical_mode(vertical);
+    }
+
+    /// Drive a scroll animation from the frame clock until it finishes or is canceled
+    fn start_smooth_scroll_tick(area: &DrawingArea, buffer: Rc<RefCell<EditorBuffer>>, ticks: Rc<FrameTicks>) {
+        ticks.scroll.set(true);
+        area.add_tick_callback(move |_, clock| {
+            let active = buffer.borrow_mut().advance_smooth_scroll(clock.frame_time());
+            buffer.borrow().request_redraw();
+            if active {
+                ControlFlow::Continue
+            } else {
+                ticks.scroll.set(false);
+                ControlFlow::Break
+            }
+        });
+    }
+
+    /// Drive the line flash fade from the frame clock until it finishes
+    fn start_line_flash_tick(area: &DrawingArea, buffer: Rc<RefCell<EditorBuffer>>, ticks: Rc<FrameTicks>) {
+        ticks.flash.set(true);
+        area.add_tick_callback(move |_, clock| {
+            let active = buffer.borrow_mut().advance_line_flash(clock.frame_time());
+            buffer.borrow().invalidate(RedrawLayers::OVERLAY);
+            if active {
+                ControlFlow::Continue
+            } else {
+                ticks.flash.set(false);
+                ControlFlow::Break
+            }
+        });
+    }
 }
@@ -0,0 +1,260 @@
+//! Headless driver for the widget input logic
+//!
+//! `HeadlessEditor` feeds synthetic key presses, input method commits and pointer events
+//! through the same `InputHandler`/`PointerHandler` code the GTK controllers use, with a
+//! synthetic `LayoutMetrics` and an in-memory clipboard. Nothing touches GDK, so widget
+//! behavior can be tested on CI without a display server.
+//!
+//! `draw_frame` renders the buffer into an image surface and takes its measured layout,
+//! so pointer events then hit-test the Pango layouts the text was drawn with.
+
+use std::collections::HashMap;
+use crate::corelogic::EditorBuffer;
+use crate::keybinds::{EditorAction, KeyCombo};
+use crate::render::layout::LayoutMetrics;
+use crate::widget::input::{InputHandler, KeyOutcome};
+use crate::widget::pointer::{PointerHandler, PointerMetrics};
+
+/// An editor buffer driven like the widget, without a window
+pub struct HeadlessEditor {
+    pub buffer: EditorBuffer,
+    pub keymap: HashMap<EditorAction, KeyCombo>,
+    pub layout: LayoutMetrics,
+    /// Simulated widget size in pixels
+    pub width: f64,
+    pub height: f64,
+    /// In-memory stand-in for the system clipboard
+    pub clipboard: String,
+}
+
+impl HeadlessEditor {
+    /// Driver with `text` loaded, the platform keymap and a 10x20 px character cell
+    pub fn new(text: &str) -> Self {
+        #[cfg(target_os = "linux")]
+        let keymap = crate::keybinds::linux::linux_keymap();
+        #[cfg(target_os = "macos")]
+        let keymap = crate::keybinds::mac::mac_keymap();
+        #[cfg(target_os = "windows")]
+        let keymap = crate::keybinds::win::win_keymap();
+
+        let mut buffer = EditorBuffer::new();
+        buffer.new_file();
+        buffer.import_from_text(text);
+        buffer.clear_undo_history();
+        let layout = LayoutMetrics::synthetic(20.0, 10.0, 50.0, 5.0, 800.0);
+        let (width, height) = (800.0, 600.0);
+        buffer.set_viewport_rows(((height - layout.viewport_top) / layout.line_height) as usize);
+        buffer.set_viewport_text_right(layout.text_right_offset);
+        Self { buffer, keymap, layout, width, height, clipboard: String::new() }
+    }
+
+    /// Buffer contents joined with newlines
+    pub fn text(&self) -> String {
+        self.buffer.lines.join("\n")
+    }
+
+    /// Cursor as (row, col)
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.buffer.cursor.row, self.buffer.cursor.col)
+    }
+
+    /// Press a key by GDK key name (e.g. "Left", "Return", "z") with modifiers
+    pub fn key(&mut self, key: &'static str, ctrl: bool, shift: bool, alt: bool) -> KeyOutcome {
+        let unicode = match key.chars().count() {
+            1 if !ctrl && !alt => key.chars().next(),
+            _ => None,
+        };
+        self.key_combo(&KeyCombo::new(key, ctrl, shift, alt), unicode)
+    }
+
+    /// Press a key combination; `unicode` is the character the key would type
+    pub fn key_combo(&mut self, combo: &KeyCombo, unicode: Option<char>) -> KeyOutcome {
+        // Clipboard actions go to the in-memory clipboard instead of GDK
+        if let Some((&action, _)) = self.keymap.iter().find(|(_, kc)| *kc == combo) {
+            match action {
+                EditorAction::CopySelection => {
+                    self.clipboard = self.buffer.copy_text();
+                    return KeyOutcome::Action(action);
+                }
+                EditorAction::CutSelection => {
+                    if let Some(text) = self.buffer.cut_text() {
+                        self.clipboard = text;
+                    }
+                    return KeyOutcome::Action(action);
+                }
+                _ => {}
+            }
+        }
+        let outcome = InputHandler::dispatch_key(&mut self.buffer, &self.keymap, combo, unicode);
+        if outcome == KeyOutcome::Paste {
+            let text = self.clipboard.clone();
+            self.buffer.paste_text(&text);
+        }
+        outcome
+    }
+
+    /// Press the key bound to `action` in the keymap; returns false if it is unbound
+    pub fn press_action(&mut self, action: EditorAction) -> bool {
+        match self.keymap.get(&action).cloned() {
+            Some(combo) => {
+                self.key_combo(&combo, None);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Type each character of `text` as an unbound printable key press
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key_combo(&KeyCombo::new("", false, false, false), Some(c));
+        }
+    }
+
+    /// Simulate an input method commit (e.g. a composed or non-ASCII character)
+    pub fn ime_commit(&mut self, text: &str) {
+        InputHandler::commit_text(&mut self.buffer, text);
+    }
+
+    /// Draw a frame at the simulated size and measure the layout from it
+    ///
+    /// Until then the layout is synthetic and every character one cell wide.
+    pub fn draw_frame(&mut self) {
+        let surface = gtk4::cairo::ImageSurface::create(gtk4::cairo::Format::ARgb32, self.width as i32, self.height as i32)
+            .expect("image surface for a headless frame");
+        let ctx = gtk4::cairo::Context::new(&surface).expect("cairo context for a headless frame");
+        crate::render::render_editor(&self.buffer, &ctx, self.width as i32, self.height as i32);
+        self.layout = LayoutMetrics::calculate(&self.buffer, &ctx, self.width as i32, self.height as i32);
+    }
+
+    /// Widget coordinates of the middle of the cell at (row, col)
+    pub fn point_at(&self, row: usize, col: usize) -> (f64, f64) {
+        self.point_in(row, col, 0.5)
+    }
+
+    /// Widget coordinates `fraction` of the way across the cell at (row, col) in reading order
+    ///
+    /// Rows drawn by `draw_frame` measure the cell on their Pango layout, others use uniform cells.
+    pub fn point_in(&self, row: usize, col: usize, fraction: f64) -> (f64, f64) {
+        let drawn = self.buffer.drawn_rows.borrow().cell(row, self.buffer.text_revision, col);
+        let x = match drawn {
+            // Widths are negative in right-to-left runs, so this also moves in reading order
+            Some((start, width)) => self.layout.text_left_offset + start + fraction * width,
+            None => {
+                let char_width = self.layout.text_metrics.average_char_width;
+                let line = self.buffer.lines.get(row).map(String::as_str).unwrap_or("");
+                let offset = crate::corelogic::layout::column_x_offset(line, col, char_width, self.layout.word_spacing) + fraction * char_width;
+                if self.buffer.is_rtl_row(row) {
+                    self.layout.text_right_offset - offset
+                } else {
+                    self.layout.text_left_offset + offset
+                }
+            }
+        };
+        let y = self.layout.top_offset + (self.buffer.display_row(row) as f64 + 0.5) * self.layout.line_height;
+        (x, y)
+    }
+
+    /// Click `n_press` times (1-3) at widget coordinates
+    pub fn click(&mut self, x: f64, y: f64, n_press: i32, shift: bool) {
+        let metrics = PointerMetrics::from_layout(&self.layout);
+        PointerHandler::pressed(&mut self.buffer, x, y, shift, (self.width, self.height), &metrics);
+        PointerHandler::released(&mut self.buffer, n_press, x, y, &metrics);
+    }
+
+    /// Press at `from`, drag through to `to` and release
+    pub fn drag(&mut self, from: (f64, f64), to: (f64, f64)) {
+        let metrics = PointerMetrics::from_layout(&self.layout);
+        PointerHandler::pressed(&mut self.buffer, from.0, from.1, false, (self.width, self.height), &metrics);
+        PointerHandler::dragged(&mut self.buffer, to.0, to.1, &metrics);
+        PointerHandler::drag_ended(&mut self.buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_and_keymap_actions_edit_the_buffer() {
+        let mut ed = HeadlessEditor::new("hello");
+        ed.key("End", false, false, false);
+        ed.type_text(" world");
+        assert_eq!(ed.text(), "hello world");
+        assert!(ed.press_action(EditorAction::Undo));
+        assert_eq!(ed.text(), "hello worl");
+        ed.key("Return", false, false, false);
+        ed.ime_commit("é");
+        assert_eq!(ed.text(), "hello worl\né");
+        assert_eq!(ed.cursor(), (1, 1));
+    }
+
+    #[test]
+    fn pointer_click_and_drag_use_synthetic_layout() {
+        let mut ed = HeadlessEditor::new("first line\nsecond line");
+        let (x, y) = ed.point_at(1, 3);
+        ed.click(x, y, 1, false);
+        assert_eq!(ed.cursor(), (1, 3));
+
+        let from = ed.point_at(0, 0);
+        let to = ed.point_at(0, 5);
+        ed.drag(from, to);
+        assert_eq!(ed.buffer.copy(), "first");
+    }
+
+    #[test]
+    fn pointer_hits_wide_characters_and_emoji() {
+        // Sample lines of the demo
+        let mut ed = HeadlessEditor::new("// Emoji: 😀 😁 😂 🤔\n// Chinese/Japanese: 漢字 かな カタカナ");
+        ed.draw_frame();
+        for row in 0..2 {
+            for col in 0..ed.buffer.lines[row].chars().count() {
+                let (x, y) = ed.point_at(row, col);
+                ed.click(x, y, 1, false);
+                assert_eq!(ed.cursor(), (row, col));
+            }
+        }
+        // The right half of a wide character still belongs to it
+        let (x, y) = ed.point_in(1, 21, 0.9);
+        ed.click(x, y, 1, false);
+        assert_eq!(ed.cursor(), (1, 21));
+
+        ed.drag(ed.point_at(0, 10), ed.point_at(0, 13));
+        assert_eq!(ed.buffer.copy(), "😀 😁");
+    }
+
+    #[test]
+    fn copy_and_paste_use_the_in_memory_clipboard() {
+        let mut ed = HeadlessEditor::new("abc");
+        assert!(ed.press_action(EditorAction::SelectAll));
+        assert!(ed.press_action(EditorAction::CopySelection));
+        assert_eq!(ed.clipboard, "abc");
+        ed.key("End", false, false, false);
+        assert_eq!(ed.key("v", true, false, false), KeyOutcome::Paste);
+        assert_eq!(ed.text(), "abcabc");
+    }
+
+    #[test]
+    fn clipboard_selection_behavior_follows_config() {
+        let mut ed = HeadlessEditor::new("one\ntwo");
+        ed.buffer.config.copy_clears_selection = true;
+        ed.buffer.config.paste_replaces_selection = false;
+        ed.buffer.config.cut_line_without_selection = false;
+        assert!(ed.press_action(EditorAction::SelectAll));
+        assert!(ed.press_action(EditorAction::CopySelection));
+        assert!(ed.buffer.selection.is_none());
+
+        ed.clipboard = "X".to_string();
+        ed.buffer.goto_position(0, 1);
+        ed.key("Right", false, true, false);
+        assert_eq!(ed.key("v", true, false, false), KeyOutcome::Paste);
+        assert_eq!(ed.text(), "onXe\ntwo");
+
+        ed.buffer.selection = None;
+        assert!(ed.press_action(EditorAction::CutSelection));
+        assert_eq!((ed.text().as_str(), ed.clipboard.as_str()), ("onXe\ntwo", "X"));
+        ed.buffer.config.cut_line_without_selection = true;
+        assert!(ed.press_action(EditorAction::CutSelection));
+        assert_eq!((ed.text().as_str(), ed.clipboard.as_str()), ("two", "onXe"));
+    }
+}
@@ -1,13 +1,77 @@
 //! Input handling for the EditorWidget
 //! Handles keyboard input, cursor movement, and text insertion
 
+use std::collections::HashMap;
 use gtk4::gdk::Key;
-use crate::corelogic::EditorBuffer;
+use crate::corelogic::{EditorBuffer, RedrawLayers};
+use crate::keybinds::{EditorAction, KeyCombo};
+
+/// What happened to a key press routed through `InputHandler::dispatch_key`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOutcome {
+    /// A keymap action was executed
+    Action(EditorAction),
+    /// Paste was requested; clipboard reads are async, so the caller fetches the text
+    /// and passes it to `EditorBuffer::paste_text`
+    Paste,
+    /// A printable character was typed
+    Text(char),
+    /// The key is not handled by the editor and should propagate
+    Unhandled,
+}
 
 /// Input handling utilities for the editor
 pub struct InputHandler;
 
 impl InputHandler {
+    /// Route a key press: keymap actions first, then printable characters
+    ///
+    /// `unicode` is the character the key produces, if any. Shared by the GTK key
+    /// controller and the headless test driver.
+    pub fn dispatch_key(buf: &mut EditorBuffer, keymap: &HashMap<EditorAction, KeyCombo>, combo: &KeyCombo, unicode: Option<char>) -> KeyOutcome {
+        if let Some((&action, _)) = keymap.iter().find(|(_, kc)| *kc == combo) {
+            if action == EditorAction::PasteClipboard {
+                return KeyOutcome::Paste;
+            }
+            buf.handle_editor_action(action);
+            return KeyOutcome::Action(action);
+        }
+        // Only handle printable characters and basic whitespace
+        match unicode {
+            Some(c) if c.is_ascii_graphic() || c == ' ' || c == '\t' => {
+                buf.handle_text_input(&c.to_string());
+                KeyOutcome::Text(c)
+            }
+            _ => KeyOutcome::Unhandled,
+        }
+    }
+
+    /// Insert text committed by the input method at the cursor
+    pub fn commit_text(buf: &mut EditorBuffer, text: &str) {
+        if buf.viewed_snapshot().is_some() || buf.is_log_buffer() {
+            return;
+        }
+        // A bracket or quote committed over a selection goes through the dispatcher to surround it,
+        // and so does pasted-looking input (line breaks must become lines, as one undo step)
+        if crate::corelogic::paste::looks_like_paste(text) {
+            buf.handle_text_input(text);
+            return;
+        }
+        let mut chars = text.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if buf.has_selection() && crate::corelogic::surround::surround_pair(c).is_some() {
+                buf.handle_text_input(text);
+                return;
+            }
+        }
+        buf.cancel_smooth_scroll();
+        let start = (buf.cursor.row, buf.lines.get(buf.cursor.row).map_or(0, |line| line.chars().count()).min(buf.cursor.col));
+        if buf.replace_range(start, start, text).is_some() {
+            buf.cursor.col = start.1 + text.chars().count();
+        }
+        buf.request_redraw();
+    }
+
     /// Ensure cursor is always valid after buffer changes
     pub fn ensure_cursor_valid(buf: &mut EditorBuffer) {
         if buf.lines.is_empty() {
@@ -33,7 +97,7 @@ impl InputHandler {
             buf.cursor.col = buf.lines[buf.cursor.row].len();
         }
         Self::ensure_cursor_valid(buf);
-        buf.request_redraw();
+        buf.invalidate(RedrawLayers::CARET);
     }
 
     /// Move cursor right
@@ -48,7 +112,7 @@ impl InputHandler {
             buf.cursor.col = 0;
         }
         Self::ensure_cursor_valid(buf);
-        buf.request_redraw();
+        buf.invalidate(RedrawLayers::CARET);
     }
 
     /// Move cursor up
@@ -57,11 +121,10 @@ impl InputHandler {
             buf.cursor.row = 0;
             buf.cursor.col = 0;
         } else if buf.cursor.row > 0 {
-            buf.cursor.row -= 1;
-            buf.cursor.col = buf.cursor.col.min(buf.lines[buf.cursor.row].len());
+            buf.move_to_row(buf.cursor.row - 1);
         }
         Self::ensure_cursor_valid(buf);
-        buf.request_redraw();
+        buf.invalidate(RedrawLayers::CARET);
     }
 
     /// Move cursor down
@@ -70,26 +133,17 @@ impl InputHandler {
             buf.cursor.row = 0;
             buf.cursor.col = 0;
         } else if buf.cursor.row + 1 < buf.lines.len() {
-            buf.cursor.row += 1;
-            buf.cursor.col = buf.cursor.col.min(buf.lines[buf.cursor.row].len());
+            buf.move_to_row(buf.cursor.row + 1);
         }
         Self::ensure_cursor_valid(buf);
-        buf.request_redraw();
+        buf.invalidate(RedrawLayers::CARET);
     }
 
     /// Insert a character at the cursor
     pub fn insert_char(buf: &mut EditorBuffer, c: char) {
-        if buf.lines.is_empty() {
-            buf.lines.push(String::new());
-            buf.cursor.row = 0;
-            buf.cursor.col = 0;
-        }
         let row = buf.cursor.row;
         let col = buf.cursor.col;
-        if row < buf.lines.len() {
-            let line = &mut buf.lines[row];
-            let byte_idx = line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
-            line.insert(byte_idx, c);
+        if row < buf.lines.len() && buf.replace_range((row, col), (row, col), c.encode_utf8(&mut [0; 4])).is_some() {
             buf.cursor.col += 1;
         }
         Self::ensure_cursor_valid(buf);
@@ -98,16 +152,9 @@ impl InputHandler {
 
     /// Insert newline at cursor
     pub fn insert_newline(buf: &mut EditorBuffer) {
-        if buf.lines.is_empty() {
-            buf.lines.push(String::new());
-            buf.cursor.row = 0;
-            buf.cursor.col = 0;
-        }
         let row = buf.cursor.row;
         let col = buf.cursor.col;
-        if row < buf.lines.len() {
-            let rest = buf.lines[row].split_off(col);
-            buf.lines.insert(row + 1, rest);
+        if row < buf.lines.len() && buf.replace_range((row, col), (row, col), "\n").is_some() {
             buf.cursor.row += 1;
             buf.cursor.col = 0;
         }
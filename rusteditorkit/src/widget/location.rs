@@ -0,0 +1,129 @@
+//! Opening and saving through GIO, for GVFS locations such as sftp://, smb:// and admin://
+//!
+//! Locations are resolved like `EditorBuffer::open_file` resolves them, so plain paths
+//! work too. Reads and writes of remote locations are asynchronous on the main loop;
+//! local files are saved through the same crash-safe `write_location` as `save_file`. If
+//! a location lives on a volume that is not mounted, the host's `gio::MountOperation`
+//! (e.g. a `gtk4::MountOperation`, which shows the password prompt) is used to mount it
+//! once before retrying; without one the call fails with `FileError::NotMounted`.
+//! Open/save transforms, the text format and error mapping are shared with `fileio`.
+
+use gtk4::gio;
+use gtk4::gio::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::corelogic::transforms::FileError;
+use crate::corelogic::EditorBuffer;
+use super::editor::EditorWidget;
+
+/// Host callback receiving the outcome of an asynchronous open or save
+pub type LocationCallback = Box<dyn FnOnce(Result<(), FileError>)>;
+
+/// Mount the volume enclosing `file` with `mount_operation`, then run `retry`
+fn mount_then(file: &gio::File, location: String, mount_operation: Option<gio::MountOperation>, done: LocationCallback, retry: impl FnOnce(LocationCallback) + 'static) {
+    let Some(operation) = mount_operation else {
+        done(Err(FileError::NotMounted { path: location }));
+        return;
+    };
+    file.mount_enclosing_volume(gio::MountMountFlags::NONE, Some(&operation), gio::Cancellable::NONE, move |mounted| {
+        match mounted {
+            Ok(()) => retry(done),
+            Err(e) => done(Err(FileError::from_gio(&location, &e))),
+        }
+    });
+}
+
+fn load(buffer: Rc<RefCell<EditorBuffer>>, file: gio::File, location: String, mount_operation: Option<gio::MountOperation>, done: LocationCallback) {
+    let file_for_mount = file.clone();
+    file.load_contents_async(gio::Cancellable::NONE, move |result| match result {
+        Ok((bytes, _etag)) => {
+            let mut buf = buffer.borrow_mut();
+            let loaded = buf.decode_file_bytes(&location, bytes.to_vec())
+                .map(|(lines, format)| buf.load_lines(&location, lines, format));
+            if loaded.is_ok() {
+                buf.refresh_statistics();
+                buf.refresh_title();
+                buf.refresh_caret_context();
+                buf.request_redraw();
+            }
+            drop(buf);
+            done(loaded);
+        }
+        Err(e) if e.matches(gio::IOErrorEnum::NotMounted) => {
+            let (retry_file, retry_location) = (file_for_mount.clone(), location.clone());
+            mount_then(&file_for_mount, location, mount_operation, done, move |done| {
+                load(buffer, retry_file, retry_location, None, done)
+            });
+        }
+        Err(e) => done(Err(FileError::from_gio(&location, &e))),
+    });
+}
+
+fn store(buffer: Rc<RefCell<EditorBuffer>>, file: gio::File, location: String, mount_operation: Option<gio::MountOperation>, done: LocationCallback) {
+    let (bytes, lines) = {
+        let buf = buffer.borrow();
+        if let Some(name) = buf.viewed_snapshot() {
+            done(Err(FileError::SnapshotView { name: name.to_string() }));
+            return;
+        }
+        match buf.encode_file_bytes(&location) {
+            Ok(bytes) => (bytes, buf.lines.clone()),
+            Err(e) => {
+                drop(buf);
+                done(Err(e));
+                return;
+            }
+        }
+    };
+    // Local files are written in place of the async replace, with the configured strategy
+    if file.path().is_some() {
+        let mut buf = buffer.borrow_mut();
+        let written = buf.write_location(&location, &bytes).map(|()| {
+            buf.mark_saved(&location, lines);
+            buf.refresh_title();
+        });
+        drop(buf);
+        done(written);
+        return;
+    }
+    let backup = buffer.borrow().config.backup_mode != crate::corelogic::safesave::BackupMode::None;
+    let file_for_mount = file.clone();
+    file.replace_contents_async(bytes, None, backup, gio::FileCreateFlags::NONE, gio::Cancellable::NONE, move |result| match result {
+        Ok(_) => {
+            let mut buf = buffer.borrow_mut();
+            buf.mark_saved(&location, lines);
+            buf.refresh_title();
+            drop(buf);
+            done(Ok(()));
+        }
+        Err((_, e)) if e.matches(gio::IOErrorEnum::NotMounted) => {
+            let (retry_file, retry_location) = (file_for_mount.clone(), location.clone());
+            mount_then(&file_for_mount, location, mount_operation, done, move |done| {
+                store(buffer, retry_file, retry_location, None, done)
+            });
+        }
+        Err((_, e)) => done(Err(FileError::from_gio(&location, &e))),
+    });
+}
+
+impl EditorWidget {
+    /// Open a path or GVFS URI asynchronously; `done` receives the outcome
+    pub fn open_location(&self, location: &str, mount_operation: Option<gio::MountOperation>, done: impl FnOnce(Result<(), FileError>) + 'static) {
+        let file = crate::corelogic::fileio::location_file(location);
+        load(self.buffer.clone(), file, location.to_string(), mount_operation, Box::new(done));
+    }
+
+    /// Save the buffer to a path or GVFS URI asynchronously; `done` receives the outcome
+    pub fn save_location(&self, location: &str, mount_operation: Option<gio::MountOperation>, done: impl FnOnce(Result<(), FileError>) + 'static) {
+        let file = crate::corelogic::fileio::location_file(location);
+        store(self.buffer.clone(), file, location.to_string(), mount_operation, Box::new(done));
+    }
+
+    /// Save a local `path` through the GIO `admin://` backend after `FileError::PermissionDenied`
+    ///
+    /// The backend is mounted with `mount_operation` on first use, which shows the polkit prompt.
+    pub fn save_location_as_admin(&self, path: &str, mount_operation: Option<gio::MountOperation>, done: impl FnOnce(Result<(), FileError>) + 'static) {
+        let absolute = std::path::absolute(path).map_or_else(|_| path.to_string(), |p| p.to_string_lossy().into_owned());
+        self.save_location(&format!("admin://{}", absolute), mount_operation, done);
+    }
+}
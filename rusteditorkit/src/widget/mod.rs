@@ -3,15 +3,26 @@
 //! This module contains all widget-related functionality, separated into logical components:
 //! - editor: Main EditorWidget implementation
 //! - input: Input handling and key events
+//! - pointer: Mouse click, multi-click and drag handling
 //! - focus: Focus management and controllers
 //! - config: Configuration loading and management
 //! - signals: Signal connections and event handling
+//! - print: Print dialog and print preview
+//! - headless: Display-free driver for testing widget input logic
+//! - location: Asynchronous open/save of GVFS locations through GIO
+//! - view: GObject subclass for UI files, composite templates and relm4
 
 pub mod editor;
 pub mod input;
+pub mod pointer;
 pub mod focus;
 pub mod config;
 pub mod signals;
+pub mod print;
+pub mod headless;
+pub mod location;
+pub mod view;
 
 // Re-export the main EditorWidget for convenience
 pub use editor::EditorWidget;
+pub use view::EditorView;
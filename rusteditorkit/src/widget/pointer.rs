@@ -0,0 +1,114 @@
+//! Pointer handling for the EditorWidget
+//! Mouse press, multi-click, drag and scroll map logic, independent of GTK controllers
+
+use crate::corelogic::{EditorBuffer, RedrawLayers};
+use crate::corelogic::gutter::{gutter_column_at, GutterColumn};
+use crate::render::layout::LayoutMetrics;
+
+/// Geometry used to map pointer coordinates to buffer positions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerMetrics {
+    pub line_height: f64,
+    pub char_width: f64,
+    pub left_margin: f64,
+    pub top_margin: f64,
+    /// Average advance of the gutter font, which sizes the gutter columns
+    pub gutter_char_width: f64,
+}
+
+impl PointerMetrics {
+    /// Fixed approximation used by the GTK controllers until they receive layout metrics
+    pub fn approximate() -> Self {
+        Self { line_height: 20.0, char_width: 10.0, left_margin: 50.0, top_margin: 5.0, gutter_char_width: 10.0 }
+    }
+
+    /// Metrics of the last frame drawn for `buf`, approximate before the first one
+    pub fn from_buffer(buf: &EditorBuffer) -> Self {
+        match buf.viewport_text_geometry.get() {
+            Some((left_margin, char_width)) => Self {
+                line_height: buf.viewport_line_height(),
+                char_width,
+                left_margin,
+                top_margin: buf.config.margin_top,
+                gutter_char_width: buf.viewport_gutter_char_width.get(),
+            },
+            None => Self::approximate(),
+        }
+    }
+
+    /// Metrics matching a computed (or synthetic) layout
+    pub fn from_layout(layout: &LayoutMetrics) -> Self {
+        Self {
+            line_height: layout.line_height,
+            char_width: layout.text_metrics.average_char_width,
+            left_margin: layout.text_left_offset,
+            top_margin: layout.viewport_top,
+            gutter_char_width: layout.gutter_metrics.average_char_width,
+        }
+    }
+}
+
+/// Pointer handling utilities for the editor
+pub struct PointerHandler;
+
+impl PointerHandler {
+    /// Primary button press at `(x, y)` in a widget of `width` x `height` pixels
+    pub fn pressed(buf: &mut EditorBuffer, x: f64, y: f64, shift_held: bool, (width, height): (f64, f64), m: &PointerMetrics) {
+        buf.cancel_smooth_scroll();
+        // Clicking the scroll map jumps to the mark (or proportional row) under the pointer
+        if buf.config.scroll_map && x >= width - buf.config.scroll_map_width {
+            let row = buf.scroll_map_row_at(y, height);
+            buf.goto_position(row, 0);
+            buf.refresh_scope();
+            buf.refresh_caret_context();
+            buf.request_redraw();
+            return;
+        }
+
+        // Clicking the line number or fold marker of a fold header expands or collapses the fold
+        let view_right = width - if buf.config.scroll_map { buf.config.scroll_map_width } else { 0.0 };
+        let columns = buf.gutter_columns(m.gutter_char_width, view_right);
+        if matches!(gutter_column_at(&columns, x), Some(GutterColumn::LineNumbers | GutterColumn::Folding)) {
+            let (row, _) = buf.screen_to_buffer_position(x, y, m.line_height, m.char_width, m.left_margin, m.top_margin);
+            if buf.folds.contains_key(&row) && buf.toggle_fold(row) {
+                return;
+            }
+        }
+
+        // Clicking a task checkbox toggles it instead of moving the selection (unless the
+        // buffer is read-only, then the dispatcher refuses and it is an ordinary click)
+        if !shift_held {
+            let (row, col) = buf.screen_to_buffer_position(x, y, m.line_height, m.char_width, m.left_margin, m.top_margin);
+            if buf.checkbox_at(row, col) && buf.handle_checkbox_click(row, col) {
+                return;
+            }
+        }
+
+        buf.handle_mouse_click(x, y, shift_held, m.line_height, m.char_width, m.left_margin, m.top_margin);
+        buf.refresh_scope();
+        buf.refresh_caret_context();
+        buf.invalidate(RedrawLayers::CARET);
+    }
+
+    /// Primary button release; `n_press` 2 selects a word, 3 selects a line
+    pub fn released(buf: &mut EditorBuffer, n_press: i32, x: f64, y: f64, m: &PointerMetrics) {
+        match n_press {
+            2 => buf.handle_double_click(x, y, m.line_height, m.char_width, m.left_margin, m.top_margin),
+            3 => buf.handle_triple_click(x, y, m.line_height, m.char_width, m.left_margin, m.top_margin),
+            // Single click - already handled in pressed
+            _ => {}
+        }
+        buf.invalidate(RedrawLayers::CARET);
+    }
+
+    /// Pointer moved to `(x, y)` while dragging
+    pub fn dragged(buf: &mut EditorBuffer, x: f64, y: f64, m: &PointerMetrics) {
+        buf.handle_mouse_drag(x, y, m.line_height, m.char_width, m.left_margin, m.top_margin);
+        buf.invalidate(RedrawLayers::CARET);
+    }
+
+    /// Drag finished
+    pub fn drag_ended(buf: &mut EditorBuffer) {
+        buf.handle_mouse_release();
+    }
+}
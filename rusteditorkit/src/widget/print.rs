@@ -0,0 +1,26 @@
+//! Printing entry points for EditorWidget
+
+use gtk4::prelude::*;
+use gtk4::{PrintOperationAction, PrintOperationResult};
+use super::editor::EditorWidget;
+use crate::export::build_print_operation;
+
+impl EditorWidget {
+    /// Show the print dialog and print the buffer
+    pub fn print(&self) -> Result<PrintOperationResult, String> {
+        self.run_print_operation(PrintOperationAction::PrintDialog)
+    }
+
+    /// Open the print preview for the buffer
+    pub fn print_preview(&self) -> Result<PrintOperationResult, String> {
+        self.run_print_operation(PrintOperationAction::Preview)
+    }
+
+    fn run_print_operation(&self, action: PrintOperationAction) -> Result<PrintOperationResult, String> {
+        let operation = build_print_operation(self.buffer.clone());
+        let parent = self.drawing_area.root().and_downcast::<gtk4::Window>();
+        operation
+            .run(action, parent.as_ref())
+            .map_err(|e| format!("Print failed: {}", e))
+    }
+}
@@ -1,10 +1,43 @@
 //! Signal connections for the EditorWidget
 //! Handles connecting various signals and event handlers
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use gtk4::prelude::*;
 use gtk4::glib::translate::IntoGlib;
+use crate::corelogic::EditorBuffer;
+use crate::corelogic::events::EditorEvent;
 use crate::widget::focus::FocusManager;
 use crate::widget::editor::EditorWidget;
+use crate::widget::input::{InputHandler, KeyOutcome};
+use crate::widget::pointer::{PointerHandler, PointerMetrics};
+
+/// Read the system clipboard and paste its text into `buffer` once it arrives
+///
+/// The read is async, so the buffer is borrowed only in the callback.
+fn paste_clipboard(buffer: &Rc<RefCell<EditorBuffer>>) {
+    let Some(display) = gtk4::gdk::Display::default() else {
+        eprintln!("[ERROR] No display found for clipboard access");
+        return;
+    };
+    let buffer = buffer.clone();
+    display.clipboard().read_text_async(gtk4::gio::Cancellable::NONE, move |result| {
+        match result {
+            Ok(Some(text)) => {
+                println!("[DEBUG] Clipboard paste: {}", text);
+                let mut buf = buffer.borrow_mut();
+                buf.paste_text(&text);
+                buf.refresh_statistics();
+                buf.refresh_scope();
+                buf.refresh_caret_context();
+                buf.refresh_title();
+                buf.request_redraw();
+            },
+            Ok(None) => println!("[DEBUG] Clipboard is empty"),
+            Err(e) => eprintln!("[ERROR] Clipboard error: {}", e),
+        }
+    });
+}
 
 impl EditorWidget {
     /// Connect all signals for the editor widget
@@ -17,6 +50,12 @@ impl EditorWidget {
         
         // Connect mouse event handlers
         self.connect_mouse_signals();
+
+        // Follow the subpixel layout of the monitor the view is shown on
+        self.connect_monitor_signals();
+
+        // Mirror the view when the widget's text direction is RTL
+        self.connect_direction_signals();
         
         // Connect key event handler using unified keybind system
         let buffer_clone = self.buffer().clone();
@@ -30,54 +69,35 @@ impl EditorWidget {
             // Debug output for key events
             println!("[KEYBIND DEBUG] Key event: {:?}", combo);
             
-            // Find matching action in keymap
-            if let Some((&action, _)) = keymap_clone.iter().find(|(_, kc)| **kc == combo) {
-                println!("[KEYBIND DEBUG] Dispatched action: {:?}", action);
-                
-                // Special handling for clipboard operations that require async access
-                if action == crate::keybinds::EditorAction::PasteClipboard {
-                    // Handle paste operation with proper async clipboard access
-                    let buffer_for_paste = buffer_clone.clone();
-                    if let Some(display) = gtk4::gdk::Display::default() {
-                        let clipboard = display.clipboard();
-                        clipboard.read_text_async(
-                            gtk4::gio::Cancellable::NONE,
-                            move |result| {
-                                match result {
-                                    Ok(Some(text)) => {
-                                        println!("[DEBUG] Clipboard paste: {}", text);
-                                        let mut buf = buffer_for_paste.borrow_mut();
-                                        buf.paste_text(&text);
-                                        buf.request_redraw();
-                                    },
-                                    Ok(None) => println!("[DEBUG] Clipboard is empty"),
-                                    Err(e) => eprintln!("[ERROR] Clipboard error: {}", e),
-                                }
-                            }
-                        );
-                    }
-                    return glib::Propagation::Stop;
-                } else {
-                    // Handle other keybind actions via dispatcher
-                    let mut buf = buffer_clone.borrow_mut();
-                    buf.handle_editor_action(action);
-                    return glib::Propagation::Stop;
+            // Keymap actions first, then printable characters
+            let outcome = {
+                let mut buf = buffer_clone.borrow_mut();
+                InputHandler::dispatch_key(&mut buf, &keymap_clone, &combo, keyval.to_unicode())
+            };
+            match outcome {
+                KeyOutcome::Action(action) => {
+                    println!("[KEYBIND DEBUG] Dispatched action: {:?}", action);
+                    glib::Propagation::Stop
                 }
-            }
-            
-            // Fallback: handle regular character input for typing
-            if let Some(text_char) = keyval.to_unicode() {
-                // Only handle printable characters and basic whitespace
-                if text_char.is_ascii_graphic() || text_char == ' ' || text_char == '\t' {
-                    let mut buf = buffer_clone.borrow_mut();
-                    buf.handle_text_input(&text_char.to_string());
-                    return glib::Propagation::Stop;
+                KeyOutcome::Paste => {
+                    paste_clipboard(&buffer_clone);
+                    glib::Propagation::Stop
                 }
+                KeyOutcome::Text(_) => glib::Propagation::Stop,
+                KeyOutcome::Unhandled => glib::Propagation::Proceed,
             }
-            
-            glib::Propagation::Proceed
         });
         self.drawing_area.add_controller(key_controller);
+
+        // PasteClipboard run through the dispatcher without text (menus, host actions)
+        let buffer_weak = Rc::downgrade(&self.buffer());
+        self.buffer().borrow_mut().events.connect(move |event| {
+            if let EditorEvent::PasteRequested = event {
+                if let Some(buffer) = buffer_weak.upgrade() {
+                    paste_clipboard(&buffer);
+                }
+            }
+        });
     }
 
     /// Connect mouse event handlers for selection support
@@ -95,41 +115,24 @@ impl EditorWidget {
             
             println!("[MOUSE DEBUG] Click at ({:.1}, {:.1}), shift: {}", x, y, shift_held);
             
+            let size = gesture.widget()
+                .map(|area| (area.width() as f64, area.height() as f64))
+                .unwrap_or((f64::MAX, 0.0));
             let mut buf = buffer_click.borrow_mut();
-            // Use approximate metrics - in a real implementation, get these from layout
-            let line_height = 20.0; // Approximate
-            let char_width = 10.0;   // Approximate  
-            let left_margin = 50.0;  // Approximate gutter width
-            let top_margin = 5.0;    // Approximate top padding
-            
-            buf.handle_mouse_click(x, y, shift_held, line_height, char_width, left_margin, top_margin);
-            buf.request_redraw();
+            // Measured by the last frame, so fonts, zoom and spacing are accounted for
+            let metrics = PointerMetrics::from_buffer(&buf);
+            PointerHandler::pressed(&mut buf, x, y, shift_held, size, &metrics);
         });
 
         // Handle double and triple clicks
         let buffer_multi = buffer_primary.clone();
         mouse_primary.connect_released(move |_gesture, n_press, x, y| {
-            let mut buf = buffer_multi.borrow_mut();
-            // Use approximate metrics
-            let line_height = 20.0;
-            let char_width = 10.0;
-            let left_margin = 50.0;
-            let top_margin = 5.0;
-            
-            match n_press {
-                2 => {
-                    println!("[MOUSE DEBUG] Double-click at ({:.1}, {:.1})", x, y);
-                    buf.handle_double_click(x, y, line_height, char_width, left_margin, top_margin);
-                },
-                3 => {
-                    println!("[MOUSE DEBUG] Triple-click at ({:.1}, {:.1})", x, y);
-                    buf.handle_triple_click(x, y, line_height, char_width, left_margin, top_margin);
-                },
-                _ => {
-                    // Single click - already handled in pressed
-                }
+            if n_press > 1 {
+                println!("[MOUSE DEBUG] {}-click at ({:.1}, {:.1})", n_press, x, y);
             }
-            buf.request_redraw();
+            let mut buf = buffer_multi.borrow_mut();
+            let metrics = PointerMetrics::from_buffer(&buf);
+            PointerHandler::released(&mut buf, n_press, x, y, &metrics);
         });
 
         self.drawing_area.add_controller(mouse_primary);
@@ -141,23 +144,15 @@ impl EditorWidget {
         let buffer_drag_update = buffer_drag.clone();
         drag_controller.connect_drag_update(move |drag_ctrl, _x, _y| {
             // Get absolute position
-            if let Some((_start_x, _start_y)) = drag_ctrl.start_point() {
-                if let Some((dx, dy)) = drag_ctrl.offset() {
-                    let current_x = _start_x + dx;
-                    let current_y = _start_y + dy;
-                    
-                    println!("[MOUSE DEBUG] Drag to ({:.1}, {:.1})", current_x, current_y);
-                    
-                    let mut buf = buffer_drag_update.borrow_mut();
-                    // Use approximate metrics
-                    let line_height = 20.0;
-                    let char_width = 10.0;
-                    let left_margin = 50.0;
-                    let top_margin = 5.0;
-                    
-                    buf.handle_mouse_drag(current_x, current_y, line_height, char_width, left_margin, top_margin);
-                    buf.request_redraw();
-                }
+            if let (Some((start_x, start_y)), Some((dx, dy))) = (drag_ctrl.start_point(), drag_ctrl.offset()) {
+                let current_x = start_x + dx;
+                let current_y = start_y + dy;
+                
+                println!("[MOUSE DEBUG] Drag to ({:.1}, {:.1})", current_x, current_y);
+                
+                let mut buf = buffer_drag_update.borrow_mut();
+                let metrics = PointerMetrics::from_buffer(&buf);
+                PointerHandler::dragged(&mut buf, current_x, current_y, &metrics);
             }
         });
 
@@ -165,10 +160,53 @@ impl EditorWidget {
         drag_controller.connect_drag_end(move |_, _x, _y| {
             println!("[MOUSE DEBUG] Drag ended");
             let mut buf = buffer_drag_end.borrow_mut();
-            buf.handle_mouse_release();
+            PointerHandler::drag_ended(&mut buf);
         });
 
         self.drawing_area.add_controller(drag_controller);
+
+        // Ctrl+wheel zooms the view; plain wheel events are left to the host
+        let buffer_zoom = self.buffer().clone();
+        let zoom_controller = gtk4::EventControllerScroll::new(gtk4::EventControllerScrollFlags::VERTICAL);
+        zoom_controller.connect_scroll(move |ctrl, _dx, dy| {
+            if !ctrl.current_event_state().contains(gtk4::gdk::ModifierType::CONTROL_MASK) {
+                return glib::Propagation::Proceed;
+            }
+            let mut buf = buffer_zoom.borrow_mut();
+            if dy < 0.0 {
+                buf.zoom_in();
+            } else if dy > 0.0 {
+                buf.zoom_out();
+            }
+            glib::Propagation::Stop
+        });
+        self.drawing_area.add_controller(zoom_controller);
+    }
+
+    /// Track the widget's text direction (locale or explicitly set by the host)
+    fn connect_direction_signals(&self) {
+        let is_rtl = |widget: &gtk4::DrawingArea| widget.direction() == gtk4::TextDirection::Rtl;
+        self.buffer().borrow_mut().set_widget_rtl(is_rtl(&self.drawing_area));
+        let buffer = self.buffer().clone();
+        self.drawing_area.connect_direction_changed(move |area, _previous| {
+            buffer.borrow_mut().set_widget_rtl(is_rtl(area));
+        });
+    }
+
+    /// Track monitor changes once the widget has a surface
+    fn connect_monitor_signals(&self) {
+        let buffer = self.buffer().clone();
+        self.drawing_area.connect_realize(move |area| {
+            let Some(surface) = area.native().and_then(|native| native.surface()) else { return };
+            let buffer_enter = buffer.clone();
+            surface.connect_enter_monitor(move |_, monitor| {
+                let order = subpixel_order_of(monitor);
+                buffer_enter.borrow_mut().set_monitor_subpixel_order(order);
+            });
+            if let Some(monitor) = area.display().monitor_at_surface(&surface) {
+                buffer.borrow_mut().set_monitor_subpixel_order(subpixel_order_of(&monitor));
+            }
+        });
     }
 
     /// Load and apply config from a RON file
@@ -238,3 +276,16 @@ impl EditorWidget {
         }
     }
 }
+
+/// Map a GDK monitor's subpixel layout to the editor's subpixel order
+fn subpixel_order_of(monitor: &gtk4::gdk::Monitor) -> Option<crate::corelogic::fontoptions::SubpixelOrder> {
+    use crate::corelogic::fontoptions::SubpixelOrder;
+    match monitor.subpixel_layout() {
+        gtk4::gdk::SubpixelLayout::None => Some(SubpixelOrder::None),
+        gtk4::gdk::SubpixelLayout::HorizontalRgb => Some(SubpixelOrder::Rgb),
+        gtk4::gdk::SubpixelLayout::HorizontalBgr => Some(SubpixelOrder::Bgr),
+        gtk4::gdk::SubpixelLayout::VerticalRgb => Some(SubpixelOrder::Vrgb),
+        gtk4::gdk::SubpixelLayout::VerticalBgr => Some(SubpixelOrder::Vbgr),
+        _ => None,
+    }
+}
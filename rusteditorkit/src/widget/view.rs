@@ -0,0 +1,205 @@
+//! Composite-template friendly editor widget
+//!
+//! `EditorView` is a GObject subclass of `GtkWidget` holding an `EditorWidget` with its
+//! signals connected, so declarative GTK code can use the editor like any other widget.
+//! Register the type once before building UI files:
+//!
+//! ```text
+//! rusteditorkit::EditorView::ensure_type();
+//!
+//! <object class="RekEditorView" id="editor">
+//!   <property name="config-path">config.ron</property>
+//!   <property name="text">Hello</property>
+//!   <property name="vexpand">true</property>
+//! </object>
+//! ```
+//!
+//! The properties (`text`, `config-path`, `zoom`) come with generated `set_*` methods,
+//! which is what relm4's `view!` macro calls, so the editor needs no component wrapper
+//! there:
+//!
+//! ```text
+//! view! {
+//!     rusteditorkit::EditorView {
+//!         set_config_path: "config.ron",
+//!         set_text: &model.initial_text,
+//!         set_vexpand: true,
+//!     }
+//! }
+//! ```
+//!
+//! `editor()` and `buffer()` reach the full API behind the view.
+//!
+//! Setting the experimental `snapshot-rendering` property draws the editor as GTK render
+//! nodes (`render::snapshot`) instead of through the cairo draw function of the
+//! `DrawingArea`, which still handles input and focus.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use crate::corelogic::events::EditorEvent;
+use crate::corelogic::EditorBuffer;
+use crate::widget::editor::EditorWidget;
+
+mod imp {
+    use std::cell::{Cell, OnceCell, RefCell};
+    use std::marker::PhantomData;
+    use super::*;
+
+    #[derive(Default, glib::Properties)]
+    #[properties(wrapper_type = super::EditorView)]
+    pub struct EditorView {
+        pub(super) editor: OnceCell<EditorWidget>,
+        /// RON config file applied to the buffer (empty: built-in defaults)
+        #[property(get, set = Self::set_config_path)]
+        config_path: RefCell<String>,
+        /// Whole buffer contents; setting it replaces the document
+        #[property(get = Self::text, set = Self::set_text)]
+        text: PhantomData<String>,
+        /// View zoom factor (1.0 = configured font size)
+        #[property(get = Self::zoom, set = Self::set_zoom, minimum = crate::corelogic::zoom::ZOOM_MIN,
+            maximum = crate::corelogic::zoom::ZOOM_MAX, default = 1.0)]
+        zoom: PhantomData<f64>,
+        /// Draw with GTK render nodes instead of cairo (experimental)
+        #[property(get, set = Self::set_snapshot_rendering)]
+        snapshot_rendering: Cell<bool>,
+        nodes: crate::render::NodeCache,
+    }
+
+    impl EditorView {
+        pub(super) fn editor(&self) -> &EditorWidget {
+            self.editor.get().expect("EditorView used before construction")
+        }
+
+        fn set_config_path(&self, path: String) {
+            if !path.is_empty() {
+                match crate::config::editor_config_loader::load_widget_config(&path) {
+                    Ok(config) => {
+                        self.editor().buffer.borrow_mut().apply_config(config);
+                        self.editor().update_cursor_config();
+                    }
+                    Err(e) => eprintln!("[EditorView] config '{}' not loaded: {}", path, e),
+                }
+            }
+            self.config_path.replace(path);
+        }
+
+        fn text(&self) -> String {
+            self.editor().buffer.borrow().lines.join("\n")
+        }
+
+        fn set_text(&self, text: String) {
+            self.editor().buffer.borrow_mut().reset(&text);
+        }
+
+        fn zoom(&self) -> f64 {
+            self.editor().buffer.borrow().zoom_level()
+        }
+
+        fn set_zoom(&self, zoom: f64) {
+            self.editor().buffer.borrow_mut().set_zoom(zoom);
+        }
+
+        fn set_snapshot_rendering(&self, enabled: bool) {
+            if self.snapshot_rendering.replace(enabled) != enabled {
+                // Neither path's cache saw the frames drawn by the other
+                self.nodes.clear();
+                self.editor().buffer.borrow().request_redraw();
+                self.obj().queue_draw();
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EditorView {
+        const NAME: &'static str = "RekEditorView";
+        type Type = super::EditorView;
+        type ParentType = gtk4::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_layout_manager_type::<gtk4::BinLayout>();
+            klass.set_css_name("rek-editor-view");
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for EditorView {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let editor = EditorWidget::new();
+            editor.connect_signals();
+            editor.widget().set_parent(&*self.obj());
+            // Zoom also changes from the keyboard and Ctrl+scroll; the buffer is still
+            // borrowed while it publishes the event, so notify from the main loop
+            let view = self.obj().downgrade();
+            editor.buffer.borrow_mut().events.connect(move |event| {
+                if let EditorEvent::ZoomChanged(_) = event {
+                    let view = view.clone();
+                    glib::idle_add_local_once(move || {
+                        if let Some(view) = view.upgrade() {
+                            view.notify_zoom();
+                        }
+                    });
+                }
+            });
+            let _ = self.editor.set(editor);
+        }
+
+        fn dispose(&self) {
+            if let Some(editor) = self.editor.get() {
+                editor.widget().unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for EditorView {
+        fn grab_focus(&self) -> bool {
+            self.editor().widget().grab_focus()
+        }
+
+        fn snapshot(&self, snapshot: &gtk4::Snapshot) {
+            if !self.snapshot_rendering.get() {
+                self.parent_snapshot(snapshot);
+                return;
+            }
+            // The drawing area's redraw requests still reach here: queueing a draw on a
+            // child invalidates its parents too
+            let editor = self.editor();
+            editor.start_animation_ticks();
+            let view = self.obj();
+            crate::render::render_editor_snapshot(&editor.buffer.borrow(), snapshot, view.width(), view.height(), &self.nodes);
+        }
+    }
+}
+
+glib::wrapper! {
+    /// The editor as a `GtkWidget` subclass, usable from UI files and relm4
+    pub struct EditorView(ObjectSubclass<imp::EditorView>)
+        @extends gtk4::Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget;
+}
+
+impl EditorView {
+    /// New view with an empty buffer and the built-in config
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// The editor widget inside the view
+    pub fn editor(&self) -> &EditorWidget {
+        self.imp().editor()
+    }
+
+    /// The buffer behind the view
+    pub fn buffer(&self) -> Rc<RefCell<EditorBuffer>> {
+        self.editor().buffer()
+    }
+}
+
+impl Default for EditorView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
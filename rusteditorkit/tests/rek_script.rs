@@ -0,0 +1,32 @@
+//! Edit scripts run on a file from disk to disk, the way `rek-script` does
+
+use rusteditorkit::corelogic::{parse_script, EditorBuffer};
+
+#[test]
+fn script_file_edits_and_saves_a_file() {
+    let dir = std::env::temp_dir().join(format!("rek-script-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("input.txt");
+    let output = dir.join("output.txt");
+    std::fs::write(&input, "pear\r\napple\r\nfig\r\nkeep\r\n").unwrap();
+    std::fs::write(dir.join("tidy.rek"), "# sort the fruit, then rename one\nselect 1 3\nsort\nindent\nselect none\nreplace /fig/plum/\n").unwrap();
+
+    let script = std::fs::read_to_string(dir.join("tidy.rek")).unwrap();
+    let commands = parse_script(&script).unwrap();
+    let mut buffer = EditorBuffer::new();
+    buffer.open_file(input.to_str().unwrap()).unwrap();
+    buffer.run_script(&commands).unwrap();
+    buffer.save_document(output.to_str().unwrap()).unwrap();
+
+    let (saved, original) = (std::fs::read_to_string(&output), std::fs::read_to_string(&input));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // Edited in one pass, written back with the file's own line endings, final one included
+    let unit = buffer.indent_style().unit();
+    let expected = format!("{unit}apple\r\n{unit}plum\r\n{unit}pear\r\nkeep\r\n");
+    assert_eq!(saved.unwrap(), expected);
+    assert_eq!(original.unwrap(), "pear\r\napple\r\nfig\r\nkeep\r\n");
+    // A failing command names its script line
+    let error = buffer.run_script(&parse_script("select all\nselect 9 9").unwrap()).unwrap_err();
+    assert_eq!(error.line, 2);
+}